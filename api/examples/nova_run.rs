@@ -23,7 +23,7 @@ fn main() {
     let mut vm: NexusVM<MerkleTrie> =
         nvm::interactive::load_elf(&pb).expect("error loading and parsing RISC-V instruction");
 
-    vm.syscalls.set_input(&[0x06]);
+    nvm::Inputs::new().private_input([0x06]).attach(&mut vm);
 
     println!("Generating execution trace of vm...");
     println!(">>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>");
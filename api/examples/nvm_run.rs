@@ -14,6 +14,10 @@ fn main() {
         k: 1,
         machine: Some(String::from("nop10")),
         file: None,
+        addr_bits: None,
+        asm: None,
+        args: Vec::new(),
+        precompiles: Default::default(),
     };
 
     run_vm::<MerkleTrie>(&opts, true).expect("error running Nexus VM");
@@ -29,6 +33,10 @@ fn main() {
         k: 1,
         machine: None,
         file: Some(pb.clone()),
+        addr_bits: None,
+        asm: None,
+        args: Vec::new(),
+        precompiles: Default::default(),
     };
 
     run_vm::<MerkleTrie>(&opts, true).expect("error running Nexus VM");
@@ -0,0 +1,64 @@
+//! Program inputs.
+
+use std::collections::BTreeMap;
+
+use nexus_vm::{eval::NexusVM, memory::Memory, syscalls::PRIVATE_INPUT};
+
+/// Builder for the inputs of a program: the private input, and any
+/// number of input streams identified by integers.
+#[derive(Clone, Debug, Default)]
+pub struct Inputs {
+    streams: BTreeMap<u32, Vec<u8>>,
+}
+
+impl Inputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the private input, read by `nexus_rt::read_from_private_input`.
+    pub fn private_input(self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.stream(PRIVATE_INPUT, bytes)
+    }
+
+    /// Set input stream `id`, read by `nexus_rt::read_stream`, 32
+    /// bytes per `ecall`. Stream 0 holds the private input.
+    pub fn stream(mut self, id: u32, bytes: impl Into<Vec<u8>>) -> Self {
+        self.streams.insert(id, bytes.into());
+        self
+    }
+
+    /// Attach the inputs to `vm`, replacing any previous contents of
+    /// the same streams.
+    pub fn attach<M: Memory>(&self, vm: &mut NexusVM<M>) {
+        for (id, bytes) in &self.streams {
+            vm.syscalls.set_stream(*id, bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::memory::paged::Paged;
+
+    #[test]
+    fn attach_inputs() {
+        let mut vm = NexusVM::<Paged>::new(0);
+        Inputs::new()
+            .private_input([1])
+            .stream(3, vec![1, 2, 3])
+            .attach(&mut vm);
+
+        let len = |vm: &mut NexusVM<Paged>, id| {
+            let mut regs = [0; 32];
+            regs[18] = 10; // stream_len
+            regs[11] = id;
+            vm.syscalls.syscall(0, 0, regs, &vm.mem).unwrap();
+            vm.syscalls.take_reg_writes()[0].1
+        };
+        assert_eq!(len(&mut vm, 0), 1);
+        assert_eq!(len(&mut vm, 3), 3);
+        assert_eq!(len(&mut vm, 4), 0);
+    }
+}
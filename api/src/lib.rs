@@ -18,10 +18,32 @@ pub mod nvm {
     pub mod interactive {
         pub use nexus_vm::{eval, load_elf, parse_elf, trace::trace, trace::Trace};
     }
-    pub use nexus_vm::{error::NexusVMError, eval::NexusVM, run_vm, trace_vm, VMOpts};
+    pub use crate::input::Inputs;
+    pub use nexus_vm::{
+        error::NexusVMError, eval::NexusVM, load_vm, precompiles::Precompiles, run_vm, trace_vm,
+        VMOpts,
+    };
+    pub mod circuit {
+        pub use nexus_vm::circuit::{check_trace, CircuitMismatch, StepMismatch, Violation};
+    }
+    pub mod validate {
+        pub use nexus_vm::validate::{validate_elf, ValidationError, MEMORY_TOP};
+    }
+    pub mod disasm {
+        pub use nexus_vm::disasm::{disassemble, Line, Listing, Segment, Unsupported};
+    }
     pub mod memory {
-        pub use nexus_vm::memory::{paged::Paged, path::Path, trie::MerkleTrie};
+        pub use nexus_vm::memory::{
+            check_addr_bits,
+            offline::{Audit, AuditError, OfflineMemory},
+            paged::Paged,
+            parse_addr_bits,
+            path::Path,
+            trie::MerkleTrie,
+        };
     }
 }
 
+mod input;
+
 pub mod prover;
@@ -67,7 +67,7 @@ mod tests {
     #[test]
     fn test_prove_seq() -> Result<(), ProofError> {
         let circuit = nop_circuit::<MerkleTrie>(1)?;
-        let trace = circuit.0.clone();
+        let trace = circuit.trace().clone();
 
         let params = pp::test_pp::gen_test_pp(&circuit)?;
 
@@ -13,35 +13,65 @@ pub use ark_relations::{
 };
 
 use nexus_vm::{
-    circuit::{build_constraints, ARITY},
-    machines::nop_vm,
+    circuit::{build_constraints, StepConstraints, ARITY},
+    machines::nop_vm_with_memory,
     memory::Memory,
+    precompiles::Precompiles,
     trace::{trace, Trace},
 };
 
 use super::error::*;
 use super::types::*;
 
-pub struct Tr<M: Memory>(pub Trace<M::Proof>);
+pub struct Tr<M: Memory> {
+    trace: Trace<M::Proof>,
+    constraints: StepConstraints,
+}
 
 impl<M: Memory> Tr<M> {
+    /// Create the step circuit proving `trace`. The constraints of the
+    /// steps are generated here, once, and shared by all steps.
+    pub fn new(trace: Trace<M::Proof>) -> Result<Self, ProofError> {
+        let constraints = StepConstraints::new(&trace).ok_or(ProofError::InvalidIndex(0))?;
+        Ok(Self { trace, constraints })
+    }
+
+    pub fn trace(&self) -> &Trace<M::Proof> {
+        &self.trace
+    }
+
     pub fn steps(&self) -> usize {
-        self.0.blocks.len()
+        self.trace.blocks.len()
     }
 
     pub fn instructions(&self) -> usize {
-        self.0.k * self.0.blocks.len()
+        self.trace.k * self.trace.blocks.len()
     }
 
     pub fn input(&self, index: usize) -> Result<Vec<F1>, ProofError> {
-        self.0.input(index).ok_or(ProofError::InvalidIndex(index))
+        self.trace
+            .input(index)
+            .ok_or(ProofError::InvalidIndex(index))
     }
 }
 
 pub fn nop_circuit<M: Memory>(k: usize) -> Result<Tr<M>, ProofError> {
-    let mut vm = nop_vm::<M>(1);
+    nop_circuit_with_memory(k, M::default(), Precompiles::default())
+}
+
+/// Create a no-op circuit using the given memory, checking `precompiles`.
+/// The shape of the memory proofs, and hence of the circuit, depends on the
+/// memory configuration, and the circuit only contains the gadgets of the
+/// selected precompiles.
+pub fn nop_circuit_with_memory<M: Memory>(
+    k: usize,
+    mem: M,
+    precompiles: Precompiles,
+) -> Result<Tr<M>, ProofError> {
+    let mut vm = nop_vm_with_memory(1, mem);
+    vm.syscalls.set_precompiles(precompiles);
     let trace = trace(&mut vm, k, false)?;
-    Ok(Tr(trace))
+    Tr::new(trace)
 }
 
 impl<M: Memory> StepCircuit<F1> for Tr<M>
@@ -59,6 +89,6 @@ where
         let index = k.value().map_or(0, |s| match s.into_bigint() {
             BigInt(l) => l[0] as usize,
         });
-        build_constraints(cs, index, z, &self.0)
+        build_constraints(cs, index, z, &self.trace, &self.constraints)
     }
 }
@@ -1,12 +1,16 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Range;
 
 pub use ark_relations::r1cs::SynthesisError;
 pub use ark_serialize::SerializationError;
 pub use nexus_nova::nova::{pcd::compression::SpartanError, Error as NovaError};
+pub use nexus_nova::pedersen::MissingLabel;
 pub use nexus_nova::r1cs::Error as R1CSError;
 pub use nexus_vm::error::NexusVMError;
 
+use super::types::F1;
+
 /// Errors related to proof generation
 #[derive(Debug)]
 pub enum ProofError {
@@ -45,6 +49,21 @@ pub enum ProofError {
 
     /// A proof has been read from a file that does not match the expected format
     InvalidProofFormat,
+
+    /// Segments of the given length cannot be proved, or do not cover the trace
+    InvalidSegmentLength(usize),
+
+    /// Segment proofs do not meet at the given step
+    SegmentMismatch(usize),
+
+    /// The proof is valid, but does not prove the expected statement
+    StatementMismatch(StatementError),
+
+    /// Offline memory checking was requested for proofs which do not support it
+    OfflineUnsupported,
+
+    /// Seeded storage was requested for bases which do not record their label
+    MissingBasesLabel(MissingLabel),
 }
 use ProofError::*;
 
@@ -72,6 +91,7 @@ impl From<NovaError> for ProofError {
             NovaError::R1CS(e) => WitnessError(e),
             NovaError::Synthesis(e) => CircuitError(e),
             NovaError::InvalidPublicInput => NovaProofError,
+            NovaError::ZKNotEnabled => InvalidPP,
         }
     }
 }
@@ -82,12 +102,24 @@ impl From<SerializationError> for ProofError {
     }
 }
 
+impl From<StatementError> for ProofError {
+    fn from(x: StatementError) -> ProofError {
+        StatementMismatch(x)
+    }
+}
+
 impl From<SpartanError> for ProofError {
     fn from(x: SpartanError) -> ProofError {
         CompressionError(x)
     }
 }
 
+impl From<MissingLabel> for ProofError {
+    fn from(x: MissingLabel) -> ProofError {
+        MissingBasesLabel(x)
+    }
+}
+
 impl Error for ProofError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -103,6 +135,11 @@ impl Error for ProofError {
             SRSSamplingError => None,
             CompressionError(e) => Some(e),
             InvalidProofFormat => None,
+            InvalidSegmentLength(_) => None,
+            SegmentMismatch(_) => None,
+            StatementMismatch(e) => Some(e),
+            OfflineUnsupported => None,
+            MissingBasesLabel(e) => Some(e),
         }
     }
 }
@@ -122,6 +159,81 @@ impl Display for ProofError {
             SRSSamplingError => write!(f, "error sampling test SRS"),
             CompressionError(e) => write!(f, "{e}"),
             InvalidProofFormat => write!(f, "invalid proof format"),
+            InvalidSegmentLength(n) => write!(f, "invalid segment length {n}"),
+            SegmentMismatch(i) => write!(f, "segments do not join at step {i}"),
+            StatementMismatch(e) => write!(f, "{e}"),
+            OfflineUnsupported => write!(
+                f,
+                "offline memory checking is only supported by sequential proofs without zero-knowledge"
+            ),
+            MissingBasesLabel(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Differences between the public values of a proof and the expected ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementError {
+    /// A state of the proof is not a valid machine state
+    InvalidState,
+
+    /// The initial state differs from the one with the expected digest
+    DigestMismatch { expected: F1, found: F1 },
+
+    /// The proof is for a different program, or different initial memory
+    ProgramMismatch { expected: F1, found: F1 },
+
+    /// The initial program counter differs
+    InitialPcMismatch { expected: u32, found: u32 },
+
+    /// An initial register differs
+    InitialRegisterMismatch {
+        index: usize,
+        expected: u32,
+        found: u32,
+    },
+
+    /// The final program counter differs
+    FinalPcMismatch { expected: u32, found: u32 },
+
+    /// The program exited with a different result (register a0)
+    ExitCodeMismatch { expected: u32, found: u32 },
+
+    /// The proof covers a different range of steps
+    StepsMismatch {
+        expected: Range<u64>,
+        found: Range<u64>,
+    },
+}
+
+impl Error for StatementError {}
+
+impl Display for StatementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use self::StatementError::*;
+        match self {
+            InvalidState => write!(f, "proof contains an invalid machine state"),
+            DigestMismatch { expected, found } => {
+                write!(f, "program digest is {found}, expected {expected}")
+            }
+            ProgramMismatch { expected, found } => {
+                write!(f, "program commitment is {found}, expected {expected}")
+            }
+            InitialPcMismatch { expected, found } => {
+                write!(f, "initial pc is {found:#x}, expected {expected:#x}")
+            }
+            InitialRegisterMismatch { index, expected, found } => {
+                write!(f, "initial x{index} is {found:#x}, expected {expected:#x}")
+            }
+            FinalPcMismatch { expected, found } => {
+                write!(f, "final pc is {found:#x}, expected {expected:#x}")
+            }
+            ExitCodeMismatch { expected, found } => {
+                write!(f, "exit code is {found}, expected {expected}")
+            }
+            StepsMismatch { expected, found } => {
+                write!(f, "proof covers steps {found:?}, expected {expected:?}")
+            }
         }
     }
 }
@@ -50,3 +50,47 @@ pub fn gen_key_to_file(pp: &ComPP, srs: &SRS, key_file: &str) -> Result<(), Proo
     let key: SpartanKey = gen_key(pp, srs)?;
     save_key(key, key_file)
 }
+
+/// Extract the verifier key for compressed proofs from the public
+/// parameters and Spartan key they were made with.
+pub fn gen_vk(pp: ComPP, key: SpartanKey) -> ComVerifierKey {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Extracting verifier key",
+    );
+
+    SNARK::verifier_key(pp, key)
+}
+
+pub fn save_vk(vk: ComVerifierKey, file: &str) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        vk_file =?file,
+        "Saving verifier key",
+    );
+
+    let f = File::create(file)?;
+    let mut enc = Encoder::new(&f, 0)?;
+    vk.serialize_compressed(&mut enc)?;
+    enc.finish()?;
+    f.sync_all()?;
+    Ok(())
+}
+
+pub fn load_vk(file: &str) -> Result<ComVerifierKey, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        vk_file =?file,
+        "Loading verifier key",
+    );
+
+    let f = File::open(file)?;
+    let mut dec = Decoder::new(&f)?;
+    let vk = ComVerifierKey::deserialize_compressed(&mut dec)?;
+    Ok(vk)
+}
+
+pub fn gen_vk_to_file(pp: ComPP, key: SpartanKey, vk_file: &str) -> Result<(), ProofError> {
+    let vk = gen_vk(pp, key);
+    save_vk(vk, vk_file)
+}
@@ -2,7 +2,9 @@ pub mod circuit;
 pub mod error;
 pub mod key;
 pub mod pp;
+pub mod segment;
 pub mod srs;
+pub mod statement;
 
 pub mod types;
 
@@ -11,7 +13,11 @@ use std::path::Path;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use nexus_vm::{
-    memory::{trie::MerkleTrie, Memory},
+    memory::{
+        offline::{trace_offline, Access, Audit, OfflineMemory},
+        trie::MerkleTrie,
+        Memory,
+    },
     VMOpts,
 };
 
@@ -20,7 +26,11 @@ use nexus_nova::nova::pcd::compression::SNARK;
 use crate::prover::nova::{
     circuit::Tr,
     error::ProofError,
-    types::{ComPCDNode, ComPP, ComProof, IVCProof, PCDNode, ParPP, SeqPP, SpartanKey, SC},
+    types::{
+        Claim, ComAggregate, ComAggregateProof, ComFoldPP, ComPCDNode, ComPP, ComProof,
+        ComVerifierKey, IVCProof, OfflineIVCProof, OfflineSC, OfflineSeqPP, PCDNode, ParPP,
+        SeqAggregate, SeqFoldPP, SeqPP, SpartanKey, ZKComProof, ZKIVCProof, ZKPCDNode, F1, SC,
+    },
 };
 
 pub const LOG_TARGET: &str = "nexus-prover";
@@ -62,8 +72,7 @@ pub fn run(opts: &VMOpts, pow: bool) -> Result<Trace, ProofError> {
 }
 
 pub fn init_circuit_trace(trace: Trace) -> Result<SC, ProofError> {
-    let tr = Tr::<MerkleTrie>(trace);
-    Ok(tr)
+    Tr::<MerkleTrie>::new(trace)
 }
 
 pub fn prove_seq(pp: &SeqPP, trace: Trace) -> Result<IVCProof, ProofError> {
@@ -91,10 +100,60 @@ pub fn prove_seq_step(
         pr = proof.unwrap();
     }
 
-    pr = IVCProof::prove_step(pr, pp, tr)?;
+    pr = IVCProof::prove_step(pr, pp, tr, &mut ark_std::rand::thread_rng())?;
     Ok(pr)
 }
 
+pub(crate) type OfflineTrace = nexus_vm::trace::Trace<Access>;
+
+/// A sequential proof using offline memory checking, together with the
+/// audit of its memory accesses, which the verifier checks as well, see
+/// [`statement::Statement::verify_seq_offline`].
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct OfflineProof {
+    pub proof: OfflineIVCProof,
+    pub audit: Audit,
+}
+
+/// Like [`run`], for offline memory checking: returns the trace, bound
+/// to the challenges, and the audit of the final memory.
+pub fn run_offline(opts: &VMOpts, pow: bool) -> Result<(OfflineTrace, Audit), ProofError> {
+    let mut vm = nexus_vm::load_vm::<OfflineMemory>(opts)?;
+    Ok(trace_offline(&mut vm, opts.k, pow)?)
+}
+
+pub fn prove_seq_offline(
+    pp: &OfflineSeqPP,
+    trace: OfflineTrace,
+    audit: Audit,
+) -> Result<OfflineProof, ProofError> {
+    let tr = Tr::<OfflineMemory>::new(trace)?;
+
+    let mut proof = prove_seq_offline_step(None, pp, &tr)?;
+    for _ in 1..tr.steps() {
+        proof = prove_seq_offline_step(Some(proof), pp, &tr)?;
+    }
+
+    Ok(OfflineProof { proof, audit })
+}
+
+pub fn prove_seq_offline_step(
+    proof: Option<OfflineIVCProof>,
+    pp: &OfflineSeqPP,
+    tr: &OfflineSC,
+) -> Result<OfflineIVCProof, ProofError> {
+    let pr = match proof {
+        None => OfflineIVCProof::new(&tr.input(0)?),
+        Some(pr) => pr,
+    };
+    Ok(OfflineIVCProof::prove_step(
+        pr,
+        pp,
+        tr,
+        &mut ark_std::rand::thread_rng(),
+    )?)
+}
+
 macro_rules! prove_par_impl {
     ( $pp_type:ty, $node_type:ty, $name:ident, $leaf_step_name:ident, $parent_step_name:ident) => {
         pub fn $name(pp: &$pp_type, trace: Trace) -> Result<$node_type, ProofError> {
@@ -137,7 +196,13 @@ macro_rules! prove_par_leaf_step_impl {
         pub fn $name(pp: &$pp_type, tr: &SC, i: usize) -> Result<$node_type, ProofError> {
             assert!((tr.steps() + 1).is_power_of_two());
 
-            let v = <$node_type>::prove_leaf(pp, tr, i, &tr.input(i)?)?;
+            let v = <$node_type>::prove_leaf(
+                pp,
+                tr,
+                i,
+                &tr.input(i)?,
+                &mut ark_std::rand::thread_rng(),
+            )?;
             Ok(v)
         }
     };
@@ -153,7 +218,7 @@ macro_rules! prove_par_parent_step_impl {
         ) -> Result<$node_type, ProofError> {
             assert!((tr.steps() + 1).is_power_of_two());
 
-            let c = <$node_type>::prove_parent(pp, tr, ab0, ab1)?;
+            let c = <$node_type>::prove_parent(pp, tr, ab0, ab1, &mut ark_std::rand::thread_rng())?;
             Ok(c)
         }
     };
@@ -193,6 +258,50 @@ pub fn compress(
     Ok(compressed_pcd_proof)
 }
 
+/// Randomize a sequential proof, so that it reveals nothing about the
+/// execution beyond its initial and final states.
+pub fn randomize_seq(pp: &SeqPP, proof: IVCProof) -> Result<ZKIVCProof, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Randomizing the proof",
+    );
+
+    let proof = proof.randomize(pp, &mut ark_std::rand::thread_rng())?;
+    Ok(proof)
+}
+
+/// Randomize a PCD node, so that it reveals nothing about the execution
+/// beyond its initial and final states.
+pub fn randomize_par(pp: &ParPP, node: PCDNode) -> Result<ZKPCDNode, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Randomizing the proof",
+    );
+
+    let node = node.randomize(pp, &mut ark_std::rand::thread_rng())?;
+    Ok(node)
+}
+
+/// Like [`compress`], but randomizes the node first, so that the
+/// compressed proof is zero-knowledge. The parameters must have been
+/// generated with [`pp::gen_pp_zk`].
+pub fn compress_zk(
+    compression_pp: &ComPP,
+    key: &SpartanKey,
+    node: ComPCDNode,
+) -> Result<ZKComProof, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Compressing the proof in zero-knowledge mode",
+    );
+
+    let mut rng = ark_std::rand::thread_rng();
+    let node = node.randomize(compression_pp, &mut rng)?;
+    let compressed_pcd_proof = SNARK::compress_zk(compression_pp, key, node, &mut rng)?;
+
+    Ok(compressed_pcd_proof)
+}
+
 pub fn verify_compressed(
     key: &SpartanKey,
     params: &ComPP,
@@ -207,6 +316,131 @@ pub fn verify_compressed(
     Ok(())
 }
 
+/// Like [`verify_compressed`], but with only the verifier key.
+pub fn verify_compressed_with_vk(vk: &ComVerifierKey, proof: &ComProof) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the compressed proof",
+    );
+
+    SNARK::verify_with_vk(vk, proof)?;
+    Ok(())
+}
+
+/// Like [`verify_compressed`], for proofs made with [`compress_zk`].
+pub fn verify_compressed_zk(
+    key: &SpartanKey,
+    params: &ComPP,
+    proof: &ZKComProof,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the compressed proof",
+    );
+
+    SNARK::verify_zk(key, params, proof)?;
+    Ok(())
+}
+
+/// Like [`verify_compressed_zk`], but with only the verifier key.
+pub fn verify_compressed_zk_with_vk(
+    vk: &ComVerifierKey,
+    proof: &ZKComProof,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the compressed proof",
+    );
+
+    SNARK::verify_zk_with_vk(vk, proof)?;
+    Ok(())
+}
+
+/// Aggregate IVC proofs of (possibly different) programs, made with the
+/// same public parameters, into a single proof. The proofs should have been
+/// verified already.
+pub fn aggregate_seq(
+    params: &SeqPP,
+    fold_pp: &SeqFoldPP,
+    proofs: &[IVCProof],
+) -> Result<SeqAggregate, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        proofs = proofs.len(),
+        "Aggregating the proofs",
+    );
+
+    Ok(SeqAggregate::new(
+        params,
+        fold_pp,
+        proofs,
+        &mut ark_std::rand::thread_rng(),
+    )?)
+}
+
+pub fn verify_aggregate_seq(
+    params: &SeqPP,
+    fold_pp: &SeqFoldPP,
+    aggregate: &SeqAggregate,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        proofs = aggregate.claims.len(),
+        "Verifying the aggregated proof",
+    );
+
+    aggregate.verify(params, fold_pp)?;
+    Ok(())
+}
+
+/// Aggregate compressible PCD proofs of (possibly different) programs,
+/// made with the same public parameters, into a single compressed proof.
+/// The proofs should have been verified already.
+pub fn aggregate(
+    compression_pp: &ComPP,
+    fold_pp: &ComFoldPP,
+    key: &SpartanKey,
+    nodes: &[ComPCDNode],
+) -> Result<ComAggregateProof, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        proofs = nodes.len(),
+        "Aggregating the proofs",
+    );
+
+    let aggregate = ComAggregate::new(
+        compression_pp,
+        fold_pp,
+        nodes,
+        &mut ark_std::rand::thread_rng(),
+    )?;
+    let proof = SNARK::compress_aggregate(key, aggregate)?;
+
+    Ok(proof)
+}
+
+pub fn verify_aggregate(
+    key: &SpartanKey,
+    params: &ComPP,
+    fold_pp: &ComFoldPP,
+    proof: &ComAggregateProof,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        proofs = proof.claims.len(),
+        "Verifying the aggregated proof",
+    );
+
+    SNARK::verify_aggregate(key, params, fold_pp, proof)?;
+    Ok(())
+}
+
+/// Return the commitment to the program of a claim, that is the root of
+/// the initial memory, which contains the program.
+pub fn program_commitment(claim: &Claim) -> Option<F1> {
+    claim.z_i.last().copied()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,7 +454,7 @@ mod tests {
         let ro_config = poseidon_config();
 
         let circuit = nop_circuit::<MerkleTrie>(1)?;
-        let trace = circuit.0.clone();
+        let trace = circuit.trace().clone();
 
         let params = SeqPP::setup(ro_config, &circuit, &(), &())?;
 
@@ -229,4 +463,94 @@ mod tests {
 
         Ok(())
     }
+
+    // Aggregate proofs of two different programs, neither a claim nor a
+    // member of which can be tampered with.
+    #[test]
+    fn test_aggregate_seq() -> Result<(), ProofError> {
+        use nexus_vm::{
+            machines::{loop_vm, nop_vm},
+            trace::trace,
+        };
+
+        let k = 1;
+        let circuit = nop_circuit::<MerkleTrie>(k)?;
+        let params = SeqPP::setup(poseidon_config(), &circuit, &(), &())?;
+        let fold_pp = pp::gen_seq_fold_pp()?;
+
+        let proofs = [nop_vm::<MerkleTrie>(1), loop_vm::<MerkleTrie>(1)]
+            .into_iter()
+            .map(|mut vm| {
+                let proof = prove_seq(&params, trace(&mut vm, k, false)?)?;
+                assert!(proof.verify(&params, proof.step_num() as _).is_ok());
+                Ok(proof)
+            })
+            .collect::<Result<Vec<_>, ProofError>>()?;
+
+        let aggregate = aggregate_seq(&params, &fold_pp, &proofs)?;
+        verify_aggregate_seq(&params, &fold_pp, &aggregate)?;
+
+        let programs: Vec<_> = aggregate.claims.iter().map(program_commitment).collect();
+        assert_eq!(programs[0], proofs[0].z_0().last().copied());
+        assert_eq!(programs[1], proofs[1].z_0().last().copied());
+        assert_ne!(programs[0], programs[1]);
+
+        let mut buf = Vec::new();
+        aggregate.serialize_compressed(&mut buf)?;
+        let mut aggregate = SeqAggregate::deserialize_compressed(&buf[..])?;
+        verify_aggregate_seq(&params, &fold_pp, &aggregate)?;
+
+        aggregate.claims.swap(0, 1);
+        assert!(verify_aggregate_seq(&params, &fold_pp, &aggregate).is_err());
+
+        // change the initial pc of the second program, which follows the
+        // length of z_0 in the encoding of the proof
+        let mut buf = Vec::new();
+        proofs[1].serialize_compressed(&mut buf)?;
+        buf[8] ^= 1;
+        let tampered = IVCProof::deserialize_compressed(&buf[..])?;
+        assert_ne!(tampered.z_0(), proofs[1].z_0());
+        assert!(tampered.verify(&params, tampered.step_num() as _).is_err());
+
+        let [proof, _] = <[IVCProof; 2]>::try_from(proofs).ok().unwrap();
+        let aggregate = aggregate_seq(&params, &fold_pp, &[proof, tampered])?;
+        assert!(verify_aggregate_seq(&params, &fold_pp, &aggregate).is_err());
+
+        Ok(())
+    }
+
+    // Prove each vendored compliance test, or those found in the
+    // directory given by NEXUS_RISCV_TESTS, see vm/compliance.
+    #[test]
+    #[ignore]
+    fn compliance_prove() -> Result<(), ProofError> {
+        use nexus_vm::{compliance, trace::trace};
+
+        let dir = compliance::tests_dir();
+        let tests = compliance::find_tests(&dir)?;
+        assert!(
+            !tests.is_empty(),
+            "no compliance tests in {}",
+            dir.display()
+        );
+
+        let k = 1;
+        let circuit = nop_circuit::<MerkleTrie>(k)?;
+        let params = SeqPP::setup(poseidon_config(), &circuit, &(), &())?;
+
+        for t in tests {
+            compliance::run_test::<MerkleTrie>(&t)?;
+
+            let (mut vm, _) = compliance::load_test::<MerkleTrie>(&t.elf)?;
+            let trace = trace(&mut vm, k, false)?;
+            let proof = prove_seq(&params, trace)?;
+            assert!(
+                proof.verify(&params, proof.step_num() as _).is_ok(),
+                "{}",
+                t.name
+            );
+        }
+
+        Ok(())
+    }
 }
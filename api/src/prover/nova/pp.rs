@@ -3,22 +3,48 @@ use zstd::stream::{Decoder, Encoder};
 
 pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
-use super::circuit::nop_circuit;
+use nexus_vm::{
+    memory::{offline::OfflineMemory, trie::MerkleTrie, Memory},
+    precompiles::Precompiles,
+};
+
+use crate::config::vm::NovaImpl;
+
+use super::circuit::{nop_circuit, nop_circuit_with_memory};
 use super::error::*;
 use super::types::*;
 use super::LOG_TARGET;
 
 pub fn gen_pp<C, SP>(circuit: &SC, aux: &C::SetupAux) -> Result<PP<C, SP>, ProofError>
+where
+    C: CommitmentScheme<P1>,
+    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+{
+    setup_pp(circuit, false, aux)
+}
+
+/// Like [`gen_pp`], but proofs made with the parameters can be randomized,
+/// see [`nexus_nova::nova::zk`].
+pub fn gen_pp_zk<C, SP>(circuit: &SC, aux: &C::SetupAux) -> Result<PP<C, SP>, ProofError>
+where
+    C: CommitmentScheme<P1>,
+    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+{
+    setup_pp(circuit, true, aux)
+}
+
+fn setup_pp<C, SP>(circuit: &SC, zk: bool, aux: &C::SetupAux) -> Result<PP<C, SP>, ProofError>
 where
     C: CommitmentScheme<P1>,
     SP: SetupParams<G1, G2, C, C2, RO, SC>,
 {
     tracing::info!(
         target: LOG_TARGET,
+        zk,
         "Generating public parameters",
     );
 
-    Ok(SP::setup(ro_config(), circuit, aux, &())?)
+    Ok(SP::setup(ro_config(), circuit, aux, &(), zk)?)
 }
 
 pub fn save_pp<C, SP>(pp: &PP<C, SP>, file: &str) -> Result<(), ProofError>
@@ -27,6 +53,10 @@ where
     SC: StepCircuit<F1>,
     SP: SetupParams<G1, G2, C, C2, RO, SC>,
 {
+    save_params(pp, file)
+}
+
+fn save_params(pp: &impl CanonicalSerialize, file: &str) -> Result<(), ProofError> {
     tracing::info!(
         target: LOG_TARGET,
         path = ?file,
@@ -41,12 +71,42 @@ where
     Ok(())
 }
 
+/// Make `pp` store its Pedersen bases as the label and length they are derived from.
+///
+/// Saved parameters become much smaller, and bases are derived again when they are loaded.
+/// The digest of `pp` is unaffected, and still covers the derived bases.
+///
+/// Fails if `pp` was loaded from a file storing bases in full, which doesn't record the label
+/// they were derived from.
+pub fn use_seeded_bases<S, SP>(
+    pp: &mut PublicParams<G1, G2, C1, C2, RO, S, SP>,
+) -> Result<(), ProofError>
+where
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C1, C2, RO, S>,
+{
+    pp.pp.set_storage(BasesStorage::Seeded)?;
+    pp.pp_secondary.set_storage(BasesStorage::Seeded)?;
+    Ok(())
+}
+
+/// Same as [`use_seeded_bases`] for compressible parameters, for which only the secondary
+/// commitment scheme is Pedersen.
+pub fn use_seeded_bases_com(pp: &mut ComPP) -> Result<(), ProofError> {
+    pp.pp_secondary.set_storage(BasesStorage::Seeded)?;
+    Ok(())
+}
+
 pub fn load_pp<C, SP>(file: &str) -> Result<PP<C, SP>, ProofError>
 where
     C: CommitmentScheme<P1>,
     SC: StepCircuit<F1> + Sync,
     SP: SetupParams<G1, G2, C, C2, RO, SC> + Sync,
 {
+    load_params(file)
+}
+
+fn load_params<T: CanonicalDeserialize>(file: &str) -> Result<T, ProofError> {
     tracing::info!(
         target: LOG_TARGET,
         path = ?file,
@@ -55,7 +115,7 @@ where
 
     let f = File::open(file)?;
     let mut dec = Decoder::new(&f)?;
-    let pp = PP::<C, SP>::deserialize_compressed(&mut dec)?;
+    let pp = T::deserialize_compressed(&mut dec)?;
     Ok(pp)
 }
 
@@ -68,6 +128,103 @@ where
     gen_pp(&tr, aux)
 }
 
+/// Generate public parameters for a memory with an address space of
+/// `addr_bits` bits; `None` selects the complete 32-bit address space.
+/// Proofs must be generated from traces using the same address space, and
+/// the same selection of `precompiles`.
+///
+/// With `zk`, proofs can be randomized, see [`gen_pp_zk`].
+pub fn gen_vm_pp_with_addr_bits<C, SP>(
+    k: usize,
+    addr_bits: Option<usize>,
+    precompiles: Precompiles,
+    zk: bool,
+    aux: &C::SetupAux,
+) -> Result<PP<C, SP>, ProofError>
+where
+    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+    C: CommitmentScheme<P1>,
+{
+    let mem = match addr_bits {
+        Some(bits) => MerkleTrie::with_addr_bits(bits)?,
+        None => MerkleTrie::default(),
+    };
+    let tr = nop_circuit_with_memory(k, mem, precompiles)?;
+    setup_pp(&tr, zk, aux)
+}
+
+/// Check that proofs made with `nova_impl`, zero-knowledge if `zk`, can
+/// use offline memory checking. The accumulator of the memory accesses
+/// is threaded through them in order, so only sequential proofs are
+/// supported, and the audit reveals the final memory, so they cannot be
+/// zero-knowledge.
+pub fn check_offline(nova_impl: NovaImpl, zk: bool) -> Result<(), ProofError> {
+    if nova_impl != NovaImpl::Sequential || zk {
+        return Err(ProofError::OfflineUnsupported);
+    }
+    Ok(())
+}
+
+/// Generate sequential public parameters for proofs using offline
+/// memory checking, see [`nexus_vm::memory::offline`]. Unlike the
+/// Merkle tree, the circuit does not depend on the address space.
+pub fn gen_vm_pp_offline(k: usize, precompiles: Precompiles) -> Result<OfflineSeqPP, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating public parameters for offline memory checking",
+    );
+
+    let tr = nop_circuit_with_memory(k, OfflineMemory::default(), precompiles)?;
+    Ok(OfflineSeqPP::setup(ro_config(), &tr, &(), &())?)
+}
+
+pub fn save_offline_pp(pp: &OfflineSeqPP, file: &str) -> Result<(), ProofError> {
+    save_params(pp, file)
+}
+
+pub fn load_offline_pp(file: &str) -> Result<OfflineSeqPP, ProofError> {
+    load_params(file)
+}
+
+/// Generate parameters for aggregating IVC proofs, see [`super::aggregate_seq`].
+/// These don't depend on the parameters of the proofs, nor on `k`.
+pub fn gen_seq_fold_pp() -> Result<SeqFoldPP, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating public parameters for aggregation of IVC proofs",
+    );
+
+    Ok(SeqAggregate::setup(ro_config(), &())?)
+}
+
+/// Generate parameters for aggregating compressible PCD proofs, see
+/// [`super::aggregate`]. These don't depend on the parameters of the proofs,
+/// nor on `k`.
+pub fn gen_com_fold_pp() -> Result<ComFoldPP, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating public parameters for aggregation of PCD proofs",
+    );
+
+    Ok(ComAggregate::setup(ro_config(), &())?)
+}
+
+pub fn save_fold_pp<S, SP>(pp: &FoldPP<S, SP>, file: &str) -> Result<(), ProofError>
+where
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C1, C2, RO, S>,
+{
+    save_params(pp, file)
+}
+
+pub fn load_fold_pp<S, SP>(file: &str) -> Result<FoldPP<S, SP>, ProofError>
+where
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C1, C2, RO, S>,
+{
+    load_params(file)
+}
+
 pub fn show_pp<C, SP>(pp: &PP<C, SP>)
 where
     SP: SetupParams<G1, G2, C, C2, RO, SC>,
@@ -0,0 +1,373 @@
+//! Segmented proving.
+//!
+//! An execution is split into segments of a fixed number of blocks,
+//! generated one at a time from the running VM (see [`Segments`]), so
+//! the full trace is never held in memory. The state of the VM at the
+//! boundary between two segments is a snapshot, from which the next
+//! segment is generated.
+//!
+//! Each segment is proved on its own as a PCD tree, using only its
+//! subtrace, so segments may be proved on different machines. The
+//! resulting node exposes the start and end states of the segment as
+//! `z_i` and `z_j` (see `Trace::input`). Two adjacent nodes are joined by
+//! proving a single block between them in a parent node, whose circuit
+//! checks that this block starts at the end state of the left node and
+//! ends at the start state of the right node:
+//!
+//! ```text
+//! [ node 0 ] b_0 [ node 1 ] b_1 ... b_{n-2} [ node n-1 ]
+//! ```
+//!
+//! Every PCD node proves an odd number of blocks, so a joining block is
+//! taken from the end of a segment, or from the start of the next one,
+//! whichever leaves an odd number of blocks to the node of the segment.
+//! The last segment is padded with a single block, if needed. Joining
+//! all segments in order gives a single node for the whole execution,
+//! verified as usual with `PCDNode::verify`.
+
+use std::ops::Range;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use nexus_vm::{eval::NexusVM, memory::trie::MerkleTrie, trace::Tracer, VMOpts};
+
+use super::{
+    error::ProofError,
+    init_circuit_trace,
+    types::{ComPCDNode, ComPP, PCDNode, ParPP, SC},
+    Trace, LOG_TARGET,
+};
+
+/// A part of an execution, between two snapshots of the VM.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Segment {
+    /// Blocks executed in this segment.
+    pub trace: Trace,
+    /// Whether the first block joins the previous segment to this one.
+    pub lead: bool,
+    /// Whether the last block joins this segment to the next one.
+    pub trail: bool,
+}
+
+impl Segment {
+    /// Indices of the blocks proved by the node of this segment.
+    pub fn blocks(&self) -> Range<usize> {
+        let start = self.trace.start + self.lead as usize;
+        let end = self.trace.start + self.trace.blocks.len() - self.trail as usize;
+        start..end
+    }
+
+    /// Subtrace containing the block joining the previous segment to
+    /// this one, if it is part of this segment.
+    pub fn leading(&self) -> Option<Trace> {
+        self.lead
+            .then(|| self.trace.get(self.trace.start))
+            .flatten()
+    }
+
+    /// Subtrace containing the block joining this segment to the next
+    /// one, if it is part of this segment.
+    pub fn trailing(&self) -> Option<Trace> {
+        self.trail
+            .then(|| self.trace.get(self.blocks().end))
+            .flatten()
+    }
+}
+
+/// Iterator generating the segments of an execution, by running the VM
+/// as it is advanced.
+pub struct Segments {
+    tracer: Tracer<MerkleTrie>,
+    len: usize,
+    trail: bool,
+    done: bool,
+}
+
+impl Segments {
+    /// Split the execution of `vm` into segments of `len` blocks, using
+    /// `k` steps per block. The last segment may be shorter, or longer
+    /// by one padding block. `len` must be at least 2.
+    pub fn new(vm: NexusVM<MerkleTrie>, k: usize, len: usize) -> Result<Self, ProofError> {
+        if len < 2 {
+            return Err(ProofError::InvalidSegmentLength(len));
+        }
+        Ok(Self {
+            tracer: Tracer::new(vm, k),
+            len,
+            trail: true,
+            done: false,
+        })
+    }
+
+    fn next_segment(&mut self) -> Result<Segment, ProofError> {
+        let lead = self.tracer.start() > 0 && !self.trail;
+        let mut trace = self.tracer.next_blocks(self.len)?;
+
+        let num_blocks = trace.blocks.len() - lead as usize;
+        self.done = self.tracer.halted();
+        self.trail = !self.done && num_blocks.is_multiple_of(2);
+        if self.done && num_blocks.is_multiple_of(2) {
+            trace.blocks.extend(self.tracer.next_blocks(1)?.blocks);
+        }
+
+        Ok(Segment { trace, lead, trail: self.trail })
+    }
+}
+
+impl Iterator for Segments {
+    type Item = Result<Segment, ProofError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let segment = self.next_segment();
+        if segment.is_err() {
+            self.done = true;
+        }
+        Some(segment)
+    }
+}
+
+/// Load the program given by `opts` and split its execution into
+/// segments of `len` blocks, see [`Segments::new`].
+pub fn segments(opts: &VMOpts, len: usize) -> Result<Segments, ProofError> {
+    let vm = nexus_vm::load_vm::<MerkleTrie>(opts)?;
+    Segments::new(vm, opts.k, len)
+}
+
+macro_rules! prove_segment_impl {
+    ( $pp_type:ty, $node_type:ty, $name:ident ) => {
+        /// Prove a segment, returning a node for the blocks of the
+        /// segment which do not join it to its neighbours.
+        pub fn $name(pp: &$pp_type, segment: &Segment) -> Result<$node_type, ProofError> {
+            let blocks = segment.blocks();
+            let tr = init_circuit_trace(segment.trace.clone())?;
+
+            tracing::info!(
+                target: LOG_TARGET,
+                start = blocks.start,
+                num_steps = blocks.len(),
+                "Proving segment",
+            );
+
+            fn prove(
+                pp: &$pp_type,
+                tr: &SC,
+                blocks: Range<usize>,
+            ) -> Result<$node_type, ProofError> {
+                let n = blocks.len();
+                if n.is_multiple_of(2) {
+                    return Err(ProofError::InvalidSegmentLength(n));
+                }
+                if n == 1 {
+                    let i = blocks.start;
+                    return Ok(<$node_type>::prove_leaf(
+                        pp,
+                        tr,
+                        i,
+                        &tr.input(i)?,
+                        &mut ark_std::rand::thread_rng(),
+                    )?);
+                }
+
+                // left and right subtrees prove an odd number of blocks
+                // each, and the parent proves the block between them
+                let half = (n - 1) / 2;
+                let mid = blocks.start + if half % 2 == 1 { half } else { half - 1 };
+                let left = prove(pp, tr, blocks.start..mid)?;
+                let right = prove(pp, tr, mid + 1..blocks.end)?;
+                Ok(<$node_type>::prove_parent(
+                    pp,
+                    tr,
+                    &left,
+                    &right,
+                    &mut ark_std::rand::thread_rng(),
+                )?)
+            }
+
+            prove(pp, &tr, blocks)
+        }
+    };
+}
+
+macro_rules! join_impl {
+    ( $pp_type:ty, $node_type:ty, $name:ident ) => {
+        /// Join two adjacent nodes by proving `block`, the block between
+        /// them.
+        pub fn $name(
+            pp: &$pp_type,
+            block: &Trace,
+            left: &$node_type,
+            right: &$node_type,
+        ) -> Result<$node_type, ProofError> {
+            let j = left.j as usize;
+            if right.i != left.j + 1 {
+                return Err(ProofError::SegmentMismatch(j));
+            }
+
+            // the circuit checks this as well, but a mismatch would only
+            // be detected when verifying
+            let tr: SC = init_circuit_trace(block.get(j).ok_or(ProofError::InvalidIndex(j))?)?;
+            if tr.input(j)? != left.z_j {
+                return Err(ProofError::SegmentMismatch(j));
+            }
+
+            let c =
+                <$node_type>::prove_parent(pp, &tr, left, right, &mut ark_std::rand::thread_rng())?;
+            Ok(c)
+        }
+    };
+}
+
+macro_rules! join_all_impl {
+    ( $pp_type:ty, $node_type:ty, $name:ident, $join_name:ident ) => {
+        /// Join the nodes of consecutive segments in order, where
+        /// `joins[i]` contains the block between `nodes[i]` and
+        /// `nodes[i + 1]`.
+        pub fn $name(
+            pp: &$pp_type,
+            joins: &[Trace],
+            nodes: Vec<$node_type>,
+        ) -> Result<$node_type, ProofError> {
+            if nodes.is_empty() || joins.len() + 1 != nodes.len() {
+                return Err(ProofError::InvalidSegmentLength(nodes.len()));
+            }
+
+            let mut nodes = nodes.into_iter();
+            let mut root = nodes.next().unwrap();
+            for (block, node) in joins.iter().zip(nodes) {
+                root = $join_name(pp, block, &root, &node)?;
+            }
+            Ok(root)
+        }
+    };
+}
+
+macro_rules! prove_segments_impl {
+    ( $pp_type:ty, $node_type:ty, $name:ident, $segment_name:ident, $join_name:ident ) => {
+        /// Prove all segments in order, joining each one to the node of
+        /// the segments before it as soon as it is proved. Only a single
+        /// segment is held in memory at a time.
+        pub fn $name(pp: &$pp_type, segments: Segments) -> Result<$node_type, ProofError> {
+            let mut root: Option<$node_type> = None;
+            let mut trailing = None;
+
+            for segment in segments {
+                let segment = segment?;
+                let node = $segment_name(pp, &segment)?;
+                root = Some(match root {
+                    None => node,
+                    Some(left) => {
+                        let block = segment
+                            .leading()
+                            .or(trailing)
+                            .ok_or(ProofError::SegmentMismatch(left.j as usize))?;
+                        $join_name(pp, &block, &left, &node)?
+                    }
+                });
+                trailing = segment.trailing();
+            }
+
+            root.ok_or(ProofError::InvalidSegmentLength(0))
+        }
+    };
+}
+
+prove_segment_impl!(ParPP, PCDNode, prove_segment);
+prove_segment_impl!(ComPP, ComPCDNode, prove_segment_com);
+join_impl!(ParPP, PCDNode, join);
+join_impl!(ComPP, ComPCDNode, join_com);
+join_all_impl!(ParPP, PCDNode, join_all, join);
+join_all_impl!(ComPP, ComPCDNode, join_all_com, join_com);
+prove_segments_impl!(ParPP, PCDNode, prove_segments, prove_segment, join);
+prove_segments_impl!(
+    ComPP,
+    ComPCDNode,
+    prove_segments_com,
+    prove_segment_com,
+    join_com
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::prover::nova::circuit::nop_circuit;
+    use nexus_nova::poseidon_config;
+    use nexus_vm::{machines::nop_vm, trace::trace};
+
+    #[test]
+    fn segment_layout() -> Result<(), ProofError> {
+        assert!(Segments::new(nop_vm(6), 1, 1).is_err());
+
+        for n in 1..12 {
+            let num_blocks = trace(&mut nop_vm::<MerkleTrie>(n), 1, false)?.blocks.len();
+            for len in 2..7 {
+                let segments = Segments::new(nop_vm(n), 1, len)?.collect::<Result<Vec<_>, _>>()?;
+
+                // nodes prove an odd number of blocks, and are separated
+                // by exactly one joining block
+                let mut next = 0;
+                for (i, s) in segments.iter().enumerate() {
+                    let blocks = s.blocks();
+                    assert_eq!(blocks.len() % 2, 1);
+                    assert_eq!(blocks.start, next + (i > 0) as usize);
+                    assert_eq!(s.lead, i > 0 && !segments[i - 1].trail);
+                    assert_eq!(s.trail, i + 1 < segments.len() && !segments[i + 1].lead);
+                    assert!(s.trace.blocks.len() <= len + 1);
+                    next = blocks.end;
+                }
+                assert!(next == num_blocks || next == num_blocks + 1);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prove_segments_nop() -> Result<(), ProofError> {
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params = ParPP::setup(poseidon_config(), &circuit, &(), &())?;
+
+        // 8 blocks in segments of 3: node 1 starts after a joining
+        // block and ends before another, and the last segment is padded
+        let tr = trace(&mut nop_vm::<MerkleTrie>(7), 1, false)?;
+        let segments = Segments::new(nop_vm(7), 1, 3)?.collect::<Result<Vec<_>, _>>()?;
+        let blocks = segments.iter().map(Segment::blocks).collect::<Vec<_>>();
+        assert_eq!(blocks, [0..3, 4..5, 6..9]);
+
+        let nodes = segments
+            .iter()
+            .map(|s| prove_segment(&params, s))
+            .collect::<Result<Vec<_>, _>>()?;
+        for node in &nodes {
+            node.verify(&params)?;
+        }
+
+        let joins = segments
+            .iter()
+            .zip(&segments[1..])
+            .map(|(a, b)| b.leading().or(a.trailing()).unwrap())
+            .collect::<Vec<_>>();
+
+        // segments must be joined in order
+        assert!(matches!(
+            join(&params, &joins[0], &nodes[1], &nodes[0]),
+            Err(ProofError::SegmentMismatch(_))
+        ));
+        assert!(join_all(&params, &joins, Vec::new()).is_err());
+
+        let root = join_all(&params, &joins, nodes)?;
+        assert_eq!((root.i, root.j), (0, 9));
+        assert_eq!(Some(root.z_i.clone()), tr.input(0));
+        root.verify(&params)?;
+
+        // streaming gives the same node
+        let streamed = prove_segments(&params, Segments::new(nop_vm(7), 1, 3)?)?;
+        assert_eq!((streamed.i, streamed.j), (root.i, root.j));
+        assert_eq!(streamed.z_j, root.z_j);
+        streamed.verify(&params)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 use std::fs::File;
 use zstd::stream::Decoder;
 
+use nexus_vm::precompiles::Precompiles;
+
 use crate::prover::nova::{
     error::ProofError,
-    pp::gen_vm_pp,
+    pp::gen_vm_pp_with_addr_bits,
     types::{ParPP, SpartanKey, SRS},
 };
 
@@ -20,10 +22,11 @@ pub fn load_srs(file: &str) -> Result<SRS, ProofError> {
 }
 
 /// Derive the minimum (log) size of the SRS to support compression for a
-/// given `k`.
-pub fn get_min_srs_size(k: usize) -> Result<usize, ProofError> {
+/// given `k` and selection of `precompiles`, with parameters set up in
+/// zero-knowledge mode if `zk` is set.
+pub fn get_min_srs_size(k: usize, precompiles: Precompiles, zk: bool) -> Result<usize, ProofError> {
     // these are only used to get the size of the r1cs matrices for a given k.
-    let dummy_pp: ParPP = gen_vm_pp(k, &())?;
+    let dummy_pp: ParPP = gen_vm_pp_with_addr_bits(k, None, precompiles, zk, &())?;
     let ParPP { shape, .. } = dummy_pp;
 
     Ok(SpartanKey::get_min_srs_size(&shape))
@@ -64,7 +67,7 @@ mod tests {
         let k = 1;
         println!(
             "min srs size for k = 1: {}",
-            super::get_min_srs_size(k).unwrap()
+            super::get_min_srs_size(k, Default::default(), false).unwrap()
         );
     }
 }
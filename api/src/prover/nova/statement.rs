@@ -0,0 +1,430 @@
+//! Verification against an expected statement.
+//!
+//! `IVCProof::verify` and `PCDNode::verify` only check that a proof is
+//! internally consistent. What it proves is given by its public values:
+//! the steps it covers, and the states `z` (see `Trace::input`) before
+//! the first and after the last of these steps. A `Statement` lists the
+//! values the caller expects, and its `verify_*` methods check both the
+//! proof and each expected value, reporting the first mismatch.
+//!
+//! The initial state of a program can also be given by its digest, see
+//! [`program_digest`], which lets a proof be checked without the program.
+
+use std::ops::Range;
+
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_ff::PrimeField;
+
+use nexus_vm::{
+    eval::NexusVM,
+    memory::{offline::OfflineMemory, trie::MerkleTrie},
+    VMOpts,
+};
+
+use super::{
+    error::{ProofError, StatementError},
+    types::{
+        ro_config, ComPCDNode, ComPP, ComProof, ComVerifierKey, IVCProof, OfflineSeqPP, PCDNode,
+        ParPP, SeqPP, SpartanKey, ZKComProof, ZKIVCProof, ZKPCDNode, F1, RO,
+    },
+    verify_compressed, verify_compressed_with_vk, verify_compressed_zk,
+    verify_compressed_zk_with_vk, OfflineProof,
+};
+
+/// Public values expected of a proof. Values which are not set are not
+/// checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Statement {
+    digest: Option<F1>,
+    program: Option<F1>,
+    initial_pc: Option<u32>,
+    initial_regs: Option<[u32; 32]>,
+    final_pc: Option<u32>,
+    exit_code: Option<u32>,
+    steps: Option<Range<u64>>,
+}
+
+/// Digest of the initial state `z_0` of a proof: the commitment to the
+/// memory, which contains the program, the program counter and the
+/// registers, which contain the arguments of the program.
+pub fn program_digest(z_0: &[F1]) -> F1 {
+    let mut ro = RO::new(&ro_config());
+    ro.absorb(&z_0);
+    ro.squeeze_field_elements(1)[0]
+}
+
+/// A machine state, as encoded in `z`.
+struct State {
+    pc: u32,
+    regs: [u32; 32],
+    root: F1,
+}
+
+fn to_u32(x: &F1) -> Option<u32> {
+    let b = x.into_bigint().0;
+    if b[1..].iter().any(|l| *l != 0) {
+        return None;
+    }
+    b[0].try_into().ok()
+}
+
+impl State {
+    fn decode(z: &[F1]) -> Result<Self, StatementError> {
+        let [pc, regs @ .., root] = z else {
+            return Err(StatementError::InvalidState);
+        };
+        let regs: Vec<u32> = regs
+            .iter()
+            .map(to_u32)
+            .collect::<Option<_>>()
+            .ok_or(StatementError::InvalidState)?;
+        Ok(State {
+            pc: to_u32(pc).ok_or(StatementError::InvalidState)?,
+            regs: regs.try_into().map_err(|_| StatementError::InvalidState)?,
+            root: *root,
+        })
+    }
+
+    fn encode(&self) -> Vec<F1> {
+        let mut z = vec![F1::from(self.pc)];
+        z.extend(self.regs.iter().map(|r| F1::from(*r)));
+        z.push(self.root);
+        z
+    }
+}
+
+impl Statement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect the initial state of `vm`: its memory, which contains the
+    /// program, its program counter and its registers.
+    pub fn for_vm(vm: &NexusVM<MerkleTrie>) -> Self {
+        Self::new()
+            .program(vm.mem.root())
+            .initial_pc(vm.regs.pc)
+            .initial_regs(vm.regs.x)
+    }
+
+    /// Expect the initial state of the program described by `opts`,
+    /// including its arguments.
+    pub fn for_program(opts: &VMOpts) -> Result<Self, ProofError> {
+        let vm = nexus_vm::load_vm::<MerkleTrie>(opts)?;
+        Ok(Self::for_vm(&vm))
+    }
+
+    /// Expect the initial state of `vm`, other than its memory, which
+    /// [`Self::verify_seq_offline`] checks against the audit instead.
+    pub fn for_offline_vm(vm: &NexusVM<OfflineMemory>) -> Self {
+        Self::new().initial_pc(vm.regs.pc).initial_regs(vm.regs.x)
+    }
+
+    /// Expect the initial state with the given digest, see [`program_digest`].
+    pub fn digest(mut self, digest: F1) -> Self {
+        self.digest = Some(digest);
+        self
+    }
+
+    /// Digest of the expected initial state, if it is fully known, as it
+    /// is for [`Self::for_vm`].
+    pub fn initial_digest(&self) -> Option<F1> {
+        let state = State {
+            pc: self.initial_pc?,
+            regs: self.initial_regs?,
+            root: self.program?,
+        };
+        Some(program_digest(&state.encode()))
+    }
+
+    /// Expect the given commitment to the initial memory.
+    pub fn program(mut self, commitment: F1) -> Self {
+        self.program = Some(commitment);
+        self
+    }
+
+    pub fn initial_pc(mut self, pc: u32) -> Self {
+        self.initial_pc = Some(pc);
+        self
+    }
+
+    pub fn initial_regs(mut self, regs: [u32; 32]) -> Self {
+        self.initial_regs = Some(regs);
+        self
+    }
+
+    pub fn final_pc(mut self, pc: u32) -> Self {
+        self.final_pc = Some(pc);
+        self
+    }
+
+    /// Expect the program to finish with `code` in register a0, which
+    /// holds the return value of `main`.
+    pub fn exit_code(mut self, code: u32) -> Self {
+        self.exit_code = Some(code);
+        self
+    }
+
+    /// Expect the proof to cover exactly `steps`. For a proof of a whole
+    /// trace of `n` blocks, this is `0..n`.
+    pub fn steps(mut self, steps: Range<u64>) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Check the public values of a proof of `steps`, from state `z_i`
+    /// to state `z_j`.
+    pub fn check(&self, steps: Range<u64>, z_i: &[F1], z_j: &[F1]) -> Result<(), StatementError> {
+        use StatementError::*;
+
+        if let Some(expected) = &self.steps {
+            if *expected != steps {
+                return Err(StepsMismatch { expected: expected.clone(), found: steps });
+            }
+        }
+
+        let initial = State::decode(z_i)?;
+        let last = State::decode(z_j)?;
+
+        if let Some(expected) = self.digest {
+            let found = program_digest(z_i);
+            if expected != found {
+                return Err(DigestMismatch { expected, found });
+            }
+        }
+        if let Some(expected) = self.program {
+            if expected != initial.root {
+                return Err(ProgramMismatch { expected, found: initial.root });
+            }
+        }
+        if let Some(expected) = self.initial_pc {
+            if expected != initial.pc {
+                return Err(InitialPcMismatch { expected, found: initial.pc });
+            }
+        }
+        if let Some(regs) = &self.initial_regs {
+            for (index, (expected, found)) in regs.iter().zip(&initial.regs).enumerate() {
+                if expected != found {
+                    return Err(InitialRegisterMismatch {
+                        index,
+                        expected: *expected,
+                        found: *found,
+                    });
+                }
+            }
+        }
+        if let Some(expected) = self.final_pc {
+            if expected != last.pc {
+                return Err(FinalPcMismatch { expected, found: last.pc });
+            }
+        }
+        if let Some(expected) = self.exit_code {
+            if expected != last.regs[10] {
+                return Err(ExitCodeMismatch { expected, found: last.regs[10] });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn verify_seq(&self, params: &SeqPP, proof: &IVCProof) -> Result<(), ProofError> {
+        let num_steps = proof.step_num();
+        proof.verify(params, num_steps as usize)?;
+        self.check(0..num_steps, proof.z_0(), proof.z_i())?;
+        Ok(())
+    }
+
+    /// Like [`Self::verify_seq`], for proofs using offline memory
+    /// checking. The audit of the proof is checked against `init`, the
+    /// initial memory of the program. The initial commitment of such
+    /// proofs depends on the memory accesses, so no program commitment
+    /// should be expected.
+    pub fn verify_seq_offline(
+        &self,
+        params: &OfflineSeqPP,
+        proof: &OfflineProof,
+        init: &OfflineMemory,
+    ) -> Result<(), ProofError> {
+        let OfflineProof { proof, audit } = proof;
+        let num_steps = proof.step_num();
+        proof.verify(params, num_steps as usize)?;
+        self.check(0..num_steps, proof.z_0(), proof.z_i())?;
+
+        let initial = State::decode(proof.z_0())?;
+        let last = State::decode(proof.z_i())?;
+        audit.verify(init, initial.root, last.root)?;
+        Ok(())
+    }
+
+    pub fn verify_par(&self, params: &ParPP, node: &PCDNode) -> Result<(), ProofError> {
+        node.verify(params)?;
+        self.check(node.i..node.j, &node.z_i, &node.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_seq_zk(&self, params: &SeqPP, proof: &ZKIVCProof) -> Result<(), ProofError> {
+        let num_steps = proof.step_num();
+        proof.verify(params, num_steps as usize)?;
+        self.check(0..num_steps, proof.z_0(), proof.z_i())?;
+        Ok(())
+    }
+
+    pub fn verify_par_zk(&self, params: &ParPP, node: &ZKPCDNode) -> Result<(), ProofError> {
+        node.verify(params)?;
+        self.check(node.i..node.j, &node.z_i, &node.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_par_com(&self, params: &ComPP, node: &ComPCDNode) -> Result<(), ProofError> {
+        node.verify(params)?;
+        self.check(node.i..node.j, &node.z_i, &node.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_compressed(
+        &self,
+        key: &SpartanKey,
+        params: &ComPP,
+        proof: &ComProof,
+    ) -> Result<(), ProofError> {
+        verify_compressed(key, params, proof)?;
+        self.check(proof.i..proof.j, &proof.z_i, &proof.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_compressed_with_vk(
+        &self,
+        vk: &ComVerifierKey,
+        proof: &ComProof,
+    ) -> Result<(), ProofError> {
+        verify_compressed_with_vk(vk, proof)?;
+        self.check(proof.i..proof.j, &proof.z_i, &proof.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_compressed_zk(
+        &self,
+        key: &SpartanKey,
+        params: &ComPP,
+        proof: &ZKComProof,
+    ) -> Result<(), ProofError> {
+        verify_compressed_zk(key, params, proof)?;
+        self.check(proof.i..proof.j, &proof.z_i, &proof.z_j)?;
+        Ok(())
+    }
+
+    pub fn verify_compressed_zk_with_vk(
+        &self,
+        vk: &ComVerifierKey,
+        proof: &ZKComProof,
+    ) -> Result<(), ProofError> {
+        verify_compressed_zk_with_vk(vk, proof)?;
+        self.check(proof.i..proof.j, &proof.z_i, &proof.z_j)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::prover::nova::{circuit::nop_circuit, prove_seq, prove_seq_offline};
+    use nexus_nova::poseidon_config;
+    use nexus_vm::{
+        error::NexusVMError,
+        machines::{loop_vm, nop_vm},
+        memory::offline::{trace_offline, AuditError},
+        trace::trace,
+    };
+
+    #[test]
+    fn verify_statement() -> Result<(), ProofError> {
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params = SeqPP::setup(poseidon_config(), &circuit, &(), &())?;
+
+        let mut vm = nop_vm::<MerkleTrie>(2);
+        let statement = Statement::for_vm(&vm);
+        let trace = trace(&mut vm, 1, false)?;
+        let num_steps = trace.blocks.len() as u64;
+        let proof = prove_seq(&params, trace)?;
+
+        let statement = statement.exit_code(0).steps(0..num_steps);
+        statement.verify_seq(&params, &proof)?;
+
+        // the digest stands for the program, its initial pc and registers
+        let digest = statement.initial_digest().expect("initial state is known");
+        assert_eq!(digest, program_digest(proof.z_0()));
+        Statement::new()
+            .digest(digest)
+            .verify_seq(&params, &proof)?;
+
+        let check = |s: Statement| match s.verify_seq(&params, &proof) {
+            Err(ProofError::StatementMismatch(e)) => e,
+            r => panic!("unexpected result {r:?}"),
+        };
+        assert!(matches!(
+            check(statement.clone().program(F1::from(1u64))),
+            StatementError::ProgramMismatch { .. }
+        ));
+        let other = Statement::for_vm(&nop_vm::<MerkleTrie>(3))
+            .initial_digest()
+            .unwrap();
+        assert_eq!(
+            check(Statement::new().digest(other)),
+            StatementError::DigestMismatch { expected: other, found: digest }
+        );
+        assert!(matches!(
+            check(statement.clone().initial_pc(1)),
+            StatementError::InitialPcMismatch { .. }
+        ));
+        assert!(matches!(
+            check(statement.clone().final_pc(u32::MAX)),
+            StatementError::FinalPcMismatch { .. }
+        ));
+        assert!(matches!(
+            check(statement.clone().exit_code(1)),
+            StatementError::ExitCodeMismatch { expected: 1, found: 0 }
+        ));
+        assert_eq!(
+            check(statement.steps(1..num_steps)),
+            StatementError::StepsMismatch {
+                expected: 1..num_steps,
+                found: 0..num_steps
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn verify_statement_offline() -> Result<(), ProofError> {
+        let circuit = nop_circuit::<OfflineMemory>(1)?;
+        let params = OfflineSeqPP::setup(poseidon_config(), &circuit, &(), &())?;
+
+        let mut vm = loop_vm::<OfflineMemory>(2);
+        let statement = Statement::for_offline_vm(&vm);
+        let (trace, audit) = trace_offline(&mut vm, 1, false)?;
+        let num_steps = trace.blocks.len() as u64;
+        let mut proof = prove_seq_offline(&params, trace, audit)?;
+
+        let statement = statement.exit_code(0).steps(0..num_steps);
+        let init = loop_vm::<OfflineMemory>(2).mem;
+        statement.verify_seq_offline(&params, &proof, &init)?;
+
+        let audit_error = |r| match r {
+            Err(ProofError::NexusVMError(NexusVMError::AuditFailed(e))) => e,
+            r => panic!("unexpected result {r:?}"),
+        };
+
+        // the memory accesses must start from the memory of the program
+        let other = loop_vm::<OfflineMemory>(3).mem;
+        let r = statement.verify_seq_offline(&params, &proof, &other);
+        assert!(matches!(audit_error(r), AuditError::InitialCommitment));
+
+        // and end in the audited memory
+        proof.audit.acc += F1::from(1u64);
+        let r = statement.verify_seq_offline(&params, &proof, &init);
+        assert!(matches!(audit_error(r), AuditError::FinalCommitment));
+
+        Ok(())
+    }
+}
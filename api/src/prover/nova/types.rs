@@ -18,15 +18,16 @@ pub use spartan::polycommitments::{zeromorph::Zeromorph, PolyCommitmentScheme};
 // types and traits from nexus prover
 pub use nexus_nova::{
     commitment::CommitmentScheme,
+    nova::aggregation,
     nova::pcd,
     nova::pcd::compression as com,
     nova::public_params::{PublicParams, SetupParams},
     nova::sequential as seq,
-    pedersen::PedersenCommitment,
+    pedersen::{BasesStorage, PedersenCommitment},
     r1cs::{R1CSShape, R1CSWitness},
     StepCircuit,
 };
-use nexus_vm::memory::trie::MerkleTrie;
+use nexus_vm::memory::{offline::OfflineMemory, trie::MerkleTrie};
 
 // concrete constraint system
 pub type CS = ConstraintSystemRef<F1>;
@@ -49,6 +50,9 @@ pub type SRS = <PC as PolyCommitmentScheme<P1>>::SRS;
 
 pub type SC = crate::prover::nova::circuit::Tr<MerkleTrie>;
 
+// step circuit using offline memory checking, see `nexus_vm::memory::offline`
+pub type OfflineSC = crate::prover::nova::circuit::Tr<OfflineMemory>;
+
 // concrete public parameters
 pub type PP<C, SP> = PublicParams<G1, G2, C, C2, RO, SC, SP>;
 
@@ -57,8 +61,25 @@ pub type ParPP = pcd::PublicParams<G1, G2, C1, C2, RO, SC>;
 pub type ComPP = pcd::PublicParams<G1, G2, PVC1, C2, RO, SC>;
 
 pub type SpartanKey = com::SNARKKey<P1, PC>;
+pub type ComVerifierKey = com::VerifierKey<G1, G2, PC, C2, RO>;
 
 pub type IVCProof = seq::IVCProof<G1, G2, C1, C2, RO, SC>;
+pub type OfflineSeqPP = seq::PublicParams<G1, G2, C1, C2, RO, OfflineSC>;
+pub type OfflineIVCProof = seq::IVCProof<G1, G2, C1, C2, RO, OfflineSC>;
 pub type PCDNode = pcd::PCDNode<G1, G2, C1, C2, RO, SC>;
 pub type ComPCDNode = pcd::PCDNode<G1, G2, PVC1, C2, RO, SC>;
 pub type ComProof = com::CompressedPCDProof<G1, G2, PC, C2, RO, SC>;
+pub type ZKComProof = com::ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>;
+
+pub type ZKIVCProof = seq::ZKIVCProof<G1, G2, C1, C2, RO, SC>;
+pub type ZKPCDNode = pcd::ZKPCDNode<G1, G2, C1, C2, RO, SC>;
+
+// public parameters of proofs folding other proofs, see `nexus_nova::nova::aggregation`
+pub type FoldPP<S, SP> = PublicParams<G1, G2, C1, C2, RO, S, SP>;
+
+pub type Claim = aggregation::Claim<F1>;
+pub type SeqFoldPP = aggregation::FoldParams<G1, G2, C1, C2, RO, IVCProof>;
+pub type ComFoldPP = aggregation::FoldParams<G1, G2, PVC1, C2, RO, ComPCDNode>;
+pub type SeqAggregate = aggregation::Aggregate<G1, G2, C1, C2, RO, IVCProof>;
+pub type ComAggregate = aggregation::Aggregate<G1, G2, PVC1, C2, RO, ComPCDNode>;
+pub type ComAggregateProof = com::CompressedAggregateProof<G1, G2, PC, C2, RO, SC>;
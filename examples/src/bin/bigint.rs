@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+
+use nexus_rt::{
+    bigint::{modmul, U256},
+    cycle_count, println, Write,
+};
+
+// The prime of the secp256k1 curve, 2^256 - 2^32 - 977
+const P: U256 = [
+    0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+// Computes `x^e mod m` by square-and-multiply.
+fn modpow(x: &U256, e: &U256, m: &U256) -> U256 {
+    let mut r = [1, 0, 0, 0, 0, 0, 0, 0];
+    for i in (0..256).rev() {
+        r = modmul(&r, &r, m);
+        if (e[i / 32] >> (i % 32)) & 1 == 1 {
+            r = modmul(&r, x, m);
+        }
+    }
+    r
+}
+
+// Checks Fermat's little theorem, x^(p-1) = 1 mod p, using the
+// bigint precompile.
+#[nexus_rt::main]
+fn main() {
+    let start = cycle_count();
+    let mut e = P;
+    e[0] -= 1;
+    let r = modpow(&[2, 0, 0, 0, 0, 0, 0, 0], &e, &P);
+    assert_eq!(r, [1, 0, 0, 0, 0, 0, 0, 0]);
+    println!("2^(p-1) = 1 mod p in {} cycles", cycle_count() - start);
+}
@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+use nexus_rt::{println, report_heap_stats, Write};
+
+// Repeatedly builds and drops temporary vectors and strings. With
+// the default allocator, the heap grows with every allocation; with
+// the `free-list` feature, freed memory is reused.
+#[nexus_rt::main]
+fn main() {
+    let mut total = 0;
+    for i in 0..200u32 {
+        let v: Vec<u32> = (0..i).collect();
+        let mut s = String::new();
+        for x in &v {
+            s.push((b'a' + (x % 26) as u8) as char);
+        }
+        total += s.len() as u32 + v.iter().sum::<u32>();
+    }
+    println!("total = {total}");
+
+    let stats = nexus_rt::heap_stats();
+    println!(
+        "{} allocations, {} frees, {} bytes of heap",
+        stats.allocs, stats.frees, stats.heap_size
+    );
+    report_heap_stats();
+}
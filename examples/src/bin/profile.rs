@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+use nexus_rt::{cycle_count, println, profile_end, profile_start, Write};
+
+fn fib(n: u32) -> u32 {
+    match n {
+        0 => 1,
+        1 => 1,
+        _ => fib(n - 1) + fib(n - 2),
+    }
+}
+
+fn fib_iter(n: u32) -> u32 {
+    let (mut a, mut b) = (1, 1);
+    for _ in 0..n {
+        (a, b) = (b, a + b);
+    }
+    a
+}
+
+// Compares the cost of two implementations; the host prints a
+// report of the profiled regions at the end of execution.
+#[nexus_rt::main]
+fn main() {
+    let start = cycle_count();
+    for n in 0..15 {
+        profile_start!("fib");
+        let x = fib(n);
+        profile_end!("fib");
+
+        profile_start!("fib_iter");
+        let y = fib_iter(n);
+        profile_end!("fib_iter");
+
+        assert_eq!(x, y);
+    }
+    println!("{} cycles", cycle_count() - start);
+}
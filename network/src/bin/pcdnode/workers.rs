@@ -154,9 +154,14 @@ fn prove_leaf(
         ?i,
         "proving leaf",
     );
-    let node = PCDNode::prove_leaf_with_commit_fn(&st.pp, &tr, i, &tr.input(i)?, |_pp, w| {
-        request_msm(rt, st, w)
-    })?;
+    let node = PCDNode::prove_leaf_with_commit_fn(
+        &st.pp,
+        &tr,
+        i,
+        &tr.input(i)?,
+        &mut ark_std::rand::thread_rng(),
+        |_pp, w| request_msm(rt, st, w),
+    )?;
     Ok(node)
 }
 
@@ -168,8 +173,14 @@ fn prove_node(
     r: PCDNode,
 ) -> std::result::Result<PCDNode, ProofError> {
     let tr = Tr(trace);
-    let node =
-        PCDNode::prove_parent_with_commit_fn(&st.pp, &tr, &l, &r, |_pp, w| request_msm(rt, st, w))?;
+    let node = PCDNode::prove_parent_with_commit_fn(
+        &st.pp,
+        &tr,
+        &l,
+        &r,
+        &mut ark_std::rand::thread_rng(),
+        |_pp, w| request_msm(rt, st, w),
+    )?;
     Ok(node)
 }
 
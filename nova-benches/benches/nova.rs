@@ -70,7 +70,9 @@ fn bench_recursive_snark(c: &mut Criterion) {
             IVCProof::new(&[CF::from(2u64)]);
 
         for i in 0..NUM_WARMUP_STEPS {
-            recursive_snark = recursive_snark.prove_step(&pp, &step_circuit).unwrap();
+            recursive_snark = recursive_snark
+                .prove_step(&pp, &step_circuit, &mut ark_std::test_rng())
+                .unwrap();
 
             // verify the recursive snark at each step of recursion
             let res = recursive_snark.verify(&pp, i + 1);
@@ -81,7 +83,11 @@ fn bench_recursive_snark(c: &mut Criterion) {
             b.iter(|| {
                 // produce a recursive SNARK for a step of the recursion
                 black_box(recursive_snark.clone())
-                    .prove_step(black_box(&pp), black_box(&step_circuit))
+                    .prove_step(
+                        black_box(&pp),
+                        black_box(&step_circuit),
+                        &mut ark_std::test_rng(),
+                    )
                     .unwrap();
             })
         });
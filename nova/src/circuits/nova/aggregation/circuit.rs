@@ -0,0 +1,427 @@
+use std::{borrow::Borrow, marker::PhantomData};
+
+use ark_crypto_primitives::sponge::{
+    constraints::{CryptographicSpongeVar, SpongeWithGadget},
+    Absorb,
+};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    groups::curves::short_weierstrass::ProjectiveVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::Zero;
+
+use super::{Aggregable, Claim};
+use crate::{
+    circuits::nova::{
+        pcd::augmented::{AUGMENTED_CIRCUIT_NUM_IO, SQUEEZE_NATIVE_ELEMENTS_NUM},
+        StepCircuit,
+    },
+    commitment::CommitmentScheme,
+    folding::nova::cyclefold::{
+        self,
+        nimfs::{NIMFSProof, R1CSInstance, R1CSShape, RelaxedR1CSInstance},
+    },
+    gadgets::cyclefold::{
+        nova::{multifold, multifold_with_relaxed, primary},
+        secondary,
+    },
+    gadgets::nonnative::short_weierstrass::NonNativeAffineVar,
+};
+
+/// Input to a single step of the [`FoldCircuit`]: a member of the aggregate, and the
+/// running instances of the members before it.
+pub struct FoldInput<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    pub claim: Claim<G1::ScalarField>,
+
+    pub U: RelaxedR1CSInstance<G1, C1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub u: R1CSInstance<G1, C1>,
+    /// Proof of folding `u` into `U`.
+    pub proof: NIMFSProof<G1, G2, C1, C2, RO>,
+
+    pub acc: RelaxedR1CSInstance<G1, C1>,
+    pub acc_secondary: RelaxedR1CSInstance<G2, C2>,
+    /// Proof of folding the result of `proof` into `acc`.
+    pub acc_proof: NIMFSProof<G1, G2, C1, C2, RO>,
+}
+
+impl<G1, G2, C1, C2, RO> FoldInput<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    /// Input used to synthesize the circuit when no member is given, as on setup.
+    fn dummy(arity: usize) -> Result<Self, SynthesisError> {
+        let shape = R1CSShape::<G1>::new(0, 0, AUGMENTED_CIRCUIT_NUM_IO, &[], &[], &[]).unwrap();
+        let shape_secondary = cyclefold::secondary::setup_shape::<G1, G2>()?;
+
+        let U = RelaxedR1CSInstance::<G1, C1>::new(&shape);
+        let U_secondary = RelaxedR1CSInstance::<G2, C2>::new(&shape_secondary);
+        let u = R1CSInstance::<G1, C1>::new(
+            &shape,
+            &(Projective::zero().into()),
+            &[G1::ScalarField::ONE; AUGMENTED_CIRCUIT_NUM_IO],
+        )
+        .unwrap();
+
+        Ok(Self {
+            claim: Claim {
+                i: 0,
+                j: 0,
+                z_i: vec![G1::ScalarField::ZERO; arity],
+                z_j: vec![G1::ScalarField::ZERO; arity],
+            },
+            U: U.clone(),
+            U_secondary: U_secondary.clone(),
+            u,
+            proof: NIMFSProof::default(),
+            acc: U,
+            acc_secondary: U_secondary,
+            acc_proof: NIMFSProof::default(),
+        })
+    }
+}
+
+#[must_use]
+struct FoldInputVar<G1, G2, C1, C2>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    G1::BaseField: PrimeField,
+    G2::BaseField: PrimeField,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    i: FpVar<G1::ScalarField>,
+    j: FpVar<G1::ScalarField>,
+    z_i: Vec<FpVar<G1::ScalarField>>,
+    z_j: Vec<FpVar<G1::ScalarField>>,
+
+    U: primary::RelaxedR1CSInstanceVar<G1, C1>,
+    U_secondary: secondary::RelaxedR1CSInstanceVar<G2, C2>,
+    u: primary::R1CSInstanceVar<G1, C1>,
+    commitment_T: NonNativeAffineVar<G1>,
+    proof_secondary: (secondary::ProofVar<G2, C2>, secondary::ProofVar<G2, C2>),
+
+    acc: primary::RelaxedR1CSInstanceVar<G1, C1>,
+    acc_secondary: secondary::RelaxedR1CSInstanceVar<G2, C2>,
+    acc_commitment_T: NonNativeAffineVar<G1>,
+    acc_commitment_T_secondary: ProjectiveVar<G2, FpVar<G2::BaseField>>,
+    acc_proof_secondary: (
+        [secondary::ProofVar<G2, C2>; 2],
+        secondary::ProofVar<G2, C2>,
+    ),
+}
+
+impl<G1, G2, C1, C2, RO> AllocVar<FoldInput<G1, G2, C1, C2, RO>, G1::ScalarField>
+    for FoldInputVar<G1, G2, C1, C2>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField,
+    G2::BaseField: PrimeField,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    fn new_variable<T: Borrow<FoldInput<G1, G2, C1, C2, RO>>>(
+        cs: impl Into<Namespace<G1::ScalarField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        let input = f()?;
+        let input = input.borrow();
+
+        let i = FpVar::new_variable(
+            cs.clone(),
+            || Ok(G1::ScalarField::from(input.claim.i)),
+            mode,
+        )?;
+        let j = FpVar::new_variable(
+            cs.clone(),
+            || Ok(G1::ScalarField::from(input.claim.j)),
+            mode,
+        )?;
+        let z_i = input
+            .claim
+            .z_i
+            .iter()
+            .map(|z| FpVar::new_variable(cs.clone(), || Ok(z), mode))
+            .collect::<Result<_, _>>()?;
+        let z_j = input
+            .claim
+            .z_j
+            .iter()
+            .map(|z| FpVar::new_variable(cs.clone(), || Ok(z), mode))
+            .collect::<Result<_, _>>()?;
+
+        let U = primary::RelaxedR1CSInstanceVar::new_variable(cs.clone(), || Ok(&input.U), mode)?;
+        let U_secondary = secondary::RelaxedR1CSInstanceVar::new_variable(
+            cs.clone(),
+            || Ok(&input.U_secondary),
+            mode,
+        )?;
+        let u = primary::R1CSInstanceVar::new_variable(cs.clone(), || Ok(&input.u), mode)?;
+
+        let commitment_T_point = input.proof.commitment_T.into();
+        let commitment_T =
+            NonNativeAffineVar::new_variable(cs.clone(), || Ok(&commitment_T_point), mode)?;
+        let proof_secondary = (
+            secondary::ProofVar::new_variable(
+                cs.clone(),
+                || Ok(&input.proof.commitment_E_proof[0]),
+                mode,
+            )?,
+            secondary::ProofVar::new_variable(
+                cs.clone(),
+                || Ok(&input.proof.commitment_W_proof),
+                mode,
+            )?,
+        );
+
+        let acc =
+            primary::RelaxedR1CSInstanceVar::new_variable(cs.clone(), || Ok(&input.acc), mode)?;
+        let acc_secondary = secondary::RelaxedR1CSInstanceVar::new_variable(
+            cs.clone(),
+            || Ok(&input.acc_secondary),
+            mode,
+        )?;
+
+        let acc_commitment_T_point = input.acc_proof.commitment_T.into();
+        let acc_commitment_T =
+            NonNativeAffineVar::new_variable(cs.clone(), || Ok(&acc_commitment_T_point), mode)?;
+        let acc_commitment_T_secondary = <ProjectiveVar<G2, FpVar<G2::BaseField>> as AllocVar<
+            Projective<G2>,
+            G2::BaseField,
+        >>::new_variable(
+            cs.clone(),
+            || Ok(input.acc_proof.proof_secondary.commitment_T.into()),
+            mode,
+        )?;
+        let acc_proof_secondary = (
+            [
+                secondary::ProofVar::new_variable(
+                    cs.clone(),
+                    || Ok(&input.acc_proof.commitment_E_proof[0]),
+                    mode,
+                )?,
+                secondary::ProofVar::new_variable(
+                    cs.clone(),
+                    || Ok(&input.acc_proof.commitment_E_proof[1]),
+                    mode,
+                )?,
+            ],
+            secondary::ProofVar::new_variable(
+                cs.clone(),
+                || Ok(&input.acc_proof.commitment_W_proof),
+                mode,
+            )?,
+        );
+
+        Ok(Self {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            U_secondary,
+            u,
+            commitment_T,
+            proof_secondary,
+            acc,
+            acc_secondary,
+            acc_commitment_T,
+            acc_commitment_T_secondary,
+            acc_proof_secondary,
+        })
+    }
+}
+
+/// Step circuit folding one member of an aggregate into the running instances of the
+/// members before it.
+///
+/// The state is `[vk, claims, acc]`, where `vk` is the digest of public parameters of the
+/// members, `claims` is a hash chain of their [`Claim`]s starting from zero, and `acc` is
+/// the hash of the running instances. Every
+/// step checks that the instance of the member commits to its claim, and folds it into
+/// the running instances.
+pub struct FoldCircuit<G1, G2, C1, C2, RO, M>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField>,
+{
+    ro_config: RO::Config,
+    input: Option<FoldInput<G1, G2, C1, C2, RO>>,
+    _member: PhantomData<fn() -> M>,
+}
+
+impl<G1, G2, C1, C2, RO, M> FoldCircuit<G1, G2, C1, C2, RO, M>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField>,
+    RO::Config: Clone,
+{
+    /// Circuit without a member, only used to set up public parameters.
+    pub fn setup(ro_config: &RO::Config) -> Self {
+        Self {
+            ro_config: ro_config.clone(),
+            input: None,
+            _member: PhantomData,
+        }
+    }
+
+    pub fn new(ro_config: &RO::Config, input: FoldInput<G1, G2, C1, C2, RO>) -> Self {
+        Self {
+            ro_config: ro_config.clone(),
+            input: Some(input),
+            _member: PhantomData,
+        }
+    }
+}
+
+impl<G1, G2, C1, C2, RO, M> StepCircuit<G1::ScalarField> for FoldCircuit<G1, G2, C1, C2, RO, M>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    M: Aggregable<G1, G2, C1, C2, RO>,
+{
+    const ARITY: usize = 3;
+
+    fn generate_constraints(
+        &self,
+        cs: ConstraintSystemRef<G1::ScalarField>,
+        _: &FpVar<G1::ScalarField>,
+        z: &[FpVar<G1::ScalarField>],
+    ) -> Result<Vec<FpVar<G1::ScalarField>>, SynthesisError> {
+        let dummy;
+        let input = match &self.input {
+            Some(input) => input,
+            None => {
+                dummy = FoldInput::<G1, G2, C1, C2, RO>::dummy(
+                    <M::StepCircuit as StepCircuit<G1::ScalarField>>::ARITY,
+                )?;
+                &dummy
+            }
+        };
+        let input = FoldInputVar::<G1, G2, C1, C2>::new_witness(cs.clone(), || Ok(input))?;
+
+        let (vk, claims, acc) = (&z[0], &z[1], &z[2]);
+
+        // check running instances
+        let acc_hash = accumulator_hash::<G1, G2, C1, C2, RO>(
+            cs.clone(),
+            &self.ro_config,
+            &input.acc,
+            &input.acc_secondary,
+        )?;
+        acc_hash.enforce_equal(acc)?;
+
+        // check that the member commits to its claim
+        let mut random_oracle = RO::Var::new(cs.clone(), &self.ro_config);
+        random_oracle.absorb(vk)?;
+        if M::SEQUENTIAL {
+            // IVC proofs always start from step 0, and only commit to the number of steps.
+            input.i.enforce_equal(&FpVar::zero())?;
+        } else {
+            random_oracle.absorb(&input.i)?;
+        }
+        random_oracle.absorb(&input.j)?;
+        random_oracle.absorb(&input.z_i)?;
+        random_oracle.absorb(&input.z_j)?;
+        random_oracle.absorb(&input.U)?;
+        random_oracle.absorb(&input.U_secondary)?;
+        let hash = &random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)?[0];
+        hash.enforce_equal(&input.u.X[1])?;
+
+        let should_enforce = Boolean::TRUE;
+        let (U, U_secondary) = multifold::<G1, G2, C1, C2, RO>(
+            &self.ro_config,
+            vk,
+            &input.U,
+            &input.U_secondary,
+            &input.u,
+            &input.commitment_T,
+            (&input.proof_secondary.0, &input.proof_secondary.1),
+            &should_enforce,
+        )?;
+        let (acc, acc_secondary) = multifold_with_relaxed::<G1, G2, C1, C2, RO>(
+            &self.ro_config,
+            vk,
+            &input.acc,
+            &input.acc_secondary,
+            &U,
+            &U_secondary,
+            &input.acc_commitment_T,
+            &input.acc_commitment_T_secondary,
+            (&input.acc_proof_secondary.0, &input.acc_proof_secondary.1),
+            &should_enforce,
+        )?;
+
+        let mut random_oracle = RO::Var::new(cs.clone(), &self.ro_config);
+        random_oracle.absorb(claims)?;
+        random_oracle.absorb(&input.i)?;
+        random_oracle.absorb(&input.j)?;
+        random_oracle.absorb(&input.z_i)?;
+        random_oracle.absorb(&input.z_j)?;
+        let claims_next =
+            random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)?[0].clone();
+
+        let acc_next =
+            accumulator_hash::<G1, G2, C1, C2, RO>(cs, &self.ro_config, &acc, &acc_secondary)?;
+
+        Ok(vec![vk.clone(), claims_next, acc_next])
+    }
+}
+
+fn accumulator_hash<G1, G2, C1, C2, RO>(
+    cs: ConstraintSystemRef<G1::ScalarField>,
+    ro_config: &RO::Config,
+    U: &primary::RelaxedR1CSInstanceVar<G1, C1>,
+    U_secondary: &secondary::RelaxedR1CSInstanceVar<G2, C2>,
+) -> Result<FpVar<G1::ScalarField>, SynthesisError>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField,
+    G2::BaseField: PrimeField,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField>,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+{
+    let mut random_oracle = RO::Var::new(cs, ro_config);
+    random_oracle.absorb(U)?;
+    random_oracle.absorb(U_secondary)?;
+    Ok(random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)?[0].clone())
+}
@@ -0,0 +1,575 @@
+//! Aggregation of proofs of independent computations.
+//!
+//! [IVC proofs](super::sequential::IVCProof) or [PCD nodes](super::pcd::PCDNode) which share
+//! public parameters, but may prove unrelated programs from unrelated inputs, are combined
+//! into a single running instance. Each member's instance `u` is first folded into its own
+//! running instance, as in [compression](super::pcd::compression), and the result is folded
+//! into the running instance of the members before it.
+//!
+//! Folding happens in the [`FoldCircuit`], which is the step circuit of a second, sequential
+//! IVC proof with one step per member. Its state commits to the digest of the parameters of
+//! the members, to the list of their [`Claim`]s, and to the running instances. An
+//! [`Aggregate`] is therefore made of the claims, one running instance with its witness --
+//! which can be replaced by a single Spartan proof -- and one IVC proof, all of which but
+//! the list of claims have a size independent of the number of members.
+
+use ark_crypto_primitives::sponge::{
+    constraints::{CryptographicSpongeVar, SpongeWithGadget},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::{AdditiveGroup, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, RngCore};
+
+use super::{
+    pcd::{self, augmented::SQUEEZE_NATIVE_ELEMENTS_NUM, PCDNode},
+    public_params::{self, PublicParams},
+    sequential::{self, IVCProof},
+    StepCircuit,
+};
+use crate::{
+    absorb::CryptographicSpongeExt,
+    commitment::CommitmentScheme,
+    folding::nova::cyclefold::{
+        self,
+        nimfs::{NIMFSProof, R1CSInstance, R1CSWitness, RelaxedR1CSInstance, RelaxedR1CSWitness},
+    },
+    pedersen::PedersenCommitment,
+};
+
+mod circuit;
+
+pub use circuit::{FoldCircuit, FoldInput};
+
+const LOG_TARGET: &str = "nexus-nova::nova::aggregation";
+
+/// Statement proved by a member of an aggregate: applying steps `i` up to `j` of the
+/// step circuit to `z_i` results in `z_j`. For IVC proofs, `i` is always 0.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Claim<F: PrimeField> {
+    pub i: u64,
+    pub j: u64,
+    pub z_i: Vec<F>,
+    pub z_j: Vec<F>,
+}
+
+/// Claim and instances of a proof, as folded into an aggregate.
+pub struct Member<'a, G1, G2, C1, C2>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    pub claim: Claim<G1::ScalarField>,
+
+    pub U: &'a RelaxedR1CSInstance<G1, C1>,
+    pub W: &'a RelaxedR1CSWitness<G1>,
+    pub U_secondary: &'a RelaxedR1CSInstance<G2, C2>,
+    pub W_secondary: &'a RelaxedR1CSWitness<G2>,
+
+    pub u: &'a R1CSInstance<G1, C1>,
+    pub w: &'a R1CSWitness<G1>,
+}
+
+/// Proofs which can be aggregated.
+pub trait Aggregable<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+{
+    type StepCircuit: StepCircuit<G1::ScalarField>;
+    type SetupParams: public_params::SetupParams<G1, G2, C1, C2, RO, Self::StepCircuit>;
+
+    /// Whether instances commit to the number of steps only, as for IVC proofs, rather
+    /// than to the range of steps.
+    const SEQUENTIAL: bool;
+
+    fn member(&self) -> Result<Member<'_, G1, G2, C1, C2>, cyclefold::Error>;
+}
+
+impl<G1, G2, C1, C2, RO, SC> Aggregable<G1, G2, C1, C2, RO> for PCDNode<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    type StepCircuit = SC;
+    type SetupParams = pcd::SetupParams<(G1, G2, C1, C2, RO, SC)>;
+
+    const SEQUENTIAL: bool = false;
+
+    fn member(&self) -> Result<Member<'_, G1, G2, C1, C2>, cyclefold::Error> {
+        Ok(Member {
+            claim: Claim {
+                i: self.i,
+                j: self.j,
+                z_i: self.z_i.clone(),
+                z_j: self.z_j.clone(),
+            },
+            U: &self.U,
+            W: &self.W,
+            U_secondary: &self.U_secondary,
+            W_secondary: &self.W_secondary,
+            u: &self.u,
+            w: &self.w,
+        })
+    }
+}
+
+impl<G1, G2, C1, C2, RO, SC> Aggregable<G1, G2, C1, C2, RO> for IVCProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    type StepCircuit = SC;
+    type SetupParams = sequential::SetupParams<(G1, G2, C1, C2, RO, SC)>;
+
+    const SEQUENTIAL: bool = true;
+
+    /// Fails for a proof of 0 steps, which has no instances.
+    fn member(&self) -> Result<Member<'_, G1, G2, C1, C2>, cyclefold::Error> {
+        let non_base = self
+            .non_base
+            .as_ref()
+            .ok_or(cyclefold::Error::InvalidPublicInput)?;
+        Ok(Member {
+            claim: Claim {
+                i: 0,
+                j: non_base.i,
+                z_i: self.z_0.clone(),
+                z_j: non_base.z_i.clone(),
+            },
+            U: &non_base.U,
+            W: &non_base.W,
+            U_secondary: &non_base.U_secondary,
+            W_secondary: &non_base.W_secondary,
+            u: &non_base.u,
+            w: &non_base.w,
+        })
+    }
+}
+
+/// Public parameters of the IVC proof folding the members of an aggregate.
+///
+/// Only the primary commitment scheme differs from the parameters of the members: the
+/// fold circuit is committed to with Pedersen commitments, so that no additional setup is
+/// needed for it.
+pub type FoldParams<G1, G2, C1, C2, RO, M> = sequential::PublicParams<
+    G1,
+    G2,
+    PedersenCommitment<Projective<G1>>,
+    C2,
+    RO,
+    FoldCircuit<G1, G2, C1, C2, RO, M>,
+>;
+
+/// IVC proof folding the members of an aggregate, see [`FoldCircuit`].
+pub type FoldProof<G1, G2, C1, C2, RO, M> = IVCProof<
+    G1,
+    G2,
+    PedersenCommitment<Projective<G1>>,
+    C2,
+    RO,
+    FoldCircuit<G1, G2, C1, C2, RO, M>,
+>;
+
+/// Running instances of a number of proofs, folded into one.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct Aggregate<G1, G2, C1, C2, RO, M>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    M: Aggregable<G1, G2, C1, C2, RO>,
+{
+    /// Statements of the aggregated proofs, in order.
+    pub claims: Vec<Claim<G1::ScalarField>>,
+
+    pub U: RelaxedR1CSInstance<G1, C1>,
+    pub W: RelaxedR1CSWitness<G1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub W_secondary: RelaxedR1CSWitness<G2>,
+
+    /// Proof that `U` and `U_secondary` result from folding proofs of `claims`.
+    pub proof: FoldProof<G1, G2, C1, C2, RO, M>,
+}
+
+impl<G1, G2, C1, C2, RO, M> Aggregate<G1, G2, C1, C2, RO, M>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    M: Aggregable<G1, G2, C1, C2, RO>,
+{
+    /// Set up parameters for folding proofs. These only depend on the arity of the step
+    /// circuit of the members, and whether they are IVC proofs or PCD nodes.
+    pub fn setup(
+        ro_config: RO::Config,
+        aux_secondary: &C2::SetupAux,
+    ) -> Result<FoldParams<G1, G2, C1, C2, RO, M>, cyclefold::Error> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "setup").entered();
+
+        let circuit = FoldCircuit::setup(&ro_config);
+        FoldParams::setup(ro_config, &circuit, &(), aux_secondary)
+    }
+
+    /// Aggregate `members`, which should have been verified by the caller:
+    /// an invalid member results in an aggregate which fails verification.
+    ///
+    /// `rng` is passed to [`IVCProof::prove_step`] for each fold step.
+    pub fn new(
+        params: &PublicParams<G1, G2, C1, C2, RO, M::StepCircuit, M::SetupParams>,
+        fold_params: &FoldParams<G1, G2, C1, C2, RO, M>,
+        members: &[M],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<Self, cyclefold::Error> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "aggregate", members = members.len())
+            .entered();
+
+        if members.is_empty() {
+            return Err(cyclefold::Error::InvalidPublicInput);
+        }
+
+        let mut U = RelaxedR1CSInstance::<G1, C1>::new(&params.shape);
+        let mut W = RelaxedR1CSWitness::<G1>::zero(&params.shape);
+        let mut U_secondary = RelaxedR1CSInstance::<G2, C2>::new(&params.shape_secondary);
+        let mut W_secondary = RelaxedR1CSWitness::<G2>::zero(&params.shape_secondary);
+
+        let z_0 = [
+            params.digest,
+            G1::ScalarField::ZERO,
+            accumulator_hash::<G1, G2, C1, C2, RO>(&params.ro_config, &U, &U_secondary),
+        ];
+        let mut proof = FoldProof::<G1, G2, C1, C2, RO, M>::new(&z_0);
+        let mut claims = Vec::with_capacity(members.len());
+
+        for member in members {
+            let member = member.member()?;
+
+            let (member_proof, (U_member, W_member), (U_member_secondary, W_member_secondary)) =
+                NIMFSProof::prove(
+                    &params.pp,
+                    &params.pp_secondary,
+                    &params.ro_config,
+                    &params.digest,
+                    (&params.shape, &params.shape_secondary),
+                    (member.U, member.W),
+                    (member.U_secondary, member.W_secondary),
+                    (member.u, member.w),
+                )?;
+            let (acc_proof, (U_next, W_next), (U_next_secondary, W_next_secondary)) =
+                NIMFSProof::prove_with_relaxed(
+                    &params.pp,
+                    &params.pp_secondary,
+                    &params.ro_config,
+                    &params.digest,
+                    (&params.shape, &params.shape_secondary),
+                    (&U, &W),
+                    (&U_secondary, &W_secondary),
+                    (&U_member, &W_member),
+                    (&U_member_secondary, &W_member_secondary),
+                )?;
+
+            let input = FoldInput {
+                claim: member.claim.clone(),
+                U: member.U.clone(),
+                U_secondary: member.U_secondary.clone(),
+                u: member.u.clone(),
+                proof: member_proof,
+                acc: U,
+                acc_secondary: U_secondary,
+                acc_proof,
+            };
+            let circuit = FoldCircuit::new(&fold_params.ro_config, input);
+            proof = proof.prove_step(fold_params, &circuit, rng)?;
+
+            claims.push(member.claim);
+            (U, W, U_secondary, W_secondary) = (U_next, W_next, U_next_secondary, W_next_secondary);
+        }
+
+        Ok(Self {
+            claims,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            proof,
+        })
+    }
+
+    pub fn verify(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, M::StepCircuit, M::SetupParams>,
+        fold_params: &FoldParams<G1, G2, C1, C2, RO, M>,
+    ) -> Result<(), cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "verify_aggregate",
+            members = self.claims.len(),
+        )
+        .entered();
+
+        verify_fold(
+            params,
+            fold_params,
+            &self.claims,
+            (&self.U, &self.U_secondary),
+            &self.proof,
+        )?;
+
+        params
+            .shape
+            .is_relaxed_satisfied(&self.U, &self.W, &params.pp)?;
+        params.shape_secondary.is_relaxed_satisfied(
+            &self.U_secondary,
+            &self.W_secondary,
+            &params.pp_secondary,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Check that `U` and `U_secondary` result from folding proofs of `claims`, as
+/// shown by `proof`.
+///
+/// It remains to check that the running instances are satisfied.
+pub(crate) fn verify_fold<G1, G2, C1, C2, RO, M>(
+    params: &PublicParams<G1, G2, C1, C2, RO, M::StepCircuit, M::SetupParams>,
+    fold_params: &FoldParams<G1, G2, C1, C2, RO, M>,
+    claims: &[Claim<G1::ScalarField>],
+    (U, U_secondary): (&RelaxedR1CSInstance<G1, C1>, &RelaxedR1CSInstance<G2, C2>),
+    proof: &FoldProof<G1, G2, C1, C2, RO, M>,
+) -> Result<(), cyclefold::Error>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    M: Aggregable<G1, G2, C1, C2, RO>,
+{
+    const INVALID_PUBLIC_INPUT: cyclefold::Error = cyclefold::Error::InvalidPublicInput;
+
+    proof.verify(fold_params, claims.len())?;
+
+    let z_0 = [
+        params.digest,
+        G1::ScalarField::ZERO,
+        accumulator_hash::<G1, G2, C1, C2, RO>(
+            &params.ro_config,
+            &RelaxedR1CSInstance::new(&params.shape),
+            &RelaxedR1CSInstance::new(&params.shape_secondary),
+        ),
+    ];
+    if proof.z_0() != z_0 {
+        return Err(INVALID_PUBLIC_INPUT);
+    }
+
+    let digest = claims.iter().fold(G1::ScalarField::ZERO, |digest, claim| {
+        claims_digest::<G1::ScalarField, RO>(&params.ro_config, &digest, claim)
+    });
+    let z_n = [
+        params.digest,
+        digest,
+        accumulator_hash::<G1, G2, C1, C2, RO>(&params.ro_config, U, U_secondary),
+    ];
+    if proof.z_i() != z_n {
+        return Err(INVALID_PUBLIC_INPUT);
+    }
+
+    Ok(())
+}
+
+/// Hash of running instances, as in the state of the [`FoldCircuit`].
+fn accumulator_hash<G1, G2, C1, C2, RO>(
+    ro_config: &RO::Config,
+    U: &RelaxedR1CSInstance<G1, C1>,
+    U_secondary: &RelaxedR1CSInstance<G2, C2>,
+) -> G1::ScalarField
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge,
+{
+    let mut random_oracle = RO::new(ro_config);
+    random_oracle.absorb(U);
+    random_oracle.absorb_non_native(U_secondary);
+    random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)[0]
+}
+
+/// Extend the hash chain of claims `digest` with `claim`, as in the state of the
+/// [`FoldCircuit`]. The chain of no claims is zero.
+fn claims_digest<F, RO>(ro_config: &RO::Config, digest: &F, claim: &Claim<F>) -> F
+where
+    F: PrimeField + Absorb,
+    RO: CryptographicSponge,
+{
+    let mut random_oracle = RO::new(ro_config);
+    random_oracle.absorb(digest);
+    random_oracle.absorb(&F::from(claim.i));
+    random_oracle.absorb(&F::from(claim.j));
+    random_oracle.absorb(&claim.z_i);
+    random_oracle.absorb(&claim.z_j);
+    random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits::nova::sequential::tests::CubicCircuit, poseidon_config};
+
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_ff::Field;
+    use ark_std::test_rng;
+
+    type G1 = ark_pallas::PallasConfig;
+    type G2 = ark_vesta::VestaConfig;
+    type C1 = PedersenCommitment<ark_pallas::Projective>;
+    type C2 = PedersenCommitment<ark_vesta::Projective>;
+    type F = ark_pallas::Fr;
+    type RO = PoseidonSponge<F>;
+    type SC = CubicCircuit<F>;
+
+    type Node = PCDNode<G1, G2, C1, C2, RO, SC>;
+    type Proof = IVCProof<G1, G2, C1, C2, RO, SC>;
+
+    #[test]
+    fn aggregate_nodes() -> Result<(), cyclefold::Error> {
+        let circuit = SC::default();
+        let params = pcd::PublicParams::<G1, G2, C1, C2, RO, SC>::setup(
+            poseidon_config(),
+            &circuit,
+            &(),
+            &(),
+        )?;
+        let fold_params = Aggregate::<G1, G2, C1, C2, RO, Node>::setup(poseidon_config(), &())?;
+
+        let mut rng = test_rng();
+
+        // unrelated computations, proved with the same parameters
+        let z = [F::ONE, F::from(2)];
+        let leaf = Node::prove_leaf(&params, &circuit, 0, &[z[0]], &mut rng)?;
+        let left = Node::prove_leaf(&params, &circuit, 0, &[z[1]], &mut rng)?;
+        // 2 -> 15 -> 3395
+        let right = Node::prove_leaf(&params, &circuit, 2, &[F::from(3395)], &mut rng)?;
+        let mut nodes = vec![
+            leaf,
+            Node::prove_parent(&params, &circuit, &left, &right, &mut rng)?,
+        ];
+        for node in &nodes {
+            node.verify(&params)?;
+        }
+
+        let aggregate = Aggregate::new(&params, &fold_params, &nodes, &mut rng)?;
+        aggregate.verify(&params, &fold_params)?;
+
+        let claims = &aggregate.claims;
+        assert_eq!(claims.len(), 2);
+        assert_eq!((claims[1].i, claims[1].j), (0, 3));
+        assert_eq!(claims[1].z_i, vec![z[1]]);
+        assert_eq!(&claims[1].z_j, &nodes[1].z_j);
+
+        // only the list of claims grows with the number of nodes
+        let single = Aggregate::new(&params, &fold_params, &nodes[..1], &mut rng)?;
+        single.verify(&params, &fold_params)?;
+        assert_eq!(
+            single.compressed_size() - single.claims.compressed_size(),
+            aggregate.compressed_size() - aggregate.claims.compressed_size(),
+        );
+
+        // a claim cannot be changed after aggregation
+        let mut aggregate = aggregate;
+        aggregate.claims[0].z_j = vec![F::ZERO];
+        assert!(aggregate.verify(&params, &fold_params).is_err());
+
+        // nor can a node be aggregated with a false claim
+        nodes[0].z_j = vec![F::ZERO];
+        let aggregate = Aggregate::new(&params, &fold_params, &nodes, &mut rng)?;
+        assert!(aggregate.verify(&params, &fold_params).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_ivc_proofs() -> Result<(), cyclefold::Error> {
+        let circuit = SC::default();
+        let params = sequential::PublicParams::<G1, G2, C1, C2, RO, SC>::setup(
+            poseidon_config(),
+            &circuit,
+            &(),
+            &(),
+        )?;
+        let fold_params = Aggregate::<G1, G2, C1, C2, RO, Proof>::setup(poseidon_config(), &())?;
+
+        let mut rng = test_rng();
+        let first = Proof::new(&[F::ONE]).prove_step(&params, &circuit, &mut rng)?;
+        let second = Proof::new(&[F::from(2)])
+            .prove_step(&params, &circuit, &mut rng)?
+            .prove_step(&params, &circuit, &mut rng)?;
+        first.verify(&params, 1)?;
+        second.verify(&params, 2)?;
+
+        // proofs of no steps cannot be aggregated
+        let empty = Proof::new(&[F::ONE]);
+        assert!(Aggregate::new(&params, &fold_params, &[empty], &mut rng).is_err());
+
+        let proofs = [first, second];
+        let aggregate = Aggregate::new(&params, &fold_params, &proofs, &mut rng)?;
+        aggregate.verify(&params, &fold_params)?;
+
+        let claims = &aggregate.claims;
+        assert_eq!((claims[0].i, claims[0].j), (0, 1));
+        assert_eq!(claims[0].z_j, vec![F::from(7)]);
+        assert_eq!((claims[1].i, claims[1].j), (0, 2));
+        assert_eq!(claims[1].z_i, vec![F::from(2)]);
+        assert_eq!(claims[1].z_j, vec![F::from(3395)]);
+
+        let mut aggregate = aggregate;
+        aggregate.claims[1].j = 1;
+        assert!(aggregate.verify(&params, &fold_params).is_err());
+
+        Ok(())
+    }
+}
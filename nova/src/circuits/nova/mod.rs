@@ -2,8 +2,10 @@ use ark_ff::PrimeField;
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 
+pub mod aggregation;
 pub mod pcd;
 pub mod sequential;
+pub mod zk;
 
 pub mod public_params;
 pub use crate::folding::nova::cyclefold::Error;
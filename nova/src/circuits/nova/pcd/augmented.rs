@@ -20,7 +20,7 @@ use ark_relations::{
 use ark_std::Zero;
 
 use crate::{
-    circuits::nova::{NovaConstraintSynthesizer, StepCircuit},
+    circuits::nova::{zk, NovaConstraintSynthesizer, StepCircuit},
     commitment::CommitmentScheme,
     folding::nova::cyclefold::{
         self,
@@ -416,6 +416,8 @@ where
     ro_config: &'a <RO::Var as CryptographicSpongeVar<G1::ScalarField, RO>>::Parameters,
     step_circuit: &'a SC,
     input: NovaAugmentedCircuitInput<G1, G2, C1, C2, RO>,
+    /// Values of the blinding witness variables, see [`zk::blind`].
+    blinding: Option<[G1::ScalarField; 2]>,
 }
 
 impl<'a, G1, G2, C1, C2, RO, SC> NovaAugmentedCircuit<'a, G1, G2, C1, C2, RO, SC>
@@ -431,8 +433,9 @@ where
         ro_config: &'a <RO::Var as CryptographicSpongeVar<G1::ScalarField, RO>>::Parameters,
         step_circuit: &'a SC,
         input: NovaAugmentedCircuitInput<G1, G2, C1, C2, RO>,
+        blinding: Option<[G1::ScalarField; 2]>,
     ) -> Self {
-        Self { ro_config, step_circuit, input }
+        Self { ro_config, step_circuit, input, blinding }
     }
 }
 
@@ -457,6 +460,9 @@ where
             NovaAugmentedCircuitInputVar::<G1, G2, C1, C2, RO>::new_witness(cs.clone(), || {
                 Ok(&self.input)
             })?;
+        if let Some(factors) = self.blinding {
+            zk::blind(cs.clone(), factors)?;
+        }
 
         let vk = &input.vk;
         let (i, j, k) = (&input.i, &input.j, &input.k);
@@ -630,6 +636,7 @@ mod tests {
             ro_config: &ro_config,
             step_circuit: &TestCircuit,
             input,
+            blinding: None,
         };
         let cs = ConstraintSystem::new_ref();
 
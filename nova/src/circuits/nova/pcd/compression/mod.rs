@@ -1,6 +1,6 @@
 use ark_crypto_primitives::sponge::{
     constraints::{CryptographicSpongeVar, SpongeWithGadget},
-    Absorb,
+    Absorb, CryptographicSponge,
 };
 use ark_ec::{
     short_weierstrass::{Projective, SWCurveConfig},
@@ -9,11 +9,16 @@ use ark_ec::{
 use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_spartan::{
-    committed_relaxed_snark as spartan_snark, committed_relaxed_snark::CRSNARKKey as SNARKGens,
-    crr1csproof::CRR1CSShape, polycommitments::PolyCommitmentScheme, ComputationCommitment,
-    ComputationDecommitment,
+    committed_relaxed_snark::{self as spartan_snark, CRSNARKKey as SNARKGens, CRSNARKVerifierKey},
+    crr1csproof::CRR1CSShape,
+    polycommitments::PolyCommitmentScheme,
+    ComputationCommitment, ComputationDecommitment,
+};
+use ark_std::{
+    cmp::max,
+    marker::PhantomData,
+    rand::{CryptoRng, RngCore},
 };
-use ark_std::{cmp::max, marker::PhantomData};
 use merlin::Transcript;
 
 use super::PublicParams;
@@ -23,7 +28,11 @@ use crate::{
     folding::nova::cyclefold::nimfs::{
         NIMFSProof, R1CSInstance, RelaxedR1CSInstance, RelaxedR1CSWitness,
     },
-    nova::pcd::{augmented::SQUEEZE_NATIVE_ELEMENTS_NUM, PCDNode},
+    nova::{
+        aggregation::{verify_fold, Aggregate, Claim, FoldParams, FoldProof},
+        pcd::{augmented::SQUEEZE_NATIVE_ELEMENTS_NUM, PCDNode, ZKPCDNode},
+        zk::{RandomizedInstances, Randomizer},
+    },
     r1cs::R1CSShape,
     StepCircuit, LOG_TARGET,
 };
@@ -69,6 +78,114 @@ where
     _step_circuit: PhantomData<SC>,
 }
 
+/// A [`CompressedPCDProof`] of a randomized node, see [`SNARK::compress_zk`].
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    PC::Commitment: Into<Projective<G1>> + From<Projective<G1>> + Copy,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub i: u64,
+    pub j: u64,
+
+    pub z_i: Vec<G1::ScalarField>,
+    pub z_j: Vec<G1::ScalarField>,
+
+    pub U: RelaxedR1CSInstance<G1, PVC<G1, PC>>,
+    pub u: R1CSInstance<G1, PVC<G1, PC>>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+
+    pub W_secondary_prime: RelaxedR1CSWitness<G2>,
+
+    pub spartan_proof: spartan_snark::ZKSNARK<Projective<G1>, PC>,
+    pub folding_proof: NIMFSProof<G1, G2, PVC<G1, PC>, C2, RO>,
+    pub randomizer: Randomizer<G1, G2, PVC<G1, PC>, C2, RO>,
+
+    _random_oracle: PhantomData<RO>,
+    _step_circuit: PhantomData<SC>,
+}
+
+/// Either kind of compressed proof, which are verified the same way up to the Spartan proof.
+enum ProofRef<'a, G1, G2, PC, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    PC::Commitment: Into<Projective<G1>> + From<Projective<G1>> + Copy,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    Plain(&'a CompressedPCDProof<G1, G2, PC, C2, RO, SC>),
+    ZK(&'a ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>),
+}
+
+/// An [`Aggregate`] with its primary witness replaced by a Spartan proof.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedAggregateProof<G1, G2, PC, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    PC::Commitment: Into<Projective<G1>> + From<Projective<G1>> + Copy,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    /// Statements of the aggregated nodes, in order.
+    pub claims: Vec<Claim<G1::ScalarField>>,
+
+    pub U: RelaxedR1CSInstance<G1, PVC<G1, PC>>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub W_secondary: RelaxedR1CSWitness<G2>,
+
+    pub spartan_proof: spartan_snark::SNARK<Projective<G1>, PC>,
+    pub proof: FoldProof<G1, G2, PVC<G1, PC>, C2, RO, PCDNode<G1, G2, PVC<G1, PC>, C2, RO, SC>>,
+}
+
+/// The parts of [`PublicParams`] and [`SNARKKey`] needed to verify a [`CompressedPCDProof`].
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierKey<G1, G2, PC, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    RO: CryptographicSponge,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize,
+{
+    ro_config: RO::Config,
+    digest: G1::ScalarField,
+    shape_secondary: R1CSShape<Projective<G2>>,
+    pp_secondary: C2::PP,
+    computation_comm: ComputationCommitment<Projective<G1>, PC>,
+    snark_vk: CRSNARKVerifierKey<Projective<G1>, PC>,
+}
+
+impl<G1, G2, PC, C2, RO> VerifierKey<G1, G2, PC, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    RO: CryptographicSponge,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize,
+{
+    /// Digest of the public parameters the key was extracted from.
+    pub fn digest(&self) -> G1::ScalarField {
+        self.digest
+    }
+}
+
 #[derive(CanonicalDeserialize, CanonicalSerialize)]
 pub struct SNARKKey<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
     shape: CRR1CSShape<G::ScalarField>,
@@ -224,15 +341,58 @@ where
         })
     }
 
-    pub fn verify(
-        key: &SNARKKey<Projective<G1>, PC>,
+    /// Compress a randomized node, see [`PCDNode::randomize`].
+    ///
+    /// Its running instances are already folded with the last step and with random
+    /// instances, and Spartan proves knowledge of the randomized witness with hiding
+    /// sumchecks, blinded with randomness from `rng`.
+    pub fn compress_zk(
         params: &PublicParams<G1, G2, PVC<G1, PC>, C2, RO, SC>,
-        proof: &CompressedPCDProof<G1, G2, PC, C2, RO, SC>,
-    ) -> Result<(), SpartanError> {
-        let _span =
-            tracing::debug_span!(target: LOG_TARGET, "Spartan_verify", i = proof.i, j = proof.j,)
-                .entered();
-        let CompressedPCDProof {
+        key: &SNARKKey<Projective<G1>, PC>,
+        pcd_proof: ZKPCDNode<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>, SpartanError> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "Spartan_prove_zk").entered();
+        let SNARKKey {
+            shape,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+        } = key;
+        let ZKPCDNode { i, j, z_i, z_j, instances, .. } = pcd_proof;
+        let RandomizedInstances {
+            U,
+            U_secondary,
+            u,
+            folding_proof,
+            randomizer,
+            W,
+            W_secondary,
+        } = instances;
+
+        // The prover does not keep the folded instance, recompute it as the verifier will.
+        let (U_prime, U_secondary_prime) =
+            folding_proof.verify(&params.ro_config, &params.digest, &U, &U_secondary, &u)?;
+        let (U_prime, _) = randomizer.verify(
+            &params.ro_config,
+            &params.digest,
+            &U_prime,
+            &U_secondary_prime,
+        )?;
+
+        let mut transcript = Transcript::new(b"spartan_snark");
+        let spartan_proof = spartan_snark::ZKSNARK::<Projective<G1>, PC>::prove(
+            shape,
+            &U_prime.try_into()?,
+            W.try_into()?,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+            &mut transcript,
+            rng,
+        );
+
+        Ok(ZKCompressedPCDProof {
             i,
             j,
             z_i,
@@ -240,15 +400,135 @@ where
             U,
             u,
             U_secondary,
-            W_secondary_prime,
+            W_secondary_prime: W_secondary,
             spartan_proof,
             folding_proof,
-            ..
-        } = proof;
+            randomizer,
+            _random_oracle: PhantomData,
+            _step_circuit: PhantomData,
+        })
+    }
+
+    /// Extract the parts of `params` and `key` needed to verify compressed proofs.
+    pub fn verifier_key(
+        params: PublicParams<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        key: SNARKKey<Projective<G1>, PC>,
+    ) -> VerifierKey<G1, G2, PC, C2, RO> {
+        VerifierKey {
+            snark_vk: key.snark_gens.verifier_key(),
+            ro_config: params.ro_config,
+            digest: params.digest,
+            shape_secondary: params.shape_secondary,
+            pp_secondary: params.pp_secondary,
+            computation_comm: key.computation_comm,
+        }
+    }
+
+    pub fn verify(
+        key: &SNARKKey<Projective<G1>, PC>,
+        params: &PublicParams<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        proof: &CompressedPCDProof<G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        Self::verify_inner(
+            &params.ro_config,
+            &params.digest,
+            &params.shape_secondary,
+            &params.pp_secondary,
+            &key.computation_comm,
+            &key.snark_gens.verifier_key(),
+            ProofRef::Plain(proof),
+        )
+    }
+
+    /// Verify a compressed proof without the prover-side parameters.
+    pub fn verify_with_vk(
+        vk: &VerifierKey<G1, G2, PC, C2, RO>,
+        proof: &CompressedPCDProof<G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        Self::verify_inner(
+            &vk.ro_config,
+            &vk.digest,
+            &vk.shape_secondary,
+            &vk.pp_secondary,
+            &vk.computation_comm,
+            &vk.snark_vk,
+            ProofRef::Plain(proof),
+        )
+    }
+
+    pub fn verify_zk(
+        key: &SNARKKey<Projective<G1>, PC>,
+        params: &PublicParams<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        proof: &ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        Self::verify_inner(
+            &params.ro_config,
+            &params.digest,
+            &params.shape_secondary,
+            &params.pp_secondary,
+            &key.computation_comm,
+            &key.snark_gens.verifier_key(),
+            ProofRef::ZK(proof),
+        )
+    }
+
+    /// Same as [`Self::verify_with_vk`] for randomized proofs.
+    pub fn verify_zk_with_vk(
+        vk: &VerifierKey<G1, G2, PC, C2, RO>,
+        proof: &ZKCompressedPCDProof<G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        Self::verify_inner(
+            &vk.ro_config,
+            &vk.digest,
+            &vk.shape_secondary,
+            &vk.pp_secondary,
+            &vk.computation_comm,
+            &vk.snark_vk,
+            ProofRef::ZK(proof),
+        )
+    }
+
+    fn verify_inner(
+        ro_config: &RO::Config,
+        digest: &G1::ScalarField,
+        shape_secondary: &R1CSShape<Projective<G2>>,
+        pp_secondary: &C2::PP,
+        computation_comm: &ComputationCommitment<Projective<G1>, PC>,
+        snark_vk: &CRSNARKVerifierKey<Projective<G1>, PC>,
+        proof: ProofRef<'_, G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        let (i, j, z_i, z_j, U, u, U_secondary, W_secondary_prime, folding_proof, randomizer) =
+            match proof {
+                ProofRef::Plain(proof) => (
+                    &proof.i,
+                    &proof.j,
+                    &proof.z_i,
+                    &proof.z_j,
+                    &proof.U,
+                    &proof.u,
+                    &proof.U_secondary,
+                    &proof.W_secondary_prime,
+                    &proof.folding_proof,
+                    None,
+                ),
+                ProofRef::ZK(proof) => (
+                    &proof.i,
+                    &proof.j,
+                    &proof.z_i,
+                    &proof.z_j,
+                    &proof.U,
+                    &proof.u,
+                    &proof.U_secondary,
+                    &proof.W_secondary_prime,
+                    &proof.folding_proof,
+                    Some(&proof.randomizer),
+                ),
+            };
+        let _span = tracing::debug_span!(target: LOG_TARGET, "Spartan_verify", i, j).entered();
         // First, we hash the running instances U, U_secondary and check that
         // the public IO of `u` is equal to this hash value.
-        let mut random_oracle = RO::new(&params.ro_config);
-        random_oracle.absorb(&params.digest);
+        let mut random_oracle = RO::new(ro_config);
+        random_oracle.absorb(digest);
         random_oracle.absorb(&G1::ScalarField::from(*i));
         random_oracle.absorb(&G1::ScalarField::from(*j));
         random_oracle.absorb(z_i);
@@ -264,31 +544,154 @@ where
 
         // Now, using the folding proof provided by the prover, we compute the folded
         // instances U_prime and U_secondary_prime.
-        let (U_prime, U_secondary_prime) = NIMFSProof::verify(
-            folding_proof,
-            &params.ro_config,
-            &params.digest,
-            U,
-            U_secondary,
-            u,
-        )?;
+        let (U_prime, U_secondary_prime) =
+            NIMFSProof::verify(folding_proof, ro_config, digest, U, U_secondary, u)?;
+
+        // If the node was randomized, the folded instances are further folded with
+        // the random ones.
+        let (U_prime, U_secondary_prime) = match randomizer {
+            Some(randomizer) => {
+                randomizer.verify(ro_config, digest, &U_prime, &U_secondary_prime)?
+            }
+            None => (U_prime, U_secondary_prime),
+        };
 
         // We check that the provided witness `W_secondary_prime` satisfies the
         // committed relaxed r1cs instance `U_secondary_prime`.`
-        params
-            .shape_secondary
-            .is_relaxed_satisfied(&U_secondary_prime, W_secondary_prime, &params.pp_secondary)
+        shape_secondary
+            .is_relaxed_satisfied(&U_secondary_prime, W_secondary_prime, pp_secondary)
             .map_err(|_| SpartanError::InvalidProof(ProofError::SecondaryCircuitNotSatisfied))?;
 
         // Finally, we verify the Spartan proof for the committed relaxed r1cs instance `U_prime`.
 
+        let mut transcript = Transcript::new(b"spartan_snark");
+        let U_prime = U_prime.try_into()?;
+        match proof {
+            ProofRef::Plain(proof) => spartan_snark::SNARK::<Projective<G1>, PC>::verify(
+                &proof.spartan_proof,
+                computation_comm,
+                &U_prime,
+                &mut transcript,
+                snark_vk,
+            )?,
+            ProofRef::ZK(proof) => {
+                proof
+                    .spartan_proof
+                    .verify(computation_comm, &U_prime, &mut transcript, snark_vk)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<G1, G2, PC, C2, RO, SC> SNARK<G1, G2, PC, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C2: CommitmentScheme<Projective<G2>>,
+    PC: PolyCommitmentScheme<Projective<G1>>,
+    PC::Commitment: Copy + Into<Projective<G1>> + From<Projective<G1>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Clone + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    /// Compress an aggregate by proving knowledge of its primary witness
+    /// with Spartan.
+    pub fn compress_aggregate(
+        key: &SNARKKey<Projective<G1>, PC>,
+        aggregate: Aggregate<G1, G2, PVC<G1, PC>, C2, RO, PCDNode<G1, G2, PVC<G1, PC>, C2, RO, SC>>,
+    ) -> Result<CompressedAggregateProof<G1, G2, PC, C2, RO, SC>, SpartanError> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "Spartan_prove_aggregate",
+            nodes = aggregate.claims.len(),
+        )
+        .entered();
+        let SNARKKey {
+            shape,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+        } = key;
+        let Aggregate {
+            claims,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            proof,
+            ..
+        } = aggregate;
+
+        let mut transcript = Transcript::new(b"spartan_snark");
+        let spartan_proof = spartan_snark::SNARK::<Projective<G1>, PC>::prove(
+            shape,
+            &U.clone().try_into()?,
+            W.try_into()?,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+            &mut transcript,
+        );
+
+        Ok(CompressedAggregateProof {
+            claims,
+            U,
+            U_secondary,
+            W_secondary,
+            spartan_proof,
+            proof,
+        })
+    }
+
+    pub fn verify_aggregate(
+        key: &SNARKKey<Projective<G1>, PC>,
+        params: &PublicParams<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        fold_params: &FoldParams<
+            G1,
+            G2,
+            PVC<G1, PC>,
+            C2,
+            RO,
+            PCDNode<G1, G2, PVC<G1, PC>, C2, RO, SC>,
+        >,
+        proof: &CompressedAggregateProof<G1, G2, PC, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "Spartan_verify_aggregate",
+            nodes = proof.claims.len(),
+        )
+        .entered();
+        let CompressedAggregateProof {
+            claims,
+            U,
+            U_secondary,
+            W_secondary,
+            spartan_proof,
+            proof,
+        } = proof;
+
+        // Check that the running instances result from folding nodes with
+        // the given claims.
+        verify_fold(params, fold_params, claims, (U, U_secondary), proof)?;
+
+        params
+            .shape_secondary
+            .is_relaxed_satisfied(U_secondary, W_secondary, &params.pp_secondary)
+            .map_err(|_| SpartanError::InvalidProof(ProofError::SecondaryCircuitNotSatisfied))?;
+
         let mut transcript = Transcript::new(b"spartan_snark");
         spartan_snark::SNARK::<Projective<G1>, PC>::verify(
             spartan_proof,
             &key.computation_comm,
-            &U_prime.try_into()?,
+            &U.clone().try_into()?,
             &mut transcript,
-            &key.snark_gens,
+            &key.snark_gens.verifier_key(),
         )?;
 
         Ok(())
@@ -299,7 +702,10 @@ where
 mod tests {
     use ark_bn254::{g1::Config as Bn254Config, Bn254};
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb};
-    use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+    use ark_ec::{
+        short_weierstrass::{Projective, SWCurveConfig},
+        CurveConfig,
+    };
     use ark_ff::PrimeField;
     use ark_grumpkin::{GrumpkinConfig, Projective as GrumpkinProjective};
     use ark_spartan::polycommitments::PolyCommitmentScheme;
@@ -316,7 +722,9 @@ mod tests {
         zeromorph::Zeromorph,
     };
 
-    fn test_setup_helper<G1, G2, PC, C2>() -> (
+    fn test_setup_helper<G1, G2, PC, C2>(
+        zk: bool,
+    ) -> (
         PC::SRS,
         PublicParams<
             G1,
@@ -357,14 +765,19 @@ mod tests {
         let ro_config = poseidon_config();
         let step_circuit = CubicCircuit::<G1::ScalarField>::default();
         let srs = PC::setup(NUM_VARS, b"test_srs", &mut rng).unwrap();
-        let params = PublicParams::<
+        type PP<G1, G2, PC, C2> = PublicParams<
             G1,
             G2,
             PVC<G1, PC>,
             C2,
-            PoseidonSponge<G1::ScalarField>,
-            CubicCircuit<G1::ScalarField>,
-        >::setup(ro_config, &step_circuit, &srs, &())
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+        let params = if zk {
+            PP::<G1, G2, PC, C2>::setup_zk(ro_config, &step_circuit, &srs, &())
+        } else {
+            PP::<G1, G2, PC, C2>::setup(ro_config, &step_circuit, &srs, &())
+        }
         .expect("setup should not fail");
         (srs, params)
     }
@@ -380,7 +793,7 @@ mod tests {
         PC::Commitment: Copy + Into<Projective<G1>> + From<Projective<G1>>,
     {
         // We set up the public parameters both for Nova and Spartan.
-        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>();
+        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>(false);
         let key = SNARK::<
             G1,
             G2,
@@ -423,7 +836,7 @@ mod tests {
         let z_1 = vec![G1::ScalarField::from(7)];
 
         // We set up the public parameters both for Nova and Spartan.
-        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>();
+        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>(false);
         let key = SNARK::<
             G1,
             G2,
@@ -435,7 +848,7 @@ mod tests {
         .unwrap();
 
         // Now, we perform a PCD proof step and check that the resulting proof verifies.
-        let nova_proof = PCDNode::prove_leaf(&params, &circuit, 0, &z_0).unwrap();
+        let nova_proof = PCDNode::prove_leaf(&params, &circuit, 0, &z_0, &mut test_rng()).unwrap();
         nova_proof.verify(&params).unwrap();
 
         assert_eq!(&nova_proof.z_j, &z_1);
@@ -461,6 +874,206 @@ mod tests {
                  CubicCircuit<G1::ScalarField>,
              >::verify(&key, &params, &compressed_pcd_proof)
              .unwrap();
+
+        // The same proof verifies with the verifier key alone.
+        type S<G1, G2, PC, C2> = SNARK<
+            G1,
+            G2,
+            PC,
+            C2,
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+        let vk = S::<G1, G2, PC, C2>::verifier_key(params, key);
+
+        let mut buf = Vec::new();
+        vk.serialize_compressed(&mut buf).unwrap();
+        let vk = VerifierKey::deserialize_compressed(buf.as_slice()).unwrap();
+
+        S::<G1, G2, PC, C2>::verify_with_vk(&vk, &compressed_pcd_proof).unwrap();
+    }
+
+    fn zk_compression_test_helper<G1, G2, PC, C2>()
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+        PC: PolyCommitmentScheme<Projective<G1>>,
+        PC::Commitment: Copy + Into<Projective<G1>> + From<Projective<G1>>,
+    {
+        type S<G1, G2, PC, C2> = SNARK<
+            G1,
+            G2,
+            PC,
+            C2,
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>(true);
+        let key = S::<G1, G2, PC, C2>::setup(&params, &srs).unwrap();
+
+        let mut rng = test_rng();
+        let node =
+            PCDNode::prove_leaf(&params, &circuit, 0, &[G1::ScalarField::one()], &mut rng).unwrap();
+        let node = node.randomize(&params, &mut rng).unwrap();
+        node.verify(&params).unwrap();
+
+        let mut proof = S::<G1, G2, PC, C2>::compress_zk(&params, &key, node, &mut rng).unwrap();
+        S::<G1, G2, PC, C2>::verify_zk(&key, &params, &proof).unwrap();
+
+        let vk = S::<G1, G2, PC, C2>::verifier_key(params, key);
+        S::<G1, G2, PC, C2>::verify_zk_with_vk(&vk, &proof).unwrap();
+
+        proof.z_j[0] += G1::ScalarField::one();
+        assert!(S::<G1, G2, PC, C2>::verify_zk_with_vk(&vk, &proof).is_err());
+    }
+
+    fn verifier_key_test_helper<G1, G2, PC, C2>()
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+        PC: PolyCommitmentScheme<Projective<G1>>,
+        PC::Commitment: Copy + Into<Projective<G1>> + From<Projective<G1>>,
+    {
+        type S<G1, G2, PC, C2> = SNARK<
+            G1,
+            G2,
+            PC,
+            C2,
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+        type PP<G1, G2, C1, C2> = PublicParams<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+
+        // The primary shape doesn't depend on the commitment scheme, so the smallest
+        // SRS can be derived from parameters which don't need one.
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+        let shape = PP::<G1, G2, PedersenCommitment<Projective<G1>>, C2>::setup(
+            poseidon_config(),
+            &circuit,
+            &(),
+            &(),
+        )
+        .unwrap()
+        .shape;
+        let num_vars = SNARKKey::<Projective<G1>, PC>::get_min_srs_size(&shape);
+
+        // each setup samples a new SRS
+        let mut rng = test_rng();
+        let mut setup = || {
+            let srs = PC::setup(num_vars, b"test_srs", &mut rng).unwrap();
+            let params =
+                PP::<G1, G2, PVC<G1, PC>, C2>::setup(poseidon_config(), &circuit, &srs, &())
+                    .unwrap();
+            let key = S::<G1, G2, PC, C2>::setup(&params, &srs).unwrap();
+            (params, key)
+        };
+        let prove = |params: &PP<G1, G2, PVC<G1, PC>, C2>, key: &SNARKKey<Projective<G1>, PC>| {
+            let node = PCDNode::prove_leaf(
+                params,
+                &circuit,
+                0,
+                &[G1::ScalarField::one()],
+                &mut test_rng(),
+            )
+            .unwrap();
+            S::<G1, G2, PC, C2>::compress(params, key, node).unwrap()
+        };
+
+        let (params, key) = setup();
+        let proof = prove(&params, &key);
+
+        // Only the serialized verifier key is kept: the parameters and the key are consumed.
+        let mut buf = Vec::new();
+        S::<G1, G2, PC, C2>::verifier_key(params, key)
+            .serialize_compressed(&mut buf)
+            .unwrap();
+        let vk = VerifierKey::deserialize_compressed(buf.as_slice()).unwrap();
+        S::<G1, G2, PC, C2>::verify_with_vk(&vk, &proof).unwrap();
+
+        // A proof made with other parameters is rejected.
+        let (other_params, other_key) = setup();
+        let other_proof = prove(&other_params, &other_key);
+        S::<G1, G2, PC, C2>::verify(&other_key, &other_params, &other_proof).unwrap();
+        assert!(S::<G1, G2, PC, C2>::verify_with_vk(&vk, &other_proof).is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn verifier_key_test() {
+        verifier_key_test_helper::<
+            Bn254Config,
+            GrumpkinConfig,
+            Zeromorph<Bn254>,
+            PedersenCommitment<GrumpkinProjective>,
+        >();
+    }
+
+    fn aggregate_compression_test_helper<G1, G2, PC, C2>()
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+        PC: PolyCommitmentScheme<Projective<G1>>,
+        PC::Commitment: Copy + Into<Projective<G1>> + From<Projective<G1>>,
+    {
+        type S<G1, G2, PC, C2> = SNARK<
+            G1,
+            G2,
+            PC,
+            C2,
+            PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+            CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+        >;
+
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+        let (srs, params) = test_setup_helper::<G1, G2, PC, C2>(false);
+        let key = S::<G1, G2, PC, C2>::setup(&params, &srs).unwrap();
+
+        // Two unrelated computations, proved with the same parameters.
+        let mut rng = test_rng();
+        let nodes = [G1::ScalarField::one(), G1::ScalarField::from(2)]
+            .iter()
+            .map(|z| PCDNode::prove_leaf(&params, &circuit, 0, &[*z], &mut rng).unwrap())
+            .collect::<Vec<_>>();
+
+        let fold_params = Aggregate::setup(poseidon_config(), &()).unwrap();
+        let aggregate = Aggregate::new(&params, &fold_params, &nodes, &mut rng).unwrap();
+        let mut proof = S::<G1, G2, PC, C2>::compress_aggregate(&key, aggregate).unwrap();
+        assert_eq!(proof.claims.len(), 2);
+        S::<G1, G2, PC, C2>::verify_aggregate(&key, &params, &fold_params, &proof).unwrap();
+
+        proof.claims[1].z_j[0] += G1::ScalarField::one();
+        assert!(
+            S::<G1, G2, PC, C2>::verify_aggregate(&key, &params, &fold_params, &proof).is_err()
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn aggregate_compression_test() {
+        aggregate_compression_test_helper::<
+            Bn254Config,
+            GrumpkinConfig,
+            Zeromorph<Bn254>,
+            PedersenCommitment<GrumpkinProjective>,
+        >();
     }
 
     #[test]
@@ -473,4 +1086,15 @@ mod tests {
             PedersenCommitment<GrumpkinProjective>,
         >();
     }
+
+    #[test]
+    #[ignore]
+    fn zk_compression_test() {
+        zk_compression_test_helper::<
+            Bn254Config,
+            GrumpkinConfig,
+            Zeromorph<Bn254>,
+            PedersenCommitment<GrumpkinProjective>,
+        >();
+    }
 }
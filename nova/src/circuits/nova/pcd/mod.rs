@@ -47,8 +47,13 @@ use ark_ff::{AdditiveGroup, PrimeField};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, RngCore};
 
-use super::{public_params, NovaConstraintSynthesizer, StepCircuit};
+use super::{
+    public_params,
+    zk::{self, RandomizedInstances},
+    NovaConstraintSynthesizer, StepCircuit,
+};
 use crate::{
     absorb::CryptographicSpongeExt,
     commitment::CommitmentScheme,
@@ -61,7 +66,7 @@ use crate::{
     },
 };
 
-mod augmented;
+pub(crate) mod augmented;
 
 #[cfg(feature = "spartan")]
 pub mod compression;
@@ -94,6 +99,7 @@ where
         step_circuit: &SC,
         aux1: &C1::SetupAux,
         aux2: &C2::SetupAux,
+        zk: bool,
     ) -> Result<public_params::PublicParams<G1, G2, C1, C2, RO, SC, Self>, cyclefold::Error> {
         let _span = tracing::debug_span!(target: LOG_TARGET, "setup").entered();
 
@@ -108,7 +114,9 @@ where
             z_i,
             vk: G1::ScalarField::ZERO,
         };
-        let circuit = NovaAugmentedCircuit::new(&ro_config, step_circuit, input);
+        // blinding values are not used in setup mode
+        let blinding = zk.then_some([G1::ScalarField::ZERO; 2]);
+        let circuit = NovaAugmentedCircuit::new(&ro_config, step_circuit, input, blinding);
         let _ = NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone())?;
 
         cs.finalize();
@@ -136,6 +144,7 @@ where
             pp,
             pp_secondary,
             digest: G1::ScalarField::ZERO,
+            zk,
 
             _step_circuit: PhantomData,
             _setup_params: PhantomData,
@@ -212,17 +221,24 @@ where
         step_circuit: &SC,
         i: usize,
         z_i: &[G1::ScalarField],
+        rng: &mut (impl RngCore + CryptoRng),
     ) -> Result<Self, cyclefold::Error> {
-        Self::prove_leaf_with_commit_fn(params, step_circuit, i, z_i, |pp, w| w.commit::<C1>(pp))
+        Self::prove_leaf_with_commit_fn(params, step_circuit, i, z_i, rng, |pp, w| {
+            w.commit::<C1>(pp)
+        })
     }
 
     /// Proves step of step circuit execution and calls `commit_fn(pp, w)` to
     /// compute commitment to the witness of the augmented circuit.
+    ///
+    /// If `params` are set up in zero-knowledge mode, the blinding witnesses of the augmented
+    /// circuit are sampled from `rng`, otherwise it is unused.
     pub fn prove_leaf_with_commit_fn(
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
         step_circuit: &SC,
         i: usize,
         z_i: &[G1::ScalarField],
+        rng: &mut (impl RngCore + CryptoRng),
         mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> C1::Commitment,
     ) -> Result<Self, cyclefold::Error> {
         let _span = tracing::debug_span!(
@@ -249,7 +265,8 @@ where
         let cs = ConstraintSystem::new_ref();
         cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
 
-        let circuit = NovaAugmentedCircuit::new(&params.ro_config, step_circuit, input);
+        let blinding = params.zk.then(|| zk::blinding_factors(rng));
+        let circuit = NovaAugmentedCircuit::new(&params.ro_config, step_circuit, input, blinding);
         let z_next = tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment")
             .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))?;
 
@@ -287,17 +304,25 @@ where
         step_circuit: &SC,
         left_node: &Self,
         right_node: &Self,
+        rng: &mut (impl RngCore + CryptoRng),
     ) -> Result<Self, cyclefold::Error> {
-        Self::prove_parent_with_commit_fn(params, step_circuit, left_node, right_node, |pp, w| {
-            w.commit::<C1>(pp)
-        })
+        Self::prove_parent_with_commit_fn(
+            params,
+            step_circuit,
+            left_node,
+            right_node,
+            rng,
+            |pp, w| w.commit::<C1>(pp),
+        )
     }
 
+    /// Proves a parent of two adjacent nodes, see [`Self::prove_leaf_with_commit_fn`].
     pub fn prove_parent_with_commit_fn(
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
         step_circuit: &SC,
         left_node: &Self,
         right_node: &Self,
+        rng: &mut (impl RngCore + CryptoRng),
         mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> C1::Commitment,
     ) -> Result<Self, cyclefold::Error> {
         let _span = tracing::debug_span!(
@@ -379,6 +404,7 @@ where
             &params.ro_config,
             step_circuit,
             NovaAugmentedCircuitInput::NonBase(input),
+            params.zk.then(|| zk::blinding_factors(rng)),
         );
         let _ = tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment")
             .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))?;
@@ -461,6 +487,129 @@ where
 
         Ok(())
     }
+
+    /// Folds running instances with the last step and with random instances, so that
+    /// the node reveals nothing about witnesses of the computation.
+    ///
+    /// `params` must be set up in zero-knowledge mode, see [`PublicParams::setup_zk`].
+    pub fn randomize(
+        self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<ZKPCDNode<G1, G2, C1, C2, RO, SC>, cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "randomize",
+            i = self.i,
+            j = self.j,
+        )
+        .entered();
+
+        let PCDNode {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            ..
+        } = self;
+
+        let instances =
+            RandomizedInstances::new(params, (U, W), (U_secondary, W_secondary), (u, w), rng)?;
+
+        Ok(ZKPCDNode {
+            i,
+            j,
+            z_i,
+            z_j,
+            instances,
+            _step_circuit: PhantomData,
+        })
+    }
+}
+
+/// [`PCDNode`] with randomized witnesses, see [`zk`](super::zk).
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZKPCDNode<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub i: u64,
+    pub j: u64,
+
+    pub z_i: Vec<G1::ScalarField>,
+    pub z_j: Vec<G1::ScalarField>,
+
+    pub instances: RandomizedInstances<G1, G2, C1, C2, RO>,
+
+    _step_circuit: PhantomData<SC>,
+}
+
+impl<G1, G2, C1, C2, RO, SC> ZKPCDNode<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub fn min_step(&self) -> u64 {
+        self.i
+    }
+
+    pub fn max_step(&self) -> u64 {
+        self.j
+    }
+
+    pub fn verify(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+    ) -> Result<(), cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "verify_zk",
+            i = self.i,
+            j = self.j,
+        )
+        .entered();
+
+        const NOT_SATISFIED_ERROR: cyclefold::Error =
+            cyclefold::Error::R1CS(crate::r1cs::Error::NotSatisfied);
+        let RandomizedInstances { U, U_secondary, u, .. } = &self.instances;
+
+        let mut random_oracle = RO::new(&params.ro_config);
+        random_oracle.absorb(&params.digest);
+        random_oracle.absorb(&G1::ScalarField::from(self.i));
+        random_oracle.absorb(&G1::ScalarField::from(self.j));
+        random_oracle.absorb(&self.z_i);
+        random_oracle.absorb(&self.z_j);
+        random_oracle.absorb(&U);
+        random_oracle.absorb_non_native(&U_secondary);
+
+        let hash: &G1::ScalarField =
+            &random_oracle.squeeze_field_elements(augmented::SQUEEZE_NATIVE_ELEMENTS_NUM)[0];
+        if hash != &u.X[1] {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        self.instances.verify(params)
+    }
 }
 
 #[cfg(test)]
@@ -473,6 +622,10 @@ mod tests {
 
     use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
     use ark_ff::Field;
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        test_rng,
+    };
 
     use tracing_subscriber::{
         filter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
@@ -513,7 +666,7 @@ mod tests {
             CubicCircuit<G1::ScalarField>,
         >::setup(ro_config, &circuit, &(), &())?;
 
-        let recursive_snark = PCDNode::prove_leaf(&params, &circuit, 0, &z_0)?;
+        let recursive_snark = PCDNode::prove_leaf(&params, &circuit, 0, &z_0, &mut test_rng())?;
         recursive_snark.verify(&params)?;
 
         assert_eq!(&recursive_snark.z_j, &z_1);
@@ -566,17 +719,48 @@ mod tests {
             C2,
             PoseidonSponge<G1::ScalarField>,
             CubicCircuit<G1::ScalarField>,
-        >::setup(ro_config, &circuit, &(), &())?;
+        >::setup(ro_config.clone(), &circuit, &(), &())?;
 
-        let node_0 = PCDNode::prove_leaf(&params, &circuit, 0, z[0])?;
-        let node_1 = PCDNode::prove_leaf(&params, &circuit, 2, z[2])?;
+        let mut rng = test_rng();
+        let node_0 = PCDNode::prove_leaf(&params, &circuit, 0, z[0], &mut rng)?;
+        let node_1 = PCDNode::prove_leaf(&params, &circuit, 2, z[2], &mut rng)?;
 
-        let root = PCDNode::prove_parent(&params, &circuit, &node_0, &node_1)?;
+        let root = PCDNode::prove_parent(&params, &circuit, &node_0, &node_1, &mut rng)?;
 
         assert_eq!(&root.z_i, &z[0]);
         assert_eq!(&root.z_j, &z[3]);
 
         root.verify(&params)?;
+        assert!(matches!(
+            root.randomize(&params, &mut rng),
+            Err(cyclefold::Error::ZKNotEnabled)
+        ));
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::setup_zk(ro_config, &circuit, &(), &())?;
+
+        // blinding witnesses are sampled from the given rng
+        let leaf = |rng: &mut StdRng| PCDNode::prove_leaf(&params, &circuit, 0, z[0], rng);
+        let seeded = leaf(&mut StdRng::seed_from_u64(1))?.u.commitment_W;
+        assert_eq!(seeded, leaf(&mut StdRng::seed_from_u64(1))?.u.commitment_W);
+        assert_ne!(seeded, leaf(&mut StdRng::seed_from_u64(2))?.u.commitment_W);
+
+        let node_0 = PCDNode::prove_leaf(&params, &circuit, 0, z[0], &mut rng)?;
+        let node_1 = PCDNode::prove_leaf(&params, &circuit, 2, z[2], &mut rng)?;
+
+        let root = PCDNode::prove_parent(&params, &circuit, &node_0, &node_1, &mut rng)?;
+        root.verify(&params)?;
+
+        let zk_root = root.randomize(&params, &mut rng)?;
+        assert_eq!(&zk_root.z_j, &z[3]);
+
+        zk_root.verify(&params)?;
 
         Ok(())
     }
@@ -3,7 +3,11 @@ use std::marker::PhantomData;
 use ark_crypto_primitives::sponge::{CryptographicSponge, FieldElementSize};
 use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
 use ark_ff::{AdditiveGroup, BigInteger, PrimeField};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, CanonicalSerializeHashExt};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, CanonicalSerializeHashExt, Compress,
+    SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
 
 use super::{Error, StepCircuit};
 use crate::{
@@ -12,7 +16,6 @@ use crate::{
     utils,
 };
 
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct PublicParams<G1, G2, C1, C2, RO, SC, SP>
 where
     G1: SWCurveConfig,
@@ -30,6 +33,9 @@ where
     pub pp: C1::PP,
     pub pp_secondary: C2::PP,
     pub digest: G1::ScalarField,
+    /// Whether the augmented circuit is blinded, which is required to randomize proofs
+    /// before compression, see [`super::zk`].
+    pub zk: bool,
 
     pub _step_circuit: PhantomData<SC>,
     pub _setup_params: PhantomData<SP>,
@@ -52,7 +58,18 @@ where
         aux1: &C1::SetupAux,
         aux2: &C2::SetupAux,
     ) -> Result<Self, Error> {
-        SP::setup(ro_config, step_circuit, aux1, aux2)
+        SP::setup(ro_config, step_circuit, aux1, aux2, false)
+    }
+
+    /// Same as [`Self::setup`], with the augmented circuit blinded so that proofs
+    /// can be randomized before compression.
+    pub fn setup_zk(
+        ro_config: RO::Config,
+        step_circuit: &SC,
+        aux1: &C1::SetupAux,
+        aux2: &C2::SetupAux,
+    ) -> Result<Self, Error> {
+        SP::setup(ro_config, step_circuit, aux1, aux2, true)
     }
 
     /// Returns first [`SQUEEZE_ELEMENTS_BIT_SIZE`] bits of public parameters sha3 hash reinterpreted
@@ -86,5 +103,119 @@ where
         step_circuit: &SC,
         aux1: &C1::SetupAux,
         aux2: &C2::SetupAux,
+        zk: bool,
     ) -> Result<PublicParams<G1, G2, C1, C2, RO, SC, Self>, Error>;
 }
+
+// The zero-knowledge flag is written after all other fields, and only if set, so that
+// parameters without it keep their encoding and digest.
+impl<G1, G2, C1, C2, RO, SC, SP> CanonicalSerialize for PublicParams<G1, G2, C1, C2, RO, SC, SP>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+    SP: Send + Sync,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.ro_config.serialize_with_mode(&mut writer, compress)?;
+        self.shape.serialize_with_mode(&mut writer, compress)?;
+        self.shape_secondary
+            .serialize_with_mode(&mut writer, compress)?;
+        self.pp.serialize_with_mode(&mut writer, compress)?;
+        self.pp_secondary
+            .serialize_with_mode(&mut writer, compress)?;
+        self.digest.serialize_with_mode(&mut writer, compress)?;
+        if self.zk {
+            self.zk.serialize_with_mode(&mut writer, compress)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.ro_config.serialized_size(compress)
+            + self.shape.serialized_size(compress)
+            + self.shape_secondary.serialized_size(compress)
+            + self.pp.serialized_size(compress)
+            + self.pp_secondary.serialized_size(compress)
+            + self.digest.serialized_size(compress)
+            + if self.zk {
+                self.zk.serialized_size(compress)
+            } else {
+                0
+            }
+    }
+}
+
+impl<G1, G2, C1, C2, RO, SC, SP> Valid for PublicParams<G1, G2, C1, C2, RO, SC, SP>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+    SP: Send + Sync,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.ro_config.check()?;
+        self.shape.check()?;
+        self.shape_secondary.check()?;
+        self.pp.check()?;
+        self.pp_secondary.check()?;
+        self.digest.check()
+    }
+}
+
+impl<G1, G2, C1, C2, RO, SC, SP> CanonicalDeserialize for PublicParams<G1, G2, C1, C2, RO, SC, SP>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+    SP: Send + Sync,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let ro_config = RO::Config::deserialize_with_mode(&mut reader, compress, validate)?;
+        let shape = R1CSShape::deserialize_with_mode(&mut reader, compress, validate)?;
+        let shape_secondary = R1CSShape::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp = C1::PP::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp_secondary = C2::PP::deserialize_with_mode(&mut reader, compress, validate)?;
+        let digest = G1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        // Parameters set up without zero-knowledge end here.
+        let mut flag = [0u8];
+        let zk = match reader.read(&mut flag)? {
+            0 => false,
+            _ if flag[0] == 1 => true,
+            _ => return Err(SerializationError::InvalidData),
+        };
+
+        Ok(Self {
+            ro_config,
+            shape,
+            shape_secondary,
+            pp,
+            pp_secondary,
+            digest,
+            zk,
+            _step_circuit: PhantomData,
+            _setup_params: PhantomData,
+        })
+    }
+}
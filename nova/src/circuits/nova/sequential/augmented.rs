@@ -19,7 +19,7 @@ use ark_relations::{
 use ark_std::Zero;
 
 use crate::{
-    circuits::nova::{NovaConstraintSynthesizer, StepCircuit},
+    circuits::nova::{zk, NovaConstraintSynthesizer, StepCircuit},
     commitment::CommitmentScheme,
     folding::nova::cyclefold::{
         self,
@@ -232,6 +232,8 @@ where
     ro_config: &'a <RO::Var as CryptographicSpongeVar<G1::ScalarField, RO>>::Parameters,
     step_circuit: &'a SC,
     input: NovaAugmentedCircuitInput<G1, G2, C1, C2, RO>,
+    /// Values of the blinding witness variables, see [`zk::blind`].
+    blinding: Option<[G1::ScalarField; 2]>,
 }
 
 impl<'a, G1, G2, C1, C2, RO, SC> NovaAugmentedCircuit<'a, G1, G2, C1, C2, RO, SC>
@@ -247,8 +249,9 @@ where
         ro_config: &'a <RO::Var as CryptographicSpongeVar<G1::ScalarField, RO>>::Parameters,
         step_circuit: &'a SC,
         input: NovaAugmentedCircuitInput<G1, G2, C1, C2, RO>,
+        blinding: Option<[G1::ScalarField; 2]>,
     ) -> Self {
-        Self { ro_config, step_circuit, input }
+        Self { ro_config, step_circuit, input, blinding }
     }
 }
 
@@ -273,6 +276,9 @@ where
             NovaAugmentedCircuitInputVar::<G1, G2, C1, C2, RO>::new_witness(cs.clone(), || {
                 Ok(&self.input)
             })?;
+        if let Some(factors) = self.blinding {
+            zk::blind(cs.clone(), factors)?;
+        }
 
         let is_base_case = input.i.is_zero()?;
         let should_enforce = is_base_case.not();
@@ -409,6 +415,7 @@ mod tests {
             ro_config: &ro_config,
             step_circuit: &TestCircuit,
             input,
+            blinding: None,
         };
         let cs = ConstraintSystem::new_ref();
 
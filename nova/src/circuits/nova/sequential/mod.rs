@@ -9,6 +9,7 @@ use ark_ff::{AdditiveGroup, PrimeField};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, RngCore};
 
 use crate::{
     absorb::CryptographicSpongeExt,
@@ -22,7 +23,11 @@ use crate::{
     },
 };
 
-use super::{public_params, NovaConstraintSynthesizer, StepCircuit};
+use super::{
+    public_params,
+    zk::{self, RandomizedInstances},
+    NovaConstraintSynthesizer, StepCircuit,
+};
 
 mod augmented;
 use augmented::{
@@ -53,6 +58,7 @@ where
         step_circuit: &SC,
         aux1: &C1::SetupAux,
         aux2: &C2::SetupAux,
+        zk: bool,
     ) -> Result<public_params::PublicParams<G1, G2, C1, C2, RO, SC, Self>, cyclefold::Error> {
         let _span = tracing::debug_span!(target: LOG_TARGET, "setup").entered();
 
@@ -65,7 +71,9 @@ where
             vk: G1::ScalarField::ZERO,
             z_0,
         };
-        let circuit = NovaAugmentedCircuit::new(&ro_config, step_circuit, input);
+        // blinding values are not used in setup mode
+        let blinding = zk.then_some([G1::ScalarField::ZERO; 2]);
+        let circuit = NovaAugmentedCircuit::new(&ro_config, step_circuit, input, blinding);
         let _ = NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone())?;
 
         cs.finalize();
@@ -93,6 +101,7 @@ where
             pp,
             pp_secondary,
             digest: G1::ScalarField::ZERO,
+            zk,
 
             _step_circuit: PhantomData,
             _setup_params: PhantomData,
@@ -124,9 +133,9 @@ where
     RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
     SC: StepCircuit<G1::ScalarField>,
 {
-    z_0: Vec<G1::ScalarField>,
+    pub(crate) z_0: Vec<G1::ScalarField>,
 
-    non_base: Option<IVCProofNonBase<G1, G2, C1, C2>>,
+    pub(crate) non_base: Option<IVCProofNonBase<G1, G2, C1, C2>>,
 
     _random_oracle: PhantomData<RO>,
     _step_circuit: PhantomData<SC>,
@@ -153,22 +162,22 @@ where
 }
 
 #[derive(CanonicalDeserialize, CanonicalSerialize)]
-struct IVCProofNonBase<G1, G2, C1, C2>
+pub(crate) struct IVCProofNonBase<G1, G2, C1, C2>
 where
     G1: SWCurveConfig,
     G2: SWCurveConfig,
     C1: CommitmentScheme<Projective<G1>>,
     C2: CommitmentScheme<Projective<G2>>,
 {
-    U: RelaxedR1CSInstance<G1, C1>,
-    W: RelaxedR1CSWitness<G1>,
-    U_secondary: RelaxedR1CSInstance<G2, C2>,
-    W_secondary: RelaxedR1CSWitness<G2>,
-
-    u: R1CSInstance<G1, C1>,
-    w: R1CSWitness<G1>,
-    i: u64,
-    z_i: Vec<G1::ScalarField>,
+    pub(crate) U: RelaxedR1CSInstance<G1, C1>,
+    pub(crate) W: RelaxedR1CSWitness<G1>,
+    pub(crate) U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub(crate) W_secondary: RelaxedR1CSWitness<G2>,
+
+    pub(crate) u: R1CSInstance<G1, C1>,
+    pub(crate) w: R1CSWitness<G1>,
+    pub(crate) i: u64,
+    pub(crate) z_i: Vec<G1::ScalarField>,
 }
 
 impl<G1, G2, C1, C2> Clone for IVCProofNonBase<G1, G2, C1, C2>
@@ -214,6 +223,10 @@ where
         }
     }
 
+    pub fn z_0(&self) -> &[G1::ScalarField] {
+        &self.z_0
+    }
+
     pub fn z_i(&self) -> &[G1::ScalarField] {
         self.non_base
             .as_ref()
@@ -228,10 +241,15 @@ where
             .unwrap_or(0)
     }
 
+    /// Proves the next step of the computation.
+    ///
+    /// If `params` are set up in zero-knowledge mode, the blinding witnesses of the augmented
+    /// circuit are sampled from `rng`, otherwise it is unused.
     pub fn prove_step(
         self,
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
         step_circuit: &SC,
+        rng: &mut (impl RngCore + CryptoRng),
     ) -> Result<Self, cyclefold::Error> {
         let _span = tracing::debug_span!(
             target: LOG_TARGET,
@@ -299,7 +317,8 @@ where
         let cs = ConstraintSystem::new_ref();
         cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
 
-        let circuit = NovaAugmentedCircuit::new(&params.ro_config, step_circuit, input);
+        let blinding = params.zk.then(|| zk::blinding_factors(rng));
+        let circuit = NovaAugmentedCircuit::new(&params.ro_config, step_circuit, input, blinding);
 
         let z_i = tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment")
             .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))?;
@@ -333,6 +352,49 @@ where
         })
     }
 
+    /// Folds running instances with the last step and with random instances, so that
+    /// the proof reveals nothing about witnesses of the computation.
+    ///
+    /// `params` must be set up in zero-knowledge mode, see [`PublicParams::setup_zk`].
+    pub fn randomize(
+        self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<ZKIVCProof<G1, G2, C1, C2, RO, SC>, cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "randomize",
+            step_num = %self.step_num(),
+        )
+        .entered();
+
+        let IVCProof { z_0, non_base, .. } = self;
+        let Some(non_base) = non_base else {
+            return Err(cyclefold::Error::R1CS(crate::r1cs::Error::NotSatisfied));
+        };
+        let IVCProofNonBase {
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            i,
+            z_i,
+        } = non_base;
+
+        let instances =
+            RandomizedInstances::new(params, (U, W), (U_secondary, W_secondary), (u, w), rng)?;
+
+        Ok(ZKIVCProof {
+            z_0,
+            i,
+            z_i,
+            instances,
+            _step_circuit: PhantomData,
+        })
+    }
+
     pub fn verify(
         &self,
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
@@ -391,6 +453,88 @@ where
     }
 }
 
+/// [`IVCProof`] with randomized witnesses, see [`zk`](super::zk).
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+pub struct ZKIVCProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    z_0: Vec<G1::ScalarField>,
+    i: u64,
+    z_i: Vec<G1::ScalarField>,
+
+    instances: RandomizedInstances<G1, G2, C1, C2, RO>,
+
+    _step_circuit: PhantomData<SC>,
+}
+
+impl<G1, G2, C1, C2, RO, SC> ZKIVCProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub fn z_0(&self) -> &[G1::ScalarField] {
+        &self.z_0
+    }
+
+    pub fn z_i(&self) -> &[G1::ScalarField] {
+        &self.z_i
+    }
+
+    pub fn step_num(&self) -> u64 {
+        self.i
+    }
+
+    pub fn verify(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        num_steps: usize,
+    ) -> Result<(), cyclefold::Error> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "verify_zk", %num_steps).entered();
+
+        const NOT_SATISFIED_ERROR: cyclefold::Error =
+            cyclefold::Error::R1CS(crate::r1cs::Error::NotSatisfied);
+
+        let num_steps = num_steps as u64;
+        if num_steps != self.i {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        let RandomizedInstances { U, U_secondary, u, .. } = &self.instances;
+        let mut random_oracle = RO::new(&params.ro_config);
+
+        random_oracle.absorb(&params.digest);
+        random_oracle.absorb(&G1::ScalarField::from(self.i));
+        random_oracle.absorb(&self.z_0);
+        random_oracle.absorb(&self.z_i);
+        random_oracle.absorb(U);
+        random_oracle.absorb_non_native(U_secondary);
+
+        let hash: &G1::ScalarField =
+            &random_oracle.squeeze_field_elements(augmented::SQUEEZE_NATIVE_ELEMENTS_NUM)[0];
+
+        if hash != &u.X[1] {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        self.instances.verify(params)
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -400,6 +544,7 @@ pub(crate) mod tests {
     use ark_ff::Field;
     use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
     use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
 
     use tracing_subscriber::{
         filter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
@@ -466,7 +611,8 @@ pub(crate) mod tests {
         >::setup(ro_config, &circuit, &(), &())?;
 
         let mut recursive_snark = IVCProof::new(&z_0);
-        recursive_snark = recursive_snark.prove_step(&params, &circuit)?;
+        recursive_snark =
+            recursive_snark.prove_step(&params, &circuit, &mut ark_std::test_rng())?;
         recursive_snark.verify(&params, num_steps).unwrap();
 
         assert_eq!(&recursive_snark.z_i()[0], &G1::ScalarField::from(7));
@@ -517,14 +663,104 @@ pub(crate) mod tests {
             CubicCircuit<G1::ScalarField>,
         >::setup(ro_config, &circuit, &(), &())?;
 
+        let mut rng = ark_std::test_rng();
         let mut recursive_snark = IVCProof::new(&z_0);
 
         for _ in 0..num_steps {
-            recursive_snark = IVCProof::prove_step(recursive_snark, &params, &circuit)?;
+            recursive_snark = IVCProof::prove_step(recursive_snark, &params, &circuit, &mut rng)?;
         }
         recursive_snark.verify(&params, num_steps).unwrap();
 
         assert_eq!(&recursive_snark.z_i()[0], &G1::ScalarField::from(44739235));
         Ok(())
     }
+
+    #[test]
+    fn ivc_randomize() {
+        ivc_randomize_with_cycle::<
+            ark_pallas::PallasConfig,
+            ark_vesta::VestaConfig,
+            PedersenCommitment<ark_pallas::Projective>,
+            PedersenCommitment<ark_vesta::Projective>,
+        >()
+        .unwrap()
+    }
+
+    fn ivc_randomize_with_cycle<G1, G2, C1, C2>() -> Result<(), cyclefold::Error>
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: CommitmentScheme<Projective<G1>, SetupAux = ()>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let ro_config = poseidon_config();
+
+        let circuit = CubicCircuit::<G1::ScalarField>(PhantomData);
+        let z_0 = vec![G1::ScalarField::ONE];
+        let num_steps = 2;
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::setup(ro_config.clone(), &circuit, &(), &())?;
+        let mut rng = ark_std::test_rng();
+        let recursive_snark =
+            IVCProof::prove_step(IVCProof::new(&z_0), &params, &circuit, &mut rng)?;
+        assert!(matches!(
+            recursive_snark.randomize(&params, &mut rng),
+            Err(cyclefold::Error::ZKNotEnabled)
+        ));
+        assert!(!reload(&params).zk);
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::setup_zk(ro_config, &circuit, &(), &())?;
+        let reloaded = reload(&params);
+        assert!(reloaded.zk);
+        assert_eq!(reloaded.digest, params.digest);
+
+        let mut recursive_snark = IVCProof::new(&z_0);
+        assert!(recursive_snark
+            .clone()
+            .randomize(&params, &mut rng)
+            .is_err());
+
+        // blinding witnesses are sampled from the given rng
+        let commitment_W = |rng: &mut StdRng| -> Result<_, cyclefold::Error> {
+            let proof = IVCProof::new(&z_0).prove_step(&params, &circuit, rng)?;
+            Ok(proof.non_base.unwrap().u.commitment_W)
+        };
+        let seeded = commitment_W(&mut StdRng::seed_from_u64(1))?;
+        assert_eq!(seeded, commitment_W(&mut StdRng::seed_from_u64(1))?);
+        assert_ne!(seeded, commitment_W(&mut StdRng::seed_from_u64(2))?);
+
+        for _ in 0..num_steps {
+            recursive_snark = IVCProof::prove_step(recursive_snark, &params, &circuit, &mut rng)?;
+        }
+
+        let zk_snark = recursive_snark.randomize(&params, &mut rng)?;
+        zk_snark.verify(&params, num_steps).unwrap();
+        assert!(zk_snark.verify(&params, num_steps + 1).is_err());
+
+        assert_eq!(&zk_snark.z_i()[0], &G1::ScalarField::from(355));
+        Ok(())
+    }
+
+    fn reload<T: CanonicalSerialize + CanonicalDeserialize>(value: &T) -> T {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), value.compressed_size());
+        T::deserialize_compressed(&bytes[..]).unwrap()
+    }
 }
@@ -0,0 +1,261 @@
+//! Zero-knowledge mode for Nova proofs.
+//!
+//! Both [`IVCProof`](super::sequential::IVCProof) and [`PCDNode`](super::pcd::PCDNode) carry
+//! the witnesses of their running instances, which are linear combinations of the witnesses of
+//! every step. Two measures are taken so that a proof reveals nothing beyond its public io:
+//!
+//! 1. Augmented circuits allocate a pair of random witness variables bound by a single constraint,
+//!    see [`blind`]. This makes commitments to step witnesses, and to cross terms computed from them,
+//!    hiding.
+//! 2. Before a proof is output, its running instances are folded with random satisfying relaxed
+//!    instances, see [`Randomizer`]. The resulting witnesses are uniformly distributed subject to
+//!    satisfying the folded instances, and can be simulated given them.
+//!
+//! Blinding changes the shape of augmented circuits, so it is chosen at setup, see
+//! [`PublicParams::setup_zk`], and proofs can only be randomized with such parameters.
+//!
+//! Randomized PCD nodes are compressed with a zero-knowledge Spartan proof, whose sumchecks
+//! only reveal Pedersen commitments to their claims, see
+//! [`ZKCompressedPCDProof`](super::pcd::compression::ZKCompressedPCDProof).
+
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, RngCore};
+
+use super::{public_params::PublicParams, StepCircuit};
+use crate::{
+    commitment::CommitmentScheme,
+    folding::nova::cyclefold::{
+        self,
+        nimfs::{NIMFSProof, R1CSInstance, R1CSWitness, RelaxedR1CSInstance, RelaxedR1CSWitness},
+    },
+};
+
+/// Samples the values of the blinding witness variables allocated by [`blind`].
+pub(crate) fn blinding_factors<F: PrimeField>(rng: &mut (impl RngCore + CryptoRng)) -> [F; 2] {
+    [F::rand(rng), F::rand(rng)]
+}
+
+/// Allocates two random witness variables, with values sampled by [`blinding_factors`],
+/// and enforces their product.
+///
+/// Every witness vector of the circuit gets uniformly random coefficients, and the cross term
+/// with any other witness of the same shape is randomized through the product constraint.
+pub(crate) fn blind<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    factors: [F; 2],
+) -> Result<(), SynthesisError> {
+    let [a, b] = factors;
+
+    let a = FpVar::new_witness(cs.clone(), || Ok(a))?;
+    let b = FpVar::new_witness(cs, || Ok(b))?;
+    let _ = &a * &b;
+
+    Ok(())
+}
+
+/// Proof of folding running instances with random relaxed instances.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct Randomizer<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+{
+    pub U: RelaxedR1CSInstance<G1, C1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub folding_proof: NIMFSProof<G1, G2, C1, C2, RO>,
+}
+
+impl<G1, G2, C1, C2, RO> Randomizer<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+{
+    /// Folds `(U, W)` and `(U_secondary, W_secondary)` with freshly sampled random instances,
+    /// returning the proof together with the folded pairs.
+    #[allow(clippy::type_complexity)]
+    pub fn prove<SC, SP>(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC, SP>,
+        (U, W): (&RelaxedR1CSInstance<G1, C1>, &RelaxedR1CSWitness<G1>),
+        (U_secondary, W_secondary): (&RelaxedR1CSInstance<G2, C2>, &RelaxedR1CSWitness<G2>),
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<
+        (
+            Self,
+            (RelaxedR1CSInstance<G1, C1>, RelaxedR1CSWitness<G1>),
+            (RelaxedR1CSInstance<G2, C2>, RelaxedR1CSWitness<G2>),
+        ),
+        cyclefold::Error,
+    >
+    where
+        SC: StepCircuit<G1::ScalarField>,
+        SP: Send + Sync,
+    {
+        // Random instances only hide the witness if step commitments are hiding.
+        if !params.zk {
+            return Err(cyclefold::Error::ZKNotEnabled);
+        }
+        let (random_U, random_W) = params.shape.random_relaxed::<C1>(&params.pp, rng);
+        let (random_U_secondary, random_W_secondary) = params
+            .shape_secondary
+            .random_relaxed::<C2>(&params.pp_secondary, rng);
+
+        let (folding_proof, folded, folded_secondary) = NIMFSProof::prove_with_relaxed(
+            &params.pp,
+            &params.pp_secondary,
+            &params.ro_config,
+            &params.digest,
+            (&params.shape, &params.shape_secondary),
+            (U, W),
+            (U_secondary, W_secondary),
+            (&random_U, &random_W),
+            (&random_U_secondary, &random_W_secondary),
+        )?;
+
+        let randomizer = Self {
+            U: random_U,
+            U_secondary: random_U_secondary,
+            folding_proof,
+        };
+        Ok((randomizer, folded, folded_secondary))
+    }
+
+    /// Returns running instances folded with the random ones.
+    pub fn verify(
+        &self,
+        config: &RO::Config,
+        vk: &G1::ScalarField,
+        U: &RelaxedR1CSInstance<G1, C1>,
+        U_secondary: &RelaxedR1CSInstance<G2, C2>,
+    ) -> Result<(RelaxedR1CSInstance<G1, C1>, RelaxedR1CSInstance<G2, C2>), cyclefold::Error> {
+        self.folding_proof.verify_with_relaxed(
+            config,
+            vk,
+            U,
+            U_secondary,
+            &self.U,
+            &self.U_secondary,
+        )
+    }
+}
+
+/// Running instances of a proof, the last step instance, and witnesses of all of them folded
+/// together with random instances.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct RandomizedInstances<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+{
+    pub U: RelaxedR1CSInstance<G1, C1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub u: R1CSInstance<G1, C1>,
+
+    /// Proof of folding `u` into running instances.
+    pub folding_proof: NIMFSProof<G1, G2, C1, C2, RO>,
+    pub randomizer: Randomizer<G1, G2, C1, C2, RO>,
+
+    pub W: RelaxedR1CSWitness<G1>,
+    pub W_secondary: RelaxedR1CSWitness<G2>,
+}
+
+impl<G1, G2, C1, C2, RO> RandomizedInstances<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+{
+    pub(crate) fn new<SC, SP>(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC, SP>,
+        (U, W): (RelaxedR1CSInstance<G1, C1>, RelaxedR1CSWitness<G1>),
+        (U_secondary, W_secondary): (RelaxedR1CSInstance<G2, C2>, RelaxedR1CSWitness<G2>),
+        (u, w): (R1CSInstance<G1, C1>, R1CSWitness<G1>),
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<Self, cyclefold::Error>
+    where
+        SC: StepCircuit<G1::ScalarField>,
+        SP: Send + Sync,
+    {
+        let (folding_proof, (folded_U, folded_W), (folded_U_secondary, folded_W_secondary)) =
+            NIMFSProof::prove(
+                &params.pp,
+                &params.pp_secondary,
+                &params.ro_config,
+                &params.digest,
+                (&params.shape, &params.shape_secondary),
+                (&U, &W),
+                (&U_secondary, &W_secondary),
+                (&u, &w),
+            )?;
+
+        let (randomizer, (_, W), (_, W_secondary)) = Randomizer::prove(
+            params,
+            (&folded_U, &folded_W),
+            (&folded_U_secondary, &folded_W_secondary),
+            rng,
+        )?;
+
+        Ok(Self {
+            U,
+            U_secondary,
+            u,
+            folding_proof,
+            randomizer,
+            W,
+            W_secondary,
+        })
+    }
+
+    /// Checks that randomized witnesses satisfy instances folded from `U`, `U_secondary` and `u`.
+    ///
+    /// The caller is responsible for checking public io of `u`.
+    pub(crate) fn verify<SC, SP>(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC, SP>,
+    ) -> Result<(), cyclefold::Error>
+    where
+        SC: StepCircuit<G1::ScalarField>,
+        SP: Send + Sync,
+    {
+        let (U, U_secondary) = self.folding_proof.verify(
+            &params.ro_config,
+            &params.digest,
+            &self.U,
+            &self.U_secondary,
+            &self.u,
+        )?;
+        let (U, U_secondary) =
+            self.randomizer
+                .verify(&params.ro_config, &params.digest, &U, &U_secondary)?;
+
+        params.shape.is_relaxed_satisfied(&U, &self.W, &params.pp)?;
+        params.shape_secondary.is_relaxed_satisfied(
+            &U_secondary,
+            &self.W_secondary,
+            &params.pp_secondary,
+        )?;
+
+        Ok(())
+    }
+}
@@ -10,8 +10,9 @@ pub enum Error {
     R1CS(R1CSError),
     Synthesis(ark_relations::r1cs::SynthesisError),
 
-    #[cfg(any(test, feature = "spartan"))]
     InvalidPublicInput,
+    /// Public parameters were not set up in zero-knowledge mode.
+    ZKNotEnabled,
 }
 
 impl From<R1CSError> for Error {
@@ -31,8 +32,11 @@ impl Display for Error {
         match self {
             Self::R1CS(error) => write!(f, "{}", error),
             Self::Synthesis(error) => write!(f, "{}", error),
-            #[cfg(any(test, feature = "spartan"))]
             Self::InvalidPublicInput => write!(f, "invalid public input"),
+            Self::ZKNotEnabled => write!(
+                f,
+                "public parameters were not set up in zero-knowledge mode"
+            ),
         }
     }
 }
@@ -42,8 +46,7 @@ impl ark_std::error::Error for Error {
         match self {
             Self::R1CS(error) => error.source(),
             Self::Synthesis(error) => error.source(),
-            #[cfg(any(test, feature = "spartan"))]
-            Self::InvalidPublicInput => None,
+            Self::InvalidPublicInput | Self::ZKNotEnabled => None,
         }
     }
 }
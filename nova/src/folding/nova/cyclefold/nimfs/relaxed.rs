@@ -191,7 +191,6 @@ where
         Ok((proof, (folded_U, folded_W), (U_secondary, W_secondary)))
     }
 
-    #[cfg(test)]
     pub fn verify_with_relaxed(
         &self,
         config: &RO::Config,
@@ -94,7 +94,6 @@ where
         ))
     }
 
-    #[cfg(test)]
     pub fn verify_with_relaxed(
         &self,
         random_oracle: &mut RO,
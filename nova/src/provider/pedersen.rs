@@ -1,21 +1,271 @@
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData, ops::Deref};
 
 use ark_ec::{CurveGroup, ScalarMul, VariableBaseMSM};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::rand::SeedableRng;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::{
+    io::{Read, Write},
+    rand::SeedableRng,
+};
+use rand_chacha::ChaCha20Rng;
 use sha3::digest::{ExtendableOutput, Update, XofReader};
 
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
 use crate::{commitment::CommitmentScheme, LOG_TARGET};
 
+/// Written in place of the number of bases to mark the seeded encoding. It can't be the length
+/// of a serialized vector of bases.
+const SEEDED_MARKER: u64 = u64::MAX;
+
+/// Maximum number of bases derived when loading parameters in the seeded encoding.
+pub const MAX_SEEDED_BASES: usize = 1 << 26;
+
+/// Number of bases sampled from each seed, chunks of bases are derived in parallel.
+const BASES_PER_SEED: usize = 1 << 10;
+
 #[derive(Debug)]
 pub struct PedersenCommitment<G>(PhantomData<G>);
 
+/// Determines how [`PedersenParams`] are serialized.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BasesStorage {
+    /// Bases are written out in full, in the same encoding as a vector of bases.
+    #[default]
+    Full,
+    /// Only the label, the number of bases and a digest of the bases are written out, bases
+    /// are derived again on load and checked against the digest.
+    Seeded,
+}
+
+/// Commitment bases deterministically derived from a label.
+///
+/// Setup always returns parameters with [`BasesStorage::Full`], so that the digest of public
+/// parameters computed on setup covers the bases themselves rather than the label.
+pub struct PedersenParams<G: CurveGroup> {
+    label: Vec<u8>,
+    bases: Vec<G::MulBase>,
+    storage: BasesStorage,
+}
+
+impl<G: CurveGroup> Clone for PedersenParams<G> {
+    fn clone(&self) -> Self {
+        Self {
+            label: self.label.clone(),
+            bases: self.bases.clone(),
+            storage: self.storage,
+        }
+    }
+}
+
+impl<G: CurveGroup> PedersenParams<G> {
+    /// The label bases were derived from, empty for parameters loaded in the full encoding.
+    pub fn label(&self) -> &[u8] {
+        &self.label
+    }
+
+    pub fn storage(&self) -> BasesStorage {
+        self.storage
+    }
+
+    /// Sets how bases are serialized, this doesn't affect the bases themselves.
+    ///
+    /// Parameters loaded in the full encoding don't record their label and can't be switched
+    /// to seeded storage.
+    pub fn set_storage(&mut self, storage: BasesStorage) -> Result<(), MissingLabel> {
+        if storage == BasesStorage::Seeded && self.label.is_empty() {
+            return Err(MissingLabel);
+        }
+        self.storage = storage;
+        Ok(())
+    }
+}
+
+/// Returned when switching parameters which don't record their label to seeded storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingLabel;
+
+impl fmt::Display for MissingLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "seeded storage requires the label bases were derived from"
+        )
+    }
+}
+
+impl std::error::Error for MissingLabel {}
+
+impl<G: CurveGroup> Deref for PedersenParams<G> {
+    type Target = [G::MulBase];
+
+    fn deref(&self) -> &Self::Target {
+        &self.bases
+    }
+}
+
+/// Derives `n` bases from `label`.
+///
+/// Bases are sampled in chunks of [`BASES_PER_SEED`], each from a ChaCha stream seeded with
+/// the label and the index of the chunk, so that chunks can be derived in parallel and the
+/// first `n` bases don't depend on the total number of bases.
+fn derive_bases<G: CurveGroup>(label: &[u8], n: usize) -> Vec<G::MulBase> {
+    // adapted from a16z/jolt
+    //
+    // https://github.com/a16z/jolt/blob/a665343662c7082c33be4766298324db798cfaa9/jolt-core/src/poly/pedersen.rs#L18-L36
+    let mut generator = vec![];
+    G::generator().serialize_compressed(&mut generator).unwrap();
+
+    let chunks: Vec<Vec<G::MulBase>> = ark_std::cfg_into_iter!(0..n.div_ceil(BASES_PER_SEED))
+        .map(|chunk| {
+            let mut shake = sha3::Shake256::default();
+            shake.update(label);
+            shake.update(&generator);
+            shake.update(&(chunk as u64).to_le_bytes());
+
+            let mut seed = [0u8; 32];
+            XofReader::read(&mut shake.finalize_xof(), &mut seed);
+            let mut rng = ChaCha20Rng::from_seed(seed);
+
+            let len = BASES_PER_SEED.min(n - chunk * BASES_PER_SEED);
+            let gens: Vec<G> = (0..len).map(|_| G::rand(&mut rng)).collect();
+            ScalarMul::batch_convert_to_mul_base(&gens)
+        })
+        .collect();
+
+    let mut bases = Vec::with_capacity(n);
+    for chunk in chunks {
+        bases.extend(chunk);
+    }
+    bases
+}
+
+/// Digest of the compressed encoding of `bases`, stored in the seeded encoding to detect
+/// bases derived differently from when parameters were saved.
+fn bases_digest<B: CanonicalSerialize>(bases: &[B]) -> [u8; 32] {
+    let mut shake = sha3::Shake256::default();
+    let mut buf = vec![];
+    for base in bases {
+        buf.clear();
+        base.serialize_compressed(&mut buf).unwrap();
+        shake.update(&buf);
+    }
+    let mut digest = [0u8; 32];
+    XofReader::read(&mut shake.finalize_xof(), &mut digest);
+    digest
+}
+
+impl<G: CurveGroup> CanonicalSerialize for PedersenParams<G>
+where
+    G::MulBase: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        match self.storage {
+            BasesStorage::Full => self.bases.serialize_with_mode(writer, compress),
+            BasesStorage::Seeded => {
+                SEEDED_MARKER.serialize_with_mode(&mut writer, compress)?;
+                self.label.serialize_with_mode(&mut writer, compress)?;
+                (self.bases.len() as u64).serialize_with_mode(&mut writer, compress)?;
+                bases_digest(&self.bases).serialize_with_mode(writer, compress)
+            }
+        }
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        match self.storage {
+            BasesStorage::Full => self.bases.serialized_size(compress),
+            BasesStorage::Seeded => {
+                SEEDED_MARKER.serialized_size(compress)
+                    + self.label.serialized_size(compress)
+                    + (self.bases.len() as u64).serialized_size(compress)
+                    + [0u8; 32].serialized_size(compress)
+            }
+        }
+    }
+}
+
+impl<G: CurveGroup> Valid for PedersenParams<G>
+where
+    G::MulBase: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.bases.check()
+    }
+}
+
+impl<G: CurveGroup> CanonicalDeserialize for PedersenParams<G>
+where
+    G::MulBase: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let len = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        if len != SEEDED_MARKER {
+            // same as deserializing a vector of bases
+            let len = usize::try_from(len).map_err(|_| SerializationError::InvalidData)?;
+            let mut bases = Vec::new();
+            for _ in 0..len {
+                bases.push(G::MulBase::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?);
+            }
+            if validate == Validate::Yes {
+                G::MulBase::batch_check(bases.iter())?;
+            }
+            return Ok(Self {
+                label: Vec::new(),
+                bases,
+                storage: BasesStorage::Full,
+            });
+        }
+
+        let label = Vec::<u8>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let n = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        let digest = <[u8; 32]>::deserialize_with_mode(reader, compress, validate)?;
+
+        let n = usize::try_from(n)
+            .ok()
+            .filter(|&n| n <= MAX_SEEDED_BASES)
+            .ok_or(SerializationError::InvalidData)?;
+
+        let bases = {
+            let _span = tracing::debug_span!(
+                target: LOG_TARGET,
+                "pedersen::derive_bases",
+                ?n,
+            )
+            .entered();
+            derive_bases::<G>(&label, n)
+        };
+        if bases_digest(&bases) != digest {
+            return Err(SerializationError::InvalidData);
+        }
+
+        Ok(Self {
+            label,
+            bases,
+            storage: BasesStorage::Seeded,
+        })
+    }
+}
+
 impl<G> CommitmentScheme<G> for PedersenCommitment<G>
 where
     G: CurveGroup,
     G::MulBase: CanonicalSerialize + CanonicalDeserialize,
 {
-    type PP = Vec<G::MulBase>;
+    type PP = PedersenParams<G>;
     type SetupAux = ();
 
     type Commitment = G;
@@ -28,28 +278,14 @@ where
         )
         .entered();
 
-        // from a16z/jolt
-        //
-        // https://github.com/a16z/jolt/blob/a665343662c7082c33be4766298324db798cfaa9/jolt-core/src/poly/pedersen.rs#L18-L36
-        let mut shake = sha3::Shake256::default();
-        shake.update(label);
-        let mut buf = vec![];
-        G::generator().serialize_compressed(&mut buf).unwrap();
-        shake.update(&buf);
-
-        let mut reader = shake.finalize_xof();
-        let mut seed = [0u8; 32];
-        reader.read(&mut seed);
-        let mut rng = rand_chacha::ChaCha20Rng::from_seed(seed);
-
-        let mut gens = Vec::with_capacity(n);
-        for _ in 0..n {
-            gens.push(G::rand(&mut rng));
+        PedersenParams {
+            label: label.to_vec(),
+            bases: derive_bases::<G>(label, n),
+            storage: BasesStorage::Full,
         }
-        ScalarMul::batch_convert_to_mul_base(&gens)
     }
 
-    fn commit(bases: &Self::PP, scalars: &[G::ScalarField]) -> G {
+    fn commit(pp: &Self::PP, scalars: &[G::ScalarField]) -> G {
         let _span = tracing::debug_span!(
             target: LOG_TARGET,
             "pedersen::commit",
@@ -57,6 +293,91 @@ where
         )
         .entered();
 
-        VariableBaseMSM::msm_unchecked(bases, scalars)
+        VariableBaseMSM::msm_unchecked(&pp.bases, scalars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type G = ark_pallas::Projective;
+
+    #[test]
+    fn seeded_serialization_round_trip() {
+        let n = 100;
+        let mut pp = PedersenCommitment::<G>::setup(n, b"test", &());
+
+        let mut full = Vec::new();
+        pp.serialize_compressed(&mut full).unwrap();
+
+        pp.set_storage(BasesStorage::Seeded).unwrap();
+        let mut seeded = Vec::new();
+        pp.serialize_compressed(&mut seeded).unwrap();
+        assert!(seeded.len() < full.len());
+        assert_eq!(seeded.len(), pp.compressed_size());
+
+        let loaded = PedersenParams::<G>::deserialize_compressed(&full[..]).unwrap();
+        assert_eq!(loaded.storage(), BasesStorage::Full);
+        assert_eq!(&loaded[..], &pp[..]);
+
+        let loaded = PedersenParams::<G>::deserialize_compressed(&seeded[..]).unwrap();
+        assert_eq!(loaded.storage(), BasesStorage::Seeded);
+        assert_eq!(loaded.label(), b"test");
+        assert_eq!(&loaded[..], &pp[..]);
+    }
+
+    #[test]
+    fn full_encoding_is_vector_of_bases() {
+        let pp = PedersenCommitment::<G>::setup(10, b"test", &());
+
+        let mut bases = Vec::new();
+        pp[..].to_vec().serialize_compressed(&mut bases).unwrap();
+
+        let mut full = Vec::new();
+        pp.serialize_compressed(&mut full).unwrap();
+        assert_eq!(full, bases);
+    }
+
+    #[test]
+    fn seeded_encoding_is_checked() {
+        let mut pp = PedersenCommitment::<G>::setup(10, b"test", &());
+        pp.set_storage(BasesStorage::Seeded).unwrap();
+        let mut seeded = Vec::new();
+        pp.serialize_compressed(&mut seeded).unwrap();
+
+        // digest is the last field
+        let mut bad = seeded.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        assert!(PedersenParams::<G>::deserialize_compressed(&bad[..]).is_err());
+
+        // number of bases precedes the digest
+        let mut bad = seeded.clone();
+        let at = bad.len() - 32 - 8;
+        bad[at..at + 8].copy_from_slice(&(MAX_SEEDED_BASES as u64 + 1).to_le_bytes());
+        assert!(matches!(
+            PedersenParams::<G>::deserialize_compressed(&bad[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn bases_do_not_depend_on_length() {
+        let short = PedersenCommitment::<G>::setup(10, b"test", &());
+        let long = PedersenCommitment::<G>::setup(BASES_PER_SEED + 10, b"test", &());
+
+        assert_eq!(&short[..], &long[..10]);
+        assert_ne!(long[BASES_PER_SEED], long[0]);
+    }
+
+    #[test]
+    fn full_encoding_cannot_be_seeded() {
+        let pp = PedersenCommitment::<G>::setup(10, b"test", &());
+        let mut full = Vec::new();
+        pp.serialize_compressed(&mut full).unwrap();
+
+        let mut loaded = PedersenParams::<G>::deserialize_compressed(&full[..]).unwrap();
+        assert_eq!(loaded.set_storage(BasesStorage::Seeded), Err(MissingLabel));
+        assert_eq!(loaded.storage(), BasesStorage::Full);
     }
 }
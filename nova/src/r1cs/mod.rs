@@ -5,7 +5,11 @@ use ark_ec::{AdditiveGroup, CurveGroup};
 use ark_ff::{Field, PrimeField};
 use ark_relations::r1cs::ConstraintSystemRef;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::fmt::Display;
+use ark_std::{
+    fmt::Display,
+    rand::{CryptoRng, RngCore},
+    UniformRand,
+};
 
 #[cfg(feature = "parallel")]
 use rayon::iter::{
@@ -184,6 +188,40 @@ impl<G: CurveGroup> R1CSShape<G> {
 
         Ok(())
     }
+
+    /// Samples a random relaxed R1CS instance together with a witness satisfying it.
+    ///
+    /// `W` and `X`, including `u`, are uniformly random, and `E` is the error term making
+    /// the constraints hold.
+    pub fn random_relaxed<C: CommitmentScheme<G>>(
+        &self,
+        pp: &C::PP,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (RelaxedR1CSInstance<G, C>, RelaxedR1CSWitness<G>) {
+        let W: Vec<G::ScalarField> = (0..self.num_vars)
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+        let X: Vec<G::ScalarField> = (0..self.num_io)
+            .map(|_| G::ScalarField::rand(rng))
+            .collect();
+
+        let z = [X.as_slice(), W.as_slice()].concat();
+        let Az = self.A.multiply_vec(&z);
+        let Bz = self.B.multiply_vec(&z);
+        let Cz = self.C.multiply_vec(&z);
+
+        let u = X[0];
+        let E: Vec<G::ScalarField> = ark_std::cfg_into_iter!(0..self.num_constraints)
+            .map(|idx| Az[idx] * Bz[idx] - u * Cz[idx])
+            .collect();
+
+        let (commitment_W, commitment_E) = (C::commit(pp, &W), C::commit(pp, &E));
+
+        (
+            RelaxedR1CSInstance { commitment_W, commitment_E, X },
+            RelaxedR1CSWitness { W, E },
+        )
+    }
 }
 
 impl<G: CurveGroup> From<ConstraintSystemRef<G::ScalarField>> for R1CSShape<G> {
@@ -887,4 +925,43 @@ pub(crate) mod tests {
         shape.is_relaxed_satisfied(&folded_U, &folded_W, &pp)?;
         Ok(())
     }
+
+    #[test]
+    fn random_relaxed_is_satisfied() -> Result<(), Error> {
+        let (a, b, c) = {
+            (
+                to_field_sparse::<G>(A),
+                to_field_sparse::<G>(B),
+                to_field_sparse::<G>(C),
+            )
+        };
+
+        const NUM_CONSTRAINTS: usize = 4;
+        const NUM_WITNESS: usize = 4;
+        const NUM_PUBLIC: usize = 2;
+
+        let pp = PedersenCommitment::<G>::setup(NUM_WITNESS, b"test", &());
+        let shape = R1CSShape::<G>::new(NUM_CONSTRAINTS, NUM_WITNESS, NUM_PUBLIC, &a, &b, &c)?;
+
+        let mut rng = ark_std::test_rng();
+        let (U1, W1) = shape.random_relaxed::<PedersenCommitment<G>>(&pp, &mut rng);
+        shape.is_relaxed_satisfied(&U1, &W1, &pp)?;
+
+        // Folding a random instance into a satisfied one keeps it satisfied.
+        let X = to_field_elements::<G>(&[1, 35]);
+        let W = to_field_elements::<G>(&[3, 9, 27, 30]);
+        let commitment_W = PedersenCommitment::<G>::commit(&pp, &W);
+        let u = R1CSInstance::<G, PedersenCommitment<G>>::new(&shape, &commitment_W, &X)?;
+        let w = R1CSWitness::<G>::new(&shape, &W)?;
+        let U2 = RelaxedR1CSInstance::from(&u);
+        let W2 = RelaxedR1CSWitness::from_r1cs_witness(&shape, &w);
+
+        let r = Scalar::from(3u64);
+        let (T, comm_T) = commit_T_with_relaxed(&shape, &pp, &U2, &W2, &U1, &W1)?;
+        let folded_U = U2.fold_with_relaxed(&U1, &comm_T, &r)?;
+        let folded_W = W2.fold_with_relaxed(&W1, &T, &r)?;
+
+        shape.is_relaxed_satisfied(&folded_U, &folded_W, &pp)?;
+        Ok(())
+    }
 }
@@ -1,13 +1,28 @@
 #!/bin/sh
+# Assemble the startup code in asm.S into the runtime archives in bin/.
+#
+# Requires llvm-mc and llvm-ar; set LLVM_MC and AR to override.
+# The archives are checked by the `runtime_args` test of nexus-vm, which
+# runs the guest in testdata/args; rebuild it with testdata/args/build.sh.
 
 set -e
+cd "$(dirname "$0")"
+
+LLVM_MC=${LLVM_MC:-llvm-mc}
+AR=${AR:-llvm-ar}
 
 rm -f bin/*.a
 
 for ext in i imc
 do
-    riscv-none-elf-gcc -c -mabi=ilp32 -march=rv32${ext} -mcmodel=medlow asm.S -o bin/nexus-rt.o
-    riscv-none-elf-ar crs bin/riscv32${ext}-unknown-none-elf.a bin/nexus-rt.o
+    case $ext in
+        i) attrs=+relax ;;
+        imc) attrs=+m,+c,+relax ;;
+    esac
+    # record the target in .riscv.attributes, which llvm-mc only does when asked to
+    { echo ".attribute arch, \"rv32${ext}\""; cat asm.S; } |
+        $LLVM_MC -triple=riscv32 -target-abi=ilp32 -mattr=$attrs -filetype=obj -o bin/nexus-rt.o
+    $AR crs bin/riscv32${ext}-unknown-none-elf.a bin/nexus-rt.o
 done
 
 rm bin/nexus-rt.o
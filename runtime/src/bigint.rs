@@ -0,0 +1,78 @@
+//! 256-bit integer arithmetic, evaluated by the host and checked by
+//! the proof.
+//!
+//! Integers are stored as 8 32-bit words, least significant first.
+//! Each operation costs a single `ecall`. The operands are passed in
+//! a0-a7, s4-s11 and, for the modulus, t0-t2, s3 and t3-t6. The result
+//! is returned in a0-a7 and, for its high half, s4-s11, where the step
+//! circuit checks it (see `nexus_vm::syscalls::PRECOMPILE_REGS`).
+//!
+//! The step circuit only checks the precompile when it is selected, so
+//! programs using this module must be run and proved with
+//! `--precompiles bigint`, and public parameters set up with it.
+
+/// A 256-bit integer, least significant word first.
+pub type U256 = [u32; 8];
+
+// ecall numbers, see `nexus_vm::syscalls::Syscalls::syscall`
+const ADD: u32 = 12;
+const MUL: u32 = 13;
+const MODMUL: u32 = 14;
+
+// Returns the values of a0-a7 and s4-s11 after the ecall. The curve
+// precompiles of `crypto` take their operands in the same registers.
+pub(crate) fn precompile(num: u32, a: &U256, b: &U256, m: &U256) -> (U256, U256) {
+    let mut lo = *a;
+    let mut hi = *b;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            in("s2") num,
+            inout("a0") lo[0],
+            inout("a1") lo[1],
+            inout("a2") lo[2],
+            inout("a3") lo[3],
+            inout("a4") lo[4],
+            inout("a5") lo[5],
+            inout("a6") lo[6],
+            inout("a7") lo[7],
+            inout("s4") hi[0],
+            inout("s5") hi[1],
+            inout("s6") hi[2],
+            inout("s7") hi[3],
+            inout("s8") hi[4],
+            inout("s9") hi[5],
+            inout("s10") hi[6],
+            inout("s11") hi[7],
+            in("t0") m[0],
+            in("t1") m[1],
+            in("t2") m[2],
+            in("s3") m[3],
+            in("t3") m[4],
+            in("t4") m[5],
+            in("t5") m[6],
+            in("t6") m[7],
+        )
+    }
+    (lo, hi)
+}
+
+/// Returns `a + b` modulo 2^256, and whether the addition overflowed.
+pub fn add(a: &U256, b: &U256) -> (U256, bool) {
+    let (s, carry) = precompile(ADD, a, b, &[0; 8]);
+    (s, carry[0] != 0)
+}
+
+/// Returns `a * b`, as 16 words, least significant first.
+pub fn mul(a: &U256, b: &U256) -> [u32; 16] {
+    let (lo, hi) = precompile(MUL, a, b, &[0; 8]);
+    let mut out = [0; 16];
+    out[..8].copy_from_slice(&lo);
+    out[8..].copy_from_slice(&hi);
+    out
+}
+
+/// Returns `a * b mod m`. The host reports an error if `m` is zero.
+pub fn modmul(a: &U256, b: &U256, m: &U256) -> U256 {
+    precompile(MODMUL, a, b, m).0
+}
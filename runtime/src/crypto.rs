@@ -0,0 +1,93 @@
+//! Signature verification, with the curve arithmetic evaluated by the
+//! host and checked by the proof.
+//!
+//! Point additions and doublings are precompiles, which take their
+//! operands in the registers used by `bigint` (see
+//! `nexus_vm::precompiles::{secp256k1, ed25519}`). The guest computes
+//! scalar multiplications from them, and field and scalar arithmetic
+//! with `bigint::modmul`, so a verification costs a few thousand
+//! `ecall`s rather than millions of instructions.
+//!
+//! The step circuit only checks the precompiles which are selected, so
+//! programs verifying ECDSA signatures must be run and proved with
+//! `--precompiles bigint,secp256k1`, programs verifying Ed25519
+//! signatures with `--precompiles bigint,ed25519`, and public
+//! parameters set up with them. Requires the `crypto` feature.
+
+pub mod ed25519;
+pub mod secp256k1;
+
+pub use ed25519::verify as ed25519_verify;
+pub use secp256k1::verify as secp256k1_verify;
+
+use crate::bigint::{modmul, U256};
+
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+fn from_be_bytes(b: &[u8]) -> U256 {
+    core::array::from_fn(|i| {
+        let j = 28 - 4 * i;
+        u32::from_be_bytes(b[j..j + 4].try_into().unwrap())
+    })
+}
+
+fn from_le_bytes(b: &[u8]) -> U256 {
+    core::array::from_fn(|i| u32::from_le_bytes(b[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+fn to_le_bytes(x: &U256) -> [u8; 32] {
+    core::array::from_fn(|i| x[i / 4].to_le_bytes()[i % 4])
+}
+
+fn bit(x: &U256, i: usize) -> bool {
+    (x[i / 32] >> (i % 32)) & 1 == 1
+}
+
+fn lt(a: &U256, b: &U256) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+// Returns `a - b` modulo 2^256, and whether the subtraction borrowed.
+fn sub(a: &U256, b: &U256) -> (U256, bool) {
+    let mut d = [0; 8];
+    let mut borrow = false;
+    for i in 0..8 {
+        let (x, b1) = a[i].overflowing_sub(b[i]);
+        let (x, b2) = x.overflowing_sub(borrow as u32);
+        d[i] = x;
+        borrow = b1 || b2;
+    }
+    (d, borrow)
+}
+
+// `a - b mod m`, for `a` and `b` less than `m`
+fn modsub(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (d, borrow) = sub(a, b);
+    if borrow {
+        sub(&d, &sub(&[0; 8], m).0).0
+    } else {
+        d
+    }
+}
+
+// `a + b mod m`, for `a` and `b` less than `m`
+fn modadd(a: &U256, b: &U256, m: &U256) -> U256 {
+    modsub(a, &modsub(&[0; 8], b, m), m)
+}
+
+// `x^e mod m`
+fn modpow(x: &U256, e: &U256, m: &U256) -> U256 {
+    let mut r = modmul(&ONE, &ONE, m);
+    for i in (0..256).rev() {
+        r = modmul(&r, &r, m);
+        if bit(e, i) {
+            r = modmul(&r, x, m);
+        }
+    }
+    r
+}
+
+// `x^-1 mod m`, for a prime `m`
+fn modinv(x: &U256, m: &U256) -> U256 {
+    modpow(x, &sub(m, &[2, 0, 0, 0, 0, 0, 0, 0]).0, m)
+}
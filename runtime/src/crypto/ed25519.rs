@@ -0,0 +1,149 @@
+//! Ed25519, over the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2.
+//!
+//! Points are affine, with coordinates stored as 8 32-bit words,
+//! least significant first, and fully reduced.
+
+use sha2::{Digest, Sha512};
+
+use super::{bit, from_le_bytes, lt, modadd, modpow, modsub, to_le_bytes, ONE};
+use crate::bigint::{modmul, precompile, U256};
+
+// ecall number, see `nexus_vm::syscalls::Syscalls::syscall`
+const ADD: u32 = 19;
+
+/// The prime of the base field, 2^255 - 19.
+pub const P: U256 = [
+    0xffffffed, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x7fffffff,
+];
+
+/// The order of the base point, 2^252 + 27742317777372353535851937790883648493.
+pub const L: U256 = [
+    0x5cf5d3ed, 0x5812631a, 0xa2f79cd6, 0x14def9de, 0x00000000, 0x00000000, 0x00000000, 0x10000000,
+];
+
+/// The identity, (0, 1).
+pub const IDENTITY: Point = ([0; 8], ONE);
+
+/// The encoding of the base point, whose x coordinate is even.
+pub const BASE: U256 = [
+    0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+];
+
+// the coefficient d of the curve equation, -121665 / 121666
+const D: U256 = [
+    0x135978a3, 0x75eb4dca, 0x4141d8ab, 0x00700a4d, 0x7779e898, 0x8cc74079, 0x2b6ffe73, 0x52036cee,
+];
+
+// a square root of -1, 2^((p - 1) / 4)
+const SQRT_M1: U256 = [
+    0x4a0ea0b0, 0xc4ee1b27, 0xad2fe478, 0x2f431806, 0x3dfbd7a7, 0x2b4d0099, 0x4fc1df0b, 0x2b832480,
+];
+
+// (p - 5) / 8
+const SQRT_EXP: U256 = [
+    0xfffffffd, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x0fffffff,
+];
+
+// 2^256 mod l
+const R256: U256 = [
+    0x8d98951d, 0xd6ec3174, 0x737dcf70, 0xc6ef5bf4, 0xfffffffe, 0xffffffff, 0xffffffff, 0x0fffffff,
+];
+
+/// A point on the curve, as its coordinates `(x, y)`.
+pub type Point = (U256, U256);
+
+fn mul(a: &U256, b: &U256) -> U256 {
+    modmul(a, b, &P)
+}
+
+fn neg(x: &U256) -> U256 {
+    modsub(&[0; 8], x, &P)
+}
+
+/// Returns `p + q`, where `q` is given by its encoding. The addition
+/// formula is complete, so `q` may be the encoding of `p`. `p` must be
+/// on the curve and `q` the encoding of a point, otherwise the host
+/// reports an error.
+pub fn add(p: &Point, q: &U256) -> Point {
+    precompile(ADD, &p.0, &p.1, q)
+}
+
+/// Returns the encoding of `p`, as in RFC 8032: its y coordinate, with
+/// the parity of its x coordinate in bit 255.
+pub fn encode(p: &Point) -> U256 {
+    let mut q = p.1;
+    q[7] |= (p.0[0] & 1) << 31;
+    q
+}
+
+/// Returns the point with encoding `q`, or `None` if there is no such
+/// point. As with the `ed25519-dalek` crate, the y coordinate is
+/// reduced modulo p, and an x coordinate of zero may be encoded as
+/// negative.
+pub fn decode(q: &U256) -> Option<Point> {
+    let mut y = *q;
+    y[7] &= 0x7fff_ffff;
+    let y = mul(&y, &ONE);
+
+    // x^2 = u / v, with u = y^2 - 1 and v = d y^2 + 1, which is not
+    // zero as d is not a square; a square root is u v^3 (u v^7)^((p-5)/8),
+    // times the square root of -1 if its square is -u / v
+    let yy = mul(&y, &y);
+    let u = modsub(&yy, &ONE, &P);
+    let v = modadd(&mul(&D, &yy), &ONE, &P);
+    let v3 = mul(&mul(&v, &v), &v);
+    let v7 = mul(&mul(&v3, &v3), &v);
+    let mut x = mul(&mul(&u, &v3), &modpow(&mul(&u, &v7), &SQRT_EXP, &P));
+    let vxx = mul(&v, &mul(&x, &x));
+    if vxx != u {
+        if vxx != neg(&u) {
+            return None;
+        }
+        x = mul(&x, &SQRT_M1);
+    }
+    if x[0] & 1 != q[7] >> 31 {
+        x = neg(&x);
+    }
+    Some((x, y))
+}
+
+/// Verify an Ed25519 signature of `msg` with public key `key`, as
+/// specified by RFC 8032, and with the checks of the `ed25519-dalek`
+/// crate: `s` must be less than l, and the key must be the encoding of
+/// a point. Returns false if the signature or key is malformed.
+pub fn verify(msg: &[u8], sig: &[u8; 64], key: &[u8; 32]) -> bool {
+    let Some(a) = decode(&from_le_bytes(key)) else {
+        return false;
+    };
+    let s = from_le_bytes(&sig[32..]);
+    if !lt(&s, &L) {
+        return false;
+    }
+
+    // k = SHA-512(R || A || msg) mod l, where the digest is read as
+    // lo + 2^256 hi
+    let h = Sha512::new()
+        .chain_update(&sig[..32])
+        .chain_update(key)
+        .chain_update(msg)
+        .finalize();
+    let lo = modmul(&from_le_bytes(&h[..32]), &ONE, &L);
+    let hi = modmul(&from_le_bytes(&h[32..]), &R256, &L);
+    let k = modadd(&lo, &hi, &L);
+
+    // the signature is valid if s B - k A encodes to R; s and k are
+    // less than l < 2^253
+    let minus_a = encode(&(neg(&a.0), a.1));
+    let b_minus_a = encode(&add(&(neg(&a.0), a.1), &BASE));
+    let mut acc = IDENTITY;
+    for i in (0..253).rev() {
+        acc = add(&acc, &encode(&acc));
+        match (bit(&s, i), bit(&k, i)) {
+            (true, true) => acc = add(&acc, &b_minus_a),
+            (true, false) => acc = add(&acc, &BASE),
+            (false, true) => acc = add(&acc, &minus_a),
+            (false, false) => (),
+        }
+    }
+    to_le_bytes(&encode(&acc)) == sig[..32]
+}
@@ -0,0 +1,159 @@
+//! ECDSA over secp256k1, the curve y^2 = x^3 + 7.
+//!
+//! Points are affine, with coordinates stored as 8 32-bit words,
+//! least significant first, and fully reduced.
+
+use super::{bit, from_be_bytes, lt, modadd, modinv, modpow, modsub, sub, ONE};
+use crate::bigint::{modmul, precompile, U256};
+
+// ecall numbers, see `nexus_vm::syscalls::Syscalls::syscall`
+const ADD: u32 = 17;
+const DOUBLE: u32 = 18;
+
+/// The prime of the base field, 2^256 - 2^32 - 977.
+pub const P: U256 = [
+    0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+/// The order of the generator.
+pub const N: U256 = [
+    0xd0364141, 0xbfd25e8c, 0xaf48a03b, 0xbaaedce6, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+/// The generator.
+pub const G: Point = Some((
+    [
+        0x16f81798, 0x59f2815b, 0x2dce28d9, 0x029bfcdb, 0xce870b07, 0x55a06295, 0xf9dcbbac,
+        0x79be667e,
+    ],
+    [
+        0xfb10d4b8, 0x9c47d08f, 0xa6855419, 0xfd17b448, 0x0e1108a8, 0x5da4fbfc, 0x26a3c465,
+        0x483ada77,
+    ],
+));
+
+// (n - 1) / 2, the largest s which is not high
+const HALF_N: U256 = [
+    0x681b20a0, 0xdfe92f46, 0x57a4501d, 0x5d576e73, 0xffffffff, 0xffffffff, 0xffffffff, 0x7fffffff,
+];
+
+// (p + 1) / 4, as p = 3 mod 4
+const SQRT_EXP: U256 = [
+    0xbfffff0c, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x3fffffff,
+];
+
+/// A point on the curve, `None` being the point at infinity.
+pub type Point = Option<(U256, U256)>;
+
+fn mul(a: &U256, b: &U256) -> U256 {
+    modmul(a, b, &P)
+}
+
+fn neg(y: &U256) -> U256 {
+    modsub(&[0; 8], y, &P)
+}
+
+// x^3 + 7
+fn rhs(x: &U256) -> U256 {
+    modadd(&mul(&mul(x, x), x), &[7, 0, 0, 0, 0, 0, 0, 0], &P)
+}
+
+/// Returns `p + q`. Both points must be on the curve, otherwise the
+/// host reports an error.
+pub fn add(p: &Point, q: &Point) -> Point {
+    let (Some((x1, y1)), Some((x2, y2))) = (p, q) else {
+        return p.or(*q);
+    };
+    if x1 == x2 {
+        return if y1 == y2 { double(p) } else { None };
+    }
+    // the precompile takes the point with x coordinate x2 and an even
+    // y coordinate; otherwise p + q = -(-p + -q)
+    if y2[0] & 1 == 0 {
+        Some(precompile(ADD, x1, y1, x2))
+    } else {
+        let (x3, y3) = precompile(ADD, x1, &neg(y1), x2);
+        Some((x3, neg(&y3)))
+    }
+}
+
+/// Returns `2 p`. The point must be on the curve, otherwise the host
+/// reports an error.
+pub fn double(p: &Point) -> Point {
+    // there is no point of order 2, so y is not zero
+    let (x, y) = (*p)?;
+    Some(precompile(DOUBLE, &x, &y, &[0; 8]))
+}
+
+// Decode a SEC1 public key, compressed or not.
+fn decode_key(key: &[u8]) -> Point {
+    let x = from_be_bytes(key.get(1..33)?);
+    if !lt(&x, &P) {
+        return None;
+    }
+    let y = match (key[0], key.len()) {
+        (2 | 3, 33) => {
+            let c = rhs(&x);
+            let y = modpow(&c, &SQRT_EXP, &P);
+            if mul(&y, &y) != c {
+                return None;
+            }
+            if (y[0] & 1) as u8 == key[0] & 1 {
+                y
+            } else {
+                neg(&y)
+            }
+        }
+        (4, 65) => {
+            let y = from_be_bytes(&key[33..]);
+            if !lt(&y, &P) || mul(&y, &y) != rhs(&x) {
+                return None;
+            }
+            y
+        }
+        _ => return None,
+    };
+    Some((x, y))
+}
+
+/// Verify an ECDSA signature over secp256k1 of the message hash
+/// `hash`. The signature holds `r` and `s` as 32-byte big-endian
+/// integers, and `key` is a SEC1 encoded public key, either
+/// compressed (33 bytes) or not (65 bytes). As with the `k256` crate,
+/// signatures with a high `s` (greater than n / 2) are rejected.
+/// Returns false if the signature or key is malformed.
+pub fn verify(hash: &[u8; 32], sig: &[u8; 64], key: &[u8]) -> bool {
+    let q = decode_key(key);
+    if q.is_none() {
+        return false;
+    }
+    let r = from_be_bytes(&sig[..32]);
+    let s = from_be_bytes(&sig[32..]);
+    if r == [0; 8] || !lt(&r, &N) || s == [0; 8] || lt(&HALF_N, &s) {
+        return false;
+    }
+
+    // R = u1 G + u2 Q, with u1 = e / s and u2 = r / s
+    let e = modmul(&from_be_bytes(hash), &ONE, &N);
+    let w = modinv(&s, &N);
+    let u1 = modmul(&e, &w, &N);
+    let u2 = modmul(&r, &w, &N);
+    let gq = add(&G, &q);
+    let mut acc = None;
+    for i in (0..256).rev() {
+        acc = double(&acc);
+        match (bit(&u1, i), bit(&u2, i)) {
+            (true, true) => acc = add(&acc, &gq),
+            (true, false) => acc = add(&acc, &G),
+            (false, true) => acc = add(&acc, &q),
+            (false, false) => (),
+        }
+    }
+
+    // the signature is valid if r is the x coordinate of R, mod n
+    let Some((x, _)) = acc else {
+        return false;
+    };
+    let x = if lt(&x, &N) { x } else { sub(&x, &N).0 };
+    x == r
+}
@@ -31,6 +31,138 @@ pub fn read_from_private_input() -> Option<u8> {
     } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
 }
 
+/// Report the heap usage statistics of the program so far to the
+/// host, which displays them at the end of verbose runs.
+pub fn report_heap_stats() {
+    let stats = crate::heap_stats();
+    let mut _out: u32;
+    ecall!(
+        3,
+        &stats as *const crate::HeapStats,
+        core::mem::size_of::<crate::HeapStats>(),
+        _out
+    );
+}
+
+// Notify the host that the program panicked. Log output written
+// after this call is reported by the host as the panic message.
+pub(crate) fn begin_panic() {
+    let mut _out: u32;
+    ecall!(4, 0, 0, _out);
+}
+
+/// Request hint `id` from the host, passing `args`, and read the
+/// result into `out`. Returns the length of the result, which may be
+/// larger than `out`, in which case the remaining words are dropped.
+///
+/// Hints are computed by the host and are not checked by the proof:
+/// the program must check that the result is correct, e.g. that the
+/// square of a square root hint equals its argument. At most 65536
+/// arguments may be passed.
+pub fn hint(id: u32, args: &[u32], out: &mut [u32]) -> usize {
+    let len: u32;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            in("s2") 8,
+            in("a1") id,
+            in("a2") args.as_ptr(),
+            in("a3") args.len(),
+            lateout("a0") len,
+        )
+    }
+    for x in out.iter_mut().take(len as usize) {
+        let inp: u32 = 0;
+        let mut w: u32;
+        ecall!(9, inp, inp, w);
+        *x = w;
+    }
+    len as usize
+}
+
+/// Returns the number of instructions executed by the program so far.
+pub fn cycle_count() -> u32 {
+    let inp: u32 = 0;
+    let mut out: u32;
+    ecall!(5, inp, inp, out);
+    out
+}
+
+#[doc(hidden)]
+pub fn profile_start(name: &str) {
+    let mut _out: u32;
+    ecall!(6, name.as_ptr(), name.len(), _out);
+}
+
+#[doc(hidden)]
+pub fn profile_end(name: &str) {
+    let mut _out: u32;
+    ecall!(7, name.as_ptr(), name.len(), _out);
+}
+
+/// Marks the start of a profiled region. The host reports the number
+/// of times each region was executed, and the number of instructions
+/// executed within it, at the end of verbose execution (`cargo nexus
+/// run -v`), or through `Syscalls::profile`.
+#[macro_export]
+macro_rules! profile_start {
+    ($name:expr) => {
+        $crate::profile_start($name)
+    };
+}
+
+/// Marks the end of a profiled region started by `profile_start!`.
+#[macro_export]
+macro_rules! profile_end {
+    ($name:expr) => {
+        $crate::profile_end($name)
+    };
+}
+
+/// Returns the number of bytes left in input stream `id`. Stream 0
+/// holds the private input.
+pub fn stream_len(id: u32) -> usize {
+    let len: u32;
+    unsafe { core::arch::asm!("ecall", in("s2") 10, in("a1") id, lateout("a0") len) }
+    len as usize
+}
+
+/// Read bytes from input stream `id` into `buf`. Returns the number
+/// of bytes read, which is less than `buf.len()` only if the stream
+/// is exhausted.
+///
+/// The host does not copy the bytes into `buf`: each `ecall` returns
+/// at most 32 bytes, so reading `n` bytes takes `n.div_ceil(32)`
+/// `ecall`s.
+pub fn read_stream(id: u32, buf: &mut [u8]) -> usize {
+    let n = buf.len().min(stream_len(id));
+
+    // Each call reads up to 32 bytes, which the host returns in
+    // a0-a7. They are stored to memory here, as writes to memory by
+    // the host would not be covered by the memory proofs.
+    for chunk in buf[..n].chunks_mut(32) {
+        let mut w = [0u32; 8];
+        unsafe {
+            core::arch::asm!(
+                "ecall",
+                in("s2") 11,
+                lateout("a0") w[0],
+                inout("a1") id => w[1],
+                inout("a2") chunk.len() => w[2],
+                lateout("a3") w[3],
+                lateout("a4") w[4],
+                lateout("a5") w[5],
+                lateout("a6") w[6],
+                lateout("a7") w[7],
+            )
+        }
+        for (b, x) in chunk.iter_mut().zip(w.iter().flat_map(|w| w.to_le_bytes())) {
+            *b = x;
+        }
+    }
+    n
+}
+
 /// An empty type representing the VM terminal
 pub struct NexusLog;
 
@@ -0,0 +1,165 @@
+// Memory allocation.
+//
+// The heap starts at the end of the program data, and grows towards
+// the stack. By default, memory is never deallocated: each allocation
+// grows the heap. With the `free-list` feature, allocations are
+// rounded up to a power of two, and freed blocks are kept on a free
+// list for their size class, to be reused by later allocations.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr::{addr_of, addr_of_mut};
+
+/// Heap usage statistics
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeapStats {
+    /// Number of bytes currently allocated
+    pub allocated: u32,
+    /// Largest number of bytes allocated at any time
+    pub peak: u32,
+    /// Number of bytes taken from the heap, including freed blocks
+    pub heap_size: u32,
+    /// Number of allocations
+    pub allocs: u32,
+    /// Number of deallocations
+    pub frees: u32,
+}
+
+impl HeapStats {
+    const fn new() -> Self {
+        Self {
+            allocated: 0,
+            peak: 0,
+            heap_size: 0,
+            allocs: 0,
+            frees: 0,
+        }
+    }
+
+    fn on_alloc(&mut self, size: usize) {
+        self.allocated += size as u32;
+        self.peak = self.peak.max(self.allocated);
+        self.allocs += 1;
+    }
+
+    fn on_free(&mut self, size: usize) {
+        self.allocated -= size as u32;
+        self.frees += 1;
+    }
+}
+
+// number of size classes; the largest holds blocks of 2^31 bytes,
+// which is more than any `Layout` can describe on RV32
+#[cfg(feature = "free-list")]
+const CLASSES: usize = 29;
+
+struct State {
+    stats: HeapStats,
+    #[cfg(feature = "free-list")]
+    free: [*mut u8; CLASSES],
+}
+
+struct Heap(UnsafeCell<State>);
+
+// The VM is single-threaded.
+unsafe impl Sync for Heap {}
+
+#[global_allocator]
+static HEAP: Heap = Heap(UnsafeCell::new(State {
+    stats: HeapStats::new(),
+    #[cfg(feature = "free-list")]
+    free: [core::ptr::null_mut(); CLASSES],
+}));
+
+/// Returns the heap usage statistics of the program so far.
+pub fn heap_stats() -> HeapStats {
+    unsafe { (*HEAP.0.get()).stats }
+}
+
+// Take `size` bytes aligned to `align` from the heap, aborting if
+// the heap would collide with the stack. The current end of the heap
+// is stored at `_heap` (see the linker script).
+unsafe fn grow(stats: &mut HeapStats, size: usize, align: usize) -> *mut u8 {
+    extern "C" {
+        static mut _heap: usize;
+        fn abort() -> !;
+    }
+
+    let top = addr_of!(_heap).read();
+    let start = (top + align - 1) & !(align - 1);
+    let end = start.checked_add(size).unwrap_or(usize::MAX);
+
+    let sp: usize;
+    core::arch::asm!("mv {}, sp", out(reg) sp);
+    if end >= sp {
+        abort();
+    }
+
+    addr_of_mut!(_heap).write(end);
+    stats.heap_size += (end - top) as u32;
+    start as *mut u8
+}
+
+#[cfg(not(feature = "free-list"))]
+unsafe impl GlobalAlloc for Heap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let state = &mut *self.0.get();
+        let sz = (layout.size() + 3) & !3;
+        let ptr = grow(&mut state.stats, sz, layout.align());
+        state.stats.on_alloc(sz);
+        ptr
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, layout: Layout) {
+        let state = &mut *self.0.get();
+        state.stats.on_free((layout.size() + 3) & !3);
+    }
+}
+
+// size class of blocks for `layout`; blocks of class `k` hold 8 << k bytes
+#[cfg(feature = "free-list")]
+fn class(layout: &Layout) -> usize {
+    let sz = layout.size().max(layout.align()).max(8);
+    (sz.next_power_of_two().trailing_zeros() - 3) as usize
+}
+
+#[cfg(feature = "free-list")]
+unsafe impl GlobalAlloc for Heap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let state = &mut *self.0.get();
+        let k = class(&layout);
+        let sz = 8 << k;
+
+        // reuse a free block, unless it is not aligned as required
+        let head = state.free[k];
+        let ptr = if !head.is_null() && head as usize % layout.align() == 0 {
+            state.free[k] = *(head as *mut *mut u8);
+            head
+        } else {
+            grow(&mut state.stats, sz, layout.align().max(4))
+        };
+        state.stats.on_alloc(sz);
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let state = &mut *self.0.get();
+        let k = class(&layout);
+        *(ptr as *mut *mut u8) = state.free[k];
+        state.free[k] = ptr;
+        state.stats.on_free(8 << k);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        if class(&new_layout) == class(&layout) {
+            return ptr;
+        }
+
+        let new_ptr = self.alloc(new_layout);
+        core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        self.dealloc(ptr, layout);
+        new_ptr
+    }
+}
@@ -4,21 +4,35 @@
 // Nexus VM runtime environment
 // Note: adapted from riscv-rt, which was adapted from cortex-m.
 
-use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
 
 pub use nexus_rt_macros::main;
 
+pub mod bigint;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+pub mod poseidon;
+
 mod ecalls;
 pub use ecalls::*;
 
+mod heap;
+pub use heap::{heap_stats, HeapStats};
+
 #[inline(never)]
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
+fn panic(info: &PanicInfo) -> ! {
     extern "C" {
         fn abort() -> !;
     }
-    write_log("PANIC\n");
+    ecalls::begin_panic();
+
+    // formatting the message pulls in `core::fmt`, which is large
+    #[cfg(feature = "panic-message")]
+    let _ = writeln!(NexusLog, "{info}");
+    #[cfg(not(feature = "panic-message"))]
+    let _ = info;
+
     unsafe {
         abort();
     }
@@ -28,28 +42,6 @@ fn panic(_info: &PanicInfo) -> ! {
 #[doc(hidden)]
 pub static __ONCE__: () = ();
 
-struct Heap;
-
-#[global_allocator]
-static HEAP: Heap = Heap;
-
-// This trivial allocate will always expand the heap, and never
-// deallocates. This should be fine for small programs.
-
-unsafe impl GlobalAlloc for Heap {
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        extern "C" {
-            fn alloc_(size: usize) -> *mut u8;
-        }
-
-        let sz = layout.size();
-        let sz = (sz + 3) & !3;
-
-        alloc_(sz)
-    }
-    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
-}
-
 /// Stack size setup (_get_stack_size)
 #[doc(hidden)]
 #[link_section = ".init.rust"]
@@ -0,0 +1,60 @@
+//! Poseidon hashing, evaluated by the host and checked by the proof.
+//!
+//! The hash functions are those used by the VM for its memory trie,
+//! over the scalar field of BN254. Field elements are stored as 8
+//! 32-bit words, least significant first, and must be less than the
+//! field modulus; the host reports an error otherwise.
+//!
+//! Each hash costs a single `ecall`. The operands are passed in
+//! a0-a7 and s4-s11, and the digest is returned in a0-a7, where the
+//! step circuit checks it (see `nexus_vm::syscalls::PRECOMPILE_REGS`).
+//!
+//! The step circuit only checks the precompile when it is selected, so
+//! programs using this module must be run and proved with
+//! `--precompiles poseidon`, and public parameters set up with it.
+
+/// A field element, least significant word first.
+pub type Element = [u32; 8];
+
+// ecall numbers, see `nexus_vm::syscalls::Syscalls::syscall`
+const HASH: u32 = 15;
+const COMPRESS: u32 = 16;
+
+fn poseidon(num: u32, left: &Element, right: &Element) -> Element {
+    let mut a = *left;
+    let b = right;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            in("s2") num,
+            inout("a0") a[0],
+            inout("a1") a[1],
+            inout("a2") a[2],
+            inout("a3") a[3],
+            inout("a4") a[4],
+            inout("a5") a[5],
+            inout("a6") a[6],
+            inout("a7") a[7],
+            in("s4") b[0],
+            in("s5") b[1],
+            in("s6") b[2],
+            in("s7") b[3],
+            in("s8") b[4],
+            in("s9") b[5],
+            in("s10") b[6],
+            in("s11") b[7],
+        )
+    }
+    a
+}
+
+/// Hash two field elements, as the memory trie hashes its leaves.
+pub fn hash(left: &Element, right: &Element) -> Element {
+    poseidon(HASH, left, right)
+}
+
+/// Hash two field elements, as the memory trie hashes its inner
+/// nodes. This is the function to use for Merkle proofs.
+pub fn compress(left: &Element, right: &Element) -> Element {
+    poseidon(COMPRESS, left, right)
+}
@@ -0,0 +1,16 @@
+#!/bin/sh
+# Link the guest used by the `runtime_args` test of nexus-vm from args.S and
+# the runtime archive, with the default linker script of the runtime.
+#
+# Requires llvm-mc and ld.lld; set LLVM_MC and LD to override, e.g.
+# LD="rust-lld -flavor gnu".
+set -e
+cd "$(dirname "$0")"
+
+LLVM_MC=${LLVM_MC:-llvm-mc}
+LD=${LD:-ld.lld}
+
+$LLVM_MC -triple=riscv32 -mattr=-c,+relax -filetype=obj args.S -o args.o
+$LD -T ../../linker-scripts/default.x -u _start args.o ../../bin/riscv32i-unknown-none-elf.a \
+    -o args.elf
+rm args.o
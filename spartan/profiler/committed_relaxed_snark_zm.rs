@@ -58,7 +58,12 @@ pub fn main() {
     // verify the proof of satisfiability
     let mut verifier_transcript = Transcript::new(b"snark_example");
     assert!(proof
-      .verify(&comm, &instance, &mut verifier_transcript, &gens)
+      .verify(
+        &comm,
+        &instance,
+        &mut verifier_transcript,
+        &gens.verifier_key(),
+      )
       .is_ok());
 
     println!();
@@ -1,17 +1,23 @@
 #![allow(dead_code)]
+#![allow(clippy::too_many_arguments)]
 /// This is mostly a copy of the SNARK implementation in lib.rs, with minor modifications to work with committed relaxed R1CS.
 use core::cmp::max;
 
 use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::Zero;
+use ark_std::rand::{CryptoRng, RngCore};
 use merlin::Transcript;
 
 use crate::{
-  crr1csproof::{CRR1CSInstance, CRR1CSKey, CRR1CSProof, CRR1CSShape, CRR1CSWitness},
+  crr1csproof::{
+    CRR1CSInstance, CRR1CSKey, CRR1CSProof, CRR1CSShape, CRR1CSWitness, ZKCRR1CSProof,
+  },
   errors::ProofVerifyError,
   polycommitments::PolyCommitmentScheme,
   r1csinstance::{R1CSCommitmentGens, R1CSEvalProof},
+  random::RandomTape,
+  sparse_mlpoly::SparseMatPolyVerifierKey,
   timer::Timer,
   transcript::{AppendToTranscript, ProofTranscript},
   ComputationCommitment, ComputationDecommitment, Instance,
@@ -62,6 +68,21 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> CRSNARKKey<G, PC> {
       R1CSCommitmentGens::<G, PC>::get_min_num_vars(num_cons, num_vars_padded, num_nz_entries);
     max(min_num_vars_sat, min_num_vars_eval)
   }
+
+  /// Extracts the keys needed to verify proofs, without the commitment keys
+  pub fn verifier_key(&self) -> CRSNARKVerifierKey<G, PC> {
+    CRSNARKVerifierKey {
+      vk_r1cs_sat: self.gens_r1cs_sat.keys.vk.clone(),
+      vk_r1cs_eval: self.gens_r1cs_eval.verifier_key(),
+    }
+  }
+}
+
+/// `CRSNARKVerifierKey` holds the part of `CRSNARKKey` needed for verifying proofs
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+pub struct CRSNARKVerifierKey<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  pub vk_r1cs_sat: PC::EvalVerifierKey,
+  pub vk_r1cs_eval: SparseMatPolyVerifierKey<G, PC>,
 }
 
 /// `SNARK` holds a proof produced by Spartan SNARK
@@ -107,9 +128,6 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
   ) -> Self {
     let timer_prove = Timer::new("SNARK::prove");
 
-    let inst = &shape.inst;
-    let CRR1CSWitness { W: vars, E } = witness;
-
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
       SNARK::<G, PC>::protocol_name(),
@@ -117,38 +135,9 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
     comm.comm.append_to_transcript(b"comm", transcript);
 
     let (r1cs_sat_proof, rx, ry) = {
-      let (proof, rx, ry) = {
-        // we might need to pad variables
-        let padded_vars = {
-          let num_padded_vars = inst.inst.get_num_vars();
-          let num_vars = vars.assignment.len();
-          if num_padded_vars > num_vars {
-            vars.pad(num_padded_vars)
-          } else {
-            vars
-          }
-        };
-
-        // we also might need to pad the error vector
-        let padded_error = {
-          let num_padded_cons = inst.inst.get_num_cons();
-          let num_cons = E.len();
-          if num_padded_cons > num_cons {
-            let mut padded_error = E.clone();
-            padded_error.resize(num_padded_cons, G::ScalarField::zero());
-            padded_error
-          } else {
-            E
-          }
-        };
-
-        let witness = CRR1CSWitness::<G::ScalarField> {
-          W: padded_vars,
-          E: padded_error,
-        };
-
-        CRR1CSProof::prove(shape, instance, witness, &key.gens_r1cs_sat, transcript)
-      };
+      let witness = pad_witness(shape, witness);
+      let (proof, rx, ry) =
+        CRR1CSProof::prove(shape, instance, witness, &key.gens_r1cs_sat, transcript);
 
       let mut proof_encoded = vec![];
       proof.serialize_compressed(&mut proof_encoded).unwrap();
@@ -158,34 +147,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
       (proof, rx, ry)
     };
 
-    // We send evaluations of A, B, C at r = (rx, ry) as claims
-    // to enable the verifier complete the first sum-check
-    let timer_eval = Timer::new("eval_sparse_polys");
-    let inst_evals = {
-      let (Ar, Br, Cr) = inst.inst.evaluate(&rx, &ry);
-      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Ar);
-      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Br);
-      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Cr);
-      (Ar, Br, Cr)
-    };
-    timer_eval.stop();
-
-    let r1cs_eval_proof = {
-      let proof = R1CSEvalProof::prove(
-        &decomm.decomm,
-        &rx,
-        &ry,
-        &inst_evals,
-        &key.gens_r1cs_eval,
-        transcript,
-      );
-
-      let mut proof_encoded = vec![];
-      proof.serialize_compressed(&mut proof_encoded).unwrap();
-
-      Timer::print(&format!("len_r1cs_eval_proof {:?}", proof_encoded.len()));
-      proof
-    };
+    let (inst_evals, r1cs_eval_proof) = prove_inst_evals(shape, decomm, &rx, &ry, key, transcript);
 
     timer_prove.stop();
     SNARK {
@@ -201,7 +163,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
     comm: &ComputationCommitment<G, PC>,
     instance: &CRR1CSInstance<G, PC>,
     transcript: &mut Transcript,
-    key: &CRSNARKKey<G, PC>,
+    key: &CRSNARKVerifierKey<G, PC>,
   ) -> Result<(), ProofVerifyError> {
     let timer_verify = Timer::new("SNARK::verify");
     <Transcript as ProofTranscript<G>>::append_protocol_name(
@@ -222,21 +184,18 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
       instance,
       &self.inst_evals,
       transcript,
-      &key.gens_r1cs_sat.keys.vk,
+      &key.vk_r1cs_sat,
     )?;
     timer_sat_proof.stop();
 
     let timer_eval_proof = Timer::new("verify_eval_proof");
-    let (Ar, Br, Cr) = &self.inst_evals;
-    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Ar);
-    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Br);
-    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Cr);
-    self.r1cs_eval_proof.verify(
-      &comm.comm,
+    verify_inst_evals(
+      comm,
       &rx,
       &ry,
       &self.inst_evals,
-      &key.gens_r1cs_eval,
+      &self.r1cs_eval_proof,
+      key,
       transcript,
     )?;
     timer_eval_proof.stop();
@@ -245,15 +204,220 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
   }
 }
 
+/// Pads the witness to the size of the instance.
+fn pad_witness<F: PrimeField>(
+  shape: &CRR1CSShape<F>,
+  witness: CRR1CSWitness<F>,
+) -> CRR1CSWitness<F> {
+  let inst = &shape.inst;
+  let CRR1CSWitness { W: vars, E } = witness;
+
+  // we might need to pad variables
+  let padded_vars = {
+    let num_padded_vars = inst.inst.get_num_vars();
+    let num_vars = vars.assignment.len();
+    if num_padded_vars > num_vars {
+      vars.pad(num_padded_vars)
+    } else {
+      vars
+    }
+  };
+
+  // we also might need to pad the error vector
+  let padded_error = {
+    let num_padded_cons = inst.inst.get_num_cons();
+    let num_cons = E.len();
+    if num_padded_cons > num_cons {
+      let mut padded_error = E.clone();
+      padded_error.resize(num_padded_cons, F::zero());
+      padded_error
+    } else {
+      E
+    }
+  };
+
+  CRR1CSWitness {
+    W: padded_vars,
+    E: padded_error,
+  }
+}
+
+/// Sends evaluations of A, B, C at r = (rx, ry) as claims to enable the verifier
+/// to complete the first sum-check, and proves them.
+#[allow(clippy::type_complexity)]
+fn prove_inst_evals<G: CurveGroup, PC: PolyCommitmentScheme<G>>(
+  shape: &CRR1CSShape<G::ScalarField>,
+  decomm: &ComputationDecommitment<G::ScalarField>,
+  rx: &[G::ScalarField],
+  ry: &[G::ScalarField],
+  key: &CRSNARKKey<G, PC>,
+  transcript: &mut Transcript,
+) -> (
+  (G::ScalarField, G::ScalarField, G::ScalarField),
+  R1CSEvalProof<G, PC>,
+) {
+  let timer_eval = Timer::new("eval_sparse_polys");
+  let inst_evals = {
+    let (Ar, Br, Cr) = shape.inst.inst.evaluate(rx, ry);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Ar);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Br);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Cr);
+    (Ar, Br, Cr)
+  };
+  timer_eval.stop();
+
+  let r1cs_eval_proof = {
+    let proof = R1CSEvalProof::prove(
+      &decomm.decomm,
+      rx,
+      ry,
+      &inst_evals,
+      &key.gens_r1cs_eval,
+      transcript,
+    );
+
+    let mut proof_encoded = vec![];
+    proof.serialize_compressed(&mut proof_encoded).unwrap();
+
+    Timer::print(&format!("len_r1cs_eval_proof {:?}", proof_encoded.len()));
+    proof
+  };
+
+  (inst_evals, r1cs_eval_proof)
+}
+
+fn verify_inst_evals<G: CurveGroup, PC: PolyCommitmentScheme<G>>(
+  comm: &ComputationCommitment<G, PC>,
+  rx: &[G::ScalarField],
+  ry: &[G::ScalarField],
+  inst_evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+  r1cs_eval_proof: &R1CSEvalProof<G, PC>,
+  key: &CRSNARKVerifierKey<G, PC>,
+  transcript: &mut Transcript,
+) -> Result<(), ProofVerifyError> {
+  let (Ar, Br, Cr) = inst_evals;
+  <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Ar);
+  <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Br);
+  <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Cr);
+  r1cs_eval_proof.verify(
+    &comm.comm,
+    rx,
+    ry,
+    inst_evals,
+    &key.vk_r1cs_eval,
+    transcript,
+  )
+}
+
+/// `ZKSNARK` holds a proof produced by Spartan SNARK, with the satisfiability of the
+/// instance proved by [`ZKCRR1CSProof`]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ZKSNARK<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  r1cs_sat_proof: ZKCRR1CSProof<G, PC>,
+  inst_evals: (G::ScalarField, G::ScalarField, G::ScalarField),
+  r1cs_eval_proof: R1CSEvalProof<G, PC>,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> ZKSNARK<G, PC> {
+  fn protocol_name() -> &'static [u8] {
+    b"Spartan ZK SNARK proof"
+  }
+
+  /// Like [`SNARK::prove`], with blinds for the sum-check commitments sampled from `rng`
+  pub fn prove<R: RngCore + CryptoRng>(
+    shape: &CRR1CSShape<G::ScalarField>,
+    instance: &CRR1CSInstance<G, PC>,
+    witness: CRR1CSWitness<G::ScalarField>,
+    comm: &ComputationCommitment<G, PC>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    key: &CRSNARKKey<G, PC>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Self {
+    let timer_prove = Timer::new("ZKSNARK::prove");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      ZKSNARK::<G, PC>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+
+    let mut random_tape = RandomTape::from_rng(b"proof", rng);
+    let witness = pad_witness(shape, witness);
+    let (r1cs_sat_proof, rx, ry) = ZKCRR1CSProof::prove(
+      shape,
+      instance,
+      witness,
+      &key.gens_r1cs_sat,
+      transcript,
+      &mut random_tape,
+    );
+
+    let (inst_evals, r1cs_eval_proof) = prove_inst_evals(shape, decomm, &rx, &ry, key, transcript);
+
+    timer_prove.stop();
+    ZKSNARK {
+      r1cs_sat_proof,
+      inst_evals,
+      r1cs_eval_proof,
+    }
+  }
+
+  /// A method to verify the SNARK proof of the satisfiability of an R1CS instance
+  pub fn verify(
+    &self,
+    comm: &ComputationCommitment<G, PC>,
+    instance: &CRR1CSInstance<G, PC>,
+    transcript: &mut Transcript,
+    key: &CRSNARKVerifierKey<G, PC>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("ZKSNARK::verify");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      ZKSNARK::<G, PC>::protocol_name(),
+    );
+
+    let CRR1CSInstance { input, .. } = instance;
+    comm.comm.append_to_transcript(b"comm", transcript);
+
+    if input.assignment.len() != comm.comm.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let (rx, ry) = self.r1cs_sat_proof.verify(
+      comm.comm.get_num_vars(),
+      comm.comm.get_num_cons(),
+      instance,
+      &self.inst_evals,
+      transcript,
+      &key.vk_r1cs_sat,
+    )?;
+
+    verify_inst_evals(
+      comm,
+      &rx,
+      &ry,
+      &self.inst_evals,
+      &self.r1cs_eval_proof,
+      key,
+      transcript,
+    )?;
+    timer_verify.stop();
+    Ok(())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::{
     crr1cs::produce_synthetic_crr1cs,
-    polycommitments::{zeromorph::Zeromorph, PolyCommitmentScheme},
+    polycommitments::{zeromorph::Zeromorph, PolyCommitmentScheme, VectorCommitmentScheme},
   };
 
   use ark_bls12_381::{Bls12_381, G1Projective};
+  use ark_std::rand::SeedableRng;
+  use rand_chacha::ChaCha20Rng;
 
   #[test]
   pub fn check_crsnark() {
@@ -285,7 +449,142 @@ mod tests {
     // verify the proof
     let mut verifier_transcript = Transcript::new(b"example");
     assert!(proof
-      .verify(&comm, &instance, &mut verifier_transcript, &key)
+      .verify(
+        &comm,
+        &instance,
+        &mut verifier_transcript,
+        &key.verifier_key(),
+      )
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_crsnark_verifier_key() {
+    check_crsnark_verifier_key_helper::<G1Projective, Zeromorph<Bls12_381>>()
+  }
+  pub fn check_crsnark_verifier_key_helper<G: CurveGroup, PC: PolyCommitmentScheme<G>>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let (shape, instance, witness, key) = produce_synthetic_crr1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::<_, PC>::encode(&shape.inst, &key);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::<_, PC>::prove(
+      &shape,
+      &instance,
+      witness.clone(),
+      &comm,
+      &decomm,
+      &key,
+      &mut prover_transcript,
+    );
+
+    // only the serialized verifier key is needed to verify
+    let mut buf = Vec::new();
+    key.verifier_key().serialize_compressed(&mut buf).unwrap();
+    drop(key);
+    let vk = CRSNARKVerifierKey::<G, PC>::deserialize_compressed(buf.as_slice()).unwrap();
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &instance, &mut verifier_transcript, &vk)
+      .is_ok());
+
+    // a proof made with a key from another SRS does not verify
+    let (num_cons, num_vars, num_inputs) = (
+      shape.get_num_cons(),
+      shape.get_num_vars(),
+      shape.get_num_inputs(),
+    );
+    let min_num_vars =
+      CRSNARKKey::<G, PC>::get_min_num_vars(num_cons, num_vars, num_inputs, num_cons);
+    let srs = PC::setup(
+      min_num_vars,
+      b"other_SRS",
+      &mut ChaCha20Rng::seed_from_u64(1),
+    )
+    .unwrap();
+    let other_key = CRSNARKKey::<G, PC>::new(&srs, num_cons, num_vars, num_inputs, num_cons);
+    let (other_comm, other_decomm) = SNARK::<_, PC>::encode(&shape.inst, &other_key);
+    let ck = &other_key.gens_r1cs_sat.keys.ck;
+    let other_instance = CRR1CSInstance::<G, PC> {
+      input: instance.input.clone(),
+      u: instance.u,
+      comm_W: <PC as VectorCommitmentScheme<G>>::commit(&witness.W.assignment, ck),
+      comm_E: <PC as VectorCommitmentScheme<G>>::commit(&witness.E, ck),
+    };
+    let mut prover_transcript = Transcript::new(b"example");
+    let other_proof = SNARK::<_, PC>::prove(
+      &shape,
+      &other_instance,
+      witness,
+      &other_comm,
+      &other_decomm,
+      &other_key,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(other_proof
+      .verify(
+        &other_comm,
+        &other_instance,
+        &mut verifier_transcript,
+        &other_key.verifier_key(),
+      )
+      .is_ok());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(other_proof
+      .verify(&other_comm, &other_instance, &mut verifier_transcript, &vk)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_zk_crsnark() {
+    check_zk_crsnark_helper::<G1Projective, Zeromorph<Bls12_381>>()
+  }
+  pub fn check_zk_crsnark_helper<G: CurveGroup, PC: PolyCommitmentScheme<G>>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let (shape, mut instance, witness, key) =
+      produce_synthetic_crr1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::<_, PC>::encode(&shape.inst, &key);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = ZKSNARK::<_, PC>::prove(
+      &shape,
+      &instance,
+      witness,
+      &comm,
+      &decomm,
+      &key,
+      &mut prover_transcript,
+      &mut ChaCha20Rng::seed_from_u64(0),
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comm,
+        &instance,
+        &mut verifier_transcript,
+        &key.verifier_key(),
+      )
       .is_ok());
+
+    // the proof does not verify for another instance
+    instance.u += G::ScalarField::from(1u64);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comm,
+        &instance,
+        &mut verifier_transcript,
+        &key.verifier_key(),
+      )
+      .is_err());
   }
 }
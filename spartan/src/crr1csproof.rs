@@ -3,11 +3,14 @@
 use crate::polycommitments::PolyCommitmentScheme;
 use crate::unipoly::{CompressedUniPoly, UniPoly};
 
+use super::commitments::{Commitments, MultiCommitGens};
 use super::dense_mlpoly::{DensePolynomial, EqPolynomial};
 use super::errors::ProofVerifyError;
 use super::math::Math;
+use super::nizk::{EqualityProof, KnowledgeProof, ProductProof};
+use super::random::RandomTape;
 use super::sparse_mlpoly::{SparsePolyEntry, SparsePolynomial};
-use super::sumcheck::SumcheckInstanceProof;
+use super::sumcheck::{SumcheckInstanceProof, ZKSumcheckInstanceProof};
 use super::timer::Timer;
 use super::transcript::{AppendToTranscript, ProofTranscript};
 use ark_ec::CurveGroup;
@@ -544,6 +547,431 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> CRR1CSProof<G, PC> {
   }
 }
 
+/// Generators for the commitments of [`ZKCRR1CSProof`], derived from fixed labels.
+struct ZKSumcheckGens<G: CurveGroup> {
+  gens_1: MultiCommitGens<G>,
+  gens_3: MultiCommitGens<G>,
+  gens_4: MultiCommitGens<G>,
+}
+
+impl<G: CurveGroup> ZKSumcheckGens<G> {
+  fn new() -> Self {
+    ZKSumcheckGens {
+      gens_1: MultiCommitGens::new(1, b"zk_crr1cs_gens_1"),
+      gens_3: MultiCommitGens::new(3, b"zk_crr1cs_gens_3"),
+      gens_4: MultiCommitGens::new(4, b"zk_crr1cs_gens_4"),
+    }
+  }
+}
+
+/// Like [`CRR1CSProof`], but both sum-checks are run on Pedersen commitments to the round
+/// polynomials, and the claims connecting them are only revealed as commitments.
+///
+/// The evaluations ~Z~(ry) and ~E~(rx) are still opened in the clear, the witness is expected
+/// to be randomized by the caller.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct ZKCRR1CSProof<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  /// Sumcheck proof for the polynomial g(x) = \sum eq(tau,x) * (~Az~(x) * ~Bz~(x) - ~D~(x)),
+  /// where D = u * Cz + E.
+  sc_proof_phase1: ZKSumcheckInstanceProof<G>,
+  /// Commitments to ~Az~(rx), ~Bz~(rx), ~Cz~(rx), and ~Az~(rx) * ~Bz~(rx).
+  claims_phase2: (G, G, G, G),
+  pok_claims_phase2: (KnowledgeProof<G>, ProductProof<G>),
+  proof_eq_sc_phase1: EqualityProof<G>,
+  sc_proof_phase2: ZKSumcheckInstanceProof<G>,
+  eval_vars_at_ry: G::ScalarField,
+  proof_eval_vars_at_ry: PC::PolyCommitmentProof,
+  eval_error_at_rx: G::ScalarField,
+  proof_eval_error_at_rx: PC::PolyCommitmentProof,
+  proof_eq_sc_phase2: EqualityProof<G>,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> ZKCRR1CSProof<G, PC> {
+  fn protocol_name() -> &'static [u8] {
+    b"ZK CRR1CS proof"
+  }
+
+  #[allow(clippy::type_complexity)]
+  pub fn prove(
+    shape: &CRR1CSShape<G::ScalarField>,
+    instance: &CRR1CSInstance<G, PC>,
+    witness: CRR1CSWitness<G::ScalarField>,
+    key: &CRR1CSKey<G, PC>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (Self, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    let timer_prove = Timer::new("ZKCRR1CSProof::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(transcript, Self::protocol_name());
+
+    let gens = ZKSumcheckGens::<G>::new();
+    let inst = &shape.inst.inst;
+    let CRR1CSInstance {
+      input,
+      u,
+      comm_W,
+      comm_E,
+    } = instance;
+    let CRR1CSWitness { W: vars, E } = witness;
+    let (input, vars) = (input.assignment.as_slice(), vars.assignment);
+
+    // we currently require the number of |inputs| + 1 to be at most number of vars
+    assert!(input.len() < vars.len());
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"u", u);
+    comm_W.append_to_transcript(b"comm_W", transcript);
+    comm_E.append_to_transcript(b"comm_E", transcript);
+
+    let poly_vars = DensePolynomial::<G::ScalarField>::new(vars.clone());
+    let poly_error = DensePolynomial::<G::ScalarField>::new(E.clone());
+
+    let z = {
+      let num_inputs = input.len();
+      let num_vars = vars.len();
+      let mut z = vars;
+      z.extend(vec![u]); // add relaxed constant term in z
+      z.extend(input);
+      z.extend(&vec![G::ScalarField::zero(); num_vars - num_inputs - 1]); // we will pad with zeros
+      z
+    };
+
+    let timer_sc_proof_phase1 = Timer::new("prove_sc_phase_one");
+    let (num_rounds_x, num_rounds_y) = (inst.get_num_cons().log_2(), z.len().log_2());
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+    let mut poly_tau = DensePolynomial::new(EqPolynomial::new(tau).evals());
+    let (mut poly_Az, mut poly_Bz, poly_Cz) = inst.multiply_vec(inst.get_num_cons(), z.len(), &z);
+    // the relaxed terms are folded into a single polynomial, so that the sum-check
+    // has the same shape as for plain R1CS
+    let mut poly_D =
+      DensePolynomial::new((0..poly_Cz.len()).map(|i| *u * poly_Cz[i] + E[i]).collect());
+
+    let comb_func = |poly_tau: &G::ScalarField,
+                     poly_A: &G::ScalarField,
+                     poly_B: &G::ScalarField,
+                     poly_D: &G::ScalarField|
+     -> G::ScalarField { *poly_tau * (*poly_A * *poly_B - *poly_D) };
+    let (sc_proof_phase1, rx, _claims_phase1, blind_claim_postsc1) =
+      ZKSumcheckInstanceProof::prove_cubic_with_additive_term(
+        &G::ScalarField::zero(), // claim is zero
+        &G::ScalarField::zero(), // blind for claim is also zero
+        num_rounds_x,
+        &mut poly_tau,
+        &mut poly_Az,
+        &mut poly_Bz,
+        &mut poly_D,
+        comb_func,
+        &gens.gens_1,
+        &gens.gens_4,
+        transcript,
+        random_tape,
+      );
+    timer_sc_proof_phase1.stop();
+
+    let (tau_claim, Az_claim, Bz_claim) = (&poly_tau[0], &poly_Az[0], &poly_Bz[0]);
+    let Cz_claim = poly_Cz.evaluate::<G>(&rx);
+    let E_claim = poly_error.evaluate::<G>(&rx);
+    debug_assert_eq!(poly_D[0], *u * Cz_claim + E_claim);
+
+    let (Az_blind, Bz_blind, Cz_blind, prod_Az_Bz_blind) = (
+      random_tape.random_scalar(b"Az_blind"),
+      random_tape.random_scalar(b"Bz_blind"),
+      random_tape.random_scalar(b"Cz_blind"),
+      random_tape.random_scalar(b"prod_Az_Bz_blind"),
+    );
+
+    let (pok_Cz_claim, comm_Cz_claim) =
+      KnowledgeProof::prove(&gens.gens_1, transcript, random_tape, &Cz_claim, &Cz_blind);
+
+    let (proof_prod, comm_Az_claim, comm_Bz_claim, comm_prod_Az_Bz_claims) = {
+      let prod = *Az_claim * *Bz_claim;
+      ProductProof::prove(
+        &gens.gens_1,
+        transcript,
+        random_tape,
+        Az_claim,
+        &Az_blind,
+        Bz_claim,
+        &Bz_blind,
+        &prod,
+        &prod_Az_Bz_blind,
+      )
+    };
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Az_claim", &comm_Az_claim);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Bz_claim", &comm_Bz_claim);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Cz_claim", &comm_Cz_claim);
+    <Transcript as ProofTranscript<G>>::append_point(
+      transcript,
+      b"comm_prod_Az_Bz_claims",
+      &comm_prod_Az_Bz_claims,
+    );
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"E_claim", &E_claim);
+
+    // prove the final step of sum-check #1
+    let blind_expected_claim_postsc1 = *tau_claim * (prod_Az_Bz_blind - *u * Cz_blind);
+    let claim_post_phase1 = *tau_claim * (*Az_claim * *Bz_claim - *u * Cz_claim - E_claim);
+    let (proof_eq_sc_phase1, _C1, _C2) = EqualityProof::prove(
+      &gens.gens_1,
+      transcript,
+      random_tape,
+      &claim_post_phase1,
+      &blind_expected_claim_postsc1,
+      &claim_post_phase1,
+      &blind_claim_postsc1,
+    );
+
+    let timer_sc_proof_phase2 = Timer::new("prove_sc_phase_two");
+    // combine the three claims into a single claim
+    let r_A = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Az");
+    let r_B = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Bz");
+    let r_C = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Cz");
+    let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
+    let blind_claim_phase2 = r_A * Az_blind + r_B * Bz_blind + r_C * Cz_blind;
+
+    let evals_ABC = {
+      let evals_rx = EqPolynomial::new(rx.clone()).evals();
+      let (evals_A, evals_B, evals_C) =
+        inst.compute_eval_table_sparse(inst.get_num_cons(), z.len(), &evals_rx);
+
+      assert_eq!(evals_A.len(), evals_B.len());
+      assert_eq!(evals_A.len(), evals_C.len());
+      (0..evals_A.len())
+        .map(|i| r_A * evals_A[i] + r_B * evals_B[i] + r_C * evals_C[i])
+        .collect::<Vec<G::ScalarField>>()
+    };
+
+    let comb_func = |poly_A_comp: &G::ScalarField,
+                     poly_B_comp: &G::ScalarField|
+     -> G::ScalarField { *poly_A_comp * *poly_B_comp };
+    let (sc_proof_phase2, ry, claims_phase2, blind_claim_postsc2) =
+      ZKSumcheckInstanceProof::prove_quad(
+        &claim_phase2,
+        &blind_claim_phase2,
+        num_rounds_y,
+        &mut DensePolynomial::new(z),
+        &mut DensePolynomial::new(evals_ABC),
+        comb_func,
+        &gens.gens_1,
+        &gens.gens_3,
+        transcript,
+        random_tape,
+      );
+    timer_sc_proof_phase2.stop();
+
+    let timer_polyevalproof = Timer::new("polyevalproof");
+    let eval_vars_at_ry = poly_vars.evaluate::<G>(&ry[1..]);
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      transcript,
+      b"eval_vars_at_ry",
+      &eval_vars_at_ry,
+    );
+    let proof_eval_vars_at_ry = PC::prove(
+      Some(comm_W),
+      &poly_vars,
+      &ry[1..],
+      &eval_vars_at_ry,
+      &key.keys.ck,
+      transcript,
+    );
+    let proof_eval_error_at_rx = PC::prove(
+      Some(comm_E),
+      &poly_error,
+      &rx,
+      &E_claim,
+      &key.keys.ck,
+      transcript,
+    );
+    timer_polyevalproof.stop();
+
+    // prove the final step of sum-check #2, whose claim is public given ~Z~(ry)
+    let claim_post_phase2 = claims_phase2[0] * claims_phase2[1];
+    let (proof_eq_sc_phase2, _C1, _C2) = EqualityProof::prove(
+      &gens.gens_1,
+      transcript,
+      random_tape,
+      &claim_post_phase2,
+      &G::ScalarField::zero(),
+      &claim_post_phase2,
+      &blind_claim_postsc2,
+    );
+
+    timer_prove.stop();
+
+    (
+      ZKCRR1CSProof {
+        sc_proof_phase1,
+        claims_phase2: (
+          comm_Az_claim,
+          comm_Bz_claim,
+          comm_Cz_claim,
+          comm_prod_Az_Bz_claims,
+        ),
+        pok_claims_phase2: (pok_Cz_claim, proof_prod),
+        proof_eq_sc_phase1,
+        sc_proof_phase2,
+        eval_vars_at_ry,
+        proof_eval_vars_at_ry,
+        eval_error_at_rx: E_claim,
+        proof_eval_error_at_rx,
+        proof_eq_sc_phase2,
+      },
+      rx,
+      ry,
+    )
+  }
+
+  #[allow(clippy::type_complexity)]
+  pub fn verify(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    instance: &CRR1CSInstance<G, PC>,
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    key: &PC::EvalVerifierKey,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(transcript, Self::protocol_name());
+
+    let gens = ZKSumcheckGens::<G>::new();
+    let CRR1CSInstance {
+      input,
+      u,
+      comm_W,
+      comm_E,
+    } = instance;
+    let input = input.assignment.as_slice();
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"u", u);
+    comm_W.append_to_transcript(b"comm_W", transcript);
+    comm_E.append_to_transcript(b"comm_E", transcript);
+
+    let (num_rounds_x, num_rounds_y) = (num_cons.log_2(), (2 * num_vars).log_2());
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // verify the first sum-check instance
+    let claim_phase1 = G::ScalarField::zero().commit(&G::ScalarField::zero(), &gens.gens_1);
+    let (comm_claim_post_phase1, rx) = self.sc_proof_phase1.verify(
+      &claim_phase1,
+      num_rounds_x,
+      3,
+      &gens.gens_1,
+      &gens.gens_4,
+      transcript,
+    )?;
+
+    let (comm_Az_claim, comm_Bz_claim, comm_Cz_claim, comm_prod_Az_Bz_claims) = &self.claims_phase2;
+    let (pok_Cz_claim, proof_prod) = &self.pok_claims_phase2;
+    pok_Cz_claim.verify(&gens.gens_1, transcript, comm_Cz_claim)?;
+    proof_prod.verify(
+      &gens.gens_1,
+      transcript,
+      comm_Az_claim,
+      comm_Bz_claim,
+      comm_prod_Az_Bz_claims,
+    )?;
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Az_claim", comm_Az_claim);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Bz_claim", comm_Bz_claim);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_Cz_claim", comm_Cz_claim);
+    <Transcript as ProofTranscript<G>>::append_point(
+      transcript,
+      b"comm_prod_Az_Bz_claims",
+      comm_prod_Az_Bz_claims,
+    );
+    let E_claim = &self.eval_error_at_rx;
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"E_claim", E_claim);
+
+    let taus_bound_rx: G::ScalarField = (0..rx.len())
+      .map(|i| rx[i] * tau[i] + (G::ScalarField::one() - rx[i]) * (G::ScalarField::one() - tau[i]))
+      .product();
+    let comm_E_claim = E_claim.commit(&G::ScalarField::zero(), &gens.gens_1);
+    let expected_claim_post_phase1 =
+      (*comm_prod_Az_Bz_claims - *comm_Cz_claim * u - comm_E_claim) * taus_bound_rx;
+    self.proof_eq_sc_phase1.verify(
+      &gens.gens_1,
+      transcript,
+      &expected_claim_post_phase1,
+      &comm_claim_post_phase1,
+    )?;
+
+    // derive three public challenges and then derive a joint claim
+    let r_A = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Az");
+    let r_B = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Bz");
+    let r_C = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Cz");
+    let comm_claim_phase2 = *comm_Az_claim * r_A + *comm_Bz_claim * r_B + *comm_Cz_claim * r_C;
+
+    // verify the joint claim with a sum-check protocol
+    let (comm_claim_post_phase2, ry) = self.sc_proof_phase2.verify(
+      &comm_claim_phase2,
+      num_rounds_y,
+      2,
+      &gens.gens_1,
+      &gens.gens_3,
+      transcript,
+    )?;
+
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      transcript,
+      b"eval_vars_at_ry",
+      &self.eval_vars_at_ry,
+    );
+    PC::verify(
+      comm_W,
+      &self.proof_eval_vars_at_ry,
+      key,
+      transcript,
+      &ry[1..],
+      &self.eval_vars_at_ry,
+    )
+    .map_err(|_| ProofVerifyError::InternalError)?;
+    PC::verify(
+      comm_E,
+      &self.proof_eval_error_at_rx,
+      key,
+      transcript,
+      &rx,
+      &self.eval_error_at_rx,
+    )
+    .map_err(|_| ProofVerifyError::InternalError)?;
+
+    let poly_input_eval = {
+      // constant term
+      let mut input_as_sparse_poly_entries = vec![SparsePolyEntry::new(0, *u)];
+      //remaining inputs
+      input_as_sparse_poly_entries.extend(
+        (0..input.len())
+          .map(|i| SparsePolyEntry::new(i + 1, input[i]))
+          .collect::<Vec<SparsePolyEntry<G::ScalarField>>>(),
+      );
+      SparsePolynomial::new(num_vars.log_2(), input_as_sparse_poly_entries).evaluate(&ry[1..])
+    };
+    let eval_Z_at_ry =
+      (G::ScalarField::one() - ry[0]) * self.eval_vars_at_ry + ry[0] * poly_input_eval;
+
+    // perform the final check in the second sum-check protocol
+    let (eval_A_r, eval_B_r, eval_C_r) = evals;
+    let expected_claim_post_phase2 = (eval_Z_at_ry
+      * (r_A * eval_A_r + r_B * eval_B_r + r_C * eval_C_r))
+      .commit(&G::ScalarField::zero(), &gens.gens_1);
+    self.proof_eq_sc_phase2.verify(
+      &gens.gens_1,
+      transcript,
+      &expected_claim_post_phase2,
+      &comm_claim_post_phase2,
+    )?;
+
+    Ok((rx, ry))
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::polycommitments::hyrax::Hyrax;
@@ -467,7 +467,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
       &rx,
       &ry,
       &self.inst_evals,
-      &gens.gens_r1cs_eval,
+      &gens.gens_r1cs_eval.verifier_key(),
       transcript,
     )?;
     timer_eval_proof.stop();
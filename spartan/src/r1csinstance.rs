@@ -4,7 +4,8 @@ use super::math::Math;
 use super::polycommitments::{PolyCommitmentScheme, SRSTrait};
 use super::sparse_mlpoly::{
   MultiSparseMatPolynomialAsDense, SparseMatEntry, SparseMatPolyCommitment,
-  SparseMatPolyCommitmentKey, SparseMatPolyEvalProof, SparseMatPolynomial,
+  SparseMatPolyCommitmentKey, SparseMatPolyEvalProof, SparseMatPolyVerifierKey,
+  SparseMatPolynomial,
 };
 use super::timer::Timer;
 use crate::transcript::AppendToTranscript;
@@ -64,6 +65,11 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> R1CSCommitmentGens<G, PC> {
       SparseMatPolyCommitmentKey::new(SRS, num_poly_vars_x, num_poly_vars_y, num_nz_entries, 3);
     R1CSCommitmentGens { gens }
   }
+
+  /// Returns the key needed to verify [`R1CSEvalProof`]s.
+  pub fn verifier_key(&self) -> SparseMatPolyVerifierKey<G, PC> {
+    self.gens.verifier_key()
+  }
   pub fn get_min_num_vars(num_cons: usize, num_vars: usize, num_nz_entries: usize) -> usize {
     let num_poly_vars_x = num_cons.log_2();
     let num_poly_vars_y = (2 * num_vars).log_2();
@@ -379,7 +385,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> R1CSEvalProof<G, PC> {
     rx: &[G::ScalarField], // point at which the R1CS matrix polynomials are evaluated
     ry: &[G::ScalarField],
     evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
-    gens: &R1CSCommitmentGens<G, PC>,
+    vk: &SparseMatPolyVerifierKey<G, PC>,
     transcript: &mut Transcript,
   ) -> Result<(), ProofVerifyError> {
     self.proof.verify(
@@ -387,7 +393,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> R1CSEvalProof<G, PC> {
       rx,
       ry,
       &[evals.0, evals.1, evals.2],
-      &gens.gens,
+      vk,
       transcript,
     )
   }
@@ -3,7 +3,10 @@ use std::marker::PhantomData;
 use super::transcript::ProofTranscript;
 use ark_ec::CurveGroup;
 use ark_ff::UniformRand;
-use ark_std::test_rng;
+use ark_std::{
+  rand::{CryptoRng, RngCore},
+  test_rng,
+};
 use merlin::Transcript;
 
 pub struct RandomTape<G> {
@@ -29,6 +32,20 @@ impl<G: CurveGroup> RandomTape<G> {
     }
   }
 
+  /// Creates a tape seeded from `rng`, for proofs whose blinds must be unpredictable.
+  pub fn from_rng<R: RngCore + CryptoRng>(name: &'static [u8], rng: &mut R) -> Self {
+    let mut tape = Transcript::new(name);
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      &mut tape,
+      b"init_randomness",
+      &G::ScalarField::rand(rng),
+    );
+    Self {
+      tape,
+      phantom: PhantomData,
+    }
+  }
+
   pub fn random_scalar(&mut self, label: &'static [u8]) -> G::ScalarField {
     <Transcript as ProofTranscript<G>>::challenge_scalar(&mut self.tape, label)
   }
@@ -13,6 +13,7 @@ use ark_ff::{Field, PrimeField};
 use ark_serialize::*;
 use ark_std::{cmp::max, One, Zero};
 use core::cmp::Ordering;
+use derivative::Derivative;
 use merlin::Transcript;
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
@@ -372,6 +373,29 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SparseMatPolyCommitmentKey<G, P
   }
 }
 
+/// The part of [`SparseMatPolyCommitmentKey`] needed to verify evaluation proofs.
+#[derive(CanonicalDeserialize, CanonicalSerialize, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct SparseMatPolyVerifierKey<G, PC>
+where
+  G: CurveGroup,
+  PC: PolyCommitmentScheme<G>,
+{
+  vk_ops: PC::EvalVerifierKey,
+  vk_mem: PC::EvalVerifierKey,
+  vk_derefs: PC::EvalVerifierKey,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SparseMatPolyCommitmentKey<G, PC> {
+  pub fn verifier_key(&self) -> SparseMatPolyVerifierKey<G, PC> {
+    SparseMatPolyVerifierKey {
+      vk_ops: self.gens_ops.vk.clone(),
+      vk_mem: self.gens_mem.vk.clone(),
+      vk_derefs: self.gens_derefs.vk.clone(),
+    }
+  }
+}
+
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SparseMatPolyCommitment<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
   batch_size: usize,
@@ -992,7 +1016,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> HashLayerProof<G, PC> {
     ),
     claims_dotp: &[G::ScalarField],
     comm: &SparseMatPolyCommitment<G, PC>,
-    gens: &SparseMatPolyCommitmentKey<G, PC>,
+    gens: &SparseMatPolyVerifierKey<G, PC>,
     comm_derefs: &DerefsCommitment<G, PC>,
     rx: &[G::ScalarField],
     ry: &[G::ScalarField],
@@ -1015,7 +1039,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> HashLayerProof<G, PC> {
       rand_ops,
       eval_row_ops_val,
       eval_col_ops_val,
-      &gens.gens_derefs.vk,
+      &gens.vk_derefs,
       comm_derefs,
       transcript,
     )?;
@@ -1069,7 +1093,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> HashLayerProof<G, PC> {
     PC::verify(
       &comm.comm_comb_ops,
       &self.proof_ops,
-      &gens.gens_ops.vk,
+      &gens.vk_ops,
       transcript,
       &r_joint_ops,
       &joint_claim_eval_ops,
@@ -1101,7 +1125,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> HashLayerProof<G, PC> {
     PC::verify(
       &comm.comm_comb_mem,
       &self.proof_mem,
-      &gens.gens_mem.vk,
+      &gens.vk_mem,
       transcript,
       &r_joint_mem,
       &joint_claim_eval_mem,
@@ -1568,7 +1592,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> PolyEvalNetworkProof<G, PC> {
     comm: &SparseMatPolyCommitment<G, PC>,
     comm_derefs: &DerefsCommitment<G, PC>,
     evals: &[G::ScalarField],
-    gens: &SparseMatPolyCommitmentKey<G, PC>,
+    gens: &SparseMatPolyVerifierKey<G, PC>,
     rx: &[G::ScalarField],
     ry: &[G::ScalarField],
     r_mem_check: &(G::ScalarField, G::ScalarField),
@@ -1733,7 +1757,7 @@ impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SparseMatPolyEvalProof<G, PC> {
     rx: &[G::ScalarField], // point at which the polynomial is evaluated
     ry: &[G::ScalarField],
     evals: &[G::ScalarField], // evaluation of \widetilde{M}(r = (rx,ry))
-    gens: &SparseMatPolyCommitmentKey<G, PC>,
+    gens: &SparseMatPolyVerifierKey<G, PC>,
     transcript: &mut Transcript,
   ) -> Result<(), ProofVerifyError> {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
@@ -1892,7 +1916,7 @@ mod tests {
         &rx,
         &ry,
         &evals,
-        &gens,
+        &gens.verifier_key(),
         &mut verifier_transcript,
       )
       .is_ok());
@@ -7,11 +7,14 @@ use nexus_tools::command::run;
 #[derive(Debug, Parser)]
 pub struct Args {
     path: PathBuf,
+
+    /// Program arguments, passed to the entry point in a0-a2.
+    args: Vec<u32>,
 }
 
 fn main() -> anyhow::Result<()> {
-    let Args { path } = Args::parse();
+    let Args { path, args } = Args::parse();
     let verbose = false;
 
-    run::run_vm_with_elf_file(&path, verbose)
+    run::run_vm_with_elf_file(&path, verbose, args)
 }
@@ -0,0 +1,321 @@
+use std::{io, path::PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+
+use nexus_api::{
+    config::{vm as vm_config, Config},
+    prover::nova::types::{ComFoldPP, ComPCDNode, IVCProof, SeqFoldPP},
+};
+
+use super::{public_params::format_params_file, spartan_key::SetupArgs};
+
+use crate::{
+    command::{cache_path, spartan_key::spartan_setup},
+    LOG_TARGET,
+};
+
+#[derive(Debug, Args)]
+pub struct AggregateArgs {
+    /// Number of vm instructions per fold
+    #[arg(short, name = "k")]
+    pub k: Option<usize>,
+
+    /// Kind of the proofs: sequential proofs are aggregated into a single proof, compressible
+    /// PCD proofs into a single compressed proof
+    #[arg(long("impl"), default_value = "nova-par-com")]
+    pub nova_impl: vm_config::NovaImpl,
+
+    /// Parameters for aggregation; generated if not found
+    #[arg(long = "fold-params")]
+    pub fold_pp_file: Option<PathBuf>,
+
+    /// Spartan key file (compressible PCD proofs only)
+    #[arg(long = "key")]
+    pub key_file: Option<PathBuf>,
+
+    /// public parameters file; needed for sequential proofs and for generating a new Spartan key
+    #[arg(short = 'p', long = "public-params")]
+    pub pp_file: Option<PathBuf>,
+
+    /// srs file; only needed if generating a new Spartan key (compressible PCD proofs only)
+    #[arg(short = 's', long = "structured-reference-string")]
+    pub srs_file: Option<PathBuf>,
+
+    /// Files containing uncompressed proofs, made with the same public parameters
+    #[arg(required = true, num_args = 1..)]
+    pub proof_files: Vec<PathBuf>,
+}
+
+pub fn handle_command(args: AggregateArgs) -> anyhow::Result<()> {
+    aggregate_proofs(args)
+}
+
+/// Name of the file for parameters of aggregation of proofs made with `nova_impl`, which
+/// don't depend on `k`.
+pub fn format_fold_params_file(nova_impl: vm_config::NovaImpl) -> String {
+    format!("nexus-public-aggregation-{nova_impl}.zst")
+}
+
+/// Load parameters for aggregating proofs made with `nova_impl` from `path`, or from the
+/// cache, generating and caching them if they are not found.
+pub(crate) fn fold_params_path(
+    path: Option<PathBuf>,
+    nova_impl: vm_config::NovaImpl,
+) -> anyhow::Result<PathBuf> {
+    if let Some(path) = path {
+        if !path.try_exists()? {
+            tracing::error!(
+                target: LOG_TARGET,
+                "path {} was not found",
+                path.display(),
+            );
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+        return Ok(path);
+    }
+
+    let path = cache_path()?.join(format_fold_params_file(nova_impl));
+    if path.try_exists()? {
+        return Ok(path);
+    }
+    let path_str = path.to_str().context("path is not valid utf8")?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let mut context = term
+        .context("Setting up")
+        .on_step(|_step| "public parameters for aggregation".into());
+    let _guard = context.display_step();
+
+    match nova_impl {
+        vm_config::NovaImpl::Sequential => {
+            let mut pp = nexus_api::prover::nova::pp::gen_seq_fold_pp()?;
+            nexus_api::prover::nova::pp::use_seeded_bases(&mut pp)?;
+            nexus_api::prover::nova::pp::save_fold_pp(&pp, path_str)?;
+        }
+        vm_config::NovaImpl::ParallelCompressible => {
+            let mut pp = nexus_api::prover::nova::pp::gen_com_fold_pp()?;
+            nexus_api::prover::nova::pp::use_seeded_bases(&mut pp)?;
+            nexus_api::prover::nova::pp::save_fold_pp(&pp, path_str)?;
+        }
+        vm_config::NovaImpl::Parallel => {
+            anyhow::bail!("only sequential and compressible PCD proofs can be aggregated")
+        }
+    }
+    Ok(path)
+}
+
+pub fn aggregate_proofs(args: AggregateArgs) -> anyhow::Result<()> {
+    match args.nova_impl {
+        vm_config::NovaImpl::Sequential => aggregate_seq_proofs(args),
+        vm_config::NovaImpl::ParallelCompressible => aggregate_com_proofs(args),
+        vm_config::NovaImpl::Parallel => {
+            anyhow::bail!("only sequential and compressible PCD proofs can be aggregated")
+        }
+    }
+}
+
+fn check_proof_files(proof_files: &[PathBuf]) -> anyhow::Result<()> {
+    for path in proof_files {
+        if !path.try_exists()? {
+            tracing::error!(
+                target: LOG_TARGET,
+                "path {} was not found",
+                path.display(),
+            );
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+    }
+    Ok(())
+}
+
+fn aggregate_seq_proofs(args: AggregateArgs) -> anyhow::Result<()> {
+    let vm_config = vm_config::VmConfig::from_env()?;
+    let k = args.k.unwrap_or(vm_config.k);
+    anyhow::ensure!(
+        args.key_file.is_none() && args.srs_file.is_none(),
+        "sequential proofs are aggregated without compression, and need no Spartan key"
+    );
+
+    let pp_file = match args.pp_file {
+        None => cache_path()?.join(format_params_file(vm_config::NovaImpl::Sequential, k)),
+        Some(path) => path,
+    };
+    if !pp_file.try_exists()? {
+        tracing::error!(
+            target: LOG_TARGET,
+            "path {} was not found",
+            pp_file.display(),
+        );
+        return Err(io::Error::from(io::ErrorKind::NotFound).into());
+    };
+    let pp_file_str = pp_file.to_str().context("path is not valid utf8")?;
+
+    let fold_pp_file = fold_params_path(args.fold_pp_file, vm_config::NovaImpl::Sequential)?;
+    let fold_pp_file_str = fold_pp_file.to_str().context("path is not valid utf8")?;
+
+    check_proof_files(&args.proof_files)?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+
+    let (pp, fold_pp) = {
+        let mut context = term
+            .context("Loading")
+            .on_step(|_step| "public parameters".into());
+        let _guard = context.display_step();
+
+        let fold_pp: SeqFoldPP = nexus_api::prover::nova::pp::load_fold_pp(fold_pp_file_str)?;
+        (nexus_api::prover::nova::pp::load_pp(pp_file_str)?, fold_pp)
+    };
+
+    let num_proofs = args.proof_files.len();
+    let proofs = {
+        let mut context = term
+            .context("Verifying")
+            .on_step(|step| format!("proof {step}"))
+            .num_steps(num_proofs);
+
+        args.proof_files
+            .iter()
+            .map(|path| {
+                let _guard = context.display_step();
+
+                let proof: IVCProof = nexus_api::prover::nova::load_proof(path)?;
+                proof
+                    .verify(&pp, proof.step_num() as _)
+                    .with_context(|| format!("proof {} is invalid", path.display()))?;
+                Ok(proof)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    };
+
+    let aggregate = {
+        let mut term_ctx = term
+            .context("Aggregating")
+            .on_step(move |_step| format!("{num_proofs} proofs"));
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::aggregate_seq(&pp, &fold_pp, &proofs)?
+    };
+
+    let current_dir = std::env::current_dir()?;
+    let aggregated_proof_path = current_dir.join("nexus-proof-aggregated");
+
+    let mut context = term.context("Saving").on_step(|_step| "proof".into());
+    let _guard = context.display_step();
+
+    nexus_api::prover::nova::save_proof(aggregate, &aggregated_proof_path)?;
+
+    Ok(())
+}
+
+fn aggregate_com_proofs(args: AggregateArgs) -> anyhow::Result<()> {
+    let vm_config = vm_config::VmConfig::from_env()?;
+    let k = args.k.unwrap_or(vm_config.k);
+
+    let pp_file = match args.pp_file {
+        None => {
+            let nova_impl = vm_config::NovaImpl::ParallelCompressible;
+
+            let pp_file_name = format_params_file(nova_impl, k);
+            let cache_path = cache_path()?;
+
+            cache_path.join(pp_file_name)
+        }
+        Some(path) => path,
+    };
+    if !pp_file.try_exists()? {
+        tracing::error!(
+            target: LOG_TARGET,
+            "path {} was not found",
+            pp_file.display(),
+        );
+        return Err(io::Error::from(io::ErrorKind::NotFound).into());
+    };
+    let pp_file_str = pp_file.to_str().context("path is not valid utf8")?;
+
+    let key_file = if let Some(path) = args.key_file {
+        // return early if the path was explicitly specified and doesn't exist
+        if !path.try_exists()? {
+            tracing::error!(
+                target: LOG_TARGET,
+                "path {} was not found",
+                path.display(),
+            );
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+        path
+    } else {
+        spartan_setup(SetupArgs {
+            path: None,
+            force: false,
+            k: Some(k),
+            pp_file: Some(pp_file.clone()),
+            srs_file: args.srs_file,
+            zk: false,
+            precompiles: Default::default(),
+        })?
+    };
+    let key_file_str = key_file.to_str().context("path is not valid utf8")?;
+
+    let fold_pp_file =
+        fold_params_path(args.fold_pp_file, vm_config::NovaImpl::ParallelCompressible)?;
+    let fold_pp_file_str = fold_pp_file.to_str().context("path is not valid utf8")?;
+
+    check_proof_files(&args.proof_files)?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+
+    let (pp, fold_pp, key) = {
+        let mut context = term
+            .context("Loading")
+            .on_step(|_step| "public parameters and key".into());
+        let _guard = context.display_step();
+
+        let fold_pp: ComFoldPP = nexus_api::prover::nova::pp::load_fold_pp(fold_pp_file_str)?;
+        (
+            nexus_api::prover::nova::pp::load_pp(pp_file_str)?,
+            fold_pp,
+            nexus_api::prover::nova::key::load_key(key_file_str)?,
+        )
+    };
+
+    let num_proofs = args.proof_files.len();
+    let nodes = {
+        let mut context = term
+            .context("Verifying")
+            .on_step(|step| format!("proof {step}"))
+            .num_steps(num_proofs);
+
+        args.proof_files
+            .iter()
+            .map(|path| {
+                let _guard = context.display_step();
+
+                let node: ComPCDNode = nexus_api::prover::nova::load_proof(path)?;
+                node.verify(&pp)
+                    .with_context(|| format!("proof {} is invalid", path.display()))?;
+                Ok(node)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    };
+
+    let aggregated_proof = {
+        let mut term_ctx = term
+            .context("Aggregating")
+            .on_step(move |_step| format!("{num_proofs} proofs"));
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::aggregate(&pp, &fold_pp, &key, &nodes)?
+    };
+
+    let current_dir = std::env::current_dir()?;
+    let aggregated_proof_path = current_dir.join("nexus-proof-aggregated");
+
+    let mut context = term.context("Saving").on_step(|_step| "proof".into());
+    let _guard = context.display_step();
+
+    nexus_api::prover::nova::save_proof(aggregated_proof, &aggregated_proof_path)?;
+
+    Ok(())
+}
@@ -1,11 +1,20 @@
-use std::{io, path::PathBuf};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Context;
+use ark_serialize::CanonicalSerialize;
 use clap::Args;
 
 use nexus_api::config::{vm as vm_config, Config};
+use nexus_api::nvm::Precompiles;
+use nexus_api::prover::nova::{error::ProofError, types::ComPP};
 
-use super::{public_params::format_params_file, spartan_key::SetupArgs};
+use super::{
+    public_params::{format_params_file, with_precompiles_suffix, with_zk_suffix},
+    spartan_key::SetupArgs,
+};
 
 use crate::{
     command::{cache_path, spartan_key::spartan_setup},
@@ -33,6 +42,15 @@ pub struct CompressArgs {
     /// File containing uncompressed proof
     #[arg(short = 'f', long = "proof-file")]
     pub proof_file: PathBuf,
+
+    /// Randomize the proof before compressing, so that it reveals nothing about the execution;
+    /// public parameters must be set up with `--zk`
+    #[arg(long)]
+    pub zk: bool,
+
+    /// Use public parameters set up with `--precompiles`.
+    #[arg(long, default_value = "none")]
+    pub precompiles: Precompiles,
 }
 
 pub fn handle_command(args: CompressArgs) -> anyhow::Result<()> {
@@ -48,6 +66,8 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
             let nova_impl = vm_config::NovaImpl::ParallelCompressible;
 
             let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = with_precompiles_suffix(pp_file_name, args.precompiles);
+            let pp_file_name = with_zk_suffix(pp_file_name, args.zk);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -69,7 +89,13 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
     );
     let pp_file_str = pp_file.to_str().context("path is not valid utf8")?;
 
-    let pp = nexus_api::prover::nova::pp::load_pp(pp_file_str)?;
+    let pp: ComPP = nexus_api::prover::nova::pp::load_pp(pp_file_str)?;
+    if args.zk && !pp.zk {
+        anyhow::bail!(
+            "public parameters in {} were not set up with `--zk`",
+            pp_file.display()
+        );
+    }
 
     let key_file = if let Some(path) = args.key_file {
         // return early if the path was explicitly specified and doesn't exist
@@ -89,6 +115,8 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
             k: Some(k),
             pp_file: Some(pp_file),
             srs_file: args.srs_file,
+            zk: args.zk,
+            precompiles: args.precompiles,
         })?
     };
     let key_file_str = key_file.to_str().context("path is not valid utf8")?;
@@ -116,19 +144,36 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
     let current_dir = std::env::current_dir()?;
     let compressed_proof_path = current_dir.join("nexus-proof-compressed");
 
+    // randomized proofs have a different format
+    if args.zk {
+        compress_to_file(&mut term, &compressed_proof_path, || {
+            nexus_api::prover::nova::compress_zk(&pp, &key, proof)
+        })
+    } else {
+        compress_to_file(&mut term, &compressed_proof_path, || {
+            nexus_api::prover::nova::compress(&pp, &key, proof)
+        })
+    }
+}
+
+fn compress_to_file<P: CanonicalSerialize>(
+    term: &mut nexus_tui::TerminalHandle,
+    path: &Path,
+    compress: impl FnOnce() -> Result<P, ProofError>,
+) -> anyhow::Result<()> {
     let compressed_proof = {
         let mut term_ctx = term
             .context("Compressing")
             .on_step(|_step| "the proof".into());
         let _guard = term_ctx.display_step();
 
-        nexus_api::prover::nova::compress(&pp, &key, proof)?
+        compress()?
     };
 
     let mut context = term.context("Saving").on_step(|_step| "proof".into());
     let _guard = context.display_step();
 
-    nexus_api::prover::nova::save_proof(compressed_proof, &compressed_proof_path)?;
+    nexus_api::prover::nova::save_proof(compressed_proof, path)?;
 
     Ok(())
 }
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::{
+    utils::{cargo, path_to_artifact},
+    LOG_TARGET,
+};
+
+#[derive(Debug, Args)]
+pub struct DisasmArgs {
+    /// ELF file to disassemble; defaults to the bin target of the current package.
+    pub path: Option<PathBuf>,
+
+    /// Build artifacts with the specified profile. "dev" is default.
+    #[arg(long, default_value = "dev")]
+    pub profile: String,
+
+    /// Name of the bin target to disassemble.
+    #[arg(long, conflicts_with = "path")]
+    pub bin: Option<String>,
+}
+
+pub fn handle_command(args: DisasmArgs) -> anyhow::Result<()> {
+    let DisasmArgs { path, profile, bin } = args;
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            // build artifact
+            cargo(None, ["build", "--profile", &profile])?;
+            path_to_artifact(bin, &profile)?
+        }
+    };
+
+    let bytes = std::fs::read(&path)?;
+    let listing = nexus_api::nvm::disasm::disassemble(&bytes)?;
+    print!("{listing}");
+
+    let unsupported: Vec<_> = listing.unsupported().collect();
+    if unsupported.is_empty() {
+        tracing::info!(
+            target: LOG_TARGET,
+            "All {} instruction(s) are supported by the Nexus VM",
+            listing.lines.len(),
+        );
+    } else {
+        for l in &unsupported {
+            tracing::warn!(target: LOG_TARGET, "{l}");
+        }
+        tracing::warn!(
+            target: LOG_TARGET,
+            "{} instruction(s) are not supported by the Nexus VM",
+            unsupported.len(),
+        );
+    }
+    Ok(())
+}
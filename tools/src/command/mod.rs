@@ -5,7 +5,9 @@ use nexus_api::config::{Config, MiscConfig};
 
 use super::ENV;
 
+pub mod aggregate;
 pub mod compress;
+pub mod disasm;
 pub mod new;
 pub mod prove;
 pub mod public_params;
@@ -35,6 +37,10 @@ pub enum Command {
     SpartanKey(spartan_key::SpartanSetupArgs),
     /// Compress a Nova proof.
     Compress(compress::CompressArgs),
+    /// Aggregate Nova proofs of different programs into one compressed proof.
+    Aggregate(aggregate::AggregateArgs),
+    /// Disassemble a binary and check it for unsupported instructions.
+    Disasm(disasm::DisasmArgs),
 }
 
 pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
@@ -48,7 +54,9 @@ pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
         Command::Verify(args) => verify::handle_command(args),
         Command::PublicParams(args) => public_params::handle_command(args),
         Command::Compress(args) => compress::handle_command(args),
+        Command::Aggregate(args) => aggregate::handle_command(args),
         Command::SpartanKey(args) => spartan_key::handle_command(args),
+        Command::Disasm(args) => disasm::handle_command(args),
     }
 }
 
@@ -23,11 +23,11 @@ pub struct ProveArgs {
     pub common_args: CommonProveArgs,
 
     /// Send prove request to the network.
-    #[arg(long, conflicts_with_all = ["k", "pp_file", "impl"])]
+    #[arg(long, conflicts_with_all = ["k", "pp_file", "impl", "addr_bits", "precompiles", "zk"])]
     pub network: bool,
 
     /// Node address for accessing API.
-    #[arg(long, conflicts_with_all = ["k", "pp_file", "impl"])]
+    #[arg(long, conflicts_with_all = ["k", "pp_file", "impl", "addr_bits", "precompiles", "zk"])]
     pub url: Option<String>,
 
     #[command(flatten)]
@@ -49,6 +49,26 @@ pub struct LocalProveArgs {
     /// Path to the SRS file: only needed when pp_file is None and nova_impl is ParallelCompressible.
     #[arg(long("srs-file"))]
     pub srs_file: Option<PathBuf>,
+
+    /// Number of bits of addressable memory; must match the public parameters.
+    #[arg(long, value_parser = nexus_api::nvm::memory::parse_addr_bits)]
+    pub addr_bits: Option<usize>,
+
+    /// Precompiles the program may call; must match the public parameters.
+    #[arg(long, default_value = "none")]
+    pub precompiles: nexus_api::nvm::Precompiles,
+
+    /// Program arguments, passed to the entry point in a0-a2.
+    #[arg(long, value_delimiter = ',')]
+    pub args: Vec<u32>,
+
+    /// Use public parameters set up with `--zk`.
+    #[arg(long)]
+    pub zk: bool,
+
+    /// Check memory offline instead of with a Merkle tree (sequential proofs only).
+    #[arg(long, conflicts_with = "zk")]
+    pub offline: bool,
 }
 
 #[derive(Debug, Args)]
@@ -84,7 +104,17 @@ pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
         let url = url.context("url must be specified")?;
         request_prove(&path, &url)
     } else {
-        let LocalProveArgs { k, pp_file, prover_impl, srs_file } = local_args;
+        let LocalProveArgs {
+            k,
+            pp_file,
+            prover_impl,
+            srs_file,
+            addr_bits,
+            precompiles,
+            args,
+            zk,
+            offline,
+        } = local_args;
 
         // workaround to enforce runtime to rebuild -- set env (cli args take priority)
         if let Some(prover) = prover_impl {
@@ -96,7 +126,18 @@ pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
 
         let k = k.unwrap_or(vm_config.k);
         let prover_impl = prover_impl.unwrap_or(vm_config.prover);
-        local_prove(&path, k, prover_impl, pp_file, srs_file)
+        local_prove(
+            &path,
+            k,
+            prover_impl,
+            pp_file,
+            srs_file,
+            addr_bits,
+            precompiles,
+            args,
+            zk,
+            offline,
+        )
     }
 }
 
@@ -121,12 +162,18 @@ fn request_prove(_path: &Path, _url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn local_prove(
     path: &Path,
     k: usize,
     prover: vm_config::ProverImpl,
     pp_file: Option<PathBuf>,
     srs_file: Option<PathBuf>,
+    addr_bits: Option<usize>,
+    precompiles: nexus_api::nvm::Precompiles,
+    args: Vec<u32>,
+    zk: bool,
+    offline: bool,
 ) -> anyhow::Result<()> {
     // handle jolt separately
     let nova_impl = match prover {
@@ -136,6 +183,13 @@ fn local_prove(
         }
         vm_config::ProverImpl::Nova(nova_impl) => nova_impl,
     };
+    if offline {
+        nexus_api::prover::nova::pp::check_offline(nova_impl, zk)?;
+    }
+
+    // reject programs the VM cannot execute before doing any work
+    let bytes = std::fs::read(path)?;
+    nexus_api::nvm::validate::validate_elf(&bytes, addr_bits)?;
 
     // setup if necessary
     let pp_file = if let Some(path) = pp_file {
@@ -156,6 +210,11 @@ fn local_prove(
             path: None,
             force: false,
             srs_file,
+            addr_bits,
+            precompiles,
+            seeded: false,
+            zk,
+            offline,
         })?
     };
     let path_str = pp_file.to_str().context("path is not valid utf8")?;
@@ -164,13 +223,32 @@ fn local_prove(
         k,
         machine: None,
         file: Some(path.into()),
+        addr_bits,
+        asm: None,
+        args,
+        precompiles,
     };
-    let trace = nexus_api::prover::nova::run(&opts, true)?;
-    let k = trace.k;
 
     let current_dir = std::env::current_dir()?;
     let proof_path = current_dir.join("nexus-proof");
 
+    if offline {
+        return local_prove_offline(&opts, path_str, &proof_path);
+    }
+
+    // lets the proof be verified without the program, with `verify --program-digest`
+    let statement = nexus_api::prover::nova::statement::Statement::for_program(&opts)?;
+    if let Some(digest) = statement.initial_digest() {
+        tracing::info!(
+            target: LOG_TARGET,
+            %digest,
+            "Program digest",
+        );
+    }
+
+    let trace = nexus_api::prover::nova::run(&opts, true)?;
+    let k = trace.k;
+
     let mut term = nexus_tui::TerminalHandle::new_enabled();
 
     let tr = nexus_api::prover::nova::init_circuit_trace(trace)?;
@@ -342,3 +420,55 @@ fn local_prove(
 
     Ok(())
 }
+
+// Sequential proving with offline memory checking; the proof is saved
+// together with the audit of its memory.
+fn local_prove_offline(
+    opts: &nexus_api::nvm::VMOpts,
+    pp_path: &str,
+    proof_path: &Path,
+) -> anyhow::Result<()> {
+    let (trace, audit) = nexus_api::prover::nova::run_offline(opts, false)?;
+    let tr = nexus_api::prover::nova::circuit::Tr::new(trace)?;
+    let num_steps = tr.steps();
+    let icount = tr.instructions();
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let mut term_ctx = term
+        .context("Computing")
+        .on_step(|iter| format!("step {iter}"))
+        .num_steps(num_steps)
+        .with_loading_bar("Proving")
+        .completion_header("Proved")
+        .completion_stats(move |elapsed| {
+            format!(
+                "{num_steps} step(s) in {elapsed}; {:.2} instructions / second",
+                icount as f32 / elapsed.as_secs_f32()
+            )
+        });
+
+    let mut iterm = nexus_tui::TerminalHandle::new_enabled();
+    let state = {
+        let mut term_ctx = iterm
+            .context("Loading")
+            .on_step(|_step| "public parameters".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::pp::load_offline_pp(pp_path)?
+    };
+
+    let mut proof = nexus_api::prover::nova::prove_seq_offline_step(None, &state, &tr)?;
+
+    for _ in 1..num_steps {
+        let _guard = term_ctx.display_step();
+        proof = nexus_api::prover::nova::prove_seq_offline_step(Some(proof), &state, &tr)?;
+    }
+
+    let mut context = term.context("Saving").on_step(|_step| "proof".into());
+    let _guard = context.display_step();
+
+    let proof = nexus_api::prover::nova::OfflineProof { proof, audit };
+    nexus_api::prover::nova::save_proof(proof, proof_path)?;
+
+    Ok(())
+}
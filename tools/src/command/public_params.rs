@@ -9,6 +9,7 @@ use nexus_api::config::{
     vm::{self as vm_config, ProverImpl},
     Config,
 };
+use nexus_api::nvm::Precompiles;
 use nexus_api::prover::nova::srs::{get_min_srs_size, test_srs::gen_test_srs_to_file};
 use nexus_api::prover::nova::types::{ComPP, ParPP, SeqPP, SRS};
 
@@ -48,11 +49,25 @@ pub(crate) fn setup_params(args: SetupArgs) -> anyhow::Result<PathBuf> {
     };
 
     let srs_file = args.srs_file;
+    let addr_bits = args.addr_bits;
+    let precompiles = args.precompiles;
+    let seeded = args.seeded;
+    let zk = args.zk;
+    let offline = args.offline;
+    if offline {
+        nexus_api::prover::nova::pp::check_offline(nova_impl, zk)?;
+    }
 
     let path = match args.path {
         Some(path) => path,
         None => {
-            let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = match addr_bits {
+                None => format_params_file(nova_impl, k),
+                Some(bits) => format_params_file_with_addr_bits(nova_impl, k, bits),
+            };
+            let pp_file_name = with_precompiles_suffix(pp_file_name, precompiles);
+            let pp_file_name = with_zk_suffix(pp_file_name, zk);
+            let pp_file_name = with_offline_suffix(pp_file_name, offline);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -68,15 +83,63 @@ pub(crate) fn setup_params(args: SetupArgs) -> anyhow::Result<PathBuf> {
         return Ok(path);
     }
 
-    setup_params_to_file(&path, nova_impl, k, srs_file)?;
+    if offline {
+        setup_offline_params_to_file(&path, k, precompiles, seeded)?;
+    } else {
+        setup_params_to_file(
+            &path,
+            nova_impl,
+            k,
+            addr_bits,
+            precompiles,
+            srs_file,
+            seeded,
+            zk,
+        )?;
+    }
     Ok(path)
 }
 
+fn setup_offline_params_to_file(
+    path: &Path,
+    k: usize,
+    precompiles: Precompiles,
+    seeded: bool,
+) -> anyhow::Result<()> {
+    let path = path.to_str().context("path is not valid utf8")?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating IVC public parameters for offline memory checking",
+    );
+
+    let mut pp = {
+        let mut term_ctx = term
+            .context("Setting up")
+            .on_step(|_step| "public parameters for IVC".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::pp::gen_vm_pp_offline(k, precompiles)?
+    };
+    if seeded {
+        nexus_api::prover::nova::pp::use_seeded_bases(&mut pp)?;
+    }
+    nexus_api::prover::nova::pp::save_offline_pp(&pp, path)?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn setup_params_to_file(
     path: &Path,
     nova_impl: vm_config::NovaImpl,
     k: usize,
+    addr_bits: Option<usize>,
+    precompiles: Precompiles,
     srs_file: Option<PathBuf>,
+    seeded: bool,
+    zk: bool,
 ) -> anyhow::Result<()> {
     let path = path.to_str().context("path is not valid utf8")?;
 
@@ -89,14 +152,23 @@ fn setup_params_to_file(
                 "Generating IVC public parameters",
             );
 
-            let pp: SeqPP = {
+            let mut pp: SeqPP = {
                 let mut term_ctx = term
                     .context("Setting up")
                     .on_step(|_step| "public parameters for IVC".into());
                 let _guard = term_ctx.display_step();
 
-                nexus_api::prover::nova::pp::gen_vm_pp(k, &())?
+                nexus_api::prover::nova::pp::gen_vm_pp_with_addr_bits(
+                    k,
+                    addr_bits,
+                    precompiles,
+                    zk,
+                    &(),
+                )?
             };
+            if seeded {
+                nexus_api::prover::nova::pp::use_seeded_bases(&mut pp)?;
+            }
             nexus_api::prover::nova::pp::show_pp(&pp);
             nexus_api::prover::nova::pp::save_pp(&pp, path)
         }
@@ -105,7 +177,16 @@ fn setup_params_to_file(
                 target: LOG_TARGET,
                 "Generating non-compressible PCD public parameters",
             );
-            let pp: ParPP = nexus_api::prover::nova::pp::gen_vm_pp(k, &())?;
+            let mut pp: ParPP = nexus_api::prover::nova::pp::gen_vm_pp_with_addr_bits(
+                k,
+                addr_bits,
+                precompiles,
+                zk,
+                &(),
+            )?;
+            if seeded {
+                nexus_api::prover::nova::pp::use_seeded_bases(&mut pp)?;
+            }
 
             nexus_api::prover::nova::pp::show_pp(&pp);
             nexus_api::prover::nova::pp::save_pp(&pp, path)
@@ -113,7 +194,7 @@ fn setup_params_to_file(
         vm_config::NovaImpl::ParallelCompressible => {
             let srs_file = match srs_file {
                 None => {
-                    let srs_file_name = format_srs_file(get_min_srs_size(k)?);
+                    let srs_file_name = format_srs_file(get_min_srs_size(k, precompiles, zk)?);
                     let cache_path = cache_path()?;
 
                     cache_path.join(srs_file_name)
@@ -145,14 +226,23 @@ fn setup_params_to_file(
                 srs.max_num_vars
             );
 
-            let pp: ComPP = {
+            let mut pp: ComPP = {
                 tracing::info!(
                     target: LOG_TARGET,
                     "Generating compressible PCD public parameters",
                 );
 
-                nexus_api::prover::nova::pp::gen_vm_pp(k, &srs)?
+                nexus_api::prover::nova::pp::gen_vm_pp_with_addr_bits(
+                    k,
+                    addr_bits,
+                    precompiles,
+                    zk,
+                    &srs,
+                )?
             };
+            if seeded {
+                nexus_api::prover::nova::pp::use_seeded_bases_com(&mut pp)?;
+            }
 
             nexus_api::prover::nova::pp::show_pp(&pp);
             nexus_api::prover::nova::pp::save_pp(&pp, path)
@@ -166,7 +256,7 @@ pub fn sample_test_srs(args: SRSSetupArgs) -> anyhow::Result<PathBuf> {
         None => {
             let vm_config = vm_config::VmConfig::from_env()?;
             let k = args.k.unwrap_or(vm_config.k);
-            get_min_srs_size(k)?
+            get_min_srs_size(k, args.precompiles, args.zk)?
         }
         Some(num_vars) => num_vars,
     };
@@ -201,6 +291,42 @@ pub fn format_params_file(nova_impl: vm_config::NovaImpl, k: usize) -> String {
     format!("nexus-public-{nova_impl}-{k}.zst")
 }
 
+pub fn format_params_file_with_addr_bits(
+    nova_impl: vm_config::NovaImpl,
+    k: usize,
+    addr_bits: usize,
+) -> String {
+    format!("nexus-public-{nova_impl}-{k}-a{addr_bits}.zst")
+}
+
+/// Name of the file for public parameters checking `precompiles`, or for keys derived from them.
+pub fn with_precompiles_suffix(file_name: String, precompiles: Precompiles) -> String {
+    if precompiles == Precompiles::default() {
+        file_name
+    } else {
+        let names = precompiles.to_string().replace(',', "-");
+        format!("{}-{names}.zst", file_name.trim_end_matches(".zst"))
+    }
+}
+
+/// Name of the file for zero-knowledge public parameters, or for keys derived from them.
+pub fn with_zk_suffix(file_name: String, zk: bool) -> String {
+    if zk {
+        format!("{}-zk.zst", file_name.trim_end_matches(".zst"))
+    } else {
+        file_name
+    }
+}
+
+/// Name of the file for public parameters using offline memory checking.
+pub fn with_offline_suffix(file_name: String, offline: bool) -> String {
+    if offline {
+        format!("{}-offline.zst", file_name.trim_end_matches(".zst"))
+    } else {
+        file_name
+    }
+}
+
 pub fn format_srs_file(num_vars: usize) -> String {
     format!("nexus-srs-{num_vars}.zst")
 }
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use clap::{Args, Subcommand};
-use nexus_api::config::vm as vm_config;
+use nexus_api::{config::vm as vm_config, nvm::Precompiles};
 
 #[derive(Debug, Args)]
 pub struct PublicParamsArgs {
@@ -34,6 +34,14 @@ pub struct SRSSetupArgs {
     /// Overwrite the file if it already exists.
     #[arg(long)]
     pub force: bool,
+
+    /// Size the SRS for public parameters set up with `--zk`.
+    #[arg(long, conflicts_with = "num_vars")]
+    pub zk: bool,
+
+    /// Size the SRS for public parameters set up with `--precompiles`.
+    #[arg(long, default_value = "none", conflicts_with = "num_vars")]
+    pub precompiles: Precompiles,
 }
 
 #[derive(Debug, Default, Args)]
@@ -56,4 +64,25 @@ pub struct SetupArgs {
     /// Path to the SRS file (only required for compressible PCD proofs).
     #[arg(long("srs_file"))]
     pub srs_file: Option<PathBuf>,
+
+    /// Number of bits of addressable memory; defaults to the full 32-bit address space.
+    #[arg(long, value_parser = nexus_api::nvm::memory::parse_addr_bits)]
+    pub addr_bits: Option<usize>,
+
+    /// Precompiles programs may call, as a comma-separated list, or none; only the selected
+    /// precompiles add constraints to each step.
+    #[arg(long, default_value = "none")]
+    pub precompiles: Precompiles,
+
+    /// Only store the seed Pedersen bases are derived from, bases are regenerated on load.
+    #[arg(long)]
+    pub seeded: bool,
+
+    /// Blind the augmented circuit, so that proofs can be compressed with `compress --zk`.
+    #[arg(long)]
+    pub zk: bool,
+
+    /// Check memory offline instead of with a Merkle tree (sequential proofs only).
+    #[arg(long, conflicts_with_all = ["srs_file", "zk"])]
+    pub offline: bool,
 }
@@ -2,7 +2,10 @@ use std::path::Path;
 
 use clap::Args;
 
-use crate::utils::{cargo, path_to_artifact};
+use crate::{
+    utils::{cargo, path_to_artifact},
+    LOG_TARGET,
+};
 
 #[derive(Debug, Args)]
 pub struct RunArgs {
@@ -17,29 +20,81 @@ pub struct RunArgs {
     /// Name of the bin target to run.
     #[arg(long)]
     pub bin: Option<String>,
+
+    /// Check each step of the execution against the step circuit.
+    #[arg(long)]
+    pub check_circuit: bool,
+
+    /// Program arguments, passed to the entry point in a0-a2.
+    #[arg(long, value_delimiter = ',')]
+    pub args: Vec<u32>,
 }
 
 pub fn handle_command(args: RunArgs) -> anyhow::Result<()> {
-    let RunArgs { verbose, profile, bin } = args;
+    let RunArgs {
+        verbose,
+        profile,
+        bin,
+        check_circuit,
+        args,
+    } = args;
 
-    run_vm(bin, verbose, &profile)
+    run_vm(bin, verbose, check_circuit, &profile, args)
 }
 
-fn run_vm(bin: Option<String>, verbose: bool, profile: &str) -> anyhow::Result<()> {
+fn run_vm(
+    bin: Option<String>,
+    verbose: bool,
+    check_circuit: bool,
+    profile: &str,
+    args: Vec<u32>,
+) -> anyhow::Result<()> {
     // build artifact
     cargo(None, ["build", "--profile", profile])?;
 
     let path = path_to_artifact(bin, profile)?;
 
-    run_vm_with_elf_file(&path, verbose)
+    if check_circuit {
+        check_circuit_with_elf_file(&path, args)
+    } else {
+        run_vm_with_elf_file(&path, verbose, args)
+    }
 }
 
-pub fn run_vm_with_elf_file(path: &Path, verbose: bool) -> anyhow::Result<()> {
+pub fn run_vm_with_elf_file(path: &Path, verbose: bool, args: Vec<u32>) -> anyhow::Result<()> {
     let opts = nexus_api::nvm::VMOpts {
         k: 1,
         machine: None,
         file: Some(path.into()),
+        addr_bits: None,
+        asm: None,
+        args,
+        // the program is only run, so it may call any precompile
+        precompiles: nexus_api::nvm::Precompiles::ALL,
     };
 
     nexus_api::nvm::run_vm::<nexus_api::nvm::memory::Paged>(&opts, verbose).map_err(Into::into)
 }
+
+pub fn check_circuit_with_elf_file(path: &Path, args: Vec<u32>) -> anyhow::Result<()> {
+    let opts = nexus_api::nvm::VMOpts {
+        k: 1,
+        machine: None,
+        file: Some(path.into()),
+        addr_bits: None,
+        asm: None,
+        args,
+        // check the gadgets of any precompile the program calls
+        precompiles: nexus_api::nvm::Precompiles::ALL,
+    };
+
+    let trace = nexus_api::nvm::trace_vm::<nexus_api::nvm::memory::Paged>(&opts, false, false)?;
+    let steps = trace.blocks.len();
+    nexus_api::nvm::circuit::check_trace(&trace)?;
+
+    tracing::info!(
+        target: LOG_TARGET,
+        "Step circuit satisfied for all {steps} step(s)",
+    );
+    Ok(())
+}
@@ -7,9 +7,12 @@ use std::{
 use anyhow::Context;
 
 use nexus_api::config::{vm as vm_config, Config};
+use nexus_api::nvm::Precompiles;
 use nexus_api::prover::nova::{srs::get_min_srs_size, types::ComPP};
 
-use super::public_params::{format_params_file, format_srs_file};
+use super::public_params::{
+    format_params_file, format_srs_file, with_precompiles_suffix, with_zk_suffix,
+};
 use crate::{command::cache_path, LOG_TARGET};
 
 #[derive(Debug, Args)]
@@ -22,6 +25,8 @@ pub struct SpartanSetupArgs {
 pub enum SpartanSetupAction {
     /// Generate Spartan key to file.
     Setup(SetupArgs),
+    /// Extract the key needed to verify compressed proofs to file.
+    VerifierKey(VerifierKeyArgs),
 }
 
 #[derive(Debug, Default, Args)]
@@ -45,12 +50,55 @@ pub struct SetupArgs {
     /// Path to the Zeromorph structured reference string.
     #[arg(short = 's', long = "srs")]
     pub srs_file: Option<PathBuf>,
+
+    /// Use public parameters set up with `--zk`.
+    #[arg(long)]
+    pub zk: bool,
+
+    /// Use public parameters set up with `--precompiles`.
+    #[arg(long, default_value = "none")]
+    pub precompiles: Precompiles,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct VerifierKeyArgs {
+    /// Where to save the file.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Overwrite the file if it already exists
+    #[arg(long)]
+    pub force: bool,
+
+    /// Number of vm instructions per fold.
+    #[arg(short, name = "k")]
+    pub k: Option<usize>,
+
+    /// Path to Nova public parameters file.
+    #[arg(short = 'p', long = "public_params")]
+    pub pp_file: Option<PathBuf>,
+
+    /// Path to the Spartan key file.
+    #[arg(long = "key")]
+    pub key_file: Option<PathBuf>,
+
+    /// Use public parameters set up with `--zk`.
+    #[arg(long)]
+    pub zk: bool,
+
+    /// Use public parameters set up with `--precompiles`.
+    #[arg(long, default_value = "none")]
+    pub precompiles: Precompiles,
 }
 
 pub fn format_key_file(k: usize) -> String {
     format!("nexus-spartan-key-{k}.zst")
 }
 
+pub fn format_vk_file(k: usize) -> String {
+    format!("nexus-verifier-key-{k}.zst")
+}
+
 pub fn handle_command(args: SpartanSetupArgs) -> anyhow::Result<()> {
     let action = args
         .command
@@ -59,6 +107,9 @@ pub fn handle_command(args: SpartanSetupArgs) -> anyhow::Result<()> {
         SpartanSetupAction::Setup(setup_args) => {
             let _ = spartan_setup(setup_args)?;
         }
+        SpartanSetupAction::VerifierKey(vk_args) => {
+            let _ = extract_verifier_key(vk_args)?;
+        }
     }
     Ok(())
 }
@@ -71,7 +122,9 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
     let nova_impl = vm_config::NovaImpl::ParallelCompressible;
     let pp_file = match args.pp_file {
         None => {
-            let pp_file = format_params_file(nova_impl, k);
+            let pp_file =
+                with_precompiles_suffix(format_params_file(nova_impl, k), args.precompiles);
+            let pp_file = with_zk_suffix(pp_file, args.zk);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file)
@@ -89,7 +142,7 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
 
     let srs_file = match args.srs_file {
         None => {
-            let srs_file_name = format_srs_file(get_min_srs_size(k)?);
+            let srs_file_name = format_srs_file(get_min_srs_size(k, args.precompiles, args.zk)?);
             let cache_path = cache_path()?;
 
             cache_path.join(srs_file_name)
@@ -108,7 +161,9 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
     let key_path = match args.path {
         Some(path) => path,
         None => {
-            let key_file_name = format_key_file(vm_config.k);
+            let key_file_name =
+                with_precompiles_suffix(format_key_file(vm_config.k), args.precompiles);
+            let key_file_name = with_zk_suffix(key_file_name, args.zk);
             let cache_path = cache_path()?;
             cache_path.join(key_file_name)
         }
@@ -174,3 +229,83 @@ fn spartan_setup_to_file(key_path: &Path, pp_path: &Path, srs_path: &Path) -> an
 
     Ok(())
 }
+
+pub(crate) fn extract_verifier_key(args: VerifierKeyArgs) -> anyhow::Result<PathBuf> {
+    let vm_config = vm_config::VmConfig::from_env()?;
+    let k = args.k.unwrap_or(vm_config.k);
+
+    let vk_path = match args.path {
+        Some(path) => path,
+        None => cache_path()?.join(with_zk_suffix(
+            with_precompiles_suffix(format_vk_file(k), args.precompiles),
+            args.zk,
+        )),
+    };
+    if !args.force && vk_path.try_exists()? {
+        tracing::info!(
+            target: LOG_TARGET,
+            "path {} already exists, use `verifier-key --force` to overwrite",
+            vk_path.display(),
+        );
+        return Ok(vk_path);
+    }
+
+    let pp_file = match args.pp_file {
+        Some(path) => path,
+        None => cache_path()?.join(with_zk_suffix(
+            with_precompiles_suffix(
+                format_params_file(vm_config::NovaImpl::ParallelCompressible, k),
+                args.precompiles,
+            ),
+            args.zk,
+        )),
+    };
+    let key_file = match args.key_file {
+        Some(path) => path,
+        None => cache_path()?.join(with_zk_suffix(
+            with_precompiles_suffix(format_key_file(k), args.precompiles),
+            args.zk,
+        )),
+    };
+    for path in [&pp_file, &key_file] {
+        if !path.try_exists()? {
+            tracing::error!(
+                target: LOG_TARGET,
+                "path {} was not found",
+                path.display(),
+            );
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+    }
+
+    let vk_path_str = vk_path.to_str().context("path is not valid utf8")?;
+    let pp_path_str = pp_file.to_str().context("path is not valid utf8")?;
+    let key_path_str = key_file.to_str().context("path is not valid utf8")?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let pp: ComPP = {
+        let mut term_ctx = term
+            .context("Loading")
+            .on_step(|_step| "Nova public parameters".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::pp::load_pp(pp_path_str)?
+    };
+    let key = {
+        let mut term_ctx = term
+            .context("Loading")
+            .on_step(|_step| "Spartan key".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_api::prover::nova::key::load_key(key_path_str)?
+    };
+
+    let mut term_ctx = term
+        .context("Extracting")
+        .on_step(|_step| "verifier key".into());
+    let _guard = term_ctx.display_step();
+
+    nexus_api::prover::nova::key::gen_vk_to_file(pp, key, vk_path_str)?;
+
+    Ok(vk_path)
+}
@@ -9,17 +9,29 @@ use ark_serialize::CanonicalDeserialize;
 use clap::Args;
 
 use super::{
+    aggregate::fold_params_path,
     jolt,
     prove::{CommonProveArgs, LocalProveArgs},
-    public_params::format_params_file,
+    public_params::{
+        format_params_file, with_offline_suffix, with_precompiles_suffix, with_zk_suffix,
+    },
     spartan_key::format_key_file,
 };
-use crate::{command::cache_path, LOG_TARGET};
+use crate::{command::cache_path, utils::path_to_artifact, LOG_TARGET};
 use nexus_api::config::{
     vm::{NovaImpl, ProverImpl, VmConfig},
     Config,
 };
-use nexus_api::prover::nova::types::{ComPCDNode, ComProof, IVCProof, PCDNode};
+use nexus_api::nvm::{memory::OfflineMemory, Precompiles, VMOpts};
+use nexus_api::prover::nova::{
+    program_commitment,
+    statement::Statement,
+    types::{
+        ComAggregateProof, ComFoldPP, ComPCDNode, ComProof, IVCProof, PCDNode, SeqAggregate,
+        SeqFoldPP, ZKComProof, F1,
+    },
+    OfflineProof,
+};
 
 #[derive(Debug, Args)]
 pub struct VerifyArgs {
@@ -31,6 +43,15 @@ pub struct VerifyArgs {
     #[arg(long, short, default_value = "false")]
     pub compressed: bool,
 
+    /// whether the proof is an aggregate of several proofs: sequential proofs if `--impl` is
+    /// nova-seq, compressible PCD proofs otherwise
+    #[arg(long, default_value = "false")]
+    pub aggregated: bool,
+
+    /// File containing the parameters for aggregation; only needed when 'aggregated' is true
+    #[arg(long = "fold-params", requires = "aggregated")]
+    pub fold_pp_file: Option<PathBuf>,
+
     #[clap(flatten)]
     pub prover_args: LocalProveArgs,
 
@@ -40,15 +61,129 @@ pub struct VerifyArgs {
     /// File containing the Spartan key; only needed when 'compressed' is true
     #[arg(long = "key-file", short = 'k')]
     pub key_file: Option<PathBuf>,
+
+    /// File containing the verifier key; if given, the public parameters and the Spartan key
+    /// are not loaded
+    #[arg(long = "vk", requires = "compressed", conflicts_with_all = ["aggregated", "key_file"])]
+    pub vk_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub statement_args: StatementArgs,
+}
+
+/// Expected final state of the program, checked in addition to its
+/// initial state, which is computed from the program.
+#[derive(Debug, Args)]
+pub struct StatementArgs {
+    /// Expected digest of the initial state of the program, as printed when proving; if given,
+    /// the program is not loaded, and need not be built
+    #[arg(long, value_parser = parse_digest, conflicts_with_all = ["aggregated", "offline", "args"])]
+    pub program_digest: Option<F1>,
+
+    /// Expected final program counter
+    #[arg(long, value_parser = parse_u32, conflicts_with = "aggregated")]
+    pub final_pc: Option<u32>,
+
+    /// Expected return value of main
+    #[arg(long, value_parser = parse_u32, conflicts_with = "aggregated")]
+    pub exit_code: Option<u32>,
+
+    /// Expected number of steps, all of which must be covered by the proof
+    #[arg(long, conflicts_with = "aggregated")]
+    pub steps: Option<u64>,
+}
+
+fn parse_u32(s: &str) -> Result<u32, std::num::ParseIntError> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+}
+
+fn parse_digest(s: &str) -> Result<F1, String> {
+    s.parse().map_err(|_| format!("{s} is not a field element"))
+}
+
+/// Options to load the program given by `common_args`.
+fn program_opts(
+    common_args: &CommonProveArgs,
+    addr_bits: Option<usize>,
+    precompiles: Precompiles,
+    args: Vec<u32>,
+) -> anyhow::Result<VMOpts> {
+    let path = path_to_artifact(common_args.bin.clone(), &common_args.profile)?;
+    Ok(VMOpts {
+        k: 1,
+        machine: None,
+        file: Some(path),
+        addr_bits,
+        asm: None,
+        args,
+        precompiles,
+    })
+}
+
+/// Build the statement a proof of the program given by `common_args`, or
+/// with the expected digest, is expected to prove.
+fn program_statement(
+    common_args: &CommonProveArgs,
+    addr_bits: Option<usize>,
+    precompiles: Precompiles,
+    args: Vec<u32>,
+    statement_args: StatementArgs,
+) -> anyhow::Result<Statement> {
+    let statement = match statement_args.program_digest {
+        Some(digest) => Statement::new().digest(digest),
+        None => {
+            let opts = program_opts(common_args, addr_bits, precompiles, args)?;
+            Statement::for_program(&opts)?
+        }
+    };
+    Ok(with_statement_args(statement, statement_args))
+}
+
+/// Add the expected final state given on the command line to `statement`.
+fn with_statement_args(mut statement: Statement, statement_args: StatementArgs) -> Statement {
+    let StatementArgs {
+        program_digest: _,
+        final_pc,
+        exit_code,
+        steps,
+    } = statement_args;
+    if let Some(pc) = final_pc {
+        statement = statement.final_pc(pc);
+    }
+    if let Some(code) = exit_code {
+        statement = statement.exit_code(code);
+    }
+    if let Some(n) = steps {
+        statement = statement.steps(0..n);
+    }
+    statement
 }
 
 pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
     let VerifyArgs {
         file,
         compressed,
-        prover_args: LocalProveArgs { k, pp_file, prover_impl: nova_impl, .. },
+        aggregated,
+        fold_pp_file,
+        prover_args:
+            LocalProveArgs {
+                k,
+                pp_file,
+                prover_impl: nova_impl,
+                addr_bits,
+                precompiles,
+                args,
+                zk,
+                offline,
+                ..
+            },
         key_file,
+        vk_file,
         common_args,
+        statement_args,
     } = args;
     println!("handle verify");
 
@@ -67,8 +202,57 @@ pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
     println!("vm_config.k: {:?}", vm_config.k);
     println!("vm_config.prover: {:?}", vm_config.prover);
 
-    if compressed {
-        verify_proof_compressed(&file, k.unwrap_or(vm_config.k), pp_file, key_file)
+    // the initial memory is checked against the audit of the proof
+    if offline {
+        anyhow::ensure!(
+            !compressed && !aggregated,
+            "offline memory checking is only supported by sequential proofs"
+        );
+        let opts = program_opts(&common_args, addr_bits, precompiles, args)?;
+        return verify_proof_offline(
+            &file,
+            k.unwrap_or(vm_config.k),
+            pp_file,
+            &opts,
+            statement_args,
+        );
+    }
+
+    // an aggregated proof is for several programs, whose claims are listed instead
+    if aggregated {
+        let nova_impl = match nova_impl {
+            Some(ProverImpl::Nova(NovaImpl::Sequential)) => NovaImpl::Sequential,
+            None | Some(ProverImpl::Nova(NovaImpl::ParallelCompressible)) => {
+                NovaImpl::ParallelCompressible
+            }
+            Some(_) => {
+                anyhow::bail!("only sequential and compressible PCD proofs can be aggregated")
+            }
+        };
+        return verify_proof_aggregated(
+            &file,
+            k.unwrap_or(vm_config.k),
+            nova_impl,
+            pp_file,
+            key_file,
+            fold_pp_file,
+        );
+    }
+
+    let statement = program_statement(&common_args, addr_bits, precompiles, args, statement_args)?;
+
+    if let Some(vk_file) = &vk_file {
+        verify_proof_with_vk(&file, vk_file, &statement, zk)
+    } else if compressed {
+        verify_proof_compressed(
+            &file,
+            k.unwrap_or(vm_config.k),
+            pp_file,
+            key_file,
+            &statement,
+            precompiles,
+            zk,
+        )
     } else {
         verify_proof(
             &file,
@@ -76,6 +260,9 @@ pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
             nova_impl.unwrap_or(vm_config.prover),
             common_args,
             pp_file,
+            &statement,
+            precompiles,
+            zk,
         )
     }
 }
@@ -85,6 +272,9 @@ fn verify_proof_compressed(
     k: usize,
     pp_file: Option<PathBuf>,
     key_file: Option<PathBuf>,
+    statement: &Statement,
+    precompiles: Precompiles,
+    zk: bool,
 ) -> anyhow::Result<()> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
@@ -93,6 +283,8 @@ fn verify_proof_compressed(
         Some(path) => path,
         None => {
             let pp_file_name = format_params_file(NovaImpl::ParallelCompressible, k);
+            let pp_file_name = with_precompiles_suffix(pp_file_name, precompiles);
+            let pp_file_name = with_zk_suffix(pp_file_name, zk);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -105,7 +297,8 @@ fn verify_proof_compressed(
     let key_path = match key_file {
         Some(path) => path,
         None => {
-            let key_file_name = format_key_file(k);
+            let key_file_name = with_precompiles_suffix(format_key_file(k), precompiles);
+            let key_file_name = with_zk_suffix(key_file_name, zk);
             let cache_path = cache_path()?;
 
             cache_path.join(key_file_name)
@@ -130,12 +323,21 @@ fn verify_proof_compressed(
         .on_step(move |_step| "proof".into());
     let mut _guard = Default::default();
 
-    let result = {
+    let result = if zk {
+        let proof = ZKComProof::deserialize_compressed(reader)?;
+        let key = nexus_api::prover::nova::key::load_key(&key_path)?;
+
+        _guard = ctx.display_step();
+        statement
+            .verify_compressed_zk(&key, &params, &proof)
+            .map_err(anyhow::Error::from)
+    } else {
         let proof = ComProof::deserialize_compressed(reader)?;
         let key = nexus_api::prover::nova::key::load_key(&key_path)?;
 
         _guard = ctx.display_step();
-        nexus_api::prover::nova::verify_compressed(&key, &params, &proof)
+        statement
+            .verify_compressed(&key, &params, &proof)
             .map_err(anyhow::Error::from)
     };
 
@@ -164,12 +366,235 @@ fn verify_proof_compressed(
     Ok(())
 }
 
+fn verify_proof_aggregated(
+    path: &Path,
+    k: usize,
+    nova_impl: NovaImpl,
+    pp_file: Option<PathBuf>,
+    key_file: Option<PathBuf>,
+    fold_pp_file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let pp_path = match pp_file {
+        Some(path) => path,
+        None => cache_path()?.join(format_params_file(nova_impl, k)),
+    }
+    .to_str()
+    .context("path is not utf-8")?
+    .to_owned();
+
+    let fold_pp_path = fold_params_path(fold_pp_file, nova_impl)?
+        .to_str()
+        .context("path is not utf-8")?
+        .to_owned();
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let mut ctx = term
+        .context("Verifying aggregated")
+        .on_step(move |_step| "proof".into());
+    let mut _guard = Default::default();
+
+    let result = if nova_impl == NovaImpl::Sequential {
+        let params = nexus_api::prover::nova::pp::load_pp(&pp_path)?;
+        let fold_pp: SeqFoldPP = nexus_api::prover::nova::pp::load_fold_pp(&fold_pp_path)?;
+        let proof = SeqAggregate::deserialize_compressed(reader)?;
+
+        _guard = ctx.display_step();
+        nexus_api::prover::nova::verify_aggregate_seq(&params, &fold_pp, &proof)
+            .map(|()| proof.claims)
+    } else {
+        let key_path = match key_file {
+            Some(path) => path,
+            None => cache_path()?.join(format_key_file(k)),
+        }
+        .to_str()
+        .context("path is not utf-8")?
+        .to_owned();
+
+        let params = nexus_api::prover::nova::pp::load_pp(&pp_path)?;
+        let fold_pp: ComFoldPP = nexus_api::prover::nova::pp::load_fold_pp(&fold_pp_path)?;
+        let key = nexus_api::prover::nova::key::load_key(&key_path)?;
+        let proof = ComAggregateProof::deserialize_compressed(reader)?;
+
+        _guard = ctx.display_step();
+        nexus_api::prover::nova::verify_aggregate(&key, &params, &fold_pp, &proof)
+            .map(|()| proof.claims)
+    };
+
+    match result {
+        Ok(claims) => {
+            drop(_guard);
+
+            for claim in &claims {
+                tracing::info!(
+                    target: LOG_TARGET,
+                    program = ?program_commitment(claim),
+                    steps = ?(claim.i..claim.j),
+                    "Proved claim",
+                );
+            }
+            tracing::info!(
+                target: LOG_TARGET,
+                "Aggregated proof is valid",
+            );
+        }
+        Err(err) => {
+            _guard.abort();
+
+            tracing::error!(
+                target: LOG_TARGET,
+                err = ?err,
+                ?k,
+                "Aggregated proof is invalid",
+            );
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+fn verify_proof_offline(
+    path: &Path,
+    k: usize,
+    pp_file: Option<PathBuf>,
+    opts: &VMOpts,
+    statement_args: StatementArgs,
+) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let pp_path = match pp_file {
+        Some(path) => path,
+        None => {
+            let pp_file_name = format_params_file(NovaImpl::Sequential, k);
+            let pp_file_name = with_precompiles_suffix(pp_file_name, opts.precompiles);
+            let pp_file_name = with_offline_suffix(pp_file_name, true);
+            let cache_path = cache_path()?;
+
+            cache_path.join(pp_file_name)
+        }
+    }
+    .to_str()
+    .context("path is not utf8")?
+    .to_owned();
+
+    let vm = nexus_api::nvm::load_vm::<OfflineMemory>(opts)?;
+    let statement = with_statement_args(Statement::for_offline_vm(&vm), statement_args);
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let params = {
+        let mut ctx = term
+            .context("Loading")
+            .on_step(|_step| "public parameters".into());
+        let _guard = ctx.display_step();
+
+        nexus_api::prover::nova::pp::load_offline_pp(&pp_path)?
+    };
+    let proof = OfflineProof::deserialize_compressed(reader)?;
+
+    let mut ctx = term
+        .context("Verifying")
+        .on_step(move |_step| "proof".into());
+    let mut _guard = ctx.display_step();
+
+    match statement.verify_seq_offline(&params, &proof, &vm.mem) {
+        Ok(_) => {
+            drop(_guard);
+
+            tracing::info!(
+                target: LOG_TARGET,
+                "Proof is valid",
+            );
+        }
+        Err(err) => {
+            _guard.abort();
+
+            tracing::error!(
+                target: LOG_TARGET,
+                err = ?err,
+                ?k,
+                "Proof is invalid",
+            );
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn verify_proof_with_vk(
+    path: &Path,
+    vk_file: &Path,
+    statement: &Statement,
+    zk: bool,
+) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let vk_path = vk_file.to_str().context("path is not utf-8")?;
+
+    let mut term = nexus_tui::TerminalHandle::new_enabled();
+    let vk = {
+        let mut ctx = term
+            .context("Loading")
+            .on_step(|_step| "verifier key".into());
+        let _guard = ctx.display_step();
+
+        nexus_api::prover::nova::key::load_vk(vk_path)?
+    };
+    let mut ctx = term
+        .context("Verifying compressed")
+        .on_step(move |_step| "proof".into());
+    let mut _guard = Default::default();
+
+    let result = if zk {
+        let proof = ZKComProof::deserialize_compressed(reader)?;
+
+        _guard = ctx.display_step();
+        statement.verify_compressed_zk_with_vk(&vk, &proof)
+    } else {
+        let proof = ComProof::deserialize_compressed(reader)?;
+
+        _guard = ctx.display_step();
+        statement.verify_compressed_with_vk(&vk, &proof)
+    };
+
+    match result {
+        Ok(_) => {
+            drop(_guard);
+
+            tracing::info!(
+                target: LOG_TARGET,
+                "Compressed proof is valid",
+            );
+        }
+        Err(err) => {
+            _guard.abort();
+
+            tracing::error!(
+                target: LOG_TARGET,
+                err = ?err,
+                "Compressed proof is invalid",
+            );
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn verify_proof(
     path: &Path,
     k: usize,
     prover: ProverImpl,
     prove_args: CommonProveArgs,
     pp_file: Option<PathBuf>,
+    statement: &Statement,
+    precompiles: Precompiles,
+    zk: bool,
 ) -> anyhow::Result<()> {
     // handle jolt separately
     let nova_impl = match prover {
@@ -185,6 +610,8 @@ pub fn verify_proof(
         Some(path) => path,
         None => {
             let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = with_precompiles_suffix(pp_file_name, precompiles);
+            let pp_file_name = with_zk_suffix(pp_file_name, zk);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -219,7 +646,9 @@ pub fn verify_proof(
             let root = PCDNode::deserialize_compressed(reader)?;
 
             _guard = ctx.display_step();
-            root.verify(&params).map_err(anyhow::Error::from)
+            statement
+                .verify_par(&params, &root)
+                .map_err(anyhow::Error::from)
         }
         NovaImpl::ParallelCompressible => {
             let mut iterm = nexus_tui::TerminalHandle::new_enabled();
@@ -234,7 +663,9 @@ pub fn verify_proof(
             let root = ComPCDNode::deserialize_compressed(reader)?;
 
             _guard = ctx.display_step();
-            root.verify(&params).map_err(anyhow::Error::from)
+            statement
+                .verify_par_com(&params, &root)
+                .map_err(anyhow::Error::from)
         }
         NovaImpl::Sequential => {
             let mut iterm = nexus_tui::TerminalHandle::new_enabled();
@@ -249,8 +680,8 @@ pub fn verify_proof(
             let proof = IVCProof::deserialize_compressed(reader)?;
 
             _guard = ctx.display_step();
-            proof
-                .verify(&params, proof.step_num() as usize)
+            statement
+                .verify_seq(&params, &proof)
                 .map_err(anyhow::Error::from)
         }
     };
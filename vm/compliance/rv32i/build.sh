@@ -0,0 +1,17 @@
+#!/bin/sh
+# Assemble the RV32I compliance tests generated by gen.py.
+#
+# Requires llvm-mc and ld.lld; set LLVM_MC and LD to override, e.g.
+# LD="rust-lld -flavor gnu".
+set -e
+cd "$(dirname "$0")"
+
+LLVM_MC=${LLVM_MC:-llvm-mc}
+LD=${LD:-ld.lld}
+
+for src in *.S; do
+  test=${src%.S}
+  $LLVM_MC -triple=riscv32 -mattr=-c,-relax -filetype=obj "$src" -o "$test.o"
+  $LD -T ../link.ld "$test.o" -o "$test.elf"
+  rm "$test.o"
+done
@@ -0,0 +1,292 @@
+#!/usr/bin/env python3
+"""Generate the vendored RV32I compliance tests.
+
+Each test is written as `<test>.S` together with its expected
+signature `<test>.reference_output`. The expected values are computed
+here from the RV32I specification, independently of the Nexus VM.
+Run `build.sh` afterwards to assemble the tests.
+"""
+
+import os
+
+M = 0xFFFFFFFF
+
+VALUES = [
+    0, 1, 2, 0xFFFFFFFF, 0x7FFFFFFF, 0x80000000, 0x80000001,
+    0x12345678, 0xFEDCBA98, 0x0000FFFF, 0xFFFF0000, 0x55555555,
+]
+IMMS = [0, 1, -1, 2047, -2048, 0x555, -0x556, 31]
+SHAMTS = [0, 1, 15, 31]
+
+
+def s32(x):
+    x &= M
+    return x - (1 << 32) if x & 0x80000000 else x
+
+
+def sext(x, bits):
+    x &= (1 << bits) - 1
+    return x - (1 << bits) if x >> (bits - 1) else x
+
+
+ALU = {
+    "add": lambda a, b: a + b,
+    "sub": lambda a, b: a - b,
+    "sll": lambda a, b: a << (b & 31),
+    "slt": lambda a, b: int(s32(a) < s32(b)),
+    "sltu": lambda a, b: int(a < b),
+    "xor": lambda a, b: a ^ b,
+    "srl": lambda a, b: a >> (b & 31),
+    "sra": lambda a, b: s32(a) >> (b & 31),
+    "or": lambda a, b: a | b,
+    "and": lambda a, b: a & b,
+}
+
+BRANCH = {
+    "beq": lambda a, b: a == b,
+    "bne": lambda a, b: a != b,
+    "blt": lambda a, b: s32(a) < s32(b),
+    "bge": lambda a, b: s32(a) >= s32(b),
+    "bltu": lambda a, b: a < b,
+    "bgeu": lambda a, b: a >= b,
+}
+
+
+class Test:
+    def __init__(self, name):
+        self.name = name
+        self.code = []
+        self.sig = []
+        self.base = 0
+
+    def emit(self, line):
+        self.code.append("  " + line)
+
+    # store register `r` in the next signature word, expecting `value`
+    def store(self, r, value):
+        off = 4 * len(self.sig) - self.base
+        if off >= 2048:
+            # keep the offset within the range of a 12-bit immediate
+            self.emit("addi x31, x31, 1024")
+            self.emit("addi x31, x31, 1024")
+            self.base += 2048
+            off -= 2048
+        self.emit(f"sw {r}, {off}(x31)")
+        self.sig.append(value & M)
+
+    def write(self, out, data=""):
+        words = len(self.sig)
+        src = f"""# {self.name}: generated by gen.py, do not edit.
+  .section .text.init
+  .globl rvtest_entry_point
+rvtest_entry_point:
+  la x31, begin_signature
+{chr(10).join(self.code)}
+  unimp
+
+  .data
+{data}
+  .align 4
+  .globl begin_signature
+begin_signature:
+  .fill {words}, 4, 0xdeadbeef
+  .align 4
+  .globl end_signature
+end_signature:
+"""
+        with open(os.path.join(out, self.name + ".S"), "w") as f:
+            f.write(src)
+        with open(os.path.join(out, self.name + ".reference_output"), "w") as f:
+            f.write("".join(f"{w:08x}\n" for w in self.sig))
+
+
+def alu():
+    t = Test("alu-01")
+    for op, f in ALU.items():
+        for a in VALUES:
+            for b in VALUES + SHAMTS:
+                t.emit(f"li x1, {a:#x}")
+                t.emit(f"li x2, {b:#x}")
+                t.emit(f"{op} x3, x1, x2")
+                t.store("x3", f(a, b))
+        # writes to x0 are discarded
+        t.emit(f"{op} x0, x1, x2")
+        t.store("x0", 0)
+    return t
+
+
+def alu_imm():
+    t = Test("alui-01")
+    ops = {
+        "addi": ALU["add"], "slti": ALU["slt"], "sltiu": ALU["sltu"],
+        "xori": ALU["xor"], "ori": ALU["or"], "andi": ALU["and"],
+    }
+    for op, f in ops.items():
+        for a in VALUES:
+            for i in IMMS:
+                t.emit(f"li x1, {a:#x}")
+                t.emit(f"{op} x3, x1, {i}")
+                t.store("x3", f(a, i & M))
+    for op in ["slli", "srli", "srai"]:
+        f = ALU[op[:-1]]
+        for a in VALUES:
+            for s in SHAMTS:
+                t.emit(f"li x1, {a:#x}")
+                t.emit(f"{op} x3, x1, {s}")
+                t.store("x3", f(a, s))
+    return t
+
+
+def upper():
+    t = Test("lui-auipc-01")
+    for i in [0, 1, 0x7FFFF, 0x80000, 0xFFFFF, 0x12345]:
+        t.emit(f"lui x3, {i:#x}")
+        t.store("x3", i << 12)
+        # the difference between two consecutive auipc results is
+        # the immediate plus the instruction length
+        t.emit("auipc x4, 0")
+        t.emit(f"auipc x3, {i:#x}")
+        t.emit("sub x3, x3, x4")
+        t.store("x3", (i << 12) + 4)
+    return t
+
+
+def branch():
+    t = Test("branch-01")
+    for op, f in BRANCH.items():
+        for a in VALUES:
+            for b in [a, 0, 1, 0xFFFFFFFF, 0x80000000]:
+                t.emit(f"li x1, {a:#x}")
+                t.emit(f"li x2, {b:#x}")
+                t.emit("li x3, 0")
+                t.emit(f"{op} x1, x2, 1f")
+                t.emit("ori x3, x3, 1")
+                t.emit("1:")
+                t.emit("ori x3, x3, 2")
+                t.store("x3", 2 if f(a, b) else 3)
+        # backward branches, with operands for which the branch is taken
+        rs2 = "x0" if f(0, 0) else "x8"
+        t.emit("li x3, 0")
+        t.emit("li x8, 1")
+        t.emit("j 2f")
+        t.emit("1:")
+        t.emit("addi x3, x3, 4")
+        t.emit("j 3f")
+        t.emit("2:")
+        t.emit(f"{op} x0, {rs2}, 1b")
+        t.emit("addi x3, x3, 1")
+        t.emit("3:")
+        t.store("x3", 4)
+    return t
+
+
+def jump():
+    t = Test("jal-jalr-01")
+    # jal links the address of the following instruction
+    t.emit("auipc x5, 0")
+    t.emit("jal x1, 1f")
+    t.emit("li x6, 1")
+    t.emit("1:")
+    t.emit("sub x3, x1, x5")
+    t.store("x3", 8)
+    # jal with rd = x0 only jumps
+    t.emit("li x3, 0")
+    t.emit("j 1f")
+    t.emit("li x3, 1")
+    t.emit("1:")
+    t.store("x3", 0)
+    # jalr adds its offset to the base
+    for off in [0, 8, -4, 2044, -2048]:
+        t.emit("li x3, 0")
+        t.emit("la x5, 1f")
+        t.emit(f"li x6, {-off}")
+        t.emit("add x5, x5, x6")
+        t.emit(f"jalr x1, {off}(x5)")
+        t.emit("li x3, 1")
+        t.emit("1:")
+        t.emit("la x6, 1b")
+        t.emit("sub x4, x6, x1")
+        t.store("x3", 0)
+        t.store("x4", 4)
+    # jalr clears the lowest bit of the target
+    for off in [1, -1]:
+        t.emit("li x3, 0")
+        t.emit("la x5, 1f")
+        t.emit(f"li x6, {1 - off}")
+        t.emit("add x5, x5, x6")
+        t.emit(f"jalr x1, {off}(x5)")
+        t.emit("li x3, 1")
+        t.emit("1:")
+        t.emit("la x6, 1b")
+        t.emit("sub x4, x6, x1")
+        t.store("x3", 0)
+        t.store("x4", 4)
+    # rd = rs1: the link is written after reading the base
+    t.emit("la x7, 1f")
+    t.emit("jalr x7, 0(x7)")
+    t.emit("1:")
+    t.emit("la x6, 1b")
+    t.emit("sub x4, x6, x7")
+    t.store("x4", 0)
+    return t
+
+
+def load_store():
+    t = Test("ld-st-01")
+    data = [0x80FF7F01, 0x00000000, 0x12345678, 0xFEDCBA98]
+    mem = bytearray(b"".join(w.to_bytes(4, "little") for w in data))
+
+    def word(off):
+        return int.from_bytes(mem[off:off + 4], "little")
+
+    t.emit("la x1, ldst_data")
+    for off in range(0, 16, 4):
+        t.emit(f"lw x3, {off}(x1)")
+        t.store("x3", word(off))
+    for off in range(0, 16, 2):
+        v = int.from_bytes(mem[off:off + 2], "little")
+        t.emit(f"lh x3, {off}(x1)")
+        t.store("x3", sext(v, 16))
+        t.emit(f"lhu x3, {off}(x1)")
+        t.store("x3", v)
+    for off in range(16):
+        t.emit(f"lb x3, {off}(x1)")
+        t.store("x3", sext(mem[off], 8))
+        t.emit(f"lbu x3, {off}(x1)")
+        t.store("x3", mem[off])
+
+    # stores only write the low bytes of their source
+    t.emit("li x2, 0xa5c3e187")
+    for off in [4, 7]:
+        t.emit(f"sb x2, {off}(x1)")
+        mem[off] = 0x87
+    for off in [8, 14]:
+        t.emit(f"sh x2, {off}(x1)")
+        mem[off:off + 2] = bytes([0x87, 0xE1])
+    t.emit("sw x2, 12(x1)")
+    mem[12:16] = (0xA5C3E187).to_bytes(4, "little")
+    for off in range(0, 16, 4):
+        t.emit(f"lw x3, {off}(x1)")
+        t.store("x3", word(off))
+
+    # negative offsets
+    t.emit("addi x1, x1, 16")
+    t.emit("lw x3, -16(x1)")
+    t.store("x3", word(0))
+    t.emit("lb x3, -1(x1)")
+    t.store("x3", sext(mem[15], 8))
+
+    data = "  .align 4\nldst_data:\n" + "".join(f"  .word {w:#010x}\n" for w in data)
+    return t, data
+
+
+def main():
+    out = os.path.dirname(os.path.abspath(__file__))
+    for t in [alu(), alu_imm(), upper(), branch(), jump()]:
+        t.write(out)
+    t, data = load_store()
+    t.write(out, data)
+
+
+if __name__ == "__main__":
+    main()
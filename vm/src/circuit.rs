@@ -1,10 +1,19 @@
+pub mod bigint;
+mod check;
+pub mod ed25519;
+pub mod poseidon;
+mod precompiles;
 mod r1cs;
 mod riscv;
+pub mod secp256k1;
 mod step;
 
+#[cfg(test)]
+mod diff;
 #[cfg(test)]
 mod test;
 
+pub use check::{check_trace, CircuitMismatch, StepMismatch, Violation};
 pub use r1cs::F;
 pub use riscv::ARITY;
-pub use step::build_constraints;
+pub use step::{build_constraints, StepConstraints};
@@ -0,0 +1,447 @@
+//! Constraints for the bigint precompile.
+//!
+//! Integers are represented by 32-bit limbs, least significant first,
+//! as in `precompiles::bigint`. Limbs of results are range checked by
+//! allocating their bits; limbs of inputs are assumed to be range
+//! checked already, e.g. by `alloc_limbs`.
+//!
+//! Identities between integers are checked column by column: in each
+//! column, the difference of the two sides plus the carry from the
+//! previous column must be a multiple of 2^32, and nothing may be
+//! carried out of the last column. Carries may be negative, and are
+//! range checked after adding an offset. All intermediate values are
+//! far smaller than the field modulus, so an identity which holds
+//! over the field also holds over the integers.
+//!
+//! The modulus of a modular multiplication is an input of the
+//! circuit rather than a parameter, so the non-native field gadgets
+//! of arkworks, which fix the target field at compile time, cannot be
+//! used here. The same gadgets, with a constant modulus, check the
+//! field arithmetic of the elliptic curve precompiles, along with
+//! linear combinations modulo a constant (see `lincomb`).
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::F;
+use crate::precompiles::bigint as native;
+
+type CS = ConstraintSystemRef<F>;
+
+/// An integer, as 32-bit limbs, least significant first.
+pub type Limbs = Vec<FpVar<F>>;
+
+// Number of bits of the (offset) carries between columns. A column of
+// a product of 8-limb and 16-limb integers is less than 2^67, so the
+// carries are less than 2^36 in absolute value.
+const CARRY_BITS: usize = 40;
+
+// Number of bits of the multiple of the modulus in `enforce_lincomb`,
+// which is offset by half its range.
+const MULTIPLE_BITS: usize = 10;
+
+fn to_u128(f: F) -> u128 {
+    let b = f.into_bigint().0;
+    b[0] as u128 | (b[1] as u128) << 64
+}
+
+fn values(x: &[FpVar<F>]) -> Option<Vec<u128>> {
+    x.iter().map(|v| v.value().ok().map(to_u128)).collect()
+}
+
+fn words<const N: usize>(x: &[FpVar<F>]) -> Option<[u32; N]> {
+    let v: Vec<u32> = values(x)?.into_iter().map(|v| v as u32).collect();
+    v.try_into().ok()
+}
+
+/// Allocate a witness in [0, 2^n), with value `x`.
+pub fn alloc_bits(cs: CS, n: usize, x: Option<u128>) -> Result<FpVar<F>, SynthesisError> {
+    let bits = (0..n)
+        .map(|i| {
+            Boolean::new_witness(cs.clone(), || {
+                let x = x.ok_or(SynthesisError::AssignmentMissing)?;
+                Ok((x >> i) & 1 == 1)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp_var(&bits)
+}
+
+/// Allocate an integer of `n` range checked limbs, with value `x`.
+pub fn alloc_limbs(cs: CS, n: usize, x: Option<&[u32]>) -> Result<Limbs, SynthesisError> {
+    (0..n)
+        .map(|i| alloc_bits(cs.clone(), 32, x.map(|x| x[i] as u128)))
+        .collect()
+}
+
+/// Allocate an integer less than the constant `m`, with value `x`.
+pub fn alloc_reduced(
+    cs: CS,
+    x: Option<&native::U256>,
+    m: &native::U256,
+) -> Result<Limbs, SynthesisError> {
+    let r = alloc_limbs(cs.clone(), 8, x.map(|x| &x[..]))?;
+    enforce_less(cs, &r, &constant(m))?;
+    Ok(r)
+}
+
+/// The integer with constant limbs `x`.
+pub fn constant(x: &[u32]) -> Limbs {
+    x.iter().map(|w| FpVar::constant(F::from(*w))).collect()
+}
+
+/// Returns the value of the 8-limb integer `x`, if it is known.
+pub fn value(x: &[FpVar<F>]) -> Option<native::U256> {
+    words(x)
+}
+
+/// Returns `x` if `sel` is one, and `y` if it is zero.
+pub fn select(sel: &FpVar<F>, x: &[FpVar<F>], y: &[FpVar<F>]) -> Limbs {
+    x.iter().zip(y).map(|(x, y)| sel * (x - y) + y).collect()
+}
+
+/// Returns the least significant bit of `x`.
+pub fn parity(cs: CS, x: &[FpVar<F>]) -> Result<Boolean<F>, SynthesisError> {
+    let v = x[0].value().ok().map(to_u128);
+    let bit = Boolean::new_witness(cs.clone(), || {
+        v.map(|v| v & 1 == 1)
+            .ok_or(SynthesisError::AssignmentMissing)
+    })?;
+    let half = alloc_bits(cs, 31, v.map(|v| v >> 1))?;
+    x[0].enforce_equal(&(half.double()? + FpVar::from(bit.clone())))?;
+    Ok(bit)
+}
+
+/// Enforce that `x` is not zero.
+pub fn enforce_nonzero(x: &[FpVar<F>]) -> Result<(), SynthesisError> {
+    // the limbs are range checked, so their sum does not wrap around
+    let sum: FpVar<F> = x.iter().sum();
+    sum.enforce_not_equal(&FpVar::zero())
+}
+
+// add the column sums of `a * b` to `cols`
+fn product_columns(a: &[FpVar<F>], b: &[FpVar<F>], cols: &mut [FpVar<F>]) {
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            cols[i + j] += x * y;
+        }
+    }
+}
+
+// enforce that the integers with column sums `lhs` and `rhs` are equal
+fn enforce_columns(cs: CS, lhs: &[FpVar<F>], rhs: &[FpVar<F>]) -> Result<(), SynthesisError> {
+    debug_assert_eq!(lhs.len(), rhs.len());
+
+    let base = F::from(1u64 << 32);
+    let offset = 1i128 << (CARRY_BITS - 1);
+    let diff: Option<Vec<i128>> = values(lhs).zip(values(rhs)).map(|(l, r)| {
+        l.iter()
+            .zip(&r)
+            .map(|(l, r)| *l as i128 - *r as i128)
+            .collect()
+    });
+
+    let mut carry = FpVar::zero();
+    let mut c = Some(0i128);
+    for (k, (l, r)) in lhs.iter().zip(rhs).enumerate() {
+        let d = l - r + &carry;
+        if k == lhs.len() - 1 {
+            d.enforce_equal(&FpVar::zero())?;
+            break;
+        }
+        c = c.zip(diff.as_ref()).map(|(c, diff)| (c + diff[k]) >> 32);
+        let shifted = alloc_bits(cs.clone(), CARRY_BITS, c.map(|c| (c + offset) as u128))?;
+        let next = shifted - F::from(offset as u128);
+        d.enforce_equal(&(&next * base))?;
+        carry = next;
+    }
+    Ok(())
+}
+
+/// Enforce `a + b = s + carry * 2^256`, returning `s` and `carry`.
+pub fn add(cs: CS, a: &[FpVar<F>], b: &[FpVar<F>]) -> Result<(Limbs, Boolean<F>), SynthesisError> {
+    let sum = words(a).zip(words(b)).map(|(a, b)| native::add(&a, &b));
+    let s = alloc_limbs(cs.clone(), 8, sum.as_ref().map(|(s, _)| &s[..]))?;
+    let carry = Boolean::new_witness(cs.clone(), || {
+        sum.map(|(_, c)| c).ok_or(SynthesisError::AssignmentMissing)
+    })?;
+
+    let mut lhs: Vec<FpVar<F>> = a.iter().zip(b).map(|(x, y)| x + y).collect();
+    lhs.push(FpVar::zero());
+    let mut rhs = s.clone();
+    rhs.push(carry.clone().into());
+    enforce_columns(cs, &lhs, &rhs)?;
+
+    Ok((s, carry))
+}
+
+/// Enforce `a * b = p`, returning the 16 limbs of `p`.
+pub fn mul(cs: CS, a: &[FpVar<F>], b: &[FpVar<F>]) -> Result<Limbs, SynthesisError> {
+    let prod = words(a).zip(words(b)).map(|(a, b)| native::mul(&a, &b));
+    let p = alloc_limbs(cs.clone(), 16, prod.as_ref().map(|p| &p[..]))?;
+
+    let mut lhs = vec![FpVar::zero(); 16];
+    product_columns(a, b, &mut lhs);
+    enforce_columns(cs, &lhs, &p)?;
+
+    Ok(p)
+}
+
+/// Enforce `a * b = q * m + r` with `r < m`, returning `r`, which is
+/// `a * b mod m`. This cannot be satisfied if `m` is zero.
+pub fn modmul(
+    cs: CS,
+    a: &[FpVar<F>],
+    b: &[FpVar<F>],
+    m: &[FpVar<F>],
+) -> Result<Limbs, SynthesisError> {
+    let qr = words(a)
+        .zip(words(b))
+        .zip(words(m))
+        .and_then(|((a, b), m)| native::divrem(&native::mul(&a, &b), &m));
+    let q = alloc_limbs(cs.clone(), 16, qr.as_ref().map(|(q, _)| &q[..]))?;
+    let r = alloc_limbs(cs.clone(), 8, qr.as_ref().map(|(_, r)| &r[..]))?;
+    enforce_modmul(cs, a, b, m, &q, &r)?;
+    Ok(r)
+}
+
+/// Enforce `a * b = r mod m`, for `r` which is already known to be
+/// less than `m`.
+pub fn enforce_modmul_eq(
+    cs: CS,
+    a: &[FpVar<F>],
+    b: &[FpVar<F>],
+    m: &[FpVar<F>],
+    r: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+    let q = words(a)
+        .zip(words(b))
+        .zip(words(m))
+        .and_then(|((a, b), m)| native::divrem(&native::mul(&a, &b), &m));
+    let q = alloc_limbs(cs.clone(), 16, q.as_ref().map(|(q, _)| &q[..]))?;
+    enforce_product(cs, a, b, m, &q, r)
+}
+
+fn enforce_modmul(
+    cs: CS,
+    a: &[FpVar<F>],
+    b: &[FpVar<F>],
+    m: &[FpVar<F>],
+    q: &[FpVar<F>],
+    r: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+    enforce_product(cs.clone(), a, b, m, q, r)?;
+    enforce_less(cs, r, m)
+}
+
+// enforce a * b = q * m + r
+fn enforce_product(
+    cs: CS,
+    a: &[FpVar<F>],
+    b: &[FpVar<F>],
+    m: &[FpVar<F>],
+    q: &[FpVar<F>],
+    r: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+    // the quotient may need up to 512 bits
+    let mut lhs = vec![FpVar::zero(); 24];
+    product_columns(a, b, &mut lhs);
+    let mut rhs = vec![FpVar::zero(); 24];
+    product_columns(q, m, &mut rhs);
+    for (x, y) in rhs.iter_mut().zip(r) {
+        *x += y;
+    }
+    enforce_columns(cs, &lhs, &rhs)
+}
+
+// enforce r < m, that is r + d + 1 = m for some d >= 0
+fn enforce_less(cs: CS, r: &[FpVar<F>], m: &[FpVar<F>]) -> Result<(), SynthesisError> {
+    let one = [1, 0, 0, 0, 0, 0, 0, 0];
+    let d = words(m)
+        .zip(words(r))
+        .map(|(m, r)| native::sub(&native::sub(&m, &r).0, &one).0);
+    let d = alloc_limbs(cs.clone(), 8, d.as_ref().map(|d| &d[..]))?;
+
+    let mut lhs: Vec<FpVar<F>> = r.iter().zip(&d).map(|(x, y)| x + y).collect();
+    lhs[0] += F::from(1u64);
+    enforce_columns(cs, &lhs, m)
+}
+
+// The value of `offset * m` plus the sum of the terms `(c, x)`, which
+// must not be negative, as 16 words.
+fn lincomb_value(terms: &[(i64, &Limbs)], m: &native::U256, offset: i64) -> Option<native::U512> {
+    let mut cols = [0i128; 16];
+    for (c, x) in terms {
+        for (i, v) in values(x)?.into_iter().enumerate() {
+            cols[i] += *c as i128 * v as i128;
+        }
+    }
+    for (i, w) in m.iter().enumerate() {
+        cols[i] += offset as i128 * *w as i128;
+    }
+    let mut words = [0; 16];
+    let mut carry = 0i128;
+    for (w, c) in words.iter_mut().zip(cols) {
+        carry += c;
+        *w = carry as u32;
+        carry >>= 32;
+    }
+    Some(words)
+}
+
+/// Enforce `sum c x = 0 mod m` for the terms `(c, x)`, where the `x` are
+/// 8-limb integers, the coefficients `c` sum to at most 64 in absolute
+/// value, and `m` is a constant of at least 2^254.
+pub fn enforce_lincomb(
+    cs: CS,
+    terms: &[(i64, &Limbs)],
+    m: &native::U256,
+) -> Result<(), SynthesisError> {
+    // positive terms + k m = negative terms + offset m, where k is
+    // within 2^MULTIPLE_BITS as the sums are less than 64 * 2^256
+    let offset = 1i64 << (MULTIPLE_BITS - 1);
+    let mut lhs = vec![FpVar::zero(); 8];
+    let mut rhs = vec![FpVar::zero(); 8];
+    for (c, x) in terms {
+        let cols = if *c > 0 { &mut lhs } else { &mut rhs };
+        for (col, v) in cols.iter_mut().zip(*x) {
+            *col += v * F::from(c.unsigned_abs());
+        }
+    }
+
+    let k = lincomb_value(terms, m, offset)
+        .and_then(|x| native::divrem(&x, m))
+        .map(|(q, _)| (2 * offset as u128).wrapping_sub(q[0] as u128));
+    let k = alloc_bits(cs.clone(), MULTIPLE_BITS, k)?;
+    for (i, w) in m.iter().enumerate() {
+        lhs[i] += &k * F::from(*w);
+        rhs[i] += F::from(offset as u64 * *w as u64);
+    }
+    enforce_columns(cs, &lhs, &rhs)
+}
+
+/// Returns `sum c x mod m` for the terms `(c, x)`, as an integer less
+/// than `m`. The conditions of `enforce_lincomb` apply.
+pub fn lincomb(cs: CS, terms: &[(i64, &Limbs)], m: &native::U256) -> Result<Limbs, SynthesisError> {
+    let offset = 1i64 << (MULTIPLE_BITS - 1);
+    let r = lincomb_value(terms, m, offset)
+        .and_then(|x| native::divrem(&x, m))
+        .map(|(_, r)| r);
+    let r = alloc_reduced(cs.clone(), r.as_ref(), m)?;
+    let mut terms = terms.to_vec();
+    terms.push((-1, &r));
+    enforce_lincomb(cs, &terms, m)?;
+    Ok(r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::precompiles::bigint::U256;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    fn setup(xs: &[&[u32]]) -> (CS, Vec<Limbs>) {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let vars = xs
+            .iter()
+            .map(|x| alloc_limbs(cs.clone(), x.len(), Some(*x)).unwrap())
+            .collect();
+        (cs, vars)
+    }
+
+    fn u256(x: u64) -> U256 {
+        [x as u32, (x >> 32) as u32, 0, 0, 0, 0, 0, 0]
+    }
+
+    #[test]
+    fn bigint_add() {
+        let max = [u32::MAX; 8];
+        let (cs, v) = setup(&[&max, &u256(2)]);
+        let (s, carry) = add(cs.clone(), &v[0], &v[1]).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(words::<8>(&s), Some(u256(1)));
+        assert!(carry.value().unwrap());
+    }
+
+    #[test]
+    fn bigint_mul() {
+        let max = [u32::MAX; 8];
+        let (cs, v) = setup(&[&max, &max]);
+        let p = mul(cs.clone(), &v[0], &v[1]).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(words::<16>(&p), Some(native::mul(&max, &max)));
+    }
+
+    #[test]
+    fn bigint_modmul() {
+        let a = [u32::MAX; 8];
+        let b = [7, 6, 5, 4, 3, 2, 1, 0x8000_0000];
+        let m = [1, 2, 3, 4, 5, 6, 7, 8];
+        let (cs, v) = setup(&[&a, &b, &m]);
+        let r = modmul(cs.clone(), &v[0], &v[1], &v[2]).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(words::<8>(&r), native::modmul(&a, &b, &m));
+
+        // the modulus may not be zero
+        let (cs, v) = setup(&[&a, &b, &[0; 8]]);
+        assert!(modmul(cs, &v[0], &v[1], &v[2]).is_err());
+    }
+
+    #[test]
+    fn bigint_modmul_unsound() {
+        // 7 * 5 = 5 * 6 + 5
+        let check = |q: u64, r: u64| {
+            let mut q16 = [0; 16];
+            q16[..8].copy_from_slice(&u256(q));
+            let (cs, v) = setup(&[&u256(7), &u256(5), &u256(6), &q16, &u256(r)]);
+            enforce_modmul(cs.clone(), &v[0], &v[1], &v[2], &v[3], &v[4]).unwrap();
+            cs.is_satisfied().unwrap()
+        };
+        assert!(check(5, 5));
+        assert!(!check(5, 6));
+        assert!(!check(4, 11));
+    }
+
+    #[test]
+    fn bigint_lincomb() {
+        let m = crate::precompiles::ed25519::P;
+        let minus = |x: u64| native::sub(&m, &u256(x)).0;
+
+        // 2 - 3 * 5 = -13
+        let (cs, v) = setup(&[&u256(2), &u256(5)]);
+        let r = lincomb(cs.clone(), &[(1, &v[0]), (-3, &v[1])], &m).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(value(&r), Some(minus(13)));
+
+        // congruence is checked, but not reduction
+        let check = |r: &U256| {
+            let (cs, v) = setup(&[&u256(2), &u256(5), r]);
+            enforce_lincomb(cs.clone(), &[(1, &v[0]), (-3, &v[1]), (-1, &v[2])], &m).unwrap();
+            cs.is_satisfied().unwrap()
+        };
+        assert!(check(&minus(13)));
+        assert!(check(&native::add(&m, &minus(13)).0));
+        assert!(!check(&minus(12)));
+    }
+
+    #[test]
+    fn bigint_reduced() {
+        let m = crate::precompiles::ed25519::P;
+        for (x, ok) in [
+            (u256(5), true),
+            (native::sub(&m, &u256(1)).0, true),
+            (m, false),
+        ] {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let v = alloc_reduced(cs.clone(), Some(&x), &m).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), ok);
+            assert_eq!(parity(cs.clone(), &v).unwrap().value(), Ok(x[0] & 1 == 1));
+            assert!(enforce_nonzero(&v).is_ok());
+        }
+
+        // zero has no inverse to witness that it is not zero
+        let (_, v) = setup(&[&[0; 8]]);
+        assert!(enforce_nonzero(&v[0]).is_err());
+    }
+}
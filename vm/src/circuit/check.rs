@@ -0,0 +1,210 @@
+//! Consistency checks between the interpreter and the step circuit.
+//!
+//! A trace produced by the interpreter should always satisfy the step
+//! circuit, including the constraints checking its memory proofs and
+//! the results of precompiles. When it does not, the folding schemes only report that some constraint
+//! is unsatisfied; `check_trace` locates the first failing step and
+//! describes the violated constraints using the variable names of the
+//! step circuit.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use ark_relations::r1cs::SynthesisError;
+
+use crate::{
+    memory::MemoryProof,
+    rv32::{parse::parse_inst, Inst},
+    trace::{Trace, Witness},
+};
+
+use super::{
+    precompiles::precompiles_satisfied,
+    r1cs::R1CS,
+    riscv,
+    step::{memory_proofs_satisfied, StepConstraints},
+    F,
+};
+
+/// A constraint of the step circuit which is not satisfied.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// Index of the constraint within the step circuit.
+    pub index: usize,
+    /// Variables occurring in the constraint, and their values.
+    pub vars: Vec<(String, F)>,
+}
+
+/// Describes the first step of a trace which does not satisfy the
+/// step circuit.
+#[derive(Debug, Clone)]
+pub struct StepMismatch {
+    /// Index of the block containing the step.
+    pub block: usize,
+    /// Index of the step within the block.
+    pub step: usize,
+    /// The decoded instruction executed by the step.
+    pub inst: Inst,
+    /// The constraints not satisfied by the step.
+    pub violations: Vec<Violation>,
+    /// Whether the memory proofs of the step are not satisfied; only
+    /// checked if there are no violations.
+    pub memory: bool,
+    /// Whether the results of precompiles written by the step are not
+    /// satisfied; only checked if there are no violations.
+    pub precompile: bool,
+}
+
+/// Reasons for which a trace does not satisfy the step circuit.
+#[derive(Debug, Clone)]
+pub enum CircuitMismatch {
+    /// A step does not satisfy the constraints of the step circuit.
+    Step(StepMismatch),
+    /// The witness of a step does not have the layout of the step
+    /// circuit.
+    Layout {
+        block: usize,
+        step: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The constraints on the memory proofs or precompiles of a step
+    /// could not be generated.
+    Synthesis {
+        block: usize,
+        step: usize,
+        error: SynthesisError,
+    },
+}
+
+impl Display for StepMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "step circuit not satisfied at block {}, step {}:",
+            self.block, self.step
+        )?;
+        writeln!(f, "  {}", self.inst)?;
+        for v in &self.violations {
+            write!(f, "  constraint {}:", v.index)?;
+            for (name, val) in &v.vars {
+                write!(f, " {name}={val}")?;
+            }
+            writeln!(f)?;
+        }
+        if self.memory {
+            writeln!(f, "  memory proofs not satisfied")?;
+        }
+        if self.precompile {
+            writeln!(f, "  precompile results not satisfied")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CircuitMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Step(m) => m.fmt(f),
+            Self::Layout { block, step, expected, found } => writeln!(
+                f,
+                "witness of block {block}, step {step} has {found} variables, \
+                 the step circuit has {expected}"
+            ),
+            Self::Synthesis { block, step, error } => writeln!(
+                f,
+                "constraints of block {block}, step {step} could not be generated: {error}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CircuitMismatch {}
+
+// describe constraints of `rcs` which are not satisfied by `w`
+fn violations(rcs: &R1CS, w: &[F]) -> Vec<Violation> {
+    let names: HashMap<usize, &str> = rcs.vars.iter().map(|(n, i)| (*i, n.as_str())).collect();
+
+    (0..rcs.a.len())
+        .filter(|&i| rcs.a[i].dot(w) * rcs.b[i].dot(w) != rcs.c[i].dot(w))
+        .map(|i| {
+            let mut js: Vec<usize> = [&rcs.a[i], &rcs.b[i], &rcs.c[i]]
+                .iter()
+                .flat_map(|r| r.iter().map(|(j, _)| j))
+                .filter(|&j| j > 0)
+                .collect();
+            js.sort_unstable();
+            js.dedup();
+
+            let vars = js
+                .into_iter()
+                .map(|j| {
+                    let name = names
+                        .get(&j)
+                        .map_or_else(|| format!("w[{j}]"), |n| n.to_string());
+                    (name, w[j])
+                })
+                .collect();
+            Violation { index: i, vars }
+        })
+        .collect()
+}
+
+fn decode(w: &Witness<impl MemoryProof>) -> Inst {
+    parse_inst(w.regs.pc, &w.inst.to_le_bytes()).unwrap_or(Inst {
+        pc: w.regs.pc,
+        len: 4,
+        word: w.inst,
+        ..Inst::default()
+    })
+}
+
+/// Check that each step of `trace` satisfies the step circuit and the
+/// constraints on its memory proofs, returning a description of the
+/// first step which does not.
+pub fn check_trace<P: MemoryProof>(trace: &Trace<P>) -> Result<(), CircuitMismatch> {
+    // the constraints are the same for every step; only the
+    // witness needs to be generated for each step.
+    let Some(constraints) = StepConstraints::new(trace) else {
+        return Ok(());
+    };
+    let matrices = constraints.matrices();
+    for (i, b) in trace.blocks.iter().enumerate() {
+        let block = trace.start + i;
+        // the block iterator generates the witness of each step
+        for (step, w) in b.iter().enumerate() {
+            let rcs = riscv::step(&w, true);
+            if rcs.w.len() != matrices.w.len() {
+                return Err(CircuitMismatch::Layout {
+                    block,
+                    step,
+                    expected: matrices.w.len(),
+                    found: rcs.w.len(),
+                });
+            }
+            let violations = violations(matrices, &rcs.w);
+
+            // memory proofs and precompiles are only checked if the
+            // step circuit is satisfied
+            let synthesis = |error| CircuitMismatch::Synthesis { block, step, error };
+            let (mut memory, mut precompile) = (false, false);
+            if violations.is_empty() {
+                memory = !memory_proofs_satisfied(&w, &rcs).map_err(synthesis)?;
+                precompile = !precompiles_satisfied(&w, &rcs, constraints.precompiles())
+                    .map_err(synthesis)?;
+            }
+
+            if !violations.is_empty() || memory || precompile {
+                return Err(CircuitMismatch::Step(StepMismatch {
+                    block,
+                    step,
+                    inst: decode(&w),
+                    violations,
+                    memory,
+                    precompile,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Randomized differential tests between the interpreter and the
+//! step circuit.
+//!
+//! Random instructions are evaluated from random register and memory
+//! states with `eval_inst`. Each step is turned into a witness with
+//! `BlockIter`, which must satisfy the step circuit, including the
+//! constraints on its memory proofs, and agree with the interpreter on
+//! the next machine state. Tampered steps must be rejected by the
+//! circuit.
+//!
+//! The number of instructions tested can be raised for local runs
+//! with the `NEXUS_DIFF_ITERS` environment variable.
+
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+use ark_std::rand::Rng;
+
+use crate::{
+    eval::{NexusVM, Regs},
+    memory::{paged::Paged, trie::MerkleTrie, Memory, MemoryProof},
+    precompiles::Precompiles,
+    rv32::{asm::encode, *},
+    trace::{k_step, Block, Trace},
+};
+
+use super::{
+    check::check_trace,
+    riscv::step,
+    step::{build_constraints, StepConstraints},
+    F,
+};
+
+// default number of instructions tested per memory type
+const ITERS: usize = 256;
+
+// instructions are placed below DATA, and loads and stores
+// access DATA..DATA+DATA_LEN (two cache lines)
+const DATA: u32 = 0x400;
+const DATA_LEN: u32 = 0x40;
+
+fn iters() -> usize {
+    std::env::var("NEXUS_DIFF_ITERS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(ITERS)
+}
+
+fn sx12(imm: u32) -> u32 {
+    (((imm << 20) as i32) >> 20) as u32
+}
+
+// Choose an aligned address in the data region, and return an
+// immediate such that x[rs1] + imm is that address.
+fn mem_access(rng: &mut impl Rng, regs: &mut Regs, rs1: u32, align: u32) -> u32 {
+    let addr = DATA + rng.gen_range(0..DATA_LEN / align) * align;
+    if rs1 == 0 {
+        return addr;
+    }
+    let imm = rng.gen::<u32>() & 0xfff;
+    regs.x[rs1 as usize] = addr.wrapping_sub(sx12(imm));
+    imm
+}
+
+// Generate a random valid instruction (other than ECALL), adjusting
+// `regs` so that memory accesses fall in the data region.
+fn random_inst(rng: &mut impl Rng, regs: &mut Regs) -> RV32 {
+    let rd = rng.gen_range(0..32);
+    let rs1 = rng.gen_range(0..32);
+    let rs2 = rng.gen_range(0..32);
+    let imm = rng.gen::<u32>() & 0xfff;
+
+    match rng.gen_range(0..11) {
+        0 => LUI { rd, imm: rng.gen::<u32>() & 0xfffff000 },
+        1 => AUIPC { rd, imm: rng.gen::<u32>() & 0xfffff000 },
+        2 => JAL { rd, imm: rng.gen::<u32>() & 0x1ffffe },
+        3 => JALR { rd, rs1, imm },
+        4 => {
+            let bop = [BEQ, BNE, BLT, BGE, BLTU, BGEU][rng.gen_range(0..6)];
+            BR {
+                bop,
+                rs1,
+                rs2,
+                imm: rng.gen::<u32>() & 0x1ffe,
+            }
+        }
+        5 => {
+            let (lop, align) = [(LB, 1), (LH, 2), (LW, 4), (LBU, 1), (LHU, 2)][rng.gen_range(0..5)];
+            let imm = mem_access(rng, regs, rs1, align);
+            LOAD { lop, rd, rs1, imm }
+        }
+        6 => {
+            let (sop, align) = [(SB, 1), (SH, 2), (SW, 4)][rng.gen_range(0..3)];
+            let imm = mem_access(rng, regs, rs1, align);
+            STORE { sop, rs1, rs2, imm }
+        }
+        7 => {
+            let aop = [ADD, SLL, SLT, SLTU, XOR, SRL, SRA, OR, AND][rng.gen_range(0..9)];
+            let imm = match aop {
+                SLL | SRL | SRA => imm & 0x1f,
+                _ => imm,
+            };
+            ALUI { aop, rd, rs1, imm }
+        }
+        8 => {
+            let aop = [ADD, SUB, SLL, SLT, SLTU, XOR, SRL, SRA, OR, AND][rng.gen_range(0..10)];
+            ALU { aop, rd, rs1, rs2 }
+        }
+        9 => FENCE,
+        _ => {
+            if rng.gen() {
+                EBREAK { rd }
+            } else {
+                UNIMP
+            }
+        }
+    }
+}
+
+// Create a VM with random registers and data memory, which will
+// execute a random instruction.
+fn random_vm<M: Memory>(rng: &mut impl Rng) -> NexusVM<M> {
+    let pc = rng.gen_range(0..DATA / 4) * 4;
+    let mut vm = NexusVM::<M>::new(pc);
+    for x in vm.regs.x.iter_mut().skip(1) {
+        *x = rng.gen();
+    }
+
+    for line in (DATA..DATA + DATA_LEN).step_by(32) {
+        let words: [u32; 8] = rng.gen();
+        vm.mem
+            .update(line, |cl| {
+                for (i, w) in words.iter().enumerate() {
+                    cl.sw(line + 4 * i as u32, *w)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    let inst = random_inst(rng, &mut vm.regs);
+    vm.mem.write_inst(pc, encode(inst)).unwrap();
+    vm
+}
+
+fn single<P: MemoryProof>(block: Block<P>) -> Trace<P> {
+    Trace {
+        k: 1,
+        start: 0,
+        precompiles: Precompiles::default(),
+        blocks: vec![block],
+    }
+}
+
+// check the constraints generated for the folding schemes, which
+// include the memory circuits
+fn ark_satisfied<P: MemoryProof>(tr: &Trace<P>) -> bool {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let inp = tr
+        .input(0)
+        .unwrap()
+        .iter()
+        .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
+        .collect::<Vec<_>>();
+
+    let rcs = StepConstraints::new(tr).unwrap();
+    build_constraints(cs.clone(), 0, &inp, tr, &rcs).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+fn diff_test<M: Memory>(rng: &mut impl Rng, n: usize) {
+    for _ in 0..n {
+        let mut vm = random_vm::<M>(rng);
+        let block = k_step(&mut vm, 1).unwrap();
+        let inst = vm.inst;
+
+        // the witness satisfies the circuit...
+        if let Err(e) = check_trace(&single(block.clone())) {
+            panic!("{e}");
+        }
+        assert!(ark_satisfied(&single(block.clone())), "{inst}");
+
+        // ...and agrees with the interpreter
+        let w = block.iter().next().unwrap();
+        let rcs = step(&w, true);
+        assert_eq!(rcs.get_var("Z"), &F::from(vm.Z), "{inst}");
+        assert_eq!(rcs.get_var("PC"), &F::from(vm.regs.pc), "{inst}");
+        for (i, x) in vm.regs.x.iter().enumerate() {
+            assert_eq!(rcs.get_var(&format!("x'{i}")), &F::from(*x), "{inst}");
+        }
+
+        // tampered results are rejected
+        let bit = 1 << rng.gen_range(0..32);
+
+        let mut bad = block.clone();
+        bad.steps[0].Z ^= bit;
+        assert!(check_trace(&single(bad)).is_err(), "Z: {inst}");
+
+        let mut bad = block;
+        bad.steps[0].PC = Some(vm.regs.pc ^ bit);
+        assert!(check_trace(&single(bad)).is_err(), "PC: {inst}");
+    }
+}
+
+#[test]
+fn diff_paged() {
+    let mut rng = ark_std::test_rng();
+    diff_test::<Paged>(&mut rng, iters());
+}
+
+#[test]
+fn diff_trie() {
+    let mut rng = ark_std::test_rng();
+    diff_test::<MerkleTrie>(&mut rng, iters() / 4);
+}
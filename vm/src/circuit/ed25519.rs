@@ -0,0 +1,94 @@
+//! Constraints for the Ed25519 precompile.
+//!
+//! The field operations of `precompiles::ed25519` are checked with the
+//! gadgets of `circuit::bigint`, modulo the constant field prime. The
+//! second operand is decoded by checking that its x coordinate, of the
+//! parity given by the encoding, satisfies the curve equation.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::{
+    bigint::{
+        alloc_bits, alloc_reduced, constant, enforce_modmul_eq, enforce_nonzero, lincomb, modmul,
+        parity, select, value, Limbs,
+    },
+    F,
+};
+use crate::precompiles::{
+    bigint::U256,
+    ed25519::{self as native, D, P},
+};
+
+type CS = ConstraintSystemRef<F>;
+
+const ONE: [u32; 8] = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Enforce that `(x3, y3)` is `(x1, y1) + q`, where `q` is the encoding
+/// of a point, returning `(x3, y3)`. Unless `sel` is one, the operands
+/// are replaced by the identity.
+pub fn add(
+    cs: CS,
+    sel: &FpVar<F>,
+    x1: &[FpVar<F>],
+    y1: &[FpVar<F>],
+    q: &[FpVar<F>],
+) -> Result<(Limbs, Limbs), SynthesisError> {
+    let p = constant(&P);
+    let d = constant(&D);
+    let one = constant(&ONE);
+    let x1 = select(sel, x1, &constant(&[0; 8]));
+    let y1 = select(sel, y1, &one);
+    let q = select(sel, q, &one);
+
+    // q holds y2 in its low 255 bits, and the parity of x2 in bit 255
+    let top = q[7].value().ok().map(|v| v.into_bigint().0[0]);
+    let sign = Boolean::new_witness(cs.clone(), || {
+        top.map(|v| v >> 31 == 1)
+            .ok_or(SynthesisError::AssignmentMissing)
+    })?;
+    let high = alloc_bits(cs.clone(), 31, top.map(|v| (v & 0x7fff_ffff) as u128))?;
+    q[7].enforce_equal(&(&high + FpVar::from(sign.clone()) * F::from(1u64 << 31)))?;
+    let mut y2 = q.clone();
+    y2[7] = high;
+
+    // x2^2 (d y2^2 + 1) = y2^2 - 1
+    let x2 = value(&q)
+        .and_then(|q| native::decompress(&q))
+        .map(|(x, _)| x);
+    let x2 = alloc_reduced(cs.clone(), x2.as_ref(), &P)?;
+    parity(cs.clone(), &x2)?.enforce_equal(&sign)?;
+    let yy = modmul(cs.clone(), &y2, &y2, &p)?;
+    let u = lincomb(cs.clone(), &[(1, &yy), (-1, &one)], &P)?;
+    let dyy = modmul(cs.clone(), &yy, &d, &p)?;
+    let v = lincomb(cs.clone(), &[(1, &dyy), (1, &one)], &P)?;
+    let xx = modmul(cs.clone(), &x2, &x2, &p)?;
+    enforce_modmul_eq(cs.clone(), &xx, &v, &p, &u)?;
+
+    // x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2)
+    // y3 = (y1 y2 + x1 x2) / (1 - d x1 x2 y1 y2)
+    let a = modmul(cs.clone(), &x1, &y2, &p)?;
+    let b = modmul(cs.clone(), &y1, &x2, &p)?;
+    let c = modmul(cs.clone(), &y1, &y2, &p)?;
+    let e = modmul(cs.clone(), &x1, &x2, &p)?;
+    let t = modmul(cs.clone(), &e, &c, &p)?;
+    let dt = modmul(cs.clone(), &t, &d, &p)?;
+    let den_x = lincomb(cs.clone(), &[(1, &one), (1, &dt)], &P)?;
+    let den_y = lincomb(cs.clone(), &[(1, &one), (-1, &dt)], &P)?;
+    enforce_nonzero(&den_x)?;
+    enforce_nonzero(&den_y)?;
+    let num_x = lincomb(cs.clone(), &[(1, &a), (1, &b)], &P)?;
+    let num_y = lincomb(cs.clone(), &[(1, &c), (1, &e)], &P)?;
+
+    let res = value(&x1)
+        .zip(value(&y1))
+        .zip(value(&q))
+        .and_then(|((x1, y1), q)| native::eval(&x1, &y1, &q));
+    let coord = |i: usize| -> Option<U256> { res.as_ref()?[8 * i..8 * i + 8].try_into().ok() };
+    let x3 = alloc_reduced(cs.clone(), coord(0).as_ref(), &P)?;
+    let y3 = alloc_reduced(cs.clone(), coord(1).as_ref(), &P)?;
+    enforce_modmul_eq(cs.clone(), &x3, &den_x, &p, &num_x)?;
+    enforce_modmul_eq(cs, &y3, &den_y, &p, &num_y)?;
+    Ok((x3, y3))
+}
@@ -0,0 +1,131 @@
+//! Constraints for the Poseidon precompile.
+//!
+//! These reuse the hash gadgets of the memory trie. Field elements
+//! are represented in guest memory by 32-bit limbs, and are checked to
+//! be less than the field modulus, so that each element has a unique
+//! representation.
+
+use ark_crypto_primitives::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::{bigint::Limbs, F};
+use crate::{
+    memory::path::{LeafHashG, ParamsVar, TwoToOneHashG},
+    precompiles::poseidon::{self as native, PoseidonOp},
+};
+
+type CS = ConstraintSystemRef<F>;
+
+// group little-endian bits into 32-bit limbs
+fn limbs(bits: &[Boolean<F>]) -> Result<Limbs, SynthesisError> {
+    bits.chunks(32).map(Boolean::le_bits_to_fp_var).collect()
+}
+
+/// Allocate a field element with value `x`, returning its limbs and
+/// the element itself.
+pub fn alloc_element(cs: CS, x: Option<F>) -> Result<(Limbs, FpVar<F>), SynthesisError> {
+    let bits = (0..F::MODULUS_BIT_SIZE as usize)
+        .map(|i| {
+            Boolean::new_witness(cs.clone(), || {
+                let x = x.ok_or(SynthesisError::AssignmentMissing)?;
+                Ok(x.into_bigint().get_bit(i))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::enforce_in_field_le(&bits)?;
+    Ok((limbs(&bits)?, Boolean::le_bits_to_fp_var(&bits)?))
+}
+
+/// Returns the field element with limbs `x`, which are assumed to be
+/// range checked, enforcing that it is less than the field modulus.
+pub fn from_limbs(cs: CS, x: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+    let words: Option<[u32; 8]> = x
+        .iter()
+        .map(|l| l.value().ok().map(|v| v.into_bigint().0[0] as u32))
+        .collect::<Option<Vec<u32>>>()
+        .and_then(|v| v.try_into().ok());
+    let (limbs, elem) = alloc_element(cs, words.as_ref().and_then(native::from_words))?;
+    for (l, y) in limbs.iter().zip(x) {
+        l.enforce_equal(y)?;
+    }
+    Ok(elem)
+}
+
+/// Returns the limbs of a field element.
+pub fn to_limbs(x: &FpVar<F>) -> Result<Limbs, SynthesisError> {
+    limbs(&x.to_bits_le()?)
+}
+
+/// In-circuit version of `precompiles::poseidon::eval`.
+pub fn eval(
+    params: &ParamsVar,
+    op: PoseidonOp,
+    elems: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    match (op, elems) {
+        (PoseidonOp::Hash, _) => LeafHashG::evaluate(params, elems),
+        (PoseidonOp::Compress, [l, r]) => TwoToOneHashG::compress(params, l, r),
+        (PoseidonOp::Compress, _) => Err(SynthesisError::Unsatisfiable),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{circuit::bigint::alloc_limbs, memory::path::poseidon_config};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    fn words(limbs: &[FpVar<F>]) -> Vec<u32> {
+        limbs
+            .iter()
+            .map(|l| l.value().unwrap().into_bigint().0[0] as u32)
+            .collect()
+    }
+
+    #[test]
+    fn poseidon_limbs() {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let x = -F::from(1u64);
+        let (limbs, v) = alloc_element(cs.clone(), Some(x)).unwrap();
+        assert_eq!(v.value().unwrap(), x);
+        assert_eq!(words(&limbs), native::to_words(x));
+        assert_eq!(words(&to_limbs(&v).unwrap()), native::to_words(x));
+        assert!(cs.is_satisfied().unwrap());
+
+        let elem = from_limbs(cs.clone(), &limbs).unwrap();
+        assert_eq!(elem.value().unwrap(), x);
+        assert!(cs.is_satisfied().unwrap());
+
+        // the modulus is not a field element
+        let cs = ConstraintSystem::<F>::new_ref();
+        let mut p = native::to_words(x);
+        p[0] += 1;
+        let limbs = alloc_limbs(cs.clone(), 8, Some(&p)).unwrap();
+        assert!(from_limbs(cs, &limbs).is_err());
+    }
+
+    #[test]
+    fn poseidon_eval() {
+        let params = poseidon_config();
+        let elems = [F::from(1u64), F::from(2u64)];
+
+        for op in [PoseidonOp::Hash, PoseidonOp::Compress] {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let params_var = ParamsVar::new_constant(cs.clone(), &params).unwrap();
+            let vars: Vec<FpVar<F>> = elems
+                .iter()
+                .map(|x| alloc_element(cs.clone(), Some(*x)).unwrap().1)
+                .collect();
+
+            let digest = eval(&params_var, op, &vars).unwrap();
+            let limbs = to_limbs(&digest).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            let expected = native::eval(&params, op, &elems).unwrap();
+            assert_eq!(digest.value().unwrap(), expected);
+            assert_eq!(words(&limbs), native::to_words(expected));
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Constraints checking the results of precompiles.
+//!
+//! Precompiles take their operands from `PRECOMPILE_REGS` and write
+//! their results back to them (see `Syscalls::syscall`), so they are
+//! checked without reading memory. The gadgets of the precompiles the
+//! circuit is set up for (see `Precompiles`) are part of each step,
+//! and the other precompiles may not be called. A precompile is used
+//! when the step executes an ecall with its number in s2, in which
+//! case its result replaces the registers it writes, after the
+//! destination register of the ecall. The operands of precompiles
+//! which are not used are replaced by constants for which the gadgets
+//! are satisfiable.
+//!
+//! Reads from input streams are handled in the same way, except that
+//! their results are only range checked.
+
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+
+use crate::{
+    memory::{
+        path::{poseidon_config, ParamsVar},
+        MemoryProof,
+    },
+    precompiles::{poseidon::PoseidonOp, Precompiles},
+    rv32::RV32::ECALL,
+    syscalls::{MODULUS_REGS, PRECOMPILE_REGS},
+    trace::Witness,
+};
+
+use super::{bigint, ed25519, poseidon, r1cs::R1CS, secp256k1, F};
+
+type CS = ConstraintSystemRef<F>;
+
+// ecall numbers of the precompiles, see `Syscalls::syscall`
+const STREAM_LEN: u32 = 10;
+const READ_STREAM: u32 = 11;
+const BIGINT_ADD: u32 = 12;
+const BIGINT_MUL: u32 = 13;
+const BIGINT_MODMUL: u32 = 14;
+const POSEIDON_HASH: u32 = 15;
+const POSEIDON_COMPRESS: u32 = 16;
+const SECP256K1_ADD: u32 = 17;
+const SECP256K1_DOUBLE: u32 = 18;
+const ED25519_ADD: u32 = 19;
+
+/// Add the constraints of the selected precompiles to the step with
+/// variables `vars`, laid out as in `rcs`. The outputs of the step
+/// holding `PRECOMPILE_REGS` are replaced by their values after the
+/// precompile.
+pub(crate) fn add_precompiles<P: MemoryProof>(
+    cs: CS,
+    w: &Witness<P>,
+    rcs: &R1CS,
+    vars: &[FpVar<F>],
+    output: &mut [FpVar<F>],
+    precompiles: Precompiles,
+) -> Result<(), SynthesisError> {
+    let x = |r: usize| &vars[rcs.input_range().start + 1 + r];
+    let is_ecall = &vars[rcs.var(&format!("J={}", (ECALL { rd: 0 }).index_j()))];
+    let select = |num: u32| -> Result<FpVar<F>, SynthesisError> {
+        let is_num = x(18).is_eq(&FpVar::constant(F::from(num)))?;
+        Ok(is_ecall * FpVar::from(is_num))
+    };
+    let (a, b) = PRECOMPILE_REGS.split_at(8);
+    // registers hold 32-bit values, so they are range checked limbs
+    let limbs = |rs: &[usize]| -> Vec<FpVar<F>> { rs.iter().map(|r| x(*r).clone()).collect() };
+
+    // selector and value of each write to `PRECOMPILE_REGS`
+    let mut writes: Vec<Vec<(FpVar<F>, FpVar<F>)>> = vec![Vec::new(); PRECOMPILE_REGS.len()];
+
+    // Input streams are provided by the host, so their contents are
+    // only range checked: the program stores them to memory itself.
+    let advice = w.precompile_regs.unwrap_or_default();
+    let advice = bigint::alloc_limbs(cs.clone(), 8, Some(&advice[..8]))?;
+    writes[0].push((select(STREAM_LEN)?, advice[0].clone()));
+    let read = select(READ_STREAM)?;
+    for (i, v) in advice.into_iter().enumerate() {
+        writes[i].push((read.clone(), v));
+    }
+
+    if precompiles.bigint {
+        let (la, lb) = (limbs(a), limbs(b));
+
+        let add = select(BIGINT_ADD)?;
+        let (s, carry) = bigint::add(cs.clone(), &la, &lb)?;
+        let high = std::iter::once(carry.into()).chain(std::iter::repeat(FpVar::zero()));
+        for (i, v) in s.into_iter().chain(high).take(16).enumerate() {
+            writes[i].push((add.clone(), v));
+        }
+
+        let mul = select(BIGINT_MUL)?;
+        for (i, v) in bigint::mul(cs.clone(), &la, &lb)?.into_iter().enumerate() {
+            writes[i].push((mul.clone(), v));
+        }
+
+        // the modulus is 1 unless selected, as zero is not satisfiable
+        let modmul = select(BIGINT_MODMUL)?;
+        let mut m: Vec<FpVar<F>> = MODULUS_REGS.iter().map(|r| &modmul * x(*r)).collect();
+        m[0] += FpVar::one() - &modmul;
+        for (i, v) in bigint::modmul(cs.clone(), &la, &lb, &m)?
+            .into_iter()
+            .enumerate()
+        {
+            writes[i].push((modmul.clone(), v));
+        }
+    } else {
+        not_called(&select, &[BIGINT_ADD, BIGINT_MUL, BIGINT_MODMUL])?;
+    }
+
+    if precompiles.poseidon {
+        let hash = select(POSEIDON_HASH)?;
+        let compress = select(POSEIDON_COMPRESS)?;
+        let sel = &hash + &compress;
+        let elem = |rs: &[usize]| {
+            let limbs: Vec<FpVar<F>> = rs.iter().map(|r| &sel * x(*r)).collect();
+            poseidon::from_limbs(cs.clone(), &limbs)
+        };
+        let (l, r) = (elem(a)?, elem(b)?);
+        let params = ParamsVar::new_constant(cs.clone(), poseidon_config())?;
+        let digest = &hash * poseidon::eval(&params, PoseidonOp::Hash, &[l.clone(), r.clone()])?
+            + &compress * poseidon::eval(&params, PoseidonOp::Compress, &[l, r])?;
+        for (i, limb) in poseidon::to_limbs(&digest)?.into_iter().enumerate() {
+            writes[i].push((sel.clone(), limb));
+        }
+    } else {
+        not_called(&select, &[POSEIDON_HASH, POSEIDON_COMPRESS])?;
+    }
+
+    if precompiles.secp256k1 {
+        let add = select(SECP256K1_ADD)?;
+        let double = select(SECP256K1_DOUBLE)?;
+        let (x3, y3) = secp256k1::point_op(
+            cs.clone(),
+            &add,
+            &double,
+            &limbs(a),
+            &limbs(b),
+            &limbs(&MODULUS_REGS),
+        )?;
+        let sel = &add + &double;
+        for (i, v) in x3.into_iter().chain(y3).enumerate() {
+            writes[i].push((sel.clone(), v));
+        }
+    } else {
+        not_called(&select, &[SECP256K1_ADD, SECP256K1_DOUBLE])?;
+    }
+
+    if precompiles.ed25519 {
+        let add = select(ED25519_ADD)?;
+        let (x3, y3) = ed25519::add(
+            cs.clone(),
+            &add,
+            &limbs(a),
+            &limbs(b),
+            &limbs(&MODULUS_REGS),
+        )?;
+        for (i, v) in x3.into_iter().chain(y3).enumerate() {
+            writes[i].push((add.clone(), v));
+        }
+    } else {
+        not_called(&select, &[ED25519_ADD])?;
+    }
+
+    for (i, r) in PRECOMPILE_REGS.iter().enumerate() {
+        let old = &output[1 + r];
+        let next = writes[i]
+            .iter()
+            .fold(old.clone(), |acc, (s, v)| acc + s * (v - old));
+        let value = FpVar::new_witness(cs.clone(), || match w.precompile_regs {
+            Some(x) => Ok(F::from(x[i])),
+            None => old.value(),
+        })?;
+        value.enforce_equal(&next)?;
+        output[1 + r] = value;
+    }
+    Ok(())
+}
+
+// Precompiles the circuit is not set up for may not be called.
+fn not_called(
+    select: &impl Fn(u32) -> Result<FpVar<F>, SynthesisError>,
+    nums: &[u32],
+) -> Result<(), SynthesisError> {
+    for num in nums {
+        select(*num)?.enforce_equal(&FpVar::zero())?;
+    }
+    Ok(())
+}
+
+/// Check that step `w` satisfies the constraints generated by
+/// `add_precompiles` for `precompiles`, given the step circuit witness
+/// `rcs`.
+pub(crate) fn precompiles_satisfied<P: MemoryProof>(
+    w: &Witness<P>,
+    rcs: &R1CS,
+    precompiles: Precompiles,
+) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let vars = rcs
+        .w
+        .iter()
+        .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut output = vars[rcs.output_range()].to_vec();
+    add_precompiles(cs.clone(), w, rcs, &vars, &mut output, precompiles)?;
+    cs.is_satisfied()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit::{build_constraints, check_trace, test::step_mismatch, StepConstraints},
+        error::NexusVMError::{InvalidCurvePoint, PrecompileNotSelected},
+        machines::asm_vm,
+        memory::{
+            path::{compress, hash_leaf, Path},
+            trie::MerkleTrie,
+        },
+        precompiles::{
+            bigint::{self as native, U256},
+            ed25519::{self, decompress},
+            poseidon::to_words,
+            secp256k1::{self, Secp256k1Op, GX, GY},
+        },
+        rv32::asm,
+        trace::{trace, Trace},
+    };
+
+    // whether the first block of `tr` satisfies the step circuit
+    fn ark_satisfied(tr: &Trace<impl MemoryProof>) -> bool {
+        synthesize(tr).is_satisfied().unwrap()
+    }
+
+    // the step circuit for the first block of `tr`
+    fn synthesize(tr: &Trace<impl MemoryProof>) -> ConstraintSystemRef<F> {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let inp = tr
+            .input(0)
+            .unwrap()
+            .iter()
+            .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
+            .collect::<Vec<_>>();
+        let rcs = StepConstraints::new(tr).unwrap();
+        build_constraints(cs.clone(), 0, &inp, tr, &rcs).unwrap();
+        cs
+    }
+
+    // Trace a program setting registers `regs` and calling precompile
+    // `num`, in a single block, selecting only the precompiles `num`
+    // needs. Returns the trace, the index of the ecall step, and the
+    // values of `PRECOMPILE_REGS` after the ecall.
+    fn precompile_trace(num: u32, regs: &[(usize, u32)]) -> (Trace<Path>, usize, [u32; 16]) {
+        stream_trace(num, regs, &[])
+    }
+
+    // The precompiles needed to call `num`, and no others: the gadgets
+    // of the curve precompiles make blocks of many steps expensive to
+    // synthesize.
+    fn needed(num: u32) -> Precompiles {
+        let mut p = Precompiles::default();
+        match num {
+            BIGINT_ADD..=BIGINT_MODMUL => p.bigint = true,
+            POSEIDON_HASH | POSEIDON_COMPRESS => p.poseidon = true,
+            SECP256K1_ADD | SECP256K1_DOUBLE => p.secp256k1 = true,
+            ED25519_ADD => p.ed25519 = true,
+            _ => (),
+        }
+        p
+    }
+
+    // As `precompile_trace`, with input stream 1 holding `stream`.
+    fn stream_trace(
+        num: u32,
+        regs: &[(usize, u32)],
+        stream: &[u8],
+    ) -> (Trace<Path>, usize, [u32; 16]) {
+        let mut src: String = regs
+            .iter()
+            .map(|(r, v)| format!("li x{r}, {v}\n"))
+            .collect();
+        src += &format!("li s2, {num}\necall\nunimp");
+        let k = asm::assemble(&src).unwrap().len();
+        let mut vm = asm_vm::<MerkleTrie>(&src).unwrap();
+        vm.syscalls.set_precompiles(needed(num));
+        vm.syscalls.set_stream(1, stream);
+        let tr = trace(&mut vm, k, false).unwrap();
+        (tr, k - 2, PRECOMPILE_REGS.map(|r| vm.regs.x[r]))
+    }
+
+    // registers holding the operands `a`, `b` and `m`
+    fn operands(a: &U256, b: &U256, m: &U256) -> Vec<(usize, u32)> {
+        let rs = PRECOMPILE_REGS.iter().chain(&MODULUS_REGS);
+        rs.zip(a.iter().chain(b).chain(m))
+            .map(|(r, v)| (*r, *v))
+            .collect()
+    }
+
+    #[test]
+    fn precompile_bigint() {
+        let max = [u32::MAX; 8];
+        let b = [7, 6, 5, 4, 3, 2, 1, 0x8000_0000];
+        let m = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        let (tr, _, regs) =
+            precompile_trace(BIGINT_ADD, &operands(&max, &[2, 0, 0, 0, 0, 0, 0, 0], &m));
+        assert_eq!(regs, [1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        let (tr, _, regs) = precompile_trace(BIGINT_MUL, &operands(&max, &b, &m));
+        assert_eq!(regs, native::mul(&max, &b));
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        let (tr, _, regs) = precompile_trace(BIGINT_MODMUL, &operands(&max, &b, &m));
+        assert_eq!(regs[..8], native::modmul(&max, &b, &m).unwrap());
+        assert_eq!(regs[8..], b);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+    }
+
+    #[test]
+    fn precompile_poseidon() {
+        let params = poseidon_config();
+        let (l, r) = (F::from(1u64), F::from(2u64));
+        for (num, expected) in [
+            (POSEIDON_HASH, hash_leaf(&params, &[l, r]).unwrap()),
+            (POSEIDON_COMPRESS, compress(&params, &l, &r).unwrap()),
+        ] {
+            let (tr, _, regs) = precompile_trace(num, &[(10, 1), (20, 2)]);
+            assert_eq!(regs[..8], to_words(expected));
+            assert!(check_trace(&tr).is_ok());
+            assert!(ark_satisfied(&tr));
+        }
+    }
+
+    #[test]
+    fn precompile_stream() {
+        let bytes: Vec<u8> = (1..=40).collect();
+        let (tr, _, regs) = stream_trace(STREAM_LEN, &[(11, 1), (12, 5), (20, 9)], &bytes);
+        assert_eq!(regs[..3], [40, 1, 5]);
+        assert_eq!(regs[8], 9);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        let (tr, _, regs) = stream_trace(READ_STREAM, &[(11, 1), (12, 6), (20, 9)], &bytes);
+        assert_eq!(regs[..8], [0x04030201, 0x0605, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(regs[8], 9);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        // the contents of streams are not checked, other registers are
+        let (mut tr, step, _) = stream_trace(READ_STREAM, &[(11, 1), (12, 32)], &bytes);
+        tr.blocks[0].steps[step].precompile_regs.as_mut().unwrap()[7] ^= 1;
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        let (mut tr, step, _) = stream_trace(STREAM_LEN, &[(11, 1)], &bytes);
+        tr.blocks[0].steps[step].precompile_regs.as_mut().unwrap()[1] = 2;
+        assert!(step_mismatch(&tr).precompile);
+        assert!(!ark_satisfied(&tr));
+    }
+
+    #[test]
+    fn precompile_secp256k1() {
+        let double = secp256k1::eval(Secp256k1Op::Double, &GX, &GY, &[0; 8]).unwrap();
+        let (tr, _, regs) = precompile_trace(SECP256K1_DOUBLE, &operands(&GX, &GY, &[0; 8]));
+        assert_eq!(regs[..], double);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        // 2G + G, where the y coordinate of G is even
+        let (x, y) = double.split_at(8);
+        let (x, y): (U256, U256) = (x.try_into().unwrap(), y.try_into().unwrap());
+        let (tr, _, regs) = precompile_trace(SECP256K1_ADD, &operands(&x, &y, &GX));
+        assert_eq!(
+            regs[..],
+            secp256k1::eval(Secp256k1Op::Add, &x, &y, &GX).unwrap()
+        );
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+    }
+
+    #[test]
+    fn precompile_ed25519() {
+        // the base point, whose encoding is its y coordinate
+        let enc = [
+            0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+            0x66666666,
+        ];
+        let (x, y) = decompress(&enc).unwrap();
+        let (tr, _, regs) = precompile_trace(ED25519_ADD, &operands(&x, &y, &enc));
+        assert_eq!(regs[..], ed25519::eval(&x, &y, &enc).unwrap());
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+
+        // B - B = (0, 1)
+        let mut minus = enc;
+        minus[7] |= 1 << 31;
+        let (tr, _, regs) = precompile_trace(ED25519_ADD, &operands(&x, &y, &minus));
+        assert_eq!(regs, [0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(check_trace(&tr).is_ok());
+        assert!(ark_satisfied(&tr));
+    }
+
+    #[test]
+    fn precompile_invalid_point() {
+        // the point (0, 0) cannot be doubled, and 2 is not the y
+        // coordinate of an Ed25519 point
+        for (num, regs) in [(SECP256K1_DOUBLE, vec![]), (ED25519_ADD, vec![(5, 2)])] {
+            let mut src: String = regs
+                .iter()
+                .map(|(r, v)| format!("li x{r}, {v}\n"))
+                .collect();
+            src += &format!("li s2, {num}\necall\nunimp");
+            let mut vm = asm_vm::<MerkleTrie>(&src).unwrap();
+            vm.syscalls.set_precompiles(Precompiles::ALL);
+            assert!(matches!(
+                trace(&mut vm, 4, false),
+                Err(InvalidCurvePoint(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_wrong_result() {
+        let max = [u32::MAX; 8];
+        let m = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        // a wrong remainder, a wrong carry, a wrong digest, and wrong
+        // coordinates
+        for (num, regs, i) in [
+            (BIGINT_MODMUL, operands(&max, &max, &m), 0),
+            (BIGINT_ADD, operands(&max, &max, &m), 8),
+            (POSEIDON_COMPRESS, vec![(10, 1), (20, 2)], 3),
+            (SECP256K1_DOUBLE, operands(&GX, &GY, &[0; 8]), 12),
+            (ED25519_ADD, vec![(10, 1), (20, 1), (5, 1)], 0),
+        ] {
+            let (mut tr, step, _) = precompile_trace(num, &regs);
+            tr.blocks[0].steps[step].precompile_regs.as_mut().unwrap()[i] ^= 1;
+
+            let err = step_mismatch(&tr);
+            assert_eq!((err.block, err.step), (0, step));
+            assert!(err.violations.is_empty());
+            assert!(err.precompile);
+            assert!(!ark_satisfied(&tr));
+        }
+
+        // registers which are not written may not change
+        let (mut tr, step, _) = precompile_trace(POSEIDON_HASH, &[(10, 1), (20, 2)]);
+        tr.blocks[0].steps[step].precompile_regs.as_mut().unwrap()[8] = 3;
+        assert!(step_mismatch(&tr).precompile);
+        assert!(!ark_satisfied(&tr));
+    }
+
+    #[test]
+    fn precompile_not_selected() {
+        let mut vm = asm_vm::<MerkleTrie>("li s2, 13\necall\nunimp").unwrap();
+        assert!(matches!(
+            trace(&mut vm, 3, false),
+            Err(PrecompileNotSelected(_, 13))
+        ));
+
+        // the gadgets of precompiles which are not selected are left out
+        let bigint = Precompiles { bigint: true, ..Default::default() };
+        let poseidon = Precompiles { poseidon: true, ..Default::default() };
+        let secp256k1 = Precompiles { secp256k1: true, ..Default::default() };
+        let ed25519 = Precompiles { ed25519: true, ..Default::default() };
+        let (mut tr, _, _) = precompile_trace(STREAM_LEN, &[(11, 1)]);
+        let mut sizes = Vec::new();
+        for p in [Precompiles::default(), bigint, poseidon, secp256k1, ed25519] {
+            tr.precompiles = p;
+            assert!(check_trace(&tr).is_ok());
+            sizes.push(synthesize(&tr).num_constraints());
+        }
+        tr.precompiles = Precompiles::ALL;
+        let all = synthesize(&tr).num_constraints();
+        assert!(sizes[1..].iter().all(|s| *s > sizes[0] && *s < all));
+
+        // and they may not be called
+        for (num, regs, p) in [
+            (BIGINT_MUL, [(10, 3), (20, 5)], poseidon),
+            (POSEIDON_HASH, [(10, 1), (20, 2)], bigint),
+            (SECP256K1_DOUBLE, [(10, 1), (20, 1)], ed25519),
+            (ED25519_ADD, [(10, 1), (20, 1)], secp256k1),
+        ] {
+            let (mut tr, _, _) = precompile_trace(num, &regs);
+            tr.precompiles = p;
+            assert!(step_mismatch(&tr).precompile);
+            assert!(!ark_satisfied(&tr));
+        }
+    }
+}
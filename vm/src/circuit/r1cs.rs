@@ -2,14 +2,14 @@
 //!
 //! This crate provides a representation of R1CS as a set of
 //! matrices without an intermediate representation of the
-//! constraints. The matrices are dense for simplicity, and
-//! are over a fixed field.
+//! constraints. The matrices are sparse, with each row stored
+//! as a list of non-zero entries, and are over a fixed field.
 //! These matrices are meant to be used at compile-time as a
 //! source for generating constraints over a target field.
 
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use std::ops::Range;
+use std::ops::{Index, IndexMut, Range};
 
 use ark_bn254::FrConfig;
 use ark_ff::{BigInt, Fp, MontConfig};
@@ -25,7 +25,52 @@ pub const MINUS: F = {
 };
 
 pub type V = Vec<F>;
-pub type M = Vec<Vec<F>>;
+pub type M = Vec<Row>;
+
+/// A sparse row of a constraint matrix, stored as a list of
+/// (column, coefficient) pairs. Indexing a missing column yields
+/// zero, and assigning to a missing column adds an entry.
+#[derive(Clone, Debug, Default)]
+pub struct Row(Vec<(usize, F)>);
+
+impl Row {
+    /// Iterate over the non-zero entries of the row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &F)> + '_ {
+        self.0
+            .iter()
+            .filter(|(_, x)| x != &ZERO)
+            .map(|(i, x)| (*i, x))
+    }
+
+    /// Compute the inner product of the row with `w`.
+    pub fn dot(&self, w: &[F]) -> F {
+        self.iter().map(|(i, x)| *x * w[i]).sum()
+    }
+}
+
+impl Index<usize> for Row {
+    type Output = F;
+
+    fn index(&self, j: usize) -> &F {
+        self.0
+            .iter()
+            .find(|(i, _)| *i == j)
+            .map_or(&ZERO, |(_, x)| x)
+    }
+}
+
+impl IndexMut<usize> for Row {
+    fn index_mut(&mut self, j: usize) -> &mut F {
+        let k = match self.0.iter().position(|(i, _)| *i == j) {
+            Some(k) => k,
+            None => {
+                self.0.push((j, ZERO));
+                self.0.len() - 1
+            }
+        };
+        &mut self.0[k].1
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct R1CS {
@@ -87,11 +132,6 @@ impl R1CS {
         let n = self.w.len();
         self.vars.insert(name.to_string(), n);
         self.w.push(ZERO);
-        if !self.a.is_empty() {
-            self.a.iter_mut().for_each(|v| v.push(ZERO));
-            self.b.iter_mut().for_each(|v| v.push(ZERO));
-            self.c.iter_mut().for_each(|v| v.push(ZERO));
-        }
         n
     }
 
@@ -136,14 +176,14 @@ impl R1CS {
 
     pub fn constraint<F>(&mut self, f: F)
     where
-        F: FnOnce(&Self, &mut V, &mut V, &mut V),
+        F: FnOnce(&Self, &mut Row, &mut Row, &mut Row),
     {
         if self.witness_only {
             return;
         }
-        let mut a = vec![ZERO; self.w.len()];
-        let mut b = a.clone();
-        let mut c = a.clone();
+        let mut a = Row::default();
+        let mut b = Row::default();
+        let mut c = Row::default();
 
         f(self, &mut a, &mut b, &mut c);
 
@@ -236,14 +276,11 @@ impl R1CS {
     #[allow(dead_code)]
     pub fn merge(&mut self, cs: &Self) {
         let left_len = self.w.len();
-        let len = left_len + cs.w.len();
         self.w.extend_from_slice(&cs.w);
         let merge_M = |x: &mut M, y: &M| {
-            x.iter_mut().for_each(|r| r.resize(len, ZERO));
             for right in y {
-                let mut left = vec![ZERO; left_len];
-                left.extend_from_slice(right);
-                x.push(left);
+                let row = right.0.iter().map(|(i, f)| (left_len + i, *f)).collect();
+                x.push(Row(row));
             }
         };
         merge_M(&mut self.a, &cs.a);
@@ -263,22 +300,14 @@ impl R1CS {
         #[cfg(debug_assertions)]
         for m in [&self.a, &self.b, &self.c] {
             for v in m {
-                debug_assert!(v.len() == self.w.len());
+                debug_assert!(v.iter().all(|(i, _)| i < self.w.len()));
             }
         }
 
-        #[rustfmt::skip]
-        fn dot(a: &V, b: &V) -> F {
-            a.iter()
-             .zip(b)
-             .map(|(a,b)| a * b)
-             .sum()
-        }
-
         #[rustfmt::skip]
         fn MxV(m: &M, v: &V) -> Vec<F> {
             m.iter()
-             .map(|r| dot(r,v))
+             .map(|r| r.dot(v))
              .collect()
         }
 
@@ -292,31 +321,22 @@ impl R1CS {
             if x[i] * y[i] != z[i] {
                 println!("constraint {i} not satisfied");
                 println!("A");
-                let v = &self.a[i];
-                for i in 0..v.len() {
-                    if v[i] != ZERO {
-                        println!("{} * {}", v[i], self.w[i]);
-                    }
+                for (i, v) in self.a[i].iter() {
+                    println!("{} * {}", v, self.w[i]);
                 }
                 println!("B");
-                let v = &self.b[i];
-                for i in 0..v.len() {
-                    if v[i] != ZERO {
-                        println!("{} * {}", v[i], self.w[i]);
-                    }
+                for (i, v) in self.b[i].iter() {
+                    println!("{} * {}", v, self.w[i]);
                 }
                 println!("C");
-                let v = &self.c[i];
-                for i in 0..v.len() {
-                    if v[i] != ZERO {
-                        let mut rv = format!("{i}");
-                        for (n, j) in &self.vars {
-                            if *j == i {
-                                rv.clone_from(n);
-                            }
+                for (i, v) in self.c[i].iter() {
+                    let mut rv = format!("{i}");
+                    for (n, j) in &self.vars {
+                        if *j == i {
+                            rv.clone_from(n);
                         }
-                        println!("{} * {} (name {})", v[i], self.w[i], rv);
                     }
+                    println!("{} * {} (name {})", v, self.w[i], rv);
                 }
             }
         }
@@ -602,6 +622,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_row() {
+        let mut r = Row::default();
+        assert_eq!(r[3], ZERO);
+        r[3] = ONE;
+        r[1] = TWO;
+        r[3] = TWO;
+        r[5] = ZERO;
+        assert_eq!(r[3], TWO);
+        assert_eq!(r.iter().count(), 2);
+        assert_eq!(r.dot(&[ONE, ONE, ONE, ONE, ONE, ONE]), F::from(4));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut cs = R1CS::default();
+        cs.set_bit("a", true);
+        let mut right = R1CS::default();
+        right.set_var("x", 7);
+        right.to_bits("x", 7);
+        cs.merge(&right);
+        assert!(cs.is_sat());
+
+        let j = cs.var("x_3");
+        cs.w[j] = ONE;
+        assert!(!cs.is_sat());
+    }
+
     fn test_mem(set: &[u32]) {
         for &x in set {
             let mut cs = R1CS::default();
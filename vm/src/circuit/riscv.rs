@@ -27,7 +27,7 @@ fn init_cs(w: &Witness<impl MemoryProof>) -> R1CS {
     for i in 0..32 {
         cs.set_var(&format!("x{i}"), w.regs.x[i]);
     }
-    cs.set_field_var("root", w.pc_proof.commit());
+    cs.set_field_var("root", w.pc_proof.commit_prior());
 
     // outputs
     cs.set_var("PC", w.PC);
@@ -639,11 +639,22 @@ fn jal(cs: &mut R1CS, _vm: &Witness<impl MemoryProof>) {
     cs.set_eq(&format!("PC{J}"), "pc+I");
 }
 
-fn jalr(cs: &mut R1CS, _vm: &Witness<impl MemoryProof>) {
+fn jalr(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
     const J: u32 = (JALR { rd: 0, rs1: 0, imm: 0 }).index_j();
 
     cs.set_eq(&format!("Z{J}"), "pc+4");
-    cs.set_eq(&format!("PC{J}"), "X+I");
+
+    // the target is X+I with the lowest bit cleared
+    let target = vm.X.wrapping_add(vm.I);
+    cs.set_bit("(X+I)_0", (target & 1) == 1);
+    cs.set_var(&format!("PC{J}"), target & !1);
+    cs.add("X+I", &format!("PC{J}"), "(X+I)_0");
+
+    // J * PC_0 = 0, so the bit removed above is the lowest bit of X+I
+    cs.constraint(|cs, a, b, _c| {
+        a[cs.var(&format!("J={J}"))] = ONE;
+        b[cs.var("PC_0")] = ONE;
+    });
 }
 
 fn alu(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
@@ -0,0 +1,89 @@
+//! Constraints for the secp256k1 precompile.
+//!
+//! The field operations of `precompiles::secp256k1` are checked with
+//! the gadgets of `circuit::bigint`, modulo the constant field prime.
+//! Additions and doublings share a gadget: they only differ in the
+//! slope of the line through their operands.
+
+use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::{
+    bigint::{
+        alloc_limbs, alloc_reduced, constant, enforce_lincomb, enforce_modmul_eq, enforce_nonzero,
+        lincomb, modmul, parity, select, value, Limbs,
+    },
+    F,
+};
+use crate::precompiles::secp256k1::{self as native, Secp256k1Op, B, GX, GY, P};
+
+type CS = ConstraintSystemRef<F>;
+
+// the x coordinate of 2G, whose y coordinate is even
+const X_2G: [u32; 8] = [
+    0x5c709ee5, 0xabac09b9, 0x8cef3ca7, 0x5c778e4b, 0x95c07cd8, 0x3045406e, 0x41ed7d6d, 0xc6047f94,
+];
+
+/// Enforce that `(x3, y3)` is `(x1, y1) + (x2, y2)`, where `y2` is
+/// even, if `add` is one, and `2 (x1, y1)` if `double` is one,
+/// returning `(x3, y3)`. At most one of `add` and `double` may be one;
+/// the operands which are not used are replaced by points for which
+/// the gadget is satisfiable.
+pub fn point_op(
+    cs: CS,
+    add: &FpVar<F>,
+    double: &FpVar<F>,
+    x1: &[FpVar<F>],
+    y1: &[FpVar<F>],
+    x2: &[FpVar<F>],
+) -> Result<(Limbs, Limbs), SynthesisError> {
+    let p = constant(&P);
+    let sel = add + double;
+    let x1 = select(&sel, x1, &constant(&GX));
+    let y1 = select(&sel, y1, &constant(&GY));
+    let x2 = select(add, x2, &constant(&X_2G));
+
+    // the point (x2, y2) is on the curve, and y2 is even
+    let y2 = value(&x2).and_then(|x| native::even_y(&x));
+    let y2 = alloc_reduced(cs.clone(), y2.as_ref(), &P)?;
+    parity(cs.clone(), &y2)?.enforce_equal(&Boolean::FALSE)?;
+    let xx = modmul(cs.clone(), &x2, &x2, &p)?;
+    let xxx = modmul(cs.clone(), &xx, &x2, &p)?;
+    let yy = modmul(cs.clone(), &y2, &y2, &p)?;
+    enforce_lincomb(cs.clone(), &[(1, &yy), (-1, &xxx), (-1, &constant(&B))], &P)?;
+
+    // the slope l satisfies l * den = num, with den = x2 - x1 and
+    // num = y2 - y1 for additions, and den = 2 y1 and num = 3 x1^2 for
+    // doublings
+    let dx = lincomb(cs.clone(), &[(1, &x2), (-1, &x1)], &P)?;
+    let dy = lincomb(cs.clone(), &[(1, &y2), (-1, &y1)], &P)?;
+    let x1x1 = modmul(cs.clone(), &x1, &x1, &p)?;
+    let num = lincomb(cs.clone(), &[(3, &x1x1)], &P)?;
+    let den = lincomb(cs.clone(), &[(2, &y1)], &P)?;
+    let den = select(double, &den, &dx);
+    let num = select(double, &num, &dy);
+    enforce_nonzero(&den)?;
+
+    let op = if double.value().is_ok_and(|d| d == F::from(1u64)) {
+        Secp256k1Op::Double
+    } else {
+        Secp256k1Op::Add
+    };
+    let l = value(&x1)
+        .zip(value(&y1))
+        .zip(value(&x2))
+        .and_then(|((x1, y1), x2)| native::slope(op, &x1, &y1, &x2));
+    let l = alloc_limbs(cs.clone(), 8, l.as_ref().map(|l| &l[..]))?;
+    enforce_modmul_eq(cs.clone(), &l, &den, &p, &num)?;
+
+    // x3 = l^2 - x1 - x2, where x2 = x1 for doublings
+    let x2 = select(double, &x1, &x2);
+    let ll = modmul(cs.clone(), &l, &l, &p)?;
+    let x3 = lincomb(cs.clone(), &[(1, &ll), (-1, &x1), (-1, &x2)], &P)?;
+
+    // y3 = l (x1 - x3) - y1
+    let e = lincomb(cs.clone(), &[(1, &x1), (-1, &x3)], &P)?;
+    let le = modmul(cs.clone(), &l, &e, &p)?;
+    let y3 = lincomb(cs, &[(1, &le), (-1, &y1)], &P)?;
+    Ok((x3, y3))
+}
@@ -2,30 +2,67 @@
 
 use ark_r1cs_std::{
     alloc::AllocVar,
-    fields::fp::{AllocatedFp, FpVar},
+    fields::{
+        fp::{AllocatedFp, FpVar},
+        FieldVar,
+    },
 };
 use ark_relations::{
     lc,
-    r1cs::{ConstraintSystemRef, SynthesisError, Variable},
+    r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError, Variable},
 };
 
 use crate::{
     error::Result,
     memory::MemoryProof,
+    precompiles::Precompiles,
     trace::{Block, Trace, Witness},
 };
 
 use super::{
-    r1cs::{R1CS, V, ZERO},
+    precompiles::add_precompiles,
+    r1cs::{Row, R1CS},
     riscv::{step, ARITY},
     F,
 };
 
 type CS = ConstraintSystemRef<F>;
 
+/// The constraints of the step circuit, which do not depend on the
+/// step. They are generated once, when the step circuit is created,
+/// and shared by all its steps; only the witness is generated for
+/// each step.
+pub struct StepConstraints {
+    matrices: R1CS,
+    precompiles: Precompiles,
+}
+
+impl StepConstraints {
+    /// Generate the constraints for steps with memory proofs of type
+    /// `P` from the first step of `tr`, checking the precompiles
+    /// selected for `tr`, or return `None` if `tr` has no steps.
+    pub fn new<P: MemoryProof>(tr: &Trace<P>) -> Option<Self> {
+        let w = tr.blocks.first()?.into_iter().next()?;
+        Some(Self {
+            matrices: step(&w, false),
+            precompiles: tr.precompiles,
+        })
+    }
+
+    pub(crate) fn matrices(&self) -> &R1CS {
+        &self.matrices
+    }
+
+    /// The precompiles checked by the step circuit.
+    pub fn precompiles(&self) -> Precompiles {
+        self.precompiles
+    }
+}
+
 fn add_memory_proofs<P: MemoryProof>(
     cs: CS,
     w: &Witness<P>,
+    rcs: &R1CS,
     vars: &[FpVar<F>],
 ) -> Result<(), SynthesisError> {
     let params = P::params(cs.clone())?;
@@ -35,14 +72,41 @@ fn add_memory_proofs<P: MemoryProof>(
     let root_out = &vars[ARITY * 2];
     let mem = ARITY * 2 + 1;
 
-    w.pc_proof
-        .circuit(cs.clone(), &params, root_in, &vars[mem..])?;
-    w.read_proof
-        .circuit(cs.clone(), &params, root_in, &vars[mem + 2..])?;
-    w.write_proof
-        .circuit(cs.clone(), &params, root_out, &vars[mem + 4..])?;
+    // instruction indices of loads (11..=15) and stores (16..=18)
+    let sum = |js: std::ops::RangeInclusive<u32>| {
+        js.map(|j| vars[rcs.var(&format!("J={j}"))].clone())
+            .fold(FpVar::zero(), |acc, v| acc + v)
+    };
+    let is_write = sum(16..=18);
+    let is_read = sum(11..=15) + &is_write;
+
+    P::step_circuit(
+        cs,
+        &params,
+        root_in,
+        root_out,
+        [&w.pc_proof, &w.read_proof, &w.write_proof],
+        &vars[mem..mem + 6],
+        &is_read,
+        &is_write,
+    )
+}
 
-    Ok(())
+/// Check that the memory proofs of step `w` satisfy the constraints
+/// generated by `add_memory_proofs`, given the step circuit witness
+/// `rcs`.
+pub(crate) fn memory_proofs_satisfied<P: MemoryProof>(
+    w: &Witness<P>,
+    rcs: &R1CS,
+) -> Result<bool, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let vars = rcs
+        .w
+        .iter()
+        .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)))
+        .collect::<Result<Vec<_>, _>>()?;
+    add_memory_proofs(cs.clone(), w, rcs, &vars)?;
+    cs.is_satisfied()
 }
 
 fn build_constraints_partial(
@@ -51,7 +115,14 @@ fn build_constraints_partial(
     z: &[FpVar<F>],
     w: &Witness<impl MemoryProof>,
     rcs: R1CS,
+    constraints: &StepConstraints,
 ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    let matrices = constraints.matrices();
+    // a witness laid out differently is not a step of this circuit
+    if matrices.w.len() != rcs.w.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
     let mut vars: Vec<FpVar<F>> = Vec::new();
     let mut output: Vec<FpVar<F>> = Vec::new();
 
@@ -69,27 +140,33 @@ fn build_constraints_partial(
         }
     }
 
-    add_memory_proofs(cs.clone(), w, &vars)?;
+    add_memory_proofs(cs.clone(), w, &rcs, &vars)?;
+    add_precompiles(
+        cs.clone(),
+        w,
+        &rcs,
+        &vars,
+        &mut output,
+        constraints.precompiles,
+    )?;
 
     if witness_only {
         return Ok(output);
     }
 
-    let row = |a: &V| {
-        a.iter().enumerate().fold(lc!(), |lc, (i, x)| {
-            if x == &ZERO {
-                lc
-            } else {
-                match &vars[i] {
-                    FpVar::Constant(f) => lc + (*x * f, Variable::One),
-                    FpVar::Var(av) => lc + (*x, av.variable),
-                }
-            }
+    let row = |a: &Row| {
+        a.iter().fold(lc!(), |lc, (i, x)| match &vars[i] {
+            FpVar::Constant(f) => lc + (*x * f, Variable::One),
+            FpVar::Var(av) => lc + (*x, av.variable),
         })
     };
 
-    for i in 0..rcs.a.len() {
-        cs.enforce_constraint(row(&rcs.a[i]), row(&rcs.b[i]), row(&rcs.c[i]))?;
+    for i in 0..matrices.a.len() {
+        cs.enforce_constraint(
+            row(&matrices.a[i]),
+            row(&matrices.b[i]),
+            row(&matrices.c[i]),
+        )?;
     }
 
     Ok(output)
@@ -100,6 +177,7 @@ pub fn build_constraints<P: MemoryProof>(
     index: usize,
     z: &[FpVar<F>],
     tr: &Trace<P>,
+    constraints: &StepConstraints,
 ) -> Result<Vec<FpVar<F>>, SynthesisError> {
     let witness_only = !cs.should_construct_matrices();
 
@@ -110,9 +188,11 @@ pub fn build_constraints<P: MemoryProof>(
     let mut z = z;
     let mut v = Vec::new();
 
+    // the witness is generated for each step, while the constraints
+    // are generated once, see `StepConstraints`.
     for w in b {
-        let rcs = step(&w, witness_only);
-        v = build_constraints_partial(cs.clone(), witness_only, z, &w, rcs)?;
+        let rcs = step(&w, true);
+        v = build_constraints_partial(cs.clone(), witness_only, z, &w, rcs, constraints)?;
         z = &v;
     }
 
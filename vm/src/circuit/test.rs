@@ -1,15 +1,25 @@
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
-use ark_relations::r1cs::ConstraintSystem;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
 
 use crate::{
     error::Result,
     eval::NexusVM,
     machines::loop_vm,
-    memory::{trie::MerkleTrie, Memory},
-    trace::trace,
+    memory::{
+        offline::{trace_offline, OfflineMemory},
+        trie::MerkleTrie,
+        Memory, MemoryProof,
+    },
+    trace::{trace, Trace},
 };
 
-use super::{r1cs::R1CS, riscv::step, step::build_constraints, F};
+use super::{
+    check::{check_trace, CircuitMismatch, StepMismatch},
+    r1cs::R1CS,
+    riscv::step,
+    step::{build_constraints, StepConstraints},
+    F,
+};
 
 // generate R1CS matrices
 fn vm_circuit(k: usize) -> Result<R1CS> {
@@ -43,22 +53,48 @@ fn nvm_step() {
     nvm_check_steps(vm).unwrap();
 }
 
+#[test]
+fn step_constraints_per_type() {
+    let tr = trace(&mut loop_vm::<MerkleTrie>(5), 1, false).unwrap();
+    let tr_offline = trace(&mut loop_vm::<OfflineMemory>(5), 1, false).unwrap();
+    let w = tr.blocks[0].into_iter().next().unwrap();
+    let w_offline = tr_offline.blocks[0].into_iter().next().unwrap();
+
+    let rcs = StepConstraints::new(&tr).unwrap();
+    let rcs_offline = StepConstraints::new(&tr_offline).unwrap();
+    assert_eq!(rcs.matrices().w.len(), step(&w, true).w.len());
+    assert_eq!(
+        rcs_offline.matrices().w.len(),
+        step(&w_offline, true).w.len()
+    );
+}
+
 fn ark_check(mut vm: NexusVM<impl Memory>, k: usize) -> Result<()> {
     let tr = trace(&mut vm, k, false)?;
+    ark_check_trace(&tr);
+    Ok(())
+}
+
+fn alloc_input(
+    cs: ConstraintSystemRef<F>,
+    tr: &Trace<impl MemoryProof>,
+    i: usize,
+) -> Vec<FpVar<F>> {
+    tr.input(i)
+        .unwrap()
+        .iter()
+        .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
+        .collect()
+}
 
+fn ark_check_trace(tr: &Trace<impl MemoryProof>) {
+    let rcs = StepConstraints::new(tr).unwrap();
     for i in 0..tr.blocks.len() {
         let cs = ConstraintSystem::<F>::new_ref();
-        let inp = tr
-            .input(i)
-            .unwrap()
-            .iter()
-            .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
-            .collect::<Vec<_>>();
-
-        build_constraints(cs.clone(), i, &inp, &tr).unwrap();
+        let inp = alloc_input(cs.clone(), tr, i);
+        build_constraints(cs.clone(), i, &inp, tr, &rcs).unwrap();
         assert!(cs.is_satisfied().unwrap());
     }
-    Ok(())
 }
 
 fn ark_check_steps(k: usize) {
@@ -74,3 +110,62 @@ fn ark_step() {
         ark_check_steps(k);
     }
 }
+
+#[test]
+fn ark_step_offline() {
+    let mut vm = loop_vm::<OfflineMemory>(5);
+    let (tr, audit) = trace_offline(&mut vm, 4, false).unwrap();
+    ark_check_trace(&tr);
+    assert!(check_trace(&tr).is_ok());
+
+    let root_in = *tr.input(0).unwrap().last().unwrap();
+    let root_out = tr
+        .blocks
+        .last()
+        .unwrap()
+        .iter()
+        .last()
+        .unwrap()
+        .write_proof
+        .commit();
+    audit
+        .verify(&loop_vm::<OfflineMemory>(5).mem, root_in, root_out)
+        .unwrap();
+
+    // an unbound trace does not satisfy the circuit
+    let tr = trace(&mut loop_vm::<OfflineMemory>(5), 4, false).unwrap();
+    assert!(check_trace(&tr).is_err());
+}
+
+// the first step of `tr` which does not satisfy the step circuit
+pub(super) fn step_mismatch(tr: &Trace<impl MemoryProof>) -> StepMismatch {
+    match check_trace(tr) {
+        Err(CircuitMismatch::Step(m)) => m,
+        r => panic!("unexpected result {r:?}"),
+    }
+}
+
+#[test]
+fn check_trace_mismatch() {
+    let mut vm = loop_vm::<MerkleTrie>(5);
+    let mut tr = trace(&mut vm, 4, false).unwrap();
+    assert!(check_trace(&tr).is_ok());
+
+    // corrupt the result of the second step of the second block
+    let mut bad = tr.clone();
+    bad.blocks[1].steps[1].Z ^= 1;
+    let err = step_mismatch(&bad);
+    assert_eq!(err.block, 1);
+    assert_eq!(err.step, 1);
+    assert!(!err.violations.is_empty());
+    assert!(!err.memory);
+
+    // corrupt the instruction memory proof of the first step of the
+    // second block, which only the memory constraints depend on
+    tr.blocks[1].steps[0].pc_proof.auth[0].1 += F::from(1u64);
+    let err = step_mismatch(&tr);
+    assert_eq!(err.block, 1);
+    assert_eq!(err.step, 0);
+    assert!(err.violations.is_empty());
+    assert!(err.memory);
+}
@@ -0,0 +1,221 @@
+//! Runner for the RISC-V architectural compliance tests.
+//!
+//! Compliance tests are ELF files built from the riscv-arch-test
+//! suite using the Nexus target model in `vm/compliance`. Each test
+//! writes its results to a signature region, delimited by the symbols
+//! `begin_signature` and `end_signature`, and halts by executing
+//! `unimp`. The signature is then compared word by word with the
+//! reference output of the test.
+//!
+//! Tests are discovered in a directory holding pairs of files named
+//! `<test>.elf` and `<test>.reference_output`. A set of RV32I tests is
+//! vendored in `vm/compliance/rv32i`, see [`VENDORED_TESTS`].
+
+use std::fs::{read, read_dir, read_to_string};
+use std::io::{Error as IOError, ErrorKind};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::{NexusVMError::*, Result},
+    eval::{eval_inst, NexusVM},
+    init_vm,
+    memory::Memory,
+    parse_elf_bytes,
+    rv32::RV32,
+};
+
+/// Default limit on the number of instructions executed by a test.
+pub const MAX_STEPS: usize = 1 << 22;
+
+/// Directory of the RV32I compliance tests vendored with this crate.
+pub const VENDORED_TESTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/compliance/rv32i");
+
+/// Directory of the compliance tests to run: the value of
+/// `NEXUS_RISCV_TESTS` if set, and [`VENDORED_TESTS`] otherwise.
+pub fn tests_dir() -> PathBuf {
+    std::env::var_os("NEXUS_RISCV_TESTS").map_or_else(|| VENDORED_TESTS.into(), PathBuf::from)
+}
+
+/// A compliance test and its reference output.
+#[derive(Clone, Debug)]
+pub struct ComplianceTest {
+    /// Name of the test.
+    pub name: String,
+    /// Path to the test ELF file.
+    pub elf: PathBuf,
+    /// Path to the reference signature.
+    pub reference: PathBuf,
+}
+
+/// Find all compliance tests in `dir`, sorted by name. ELF files
+/// without a reference output are ignored.
+pub fn find_tests(dir: &Path) -> Result<Vec<ComplianceTest>> {
+    let mut tests = Vec::new();
+    for entry in read_dir(dir)? {
+        let elf = entry?.path();
+        if elf.extension().and_then(|e| e.to_str()) != Some("elf") {
+            continue;
+        }
+        let reference = elf.with_extension("reference_output");
+        if !reference.exists() {
+            continue;
+        }
+        let name = elf.file_stem().unwrap().to_string_lossy().to_string();
+        tests.push(ComplianceTest { name, elf, reference });
+    }
+    tests.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tests)
+}
+
+/// Read a reference signature, which contains one hexadecimal word
+/// per line.
+pub fn read_reference(path: &Path) -> Result<Vec<u32>> {
+    read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            u32::from_str_radix(l, 16).map_err(|e| IOError::new(ErrorKind::InvalidData, e).into())
+        })
+        .collect()
+}
+
+/// Load a compliance test, returning the initial machine state and
+/// the address range of the signature.
+pub fn load_test<M: Memory>(path: &Path) -> Result<(NexusVM<M>, Range<u32>)> {
+    let bytes = read(path)?;
+    let elf = parse_elf_bytes(&bytes)?;
+
+    let lookup = |name: &'static str| -> Result<u32> {
+        let (syms, strs) = elf.symbol_table()?.ok_or(MissingSymbol(name))?;
+        for sym in syms.iter() {
+            if strs.get(sym.st_name as usize)? == name {
+                return Ok(sym.st_value as u32);
+            }
+        }
+        Err(MissingSymbol(name))
+    };
+    let sig = lookup("begin_signature")?..lookup("end_signature")?;
+
+    let vm = init_vm(&elf, &bytes)?;
+    Ok((vm, sig))
+}
+
+/// Run `vm` until it halts, executing at most `max_steps`
+/// instructions, and return the words of the signature region.
+pub fn signature<M: Memory>(
+    vm: &mut NexusVM<M>,
+    sig: Range<u32>,
+    max_steps: usize,
+) -> Result<Vec<u32>> {
+    let mut halted = false;
+    for _ in 0..max_steps {
+        eval_inst(vm)?;
+        if vm.inst.inst == RV32::UNIMP {
+            halted = true;
+            break;
+        }
+    }
+    if !halted {
+        return Err(StepLimit(max_steps));
+    }
+
+    sig.step_by(4)
+        .map(|addr| vm.mem.peek(addr)?.lw(addr))
+        .collect()
+}
+
+/// Compare a signature with its reference output.
+pub fn compare(reference: &[u32], sig: &[u32]) -> Result<()> {
+    // the signature region may be padded beyond the reference
+    if sig.len() < reference.len() {
+        return Err(SignatureLength(reference.len(), sig.len()));
+    }
+    for (i, (r, s)) in reference.iter().zip(sig).enumerate() {
+        if r != s {
+            return Err(SignatureMismatch(i, *r, *s));
+        }
+    }
+    Ok(())
+}
+
+/// Run a compliance test using memory `M`, and check its signature
+/// against the reference output.
+pub fn run_test<M: Memory>(test: &ComplianceTest) -> Result<()> {
+    let (mut vm, sig) = load_test::<M>(&test.elf)?;
+    let sig = signature(&mut vm, sig, MAX_STEPS)?;
+    compare(&read_reference(&test.reference)?, &sig)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        machines::{loop_vm, nop_vm},
+        memory::{paged::Paged, trie::MerkleTrie},
+    };
+
+    #[test]
+    fn compliance_signature() {
+        let mut vm = nop_vm::<Paged>(4);
+        vm.init_memory(0x100, &[1, 0, 0, 0, 2, 0, 0, 0]).unwrap();
+        let sig = signature(&mut vm, 0x100..0x10c, 100).unwrap();
+        assert_eq!(sig, [1, 2, 0]);
+
+        assert!(compare(&[1, 2], &sig).is_ok());
+        assert!(matches!(
+            compare(&[1, 3], &sig),
+            Err(SignatureMismatch(1, 3, 2))
+        ));
+        assert!(matches!(
+            compare(&[1, 2, 0, 0], &sig),
+            Err(SignatureLength(4, 3))
+        ));
+    }
+
+    #[test]
+    fn compliance_step_limit() {
+        let mut vm = loop_vm::<Paged>(100);
+        let res = signature(&mut vm, 0..0, 10);
+        assert!(matches!(res, Err(StepLimit(10))));
+    }
+
+    // Run the compliance tests of `tests_dir` using memory `M`.
+    fn run_suite<M: Memory>() {
+        let dir = tests_dir();
+        let tests = find_tests(&dir).unwrap();
+        assert!(
+            !tests.is_empty(),
+            "no compliance tests in {}",
+            dir.display()
+        );
+
+        let mut failed = Vec::new();
+        for t in &tests {
+            match run_test::<M>(t) {
+                Ok(()) => println!("{}: ok", t.name),
+                Err(e) => {
+                    println!("{}: {e}", t.name);
+                    failed.push(t.name.clone());
+                }
+            }
+        }
+        assert!(failed.is_empty(), "failed: {failed:?}");
+    }
+
+    // Run the vendored compliance tests, or those found in the
+    // directory given by NEXUS_RISCV_TESTS.
+    #[test]
+    fn compliance_suite() {
+        run_suite::<Paged>();
+    }
+
+    // Loading a test into the Merkle trie hashes each byte of the
+    // ELF, which takes minutes for the vendored tests.
+    #[test]
+    #[ignore]
+    fn compliance_suite_trie() {
+        run_suite::<MerkleTrie>();
+    }
+}
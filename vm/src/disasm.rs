@@ -0,0 +1,351 @@
+//! Disassembly of ELF files.
+//!
+//! The listing produced here shows the segments of an ELF file as
+//! they are loaded by `init_vm`, and the instructions of executable
+//! segments as decoded by the VM. Instructions which the VM cannot
+//! execute, such as compressed, multiply/divide or CSR instructions,
+//! are flagged so they can be found before executing or proving a
+//! program.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+use elf::{
+    abi::{PF_R, PF_W, PF_X, PT_LOAD, STT_FUNC, STT_NOTYPE},
+    endian::LittleEndian,
+    ElfBytes,
+};
+
+use crate::{
+    error::{NexusVMError::ELFFormat, Result},
+    parse_elf_bytes,
+    rv32::{parse::*, Inst, RV32},
+};
+
+/// Reasons an instruction cannot be executed by the VM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unsupported {
+    /// Compressed (RVC) instruction
+    Compressed,
+    /// Multiply or divide (RV32M) instruction
+    MulDiv(&'static str),
+    /// Atomic (RV32A) instruction
+    Atomic,
+    /// Floating-point instruction
+    Float,
+    /// Control and status register access
+    Csr,
+    /// Any other invalid instruction
+    Invalid,
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compressed => write!(f, "compressed instruction"),
+            Self::MulDiv(name) => write!(f, "RV32M instruction {name}"),
+            Self::Atomic => write!(f, "atomic instruction"),
+            Self::Float => write!(f, "floating-point instruction"),
+            Self::Csr => write!(f, "CSR access"),
+            Self::Invalid => write!(f, "invalid instruction"),
+        }
+    }
+}
+
+/// Classify instruction words from common RISC-V extensions which are
+/// not supported by the VM. Note that the decoder alone does not
+/// reject all of these: RV32M instructions share their opcode with
+/// the ALU instructions and would otherwise decode as RV32I.
+pub fn unsupported(word: u32) -> Option<Unsupported> {
+    const MULDIV: [&str; 8] = [
+        "mul", "mulh", "mulhsu", "mulhu", "div", "divu", "rem", "remu",
+    ];
+
+    if word & 3 != 3 {
+        return Some(Unsupported::Compressed);
+    }
+    let res = match opcode(word) {
+        OPC_ALU if funct7(word) == 1 => Unsupported::MulDiv(MULDIV[funct3(word) as usize]),
+        0b010_1111 => Unsupported::Atomic,
+        0b000_0111 | 0b010_0111 | 0b100_0011 | 0b100_0111 | 0b100_1011 | 0b100_1111
+        | 0b101_0011 => Unsupported::Float,
+        // UNIMP is encoded as a CSR write, and is supported
+        OPC_ECALL if funct3(word) != 0 && word != 0xc0001073 => Unsupported::Csr,
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// A loadable segment of an ELF file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// offset of segment in file
+    pub offset: u32,
+    /// address the segment is loaded at
+    pub vaddr: u32,
+    /// number of bytes loaded from the file
+    pub filesz: u32,
+    /// size of the segment in memory
+    pub memsz: u32,
+    /// segment permission flags
+    pub flags: u32,
+}
+
+impl Segment {
+    /// Returns true if the segment is executable.
+    pub fn is_exec(&self) -> bool {
+        self.flags & PF_X != 0
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let flag = |b, c| if self.flags & b != 0 { c } else { '-' };
+        write!(
+            f,
+            "{:08x}  {:08x}  {:08x}  {:08x}  {}{}{}",
+            self.offset,
+            self.vaddr,
+            self.filesz,
+            self.memsz,
+            flag(PF_R, 'r'),
+            flag(PF_W, 'w'),
+            flag(PF_X, 'x'),
+        )
+    }
+}
+
+/// A disassembled instruction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    /// address of instruction
+    pub pc: u32,
+    /// length of instruction in bytes
+    pub len: u32,
+    /// instruction word, as stored in memory
+    pub word: u32,
+    /// the decoded instruction, or the reason it is not supported
+    pub inst: std::result::Result<RV32, Unsupported>,
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.inst {
+            Ok(inst) => write!(
+                f,
+                "{}",
+                Inst {
+                    pc: self.pc,
+                    len: self.len,
+                    word: self.word,
+                    inst
+                }
+            ),
+            Err(Unsupported::Compressed) => {
+                write!(
+                    f,
+                    "{:07x}     {:04x} <{}>",
+                    self.pc,
+                    self.word,
+                    Unsupported::Compressed
+                )
+            }
+            Err(u) => write!(f, "{:07x} {:08x} <{u}>", self.pc, self.word),
+        }
+    }
+}
+
+/// Decode the instruction at the start of `bytes`, loaded at address
+/// `pc`. Returns `None` if `bytes` does not hold a complete instruction.
+pub fn decode(pc: u32, bytes: &[u8]) -> Option<Line> {
+    if bytes.len() < 2 {
+        return None;
+    }
+    let half = u16::from_le_bytes([bytes[0], bytes[1]]) as u32;
+    if half & 3 != 3 {
+        let inst = Err(Unsupported::Compressed);
+        return Some(Line { pc, len: 2, word: half, inst });
+    }
+    let word = u32::from_le_bytes(bytes.get(..4)?.try_into().unwrap());
+    let inst = match unsupported(word) {
+        Some(u) => Err(u),
+        None => parse_inst(pc, bytes)
+            .map(|inst| inst.inst)
+            .map_err(|_| Unsupported::Invalid),
+    };
+    Some(Line { pc, len: 4, word, inst })
+}
+
+/// Disassemble the instructions in `bytes`, loaded at address `pc`.
+/// Trailing bytes which do not form a complete instruction, or which
+/// lie beyond the end of the address space, are ignored.
+pub fn disassemble_bytes(pc: u32, bytes: &[u8]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut i = 0;
+    while let Some(addr) = u32::try_from(i).ok().and_then(|i| pc.checked_add(i)) {
+        let Some(line) = decode(addr, &bytes[i..]) else {
+            break;
+        };
+        if addr.checked_add(line.len - 1).is_none() {
+            break;
+        }
+        i += line.len as usize;
+        lines.push(line);
+    }
+    lines
+}
+
+/// Returns the loadable segments of an ELF file, in file order.
+pub fn segments(elf: &ElfBytes<LittleEndian>) -> Vec<Segment> {
+    let Some(phdrs) = elf.segments() else {
+        return Vec::new();
+    };
+    phdrs
+        .iter()
+        .filter(|p| p.p_type == PT_LOAD)
+        .map(|p| Segment {
+            offset: p.p_offset as u32,
+            vaddr: p.p_vaddr as u32,
+            filesz: p.p_filesz as u32,
+            memsz: p.p_memsz as u32,
+            flags: p.p_flags,
+        })
+        .collect()
+}
+
+/// Disassembly of an ELF file.
+#[derive(Clone, Debug, Default)]
+pub struct Listing {
+    /// program entry point
+    pub entry: u32,
+    /// loadable segments, in file order
+    pub segments: Vec<Segment>,
+    /// symbols of the program, by address
+    pub symbols: BTreeMap<u32, String>,
+    /// instructions of the executable segments
+    pub lines: Vec<Line>,
+}
+
+impl Listing {
+    /// Returns the instructions which are not supported by the VM.
+    pub fn unsupported(&self) -> impl Iterator<Item = &Line> {
+        self.lines.iter().filter(|l| l.inst.is_err())
+    }
+}
+
+impl Display for Listing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entry point: {:08x}", self.entry)?;
+        writeln!(f)?;
+        writeln!(f, "segments:")?;
+        writeln!(f, "  offset    vaddr     filesz    memsz     flags")?;
+        for s in &self.segments {
+            writeln!(f, "  {s}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "disassembly:")?;
+        for l in &self.lines {
+            if let Some(name) = self.symbols.get(&l.pc) {
+                writeln!(f)?;
+                writeln!(f, "{:07x} <{name}>:", l.pc)?;
+            }
+            writeln!(f, "  {l}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Disassemble the executable segments of an ELF file.
+pub fn disassemble(bytes: &[u8]) -> Result<Listing> {
+    let elf = parse_elf_bytes(bytes)?;
+    let mut listing = Listing {
+        entry: elf.ehdr.e_entry as u32,
+        segments: segments(&elf),
+        ..Listing::default()
+    };
+
+    // prefer function names over other symbols at the same address
+    if let Some((syms, strs)) = elf.symbol_table()? {
+        for sym in syms.iter() {
+            let ty = sym.st_symtype();
+            if ty != STT_FUNC && ty != STT_NOTYPE {
+                continue;
+            }
+            let name = strs.get(sym.st_name as usize)?;
+            if name.is_empty() || name.starts_with('$') || name.starts_with(".L") {
+                continue;
+            }
+            let addr = sym.st_value as u32;
+            if ty == STT_FUNC || !listing.symbols.contains_key(&addr) {
+                listing.symbols.insert(addr, name.to_string());
+            }
+        }
+    }
+
+    for s in listing.segments.iter().filter(|s| s.is_exec()) {
+        let start = s.offset as usize;
+        let end = start + s.filesz as usize;
+        let seg = bytes
+            .get(start..end)
+            .ok_or(ELFFormat("segment outside of file"))?;
+        let lines = disassemble_bytes(s.vaddr, seg);
+        listing.lines.extend(lines);
+    }
+
+    Ok(listing)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rv32::{asm::assemble, *};
+
+    #[test]
+    fn test_unsupported() {
+        // mul, divu, rem x1, x2, x3
+        assert_eq!(unsupported(0x023100b3), Some(Unsupported::MulDiv("mul")));
+        assert_eq!(unsupported(0x023150b3), Some(Unsupported::MulDiv("divu")));
+        assert_eq!(unsupported(0x023160b3), Some(Unsupported::MulDiv("rem")));
+        // csrr x1, cycle
+        assert_eq!(unsupported(0xc00020f3), Some(Unsupported::Csr));
+        // amoadd.w x1, x3, (x2)
+        assert_eq!(unsupported(0x003120af), Some(Unsupported::Atomic));
+        // flw f1, 0(x2)
+        assert_eq!(unsupported(0x00012087), Some(Unsupported::Float));
+        // c.nop
+        assert_eq!(unsupported(0x0001), Some(Unsupported::Compressed));
+
+        let words = assemble("add x1, x2, x3\nsub x1, x2, x3\necall\nebreak\nunimp").unwrap();
+        for w in words {
+            assert_eq!(unsupported(w), None);
+        }
+    }
+
+    #[test]
+    fn test_disassemble_bytes() {
+        let mut bytes = Vec::new();
+        for w in assemble("addi x1, x0, 5\nnop").unwrap() {
+            bytes.extend(w.to_le_bytes());
+        }
+        bytes.extend(0x0001u16.to_le_bytes()); // c.nop
+        bytes.extend(0x023100b3u32.to_le_bytes()); // mul x1, x2, x3
+        bytes.extend(0xffffffffu32.to_le_bytes()); // invalid
+        bytes.extend([0x13, 0x00]); // partial
+
+        let lines = disassemble_bytes(0x1000, &bytes);
+        let pcs: Vec<u32> = lines.iter().map(|l| l.pc).collect();
+        assert_eq!(pcs, [0x1000, 0x1004, 0x1008, 0x100a, 0x100e]);
+
+        assert_eq!(lines[0].inst, Ok(ALUI { aop: ADD, rd: 1, rs1: 0, imm: 5 }));
+        assert_eq!(lines[1].inst, Ok(ALUI { aop: ADD, rd: 0, rs1: 0, imm: 0 }));
+        assert_eq!(lines[2].inst, Err(Unsupported::Compressed));
+        assert_eq!(lines[2].len, 2);
+        assert_eq!(lines[3].inst, Err(Unsupported::MulDiv("mul")));
+        assert_eq!(lines[4].inst, Err(Unsupported::Invalid));
+
+        // decoding stops at the end of the address space
+        let lines = disassemble_bytes(u32::MAX - 5, &bytes);
+        let pcs: Vec<u32> = lines.iter().map(|l| l.pc).collect();
+        assert_eq!(pcs, [u32::MAX - 5]);
+    }
+}
@@ -15,14 +15,62 @@ pub enum NexusVMError {
     #[error("invalid instruction {1:x} at pc:{0:x}")]
     InvalidInstruction(u32, u32),
 
+    /// Error in assembly source
+    #[error("assembly error at line {0}: {1}")]
+    AsmError(usize, String),
+
     /// Unknown ECALL number
     #[error("unknown ecall {1} at pc:{0:x}")]
     UnknownECall(u32, u32),
 
+    /// Program read past the end of an input stream
+    #[error("input stream {1} exhausted at pc:{0:x}")]
+    InputExhausted(u32, u32),
+
+    /// Program passed more values to an ecall than are supported
+    #[error("ecall argument of length {1} too long at pc:{0:x}")]
+    ArgumentTooLong(u32, u32),
+
+    /// Hint requested by the program was not registered
+    #[error("unknown hint {1} at pc:{0:x}")]
+    UnknownHint(u32, u32),
+
+    /// Program read more hint words than were returned by the hint
+    #[error("no hint output left at pc:{0:x}")]
+    HintExhausted(u32),
+
+    /// Modular arithmetic with a zero modulus
+    #[error("zero modulus at pc:{0:x}")]
+    ZeroModulus(u32),
+
+    /// Program called a precompile which was not selected
+    #[error("ecall {1} at pc:{0:x} calls a precompile which was not selected")]
+    PrecompileNotSelected(u32, u32),
+
+    /// Value passed as a field element is not less than the field modulus
+    #[error("invalid field element at pc:{0:x}")]
+    InvalidFieldElement(u32),
+
+    /// Operands of an elliptic curve precompile for which the operation is not defined
+    #[error("invalid elliptic curve operands at pc:{0:x}")]
+    InvalidCurvePoint(u32),
+
+    /// End of a profiled region which was not started
+    #[error("end of profiled region {0} which was not started")]
+    UnmatchedProfileEnd(String),
+
+    /// The program panicked
+    #[error("program panicked: {0}")]
+    GuestPanic(String),
+
     /// An I/O error occurred
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 
+    /// Too many program arguments
+    #[error("at most 3 program arguments are supported, found {0}")]
+    TooManyArgs(usize),
+
     /// Unknown (test) machine
     #[error("unknown machine {0}")]
     UnknownMachine(String),
@@ -35,10 +83,42 @@ pub enum NexusVMError {
     #[error("ELF format not supported: {0}")]
     ELFFormat(&'static str),
 
+    /// ELF file cannot be executed by the VM
+    #[error("invalid ELF file: {0}")]
+    InvalidELF(#[from] crate::validate::ValidationError),
+
     /// Invalid memory alignment
     #[error("misaligned memory access {0:x}")]
     Misaligned(u32),
 
+    /// Memory accesses failed offline memory checking
+    #[error("memory audit failed: {0}")]
+    AuditFailed(#[from] crate::memory::offline::AuditError),
+
+    /// Memory access outside of the address space
+    #[error("memory access out of range {0:x}")]
+    InvalidAddress(u32),
+
+    /// Address width not supported by the memory controllers
+    #[error("address width of {0} bits not supported, expected {min} to {max} bits", min = crate::memory::cacheline::CACHE_BITS + 1, max = crate::memory::cacheline::ADDR_BITS)]
+    InvalidAddrBits(usize),
+
+    /// Symbol not found in ELF file
+    #[error("symbol {0} not found")]
+    MissingSymbol(&'static str),
+
+    /// Program did not halt within the step limit
+    #[error("program did not halt within {0} steps")]
+    StepLimit(usize),
+
+    /// Signature length differs from reference output
+    #[error("signature has {1} words, expected {0}")]
+    SignatureLength(usize, usize),
+
+    /// Signature word differs from reference output
+    #[error("signature mismatch at word {0}: expected {1:08x}, found {2:08x}")]
+    SignatureMismatch(usize, u32, u32),
+
     /// An error occured while hashing
     #[error("error hashing {0}")]
     HashError(String),
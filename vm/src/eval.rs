@@ -4,7 +4,7 @@ use crate::{
     error::*,
     memory::Memory,
     rv32::{parse::*, *},
-    syscalls::Syscalls,
+    syscalls::{Syscalls, PRECOMPILE_REGS},
 };
 
 use std::collections::HashSet;
@@ -12,6 +12,9 @@ use std::collections::HashSet;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
 
+/// Maximum number of program arguments, see `NexusVM::set_args`.
+pub const MAX_ARGS: usize = 3;
+
 /// virtual machine state
 #[derive(Default)]
 pub struct NexusVM<M: Memory> {
@@ -21,8 +24,12 @@ pub struct NexusVM<M: Memory> {
     pub syscalls: Syscalls,
     /// current instruction
     pub inst: Inst,
+    /// number of instructions executed
+    pub cycles: u64,
     /// internal result register
     pub Z: u32,
+    /// Values of `PRECOMPILE_REGS` after an ecall which wrote any of them
+    pub precompile_regs: Option<[u32; 16]>,
     /// used instruction sets
     pub instruction_sets: HashSet<InstructionSet>,
     /// Machine memory.
@@ -55,10 +62,16 @@ pub struct Regs {
 
 impl<M: Memory> NexusVM<M> {
     pub fn new(pc: u32) -> Self {
+        Self::new_with_memory(pc, M::default())
+    }
+
+    /// Create a new VM using the given memory.
+    pub fn new_with_memory(pc: u32, mem: M) -> Self {
         let mut vm = Self::default();
 
         vm.regs.pc = pc;
         vm.instruction_sets = HashSet::new();
+        vm.mem = mem;
 
         vm
     }
@@ -79,6 +92,17 @@ impl<M: Memory> NexusVM<M> {
         }
     }
 
+    /// set the program arguments, passed to the entry point in a0-a2
+    pub fn set_args(&mut self, args: &[u32]) -> Result<()> {
+        if args.len() > MAX_ARGS {
+            return Err(NexusVMError::TooManyArgs(args.len()));
+        }
+        for (i, a) in args.iter().enumerate() {
+            self.set_reg(10 + i as u32, *a);
+        }
+        Ok(())
+    }
+
     /// initialize memory from slice
     pub fn init_memory(&mut self, addr: u32, bytes: &[u8]) -> Result<()> {
         // slow, but simple
@@ -182,6 +206,7 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
 
     // initialize micro-architecture state
     vm.Z = 0;
+    vm.precompile_regs = None;
     let mut RD = 0u32;
     let mut PC = 0;
 
@@ -209,7 +234,7 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
             let X = vm.get_reg(rs1);
             RD = rd;
             vm.Z = add32(vm.regs.pc, 4);
-            PC = add32(X, imm);
+            PC = add32(X, imm) & !1;
         }
         BR { bop, rs1, rs2, imm } => {
             let X = vm.get_reg(rs1);
@@ -255,10 +280,14 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
             vm.Z = alu_op(aop, X, Y);
         }
         FENCE => {}
-        EBREAK { .. } => {}
+        EBREAK { rd } => {
+            RD = rd;
+        }
         ECALL { rd } => {
             RD = rd;
-            vm.Z = vm.syscalls.syscall(vm.regs.pc, vm.regs.x, &vm.mem)?;
+            vm.Z = vm
+                .syscalls
+                .syscall(vm.regs.pc, vm.cycles, vm.regs.x, &vm.mem)?;
         }
         UNIMP => {
             PC = vm.inst.pc;
@@ -269,6 +298,16 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
         PC = add32(vm.inst.pc, vm.inst.len);
     }
     vm.set_reg(RD, vm.Z);
+    if let ECALL { .. } = vm.inst.inst {
+        let writes = vm.syscalls.take_reg_writes();
+        if !writes.is_empty() {
+            for (r, x) in writes {
+                vm.set_reg(r, x);
+            }
+            vm.precompile_regs = Some(PRECOMPILE_REGS.map(|r| vm.regs.x[r]));
+        }
+    }
     vm.regs.pc = PC;
+    vm.cycles += 1;
     Ok(())
 }
@@ -3,13 +3,17 @@
 #![allow(non_snake_case)]
 #![allow(clippy::field_reassign_with_default)]
 
+pub mod compliance;
+pub mod disasm;
 pub mod error;
 pub mod eval;
 pub mod machines;
+pub mod precompiles;
 pub mod rv32;
 
 pub mod syscalls;
 pub mod trace;
+pub mod validate;
 
 mod ark_serde;
 pub mod memory;
@@ -42,6 +46,14 @@ pub fn load_elf<M: Memory>(path: &PathBuf) -> Result<NexusVM<M>> {
     parse_elf(slice)
 }
 
+/// Load a VM state from an ELF file, using the given memory
+pub fn load_elf_with_memory<M: Memory>(path: &PathBuf, mem: M) -> Result<NexusVM<M>> {
+    let file_data = read(path)?;
+    let slice = file_data.as_slice();
+    let file = parse_elf_bytes(slice)?;
+    init_vm_with_memory(&file, slice, mem)
+}
+
 #[doc(hidden)]
 pub fn parse_elf_bytes(bytes: &[u8]) -> Result<ElfBytes<LittleEndian>> {
     let file = ElfBytes::<LittleEndian>::minimal_parse(bytes)?;
@@ -50,19 +62,35 @@ pub fn parse_elf_bytes(bytes: &[u8]) -> Result<ElfBytes<LittleEndian>> {
 
 #[doc(hidden)]
 pub fn init_vm<M: Memory>(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<NexusVM<M>> {
+    init_vm_with_memory(elf, data, M::default())
+}
+
+#[doc(hidden)]
+pub fn init_vm_with_memory<M: Memory>(
+    elf: &ElfBytes<LittleEndian>,
+    data: &[u8],
+    mem: M,
+) -> Result<NexusVM<M>> {
     let e_entry = elf.ehdr.e_entry as u32;
 
     let load_phdrs = elf
         .segments()
-        .unwrap()
+        .ok_or(NexusVMError::ELFFormat("no program headers"))?
         .iter()
         .filter(|phdr| phdr.p_type == PT_LOAD);
 
-    let mut vm = NexusVM::new(e_entry);
+    let mut vm = NexusVM::new_with_memory(e_entry, mem);
     for p in load_phdrs {
-        let s = p.p_offset as usize;
-        let e = (p.p_offset + p.p_filesz) as usize;
-        let bytes = &data[s..e];
+        let bytes = p
+            .p_offset
+            .checked_add(p.p_filesz)
+            .and_then(|e| {
+                let s = usize::try_from(p.p_offset).ok()?;
+                data.get(s..usize::try_from(e).ok()?)
+            })
+            .ok_or(NexusVMError::ELFFormat(
+                "segment extends beyond end of file",
+            ))?;
         vm.init_memory(p.p_vaddr as u32, bytes)?;
     }
     Ok(vm)
@@ -88,6 +116,22 @@ pub struct VMOpts {
     /// Input file, RISC-V 32i ELF
     #[arg(group = "vm", required = true)]
     pub file: Option<std::path::PathBuf>,
+
+    /// Input file, RISC-V 32i assembly
+    #[arg(group = "vm", long)]
+    pub asm: Option<std::path::PathBuf>,
+
+    /// Number of bits of addressable memory; defaults to the full 32-bit address space
+    #[arg(long, value_parser = memory::parse_addr_bits)]
+    pub addr_bits: Option<usize>,
+
+    /// Program arguments, passed to the entry point in a0-a2
+    #[arg(long, value_delimiter = ',')]
+    pub args: Vec<u32>,
+
+    /// Precompiles the program may call, as a comma-separated list, or none
+    #[arg(long, default_value = "none")]
+    pub precompiles: precompiles::Precompiles,
 }
 
 fn list_machines() -> String {
@@ -101,15 +145,24 @@ fn list_machines() -> String {
 
 /// Load the VM described by `opts`
 pub fn load_vm<M: Memory>(opts: &VMOpts) -> Result<NexusVM<M>> {
-    if let Some(m) = &opts.machine {
-        if let Some(vm) = machines::lookup_test_machine(m) {
-            Ok(vm)
+    let mut vm = if let Some(m) = &opts.machine {
+        machines::lookup_test_machine(m).ok_or_else(|| NexusVMError::UnknownMachine(m.clone()))?
+    } else {
+        let mem = match opts.addr_bits {
+            Some(bits) => M::with_addr_bits(bits)?,
+            None => M::default(),
+        };
+        if let Some(path) = &opts.asm {
+            let src = std::fs::read_to_string(path)?;
+            machines::asm_vm_with_memory(&src, mem)?
         } else {
-            Err(NexusVMError::UnknownMachine(m.clone()))
+            load_elf_with_memory(opts.file.as_ref().unwrap(), mem)?
         }
-    } else {
-        load_elf(opts.file.as_ref().unwrap())
-    }
+    };
+    // the argument registers are part of the initial state of the proof
+    vm.set_args(&opts.args)?;
+    vm.syscalls.set_precompiles(opts.precompiles);
+    Ok(vm)
 }
 
 /// Evaluate a program starting from a given machine state
@@ -150,6 +203,30 @@ pub fn eval(vm: &mut NexusVM<impl Memory>, show: bool) -> Result<()> {
         table("x", &vm.regs.x);
 
         println!("Executed {count} instructions in {:?}", t.elapsed());
+        if let Some(h) = vm.syscalls.heap_stats() {
+            println!(
+                "Heap: {} bytes allocated ({} peak) in {} bytes of heap; {} allocations, {} frees",
+                h.allocated, h.peak, h.heap_size, h.allocs, h.frees
+            );
+        }
+
+        let profile = vm.syscalls.profile();
+        if !profile.is_empty() {
+            println!("\nProfile:");
+            println!("  {:32} {:>8} {:>12}", "region", "count", "cycles");
+            for r in profile {
+                println!("  {:32} {:>8} {:>12}", r.name, r.count, r.cycles);
+            }
+        }
+    }
+
+    if let Some(msg) = vm.syscalls.panic_message() {
+        let msg = if msg.is_empty() {
+            "no message; enable the `panic-message` feature of nexus-rt".to_string()
+        } else {
+            msg
+        };
+        return Err(NexusVMError::GuestPanic(msg));
     }
     Ok(())
 }
@@ -186,3 +263,37 @@ pub fn trace_vm<M: Memory>(
 
     Ok(trace)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memory::trie::MerkleTrie;
+
+    /// Guest linked with the startup code of the runtime, whose `main`
+    /// returns `a0 | a1 << 8 | a2 << 16`; see `runtime/testdata/args`.
+    const ARGS_GUEST: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../runtime/testdata/args/args.elf"
+    );
+
+    #[test]
+    fn runtime_args() {
+        let run = |args: Vec<u32>| {
+            let opts = VMOpts {
+                k: 1,
+                machine: None,
+                file: Some(PathBuf::from(ARGS_GUEST)),
+                addr_bits: None,
+                asm: None,
+                args,
+                precompiles: Default::default(),
+            };
+            let mut vm: NexusVM<MerkleTrie> = load_vm(&opts).unwrap();
+            eval(&mut vm, false).unwrap();
+            vm.regs.x[10]
+        };
+        assert_eq!(run(vec![1, 2, 3]), 0x030201);
+        assert_eq!(run(vec![0xa, 0xb]), 0x0b0a);
+        assert_eq!(run(vec![]), 0);
+    }
+}
@@ -3,8 +3,11 @@
 #![allow(clippy::field_reassign_with_default)]
 #![allow(clippy::identity_op)]
 
-use super::{memory::Memory, rv32::SOP};
-use crate::{NexusVM, Regs};
+use super::{
+    memory::Memory,
+    rv32::{asm, SOP},
+};
+use crate::{error::Result, NexusVM, Regs};
 
 /// An array of test machines, useful for debugging and developemnt.
 #[allow(clippy::type_complexity)]
@@ -36,18 +39,37 @@ pub fn lookup_test_code(name: &str) -> Option<Vec<u32>> {
 }
 
 fn assemble<M: Memory>(words: &[u32]) -> NexusVM<M> {
-    let mut vm = NexusVM::<M>::new(0);
+    assemble_with_memory(words, M::default())
+}
+
+fn assemble_with_memory<M: Memory>(words: &[u32], mem: M) -> NexusVM<M> {
+    let mut vm = NexusVM::<M>::new_with_memory(0, mem);
     for (i, w) in words.iter().enumerate() {
         vm.mem.store(SOP::SW, i as u32 * 4, *w).unwrap();
     }
     vm
 }
 
+/// Create a VM running the assembly program `src`
+pub fn asm_vm<M: Memory>(src: &str) -> Result<NexusVM<M>> {
+    asm_vm_with_memory(src, M::default())
+}
+
+/// Create a VM running the assembly program `src`, using the given memory
+pub fn asm_vm_with_memory<M: Memory>(src: &str, mem: M) -> Result<NexusVM<M>> {
+    Ok(assemble_with_memory(&asm::assemble(src)?, mem))
+}
+
 /// Create a VM with k no-op instructions
 pub fn nop_vm<M: Memory>(k: usize) -> NexusVM<M> {
     assemble(&nop_code(k))
 }
 
+/// Create a VM with k no-op instructions, using the given memory
+pub fn nop_vm_with_memory<M: Memory>(k: usize, mem: M) -> NexusVM<M> {
+    assemble_with_memory(&nop_code(k), mem)
+}
+
 /// Create an instruction sequence with k no-op instructions
 pub fn nop_code(k: usize) -> Vec<u32> {
     let mut v = vec![0x13; k];
@@ -376,6 +398,7 @@ mod test {
     use super::*;
     use crate::eval;
     use crate::trie::MerkleTrie;
+    use crate::NexusVMError;
 
     #[test]
     fn test_machines() {
@@ -387,4 +410,67 @@ mod test {
             assert_eq!(regs, vm.regs);
         }
     }
+
+    const BITOP_ASM: &str = "
+        lui  x1, 0xaaaab
+        addi x1, x1, -86
+        lui  x2, 0x55555
+        addi x2, x2, 1365
+        and  x3, x1, x2
+        andi x4, x1, 255
+        or   x5, x1, x2
+        ori  x6, x1, 255
+        xor  x7, x1, x2
+        xori x8, x1, 255
+        unimp
+    ";
+
+    const JUMP_ASM: &str = "
+              jal   ra, l1
+        l0:   unimp
+        l1:   jal   ra, l2
+              unimp
+        l2:   jal   sp, l3
+              unimp
+        l3:   auipc ra, 0
+              jalr  gp, 12(ra)
+              unimp
+              j     l0
+              unimp
+    ";
+
+    const LDST_ASM: &str = "
+        li  t0, -1
+        sw  t0, 4(zero)
+        sb  t0, 1(zero)
+        sw  t0, -4(zero)
+        lw  ra, 4(zero)
+        lw  sp, -4(zero)
+        lb  gp, 1(zero)
+        lbu tp, 1(zero)
+        unimp
+    ";
+
+    #[test]
+    fn test_asm_machines() {
+        assert_eq!(asm::assemble(BITOP_ASM).unwrap(), bitop_code());
+        assert_eq!(asm::assemble(JUMP_ASM).unwrap(), jump_code());
+        assert_eq!(asm::assemble(LDST_ASM).unwrap(), ldst_code());
+
+        let mut vm: NexusVM<MerkleTrie> = asm_vm(LDST_ASM).unwrap();
+        eval(&mut vm, false).unwrap();
+        assert_eq!(ldst_result(), vm.regs);
+    }
+
+    #[test]
+    fn test_args() {
+        let src = "add a0, a0, a1\nsub a0, a0, a2\nunimp";
+        let mut vm: NexusVM<MerkleTrie> = asm_vm(src).unwrap();
+        vm.set_args(&[7, 5, 2]).unwrap();
+        eval(&mut vm, false).unwrap();
+        assert_eq!(vm.regs.x[10], 10);
+
+        let res = vm.set_args(&[1, 2, 3, 4]);
+        assert!(matches!(res, Err(NexusVMError::TooManyArgs(4))));
+    }
 }
@@ -1,6 +1,7 @@
 //! Virtual Machine Memory
 
 pub mod cacheline;
+pub mod offline;
 pub mod paged;
 pub mod path;
 pub mod trie;
@@ -10,14 +11,19 @@ use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use crate::circuit::F;
-use crate::error::Result;
+use crate::error::{
+    NexusVMError::{InvalidAddrBits, InvalidAddress},
+    Result,
+};
 use crate::rv32::{LOP, SOP};
-use cacheline::CacheLine;
+use cacheline::{CacheLine, ADDR_BITS, CACHE_BITS};
 
 /// A `MemoryProof` implementation provides the memory commitments and
 /// in-circuit proofs of the memory commitments. Each memory controller
 /// must provide proof values that implement this trait.
-pub trait MemoryProof: Default + Clone + CanonicalSerialize + CanonicalDeserialize {
+pub trait MemoryProof:
+    Default + Clone + CanonicalSerialize + CanonicalDeserialize + 'static
+{
     /// Type of in-circuit parameters needed, if any. For instance, hash parameters.
     type Params;
 
@@ -33,13 +39,77 @@ pub trait MemoryProof: Default + Clone + CanonicalSerialize + CanonicalDeseriali
         data: &[FpVar<F>],
     ) -> Result<(), SynthesisError>;
 
+    /// Generate in-circuit verification of the memory accesses of a
+    /// single step: the instruction fetch, the load and the store, in
+    /// that order. The cacheline data of each access is held in
+    /// consecutive pairs of `data`. The variables `is_read` and
+    /// `is_write` are one if the step performs a load (or store) and a
+    /// store respectively, and zero otherwise.
+    ///
+    /// By default, each proof is checked independently: the fetch
+    /// and load against `root_in`, and the store against `root_out`.
+    #[allow(clippy::too_many_arguments)]
+    fn step_circuit(
+        cs: ConstraintSystemRef<F>,
+        params: &Self::Params,
+        root_in: &FpVar<F>,
+        root_out: &FpVar<F>,
+        proofs: [&Self; 3],
+        data: &[FpVar<F>],
+        is_read: &FpVar<F>,
+        is_write: &FpVar<F>,
+    ) -> Result<(), SynthesisError> {
+        let _ = (is_read, is_write);
+        let [pc, read, write] = proofs;
+        pc.circuit(cs.clone(), params, root_in, &data[0..2])?;
+        read.circuit(cs.clone(), params, root_in, &data[2..4])?;
+        write.circuit(cs, params, root_out, &data[4..6])
+    }
+
     /// Return the memory commitment related to this proof.
     fn commit(&self) -> F;
 
+    /// Return the memory commitment prior to the access related to
+    /// this proof. This is the same as `commit` for controllers whose
+    /// commitment is not modified by reads.
+    fn commit_prior(&self) -> F {
+        self.commit()
+    }
+
+    /// Return a proof standing in for an access which did not take
+    /// place, immediately following the access related to this proof.
+    fn skip(&self) -> Self {
+        self.clone()
+    }
+
     /// Return the `CacheLine` data related to this proof.
     fn data(&self) -> [F; 2];
 }
 
+/// Check that `addr` lies within an address space of `addr_bits` bits.
+pub fn check_addr(addr_bits: usize, addr: u32) -> Result<()> {
+    if addr_bits < ADDR_BITS && addr >> addr_bits != 0 {
+        return Err(InvalidAddress(addr));
+    }
+    Ok(())
+}
+
+/// Check that an address space of `addr_bits` bits is supported: it
+/// must hold at least two cache lines, and at most `ADDR_BITS` bits.
+pub fn check_addr_bits(addr_bits: usize) -> Result<()> {
+    if addr_bits <= CACHE_BITS || addr_bits > ADDR_BITS {
+        return Err(InvalidAddrBits(addr_bits));
+    }
+    Ok(())
+}
+
+/// Parse a command line address width, see `check_addr_bits`.
+pub fn parse_addr_bits(s: &str) -> std::result::Result<usize, String> {
+    let bits = s.parse().map_err(|e| format!("{e}"))?;
+    check_addr_bits(bits).map_err(|e| e.to_string())?;
+    Ok(bits)
+}
+
 /// A `Memory` implementation is responsible for managing the machine's
 /// memory, and providing access to `CacheLine`s. Each implementation
 /// has associated commitment values and circuits for checking the
@@ -50,17 +120,29 @@ pub trait Memory: Default {
     /// this memory controller.
     type Proof: MemoryProof;
 
-    /// Query the cacheline at `addr`
-    fn query(&self, addr: u32) -> (&CacheLine, Self::Proof);
+    /// Create a memory with an address space of `addr_bits` bits.
+    /// Accesses outside of the address space fail with `InvalidAddress`.
+    /// The default memory has an address space of `ADDR_BITS` bits.
+    /// Fails with `InvalidAddrBits` if `addr_bits` is not supported, see
+    /// `check_addr_bits`.
+    fn with_addr_bits(addr_bits: usize) -> Result<Self>;
+
+    /// Query the cacheline at `addr`. This is a proven access, which
+    /// may update the commitment of controllers checking reads.
+    fn query(&mut self, addr: u32) -> Result<(&CacheLine, Self::Proof)>;
 
     /// Updatee the cacheline at `addr` using the function `f`.
     fn update<F>(&mut self, addr: u32, f: F) -> Result<Self::Proof>
     where
         F: Fn(&mut CacheLine) -> Result<()>;
 
+    /// Query the cacheline at `addr` for an access which is not part
+    /// of the proven computation, such as writing to the log.
+    fn peek(&self, addr: u32) -> Result<&CacheLine>;
+
     /// read instruction at address
-    fn read_inst(&self, addr: u32) -> Result<(u32, Self::Proof)> {
-        let (cl, path) = self.query(addr);
+    fn read_inst(&mut self, addr: u32) -> Result<(u32, Self::Proof)> {
+        let (cl, path) = self.query(addr)?;
         Ok((cl.lw(addr)?, path))
     }
 
@@ -71,8 +153,8 @@ pub trait Memory: Default {
     }
 
     /// perform load according to `lop`
-    fn load(&self, lop: LOP, addr: u32) -> Result<(u32, Self::Proof)> {
-        let (cl, path) = self.query(addr);
+    fn load(&mut self, lop: LOP, addr: u32) -> Result<(u32, Self::Proof)> {
+        let (cl, path) = self.query(addr)?;
         Ok((cl.load(lop, addr)?, path))
     }
 
@@ -84,7 +166,7 @@ pub trait Memory: Default {
 
 #[cfg(test)]
 mod test {
-    use super::{paged::Paged, trie::MerkleTrie, *};
+    use super::{offline::OfflineMemory, paged::Paged, trie::MerkleTrie, *};
     use crate::rv32::{LOP::*, SOP::*};
 
     #[test]
@@ -97,6 +179,50 @@ mod test {
         test_mem(Paged::default());
     }
 
+    #[test]
+    fn test_mem_offline() {
+        test_mem(OfflineMemory::default());
+    }
+
+    #[test]
+    fn test_addr_bits() {
+        test_addr_range(MerkleTrie::with_addr_bits(16).unwrap());
+        test_addr_range(Paged::with_addr_bits(16).unwrap());
+        test_addr_range(OfflineMemory::with_addr_bits(16).unwrap());
+
+        for bits in [0, CACHE_BITS, ADDR_BITS + 1, usize::MAX] {
+            assert!(matches!(check_addr_bits(bits), Err(InvalidAddrBits(b)) if b == bits));
+            assert!(matches!(
+                MerkleTrie::with_addr_bits(bits),
+                Err(InvalidAddrBits(_))
+            ));
+            assert!(matches!(
+                Paged::with_addr_bits(bits),
+                Err(InvalidAddrBits(_))
+            ));
+            assert!(matches!(
+                OfflineMemory::with_addr_bits(bits),
+                Err(InvalidAddrBits(_))
+            ));
+            assert!(parse_addr_bits(&bits.to_string()).is_err());
+        }
+        assert!(check_addr_bits(CACHE_BITS + 1).is_ok());
+        assert_eq!(parse_addr_bits("32"), Ok(32));
+        assert!(parse_addr_bits("x").is_err());
+
+        // the full address space contains every address
+        assert!(check_addr(ADDR_BITS, u32::MAX).is_ok());
+        assert!(check_addr(usize::MAX, u32::MAX).is_ok());
+    }
+
+    fn test_addr_range(mut mem: impl Memory) {
+        mem.store(SW, 0xfffc, 1).unwrap();
+        assert_eq!(mem.load(LW, 0xfffc).unwrap().0, 1);
+        assert!(mem.store(SW, 0x10000, 1).is_err());
+        assert!(mem.load(LW, 0x10000).is_err());
+        assert!(mem.read_inst(0xffff_fffc).is_err());
+    }
+
     fn test_mem(mut mem: impl Memory) {
         // read before write
         assert_eq!(mem.load(LW, 0x1000).unwrap().0, 0);
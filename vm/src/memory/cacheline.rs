@@ -23,8 +23,11 @@ pub union CacheLine {
 /// The number of bits of address the cacheline holds
 pub const CACHE_BITS: usize = 5;
 
+/// The number of bits of address in a complete memory.
+pub const ADDR_BITS: usize = 32;
+
 /// The log of the number of `CacheLines` in a complete memory.
-pub const CACHE_LOG: usize = 32 - CACHE_BITS;
+pub const CACHE_LOG: usize = ADDR_BITS - CACHE_BITS;
 
 // This will generate a compile error if CacheLine is not the right size
 const _: fn() = || {
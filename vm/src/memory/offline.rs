@@ -0,0 +1,784 @@
+//! Offline memory checking.
+//!
+//! Rather than proving each access against a Merkle root, the
+//! `OfflineMemory` controller records each access as a pair of
+//! (address, value, timestamp) tuples: the tuple taken from memory
+//! (the read set), and the tuple put back into memory with the
+//! current time (the write set). The two sets are fingerprinted into
+//! a single running value,
+//!
+//! ```text
+//!   acc = prod(gamma - h(ws)) / prod(gamma - h(rs))
+//!   h(a, lo, hi, t) = a + alpha*lo + alpha^2*hi + alpha^3*t
+//! ```
+//!
+//! Checking an access in-circuit costs a few multiplications, a
+//! 32-bit range check and one Poseidon hash, rather than a Poseidon
+//! hash per tree level.
+//!
+//! The accesses are consistent if the initial memory together with
+//! the write set equals the read set together with the final memory.
+//! This is checked by the verifier, outside of the step circuit, see
+//! [`Audit::verify`]. For the check to be sound, the challenges
+//! `alpha` and `gamma` must be chosen after the accesses are fixed.
+//! To this end, each access is also absorbed into a hash chain, the
+//! access digest, and the challenges are derived from the final
+//! digest and the final memory contents. A trace is therefore
+//! generated in two stages, see [`trace_offline`]: the accesses are
+//! recorded first, and bound to the challenges afterwards.
+//!
+//! The commitment in the folded state is the hash of the accumulator,
+//! the digest, the clock and the challenges. Each step opens the
+//! commitment, checks its accesses, and commits to the result, so the
+//! challenges are the same for all steps.
+
+use std::collections::BTreeMap;
+use std::iter::once;
+
+use ark_crypto_primitives::crh::CRHSchemeGadget;
+use ark_ff::{Field, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use thiserror::Error;
+
+use super::cacheline::*;
+use super::path::{hash_leaf, poseidon_config, LeafHashG, Params, ParamsVar};
+use super::{check_addr, check_addr_bits, Memory, MemoryProof};
+use crate::circuit::F;
+use crate::error::Result;
+use crate::eval::NexusVM;
+use crate::trace::{trace, Trace};
+
+/// Errors found while auditing the accesses of an `OfflineMemory`.
+#[derive(Debug, Error)]
+pub enum AuditError {
+    /// Accesses were audited before they were recorded
+    #[error("memory accesses were not recorded")]
+    NotStarted,
+
+    /// Challenges were not derived from the accesses
+    #[error("challenges do not match the memory accesses")]
+    Challenges,
+
+    /// Initial commitment does not match the initial memory
+    #[error("initial memory commitment does not match")]
+    InitialCommitment,
+
+    /// Final commitment does not match the audit
+    #[error("final memory commitment does not match")]
+    FinalCommitment,
+
+    /// Final cachelines are not sorted, or repeated
+    #[error("cacheline {0} out of order")]
+    Order(u32),
+
+    /// Memory accesses are not consistent
+    #[error("memory accesses are inconsistent")]
+    Inconsistent,
+
+    /// A challenge equals the fingerprint of the memory access at the
+    /// given time, which happens with negligible probability
+    #[error("challenge collides with the memory access at time {0}")]
+    Collision(u64),
+}
+
+/// Challenges used to fingerprint memory tuples.
+#[derive(Debug, Default, Clone, Copy, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Challenges {
+    pub alpha: F,
+    pub gamma: F,
+}
+
+impl Challenges {
+    /// Derive the challenges from the final access digest, and the
+    /// digest of the final memory contents.
+    pub fn derive(params: &Params, digest: F, lines: F) -> Result<Self> {
+        let alpha = hash_leaf(params, &[digest, lines, F::from(1u64)])?;
+        let gamma = hash_leaf(params, &[digest, lines, F::from(2u64)])?;
+        Ok(Challenges { alpha, gamma })
+    }
+
+    /// Fingerprint of the tuple (`line`, `data`, `ts`).
+    pub fn fingerprint(&self, line: u32, data: &[F; 2], ts: u64) -> F {
+        let a = self.alpha;
+        F::from(line) + a * data[0] + a * a * data[1] + a * a * a * F::from(ts)
+    }
+}
+
+// The memory commitment held in the folded state.
+fn commitment(params: &Params, acc: F, digest: F, clock: u64, c: &Challenges) -> Result<F> {
+    hash_leaf(params, &[acc, digest, F::from(clock), c.alpha, c.gamma])
+}
+
+/// A memory controller using offline memory checking.
+///
+/// The memory contents are held in a sparse map of `CacheLines`.
+/// Once started, every access, including reads, updates the
+/// timestamp of the accessed cacheline and the access digest.
+pub struct OfflineMemory {
+    lines: BTreeMap<u32, CacheLine>,
+    addr_bits: usize,
+    params: Params,
+    log: Option<Log>,
+}
+
+// Timestamps and access digest; these are updated on reads.
+struct Log {
+    clock: u64,
+    ts: BTreeMap<u32, u64>,
+    digest: F,
+}
+
+impl Default for OfflineMemory {
+    fn default() -> Self {
+        Self {
+            lines: BTreeMap::new(),
+            addr_bits: ADDR_BITS,
+            params: poseidon_config(),
+            log: None,
+        }
+    }
+}
+
+/// A single memory access, as seen by the offline memory checker.
+#[derive(Debug, Default, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Access {
+    /// Index of the accessed cacheline.
+    pub line: u32,
+    /// Contents of the cacheline before the access.
+    pub prev: [F; 2],
+    /// Contents of the cacheline after the access.
+    pub data: [F; 2],
+    /// Timestamp of the previous access to the cacheline.
+    pub prev_ts: u64,
+    /// Timestamp of this access, which is the clock after the access.
+    pub ts: u64,
+    /// Access digest before and after the access.
+    pub digest_in: F,
+    pub digest_out: F,
+    /// Challenges the access is bound to.
+    pub challenges: Challenges,
+    /// Accumulator before and after the access.
+    pub acc_in: F,
+    pub acc_out: F,
+    /// Memory commitment before and after the access.
+    pub root_in: F,
+    pub root_out: F,
+}
+
+/// A cacheline in the final memory.
+#[derive(Debug, Default, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FinalLine {
+    pub line: u32,
+    pub data: [F; 2],
+    pub ts: u64,
+}
+
+/// The final state of an `OfflineMemory`, which the verifier checks
+/// against the initial memory and the commitments in the proof.
+#[derive(Debug, Default, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Audit {
+    pub challenges: Challenges,
+    pub acc: F,
+    pub digest: F,
+    pub clock: u64,
+    /// Every accessed cacheline, ordered by index.
+    pub lines: Vec<FinalLine>,
+}
+
+impl OfflineMemory {
+    /// Start recording accesses. The current contents of the memory
+    /// become the initial memory checked by [`Audit::verify`].
+    pub fn start(&mut self) -> Result<()> {
+        self.log = Some(Log {
+            clock: 0,
+            ts: BTreeMap::new(),
+            digest: self.image_digest()?,
+        });
+        Ok(())
+    }
+
+    /// Return the digest of the non-zero cachelines of the memory.
+    pub fn image_digest(&self) -> Result<F> {
+        let mut digest = F::from(self.addr_bits as u64);
+        for (line, cl) in &self.lines {
+            if *cl != CacheLine::ZERO {
+                let [lo, hi] = cl.scalars();
+                digest = hash_leaf(&self.params, &[digest, F::from(*line), lo, hi])?;
+            }
+        }
+        Ok(digest)
+    }
+
+    /// Return the final state of the memory. The accumulator is set
+    /// when the recorded accesses are bound, see [`Audit::bind`].
+    pub fn audit(&self) -> Result<Audit> {
+        let log = self.log.as_ref().ok_or(AuditError::NotStarted)?;
+        let lines = log
+            .ts
+            .iter()
+            .map(|(line, ts)| FinalLine {
+                line: *line,
+                data: self.lines.get(line).copied().unwrap_or_default().scalars(),
+                ts: *ts,
+            })
+            .collect::<Vec<_>>();
+        let mut audit = Audit {
+            challenges: Challenges::default(),
+            acc: F::ONE,
+            digest: log.digest,
+            clock: log.clock,
+            lines,
+        };
+        audit.challenges =
+            Challenges::derive(&self.params, log.digest, audit.lines_digest(&self.params)?)?;
+        Ok(audit)
+    }
+
+    fn line(&self, addr: u32) -> &CacheLine {
+        match self.lines.get(&(addr >> CACHE_BITS)) {
+            None => &CacheLine::ZERO,
+            Some(cl) => cl,
+        }
+    }
+
+    // record an access which changes the cacheline from `prev` to `next`
+    fn access(&mut self, addr: u32, prev: &CacheLine, next: &CacheLine) -> Result<Access> {
+        let mut a = Access {
+            line: addr >> CACHE_BITS,
+            prev: prev.scalars(),
+            data: next.scalars(),
+            ..Access::default()
+        };
+        if let Some(log) = &mut self.log {
+            log.clock += 1;
+            a.ts = log.clock;
+            a.prev_ts = log.ts.insert(a.line, a.ts).unwrap_or(0);
+            a.digest_in = log.digest;
+            a.digest_out = hash_leaf(
+                &self.params,
+                &[
+                    a.digest_in,
+                    F::from(a.line),
+                    a.prev[0],
+                    a.prev[1],
+                    F::from(a.prev_ts),
+                    a.data[0],
+                    a.data[1],
+                    F::from(a.ts),
+                ],
+            )?;
+            log.digest = a.digest_out;
+        }
+        Ok(a)
+    }
+}
+
+impl Memory for OfflineMemory {
+    type Proof = Access;
+
+    fn with_addr_bits(addr_bits: usize) -> Result<Self> {
+        check_addr_bits(addr_bits)?;
+        Ok(Self { addr_bits, ..Self::default() })
+    }
+
+    fn query(&mut self, addr: u32) -> Result<(&CacheLine, Self::Proof)> {
+        check_addr(self.addr_bits, addr)?;
+        let cl = *self.line(addr);
+        let proof = self.access(addr, &cl, &cl)?;
+        Ok((self.line(addr), proof))
+    }
+
+    fn update<UF>(&mut self, addr: u32, f: UF) -> Result<Self::Proof>
+    where
+        UF: Fn(&mut CacheLine) -> Result<()>,
+    {
+        check_addr(self.addr_bits, addr)?;
+        let prev = *self.line(addr);
+        let mut cl = prev;
+        f(&mut cl)?;
+        self.lines.insert(addr >> CACHE_BITS, cl);
+        self.access(addr, &prev, &cl)
+    }
+
+    fn peek(&self, addr: u32) -> Result<&CacheLine> {
+        check_addr(self.addr_bits, addr)?;
+        Ok(self.line(addr))
+    }
+}
+
+impl Audit {
+    // digest of the final memory contents
+    fn lines_digest(&self, params: &Params) -> Result<F> {
+        let mut digest = F::from(self.clock);
+        for l in &self.lines {
+            let [lo, hi] = l.data;
+            digest = hash_leaf(params, &[digest, F::from(l.line), lo, hi, F::from(l.ts)])?;
+        }
+        Ok(digest)
+    }
+
+    /// Bind the recorded accesses in `proofs`, given in the order the
+    /// accesses took place, to the challenges, filling in the
+    /// accumulators and commitments, and set the final accumulator.
+    pub fn bind<'a>(
+        &mut self,
+        params: &Params,
+        proofs: impl IntoIterator<Item = &'a mut Access>,
+    ) -> Result<()> {
+        let c = self.challenges;
+        let mut acc = F::ONE;
+        for p in proofs {
+            let r = c.fingerprint(p.line, &p.prev, p.prev_ts);
+            let w = c.fingerprint(p.line, &p.data, p.ts);
+            p.challenges = c;
+            p.acc_in = acc;
+            let inv = (c.gamma - r).inverse().ok_or(AuditError::Collision(p.ts))?;
+            acc *= (c.gamma - w) * inv;
+            p.acc_out = acc;
+            p.root_in = commitment(params, p.acc_in, p.digest_in, p.ts - 1, &c)?;
+            p.root_out = commitment(params, p.acc_out, p.digest_out, p.ts, &c)?;
+        }
+        self.acc = acc;
+        Ok(())
+    }
+
+    /// Check that the memory accesses of a proof are consistent with
+    /// the memory `init`, which must be in its initial state. The
+    /// commitments `root_in` and `root_out` are those of the initial
+    /// and final states of the proof.
+    pub fn verify(&self, init: &OfflineMemory, root_in: F, root_out: F) -> Result<()> {
+        let params = &init.params;
+        let c = &self.challenges;
+        if *c != Challenges::derive(params, self.digest, self.lines_digest(params)?)? {
+            return Err(AuditError::Challenges.into());
+        }
+        if commitment(params, F::ONE, init.image_digest()?, 0, c)? != root_in {
+            return Err(AuditError::InitialCommitment.into());
+        }
+        if commitment(params, self.acc, self.digest, self.clock, c)? != root_out {
+            return Err(AuditError::FinalCommitment.into());
+        }
+
+        // acc * prod(gamma - h(init)) = prod(gamma - h(final))
+        let mut lhs = self.acc;
+        let mut rhs = F::ONE;
+        let mut prev = None;
+        for l in &self.lines {
+            if prev.is_some_and(|p| p >= l.line) {
+                return Err(AuditError::Order(l.line).into());
+            }
+            prev = Some(l.line);
+
+            let data = init.lines.get(&l.line).copied().unwrap_or_default();
+            lhs *= c.gamma - c.fingerprint(l.line, &data.scalars(), 0);
+            rhs *= c.gamma - c.fingerprint(l.line, &l.data, l.ts);
+        }
+        if lhs != rhs {
+            return Err(AuditError::Inconsistent.into());
+        }
+        Ok(())
+    }
+}
+
+/// Generate a program trace by evaluating `vm`, as `trace` does, and
+/// bind its memory accesses to the challenges. The memory of `vm`
+/// must be in its initial state. Returns the trace, together with
+/// the audit of the final memory which the verifier checks.
+pub fn trace_offline(
+    vm: &mut NexusVM<OfflineMemory>,
+    k: usize,
+    pow: bool,
+) -> Result<(Trace<Access>, Audit)> {
+    vm.mem.start()?;
+    let mut tr = trace(vm, k, pow)?;
+    let mut audit = vm.mem.audit()?;
+    let proofs = tr
+        .blocks
+        .iter_mut()
+        .flat_map(|b| &mut b.steps)
+        .flat_map(|s| {
+            once(&mut s.pc_proof)
+                .chain(&mut s.read_proof)
+                .chain(&mut s.write_proof)
+        });
+    audit.bind(&vm.mem.params, proofs)?;
+    Ok((tr, audit))
+}
+
+// enforce that `v` is a 32-bit value
+fn enforce_u32(cs: ConstraintSystemRef<F>, v: &FpVar<F>) -> Result<(), SynthesisError> {
+    let bits = (0..32)
+        .map(|i| {
+            Boolean::new_witness(cs.clone(), || {
+                let x = v.value()?.into_bigint().0[0];
+                Ok((x >> i) & 1 == 1)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(v)
+}
+
+// In-circuit challenges, with the powers of alpha used by `fingerprint`.
+struct ChallengesVar {
+    alpha: [FpVar<F>; 3],
+    gamma: FpVar<F>,
+}
+
+impl ChallengesVar {
+    fn fingerprint(&self, line: &FpVar<F>, data: &[FpVar<F>], ts: &FpVar<F>) -> FpVar<F> {
+        let [a1, a2, a3] = &self.alpha;
+        line + &data[0] * a1 + &data[1] * a2 + ts * a3
+    }
+}
+
+// In-circuit contents of a memory commitment, less the challenges.
+struct StateVar {
+    acc: FpVar<F>,
+    digest: FpVar<F>,
+    clock: FpVar<F>,
+}
+
+impl StateVar {
+    fn commit(&self, params: &ParamsVar, c: &ChallengesVar) -> Result<FpVar<F>, SynthesisError> {
+        let v = [&self.acc, &self.digest, &self.clock, &c.alpha[0], &c.gamma];
+        LeafHashG::evaluate(params, &v.map(FpVar::clone))
+    }
+}
+
+impl Access {
+    // open the commitment `root_in`, which must equal `self.root_in`
+    fn open(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        params: &ParamsVar,
+        root_in: &FpVar<F>,
+    ) -> Result<(ChallengesVar, StateVar), SynthesisError> {
+        let alpha = FpVar::new_witness(cs.clone(), || Ok(self.challenges.alpha))?;
+        let alpha2 = &alpha * &alpha;
+        let alpha3 = &alpha2 * &alpha;
+        let c = ChallengesVar {
+            alpha: [alpha, alpha2, alpha3],
+            gamma: FpVar::new_witness(cs.clone(), || Ok(self.challenges.gamma))?,
+        };
+        let state = StateVar {
+            acc: FpVar::new_witness(cs.clone(), || Ok(self.acc_in))?,
+            digest: FpVar::new_witness(cs.clone(), || Ok(self.digest_in))?,
+            clock: FpVar::new_witness(cs, || Ok(F::from(self.ts) - F::ONE))?,
+        };
+        state.commit(params, &c)?.enforce_equal(root_in)?;
+        Ok((c, state))
+    }
+
+    // In-circuit verification of the access, updating `state`. If
+    // `active` is zero, the access is ignored and `state` is unchanged.
+    // Queries leave the cacheline unchanged.
+    #[allow(clippy::too_many_arguments)]
+    fn access_circuit(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        params: &ParamsVar,
+        c: &ChallengesVar,
+        state: StateVar,
+        data: &[FpVar<F>],
+        active: &FpVar<F>,
+        query: bool,
+    ) -> Result<StateVar, SynthesisError> {
+        let line = FpVar::new_witness(cs.clone(), || Ok(F::from(self.line)))?;
+        let prev = if query {
+            [data[0].clone(), data[1].clone()]
+        } else {
+            [
+                FpVar::new_witness(cs.clone(), || Ok(self.prev[0]))?,
+                FpVar::new_witness(cs.clone(), || Ok(self.prev[1]))?,
+            ]
+        };
+        let prev_ts = FpVar::new_witness(cs.clone(), || Ok(F::from(self.prev_ts)))?;
+
+        // the timestamp of the access is the clock, which is
+        // strictly greater than the previous timestamp
+        let one = FpVar::one();
+        let ts = &state.clock + active;
+        let delta = active * (&ts - &prev_ts - &one);
+        enforce_u32(cs.clone(), &delta)?;
+
+        let r = c.fingerprint(&line, &prev, &prev_ts);
+        let w = c.fingerprint(&line, data, &ts);
+        let r = active * (&c.gamma - r) + (&one - active);
+        let w = active * (&c.gamma - w) + (&one - active);
+        let acc = FpVar::new_witness(cs, || Ok(self.acc_out))?;
+        (&acc * r).enforce_equal(&(&state.acc * w))?;
+
+        let tuple = [
+            state.digest.clone(),
+            line,
+            prev[0].clone(),
+            prev[1].clone(),
+            prev_ts,
+            data[0].clone(),
+            data[1].clone(),
+            ts.clone(),
+        ];
+        let h = LeafHashG::evaluate(params, &tuple)?;
+        let digest = &state.digest + active * (h - &state.digest);
+
+        Ok(StateVar { acc, digest, clock: ts })
+    }
+}
+
+impl MemoryProof for Access {
+    type Params = ParamsVar;
+
+    fn params(cs: ConstraintSystemRef<F>) -> Result<Self::Params, SynthesisError> {
+        ParamsVar::new_constant(cs, poseidon_config())
+    }
+
+    fn circuit(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        params: &Self::Params,
+        root: &FpVar<F>,
+        data: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let root_in = FpVar::new_witness(cs.clone(), || Ok(self.root_in))?;
+        let (c, state) = self.open(cs.clone(), params, &root_in)?;
+        let state = self.access_circuit(cs, params, &c, state, data, &FpVar::one(), false)?;
+        state.commit(params, &c)?.enforce_equal(root)
+    }
+
+    fn step_circuit(
+        cs: ConstraintSystemRef<F>,
+        params: &Self::Params,
+        root_in: &FpVar<F>,
+        root_out: &FpVar<F>,
+        proofs: [&Self; 3],
+        data: &[FpVar<F>],
+        is_read: &FpVar<F>,
+        is_write: &FpVar<F>,
+    ) -> Result<(), SynthesisError> {
+        let [pc, read, write] = proofs;
+        let (c, state) = pc.open(cs.clone(), params, root_in)?;
+
+        let one = FpVar::one();
+        let state = pc.access_circuit(cs.clone(), params, &c, state, &data[0..2], &one, true)?;
+        let state =
+            read.access_circuit(cs.clone(), params, &c, state, &data[2..4], is_read, true)?;
+        let state = write.access_circuit(cs, params, &c, state, &data[4..6], is_write, false)?;
+        state.commit(params, &c)?.enforce_equal(root_out)
+    }
+
+    fn commit(&self) -> F {
+        self.root_out
+    }
+
+    fn commit_prior(&self) -> F {
+        self.root_in
+    }
+
+    fn skip(&self) -> Self {
+        Access {
+            prev: self.data,
+            prev_ts: self.ts,
+            digest_in: self.digest_out,
+            acc_in: self.acc_out,
+            root_in: self.root_out,
+            ..self.clone()
+        }
+    }
+
+    fn data(&self) -> [F; 2] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::NexusVMError::AuditFailed;
+    use crate::machines::loop_vm;
+    use crate::rv32::{LOP::*, SOP::*};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    // Initial memory used by the tests below.
+    fn image() -> OfflineMemory {
+        let mut mem = OfflineMemory::default();
+        mem.store(SW, 0x1000, 7).unwrap();
+        mem.store(SW, 0x2000, 8).unwrap();
+        mem
+    }
+
+    // Record the accesses made by `f`, starting from `image`, and
+    // return them bound to the challenges, with the audit.
+    fn accesses(f: impl Fn(&mut OfflineMemory) -> Vec<Access>) -> (Vec<Access>, Audit) {
+        let mut mem = image();
+        mem.start().unwrap();
+        let mut proofs = f(&mut mem);
+        let mut audit = mem.audit().unwrap();
+        audit.bind(&mem.params, &mut proofs).unwrap();
+        (proofs, audit)
+    }
+
+    fn verify(proofs: &[Access], audit: &Audit) -> Result<()> {
+        let root_in = proofs[0].commit_prior();
+        let root_out = proofs.last().unwrap().commit();
+        audit.verify(&image(), root_in, root_out)
+    }
+
+    fn failure(proofs: &[Access], audit: &Audit) -> AuditError {
+        match verify(proofs, audit) {
+            Err(AuditFailed(e)) => e,
+            r => panic!("unexpected result {r:?}"),
+        }
+    }
+
+    fn program(mem: &mut OfflineMemory) -> Vec<Access> {
+        let (v, p1) = mem.load(LW, 0x1000).unwrap();
+        assert_eq!(v, 7);
+        let p2 = mem.store(SW, 0x1004, 1).unwrap();
+        let p3 = mem.store(SB, 0x3101, 2).unwrap();
+        let (v, p4) = mem.load(LW, 0x1004).unwrap();
+        assert_eq!(v, 1);
+        vec![p1, p2, p3, p4]
+    }
+
+    #[test]
+    fn offline_audit() {
+        let (proofs, audit) = accesses(program);
+        verify(&proofs, &audit).unwrap();
+        assert_eq!(audit.clock, 4);
+        assert_eq!(audit.lines.len(), 2);
+
+        // unproven accesses are not recorded
+        let mut mem = image();
+        mem.start().unwrap();
+        assert_eq!(mem.peek(0x1000).unwrap().lw(0x1000).unwrap(), 7);
+        assert!(mem.audit().unwrap().lines.is_empty());
+
+        // challenges must be derived from the accesses
+        let mut bad = audit.clone();
+        bad.challenges.alpha += F::ONE;
+        assert!(matches!(failure(&proofs, &bad), AuditError::Challenges));
+
+        let mut bad = audit.clone();
+        bad.lines[0].ts += 1;
+        assert!(matches!(failure(&proofs, &bad), AuditError::Challenges));
+
+        // commitments must match the audit
+        let mut bad = audit.clone();
+        bad.acc += F::ONE;
+        assert!(matches!(
+            failure(&proofs, &bad),
+            AuditError::FinalCommitment
+        ));
+
+        let mut mem = image();
+        mem.store(SW, 0x2000, 9).unwrap();
+        let err = audit.verify(&mem, proofs[0].commit_prior(), proofs[3].commit());
+        assert!(matches!(
+            err,
+            Err(AuditFailed(AuditError::InitialCommitment))
+        ));
+
+        // auditing requires recorded accesses
+        let err = image().audit();
+        assert!(matches!(err, Err(AuditFailed(AuditError::NotStarted))));
+    }
+
+    #[test]
+    fn offline_inconsistent() {
+        // the memory changes behind the back of the controller, so
+        // the second load does not see the value stored by the first
+        let (proofs, audit) = accesses(|mem| {
+            let p1 = mem.store(SW, 0x1000, 1).unwrap();
+            mem.lines
+                .insert(0x1000 >> CACHE_BITS, CacheLine::from([2u32; 8]));
+            let (v, p2) = mem.load(LW, 0x1000).unwrap();
+            assert_eq!(v, 2);
+            vec![p1, p2]
+        });
+        assert!(matches!(failure(&proofs, &audit), AuditError::Inconsistent));
+
+        // reordering the final lines changes the challenges
+        let (mut proofs, mut audit) = accesses(program);
+        audit.lines.swap(0, 1);
+        assert!(matches!(failure(&proofs, &audit), AuditError::Challenges));
+
+        // even if the accesses are bound to the new challenges
+        let params = poseidon_config();
+        let lines = audit.lines_digest(&params).unwrap();
+        audit.challenges = Challenges::derive(&params, audit.digest, lines).unwrap();
+        audit.bind(&params, &mut proofs).unwrap();
+        assert!(matches!(failure(&proofs, &audit), AuditError::Order(_)));
+    }
+
+    #[test]
+    fn offline_chain() {
+        let (proofs, audit) = accesses(program);
+        let [p1, p2, ..] = &proofs[..] else { unreachable!() };
+        assert_eq!(p1.commit(), p2.commit_prior());
+        assert_eq!(p1.line, p2.line);
+        assert_eq!(p1.ts, p2.prev_ts);
+        assert_eq!(p1.challenges, audit.challenges);
+
+        let p3 = p2.skip();
+        assert_eq!(p3.commit_prior(), p3.commit());
+        assert_eq!(p2.commit(), p3.commit());
+    }
+
+    #[test]
+    fn offline_trace() {
+        let mut vm = loop_vm::<OfflineMemory>(5);
+        let (tr, audit) = trace_offline(&mut vm, 1, false).unwrap();
+        let root_in = *tr.input(0).unwrap().last().unwrap();
+        let root_out = tr
+            .blocks
+            .last()
+            .unwrap()
+            .iter()
+            .last()
+            .unwrap()
+            .write_proof
+            .commit();
+        let init = loop_vm::<OfflineMemory>(5).mem;
+        audit.verify(&init, root_in, root_out).unwrap();
+
+        // a different program does not match
+        let init = loop_vm::<OfflineMemory>(6).mem;
+        assert!(audit.verify(&init, root_in, root_out).is_err());
+    }
+
+    fn check_access(p: &Access, data: [F; 2]) -> bool {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let params = Access::params(cs.clone()).unwrap();
+        let root = FpVar::new_input(cs.clone(), || Ok(p.root_out)).unwrap();
+        let data = [
+            FpVar::new_input(cs.clone(), || Ok(data[0])).unwrap(),
+            FpVar::new_input(cs.clone(), || Ok(data[1])).unwrap(),
+        ];
+        p.circuit(cs.clone(), &params, &root, &data).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn offline_circuit() {
+        let (proofs, _) = accesses(program);
+        let p = &proofs[2];
+        assert!(check_access(p, p.data));
+
+        // wrong data
+        let mut data = p.data;
+        data[0] += F::ONE;
+        assert!(!check_access(p, data));
+
+        // wrong accumulator
+        let mut q = p.clone();
+        q.acc_out += F::ONE;
+        assert!(!check_access(&q, q.data));
+
+        // timestamps must increase
+        let mut q = p.clone();
+        q.prev_ts = q.ts;
+        assert!(!check_access(&q, q.data));
+    }
+}
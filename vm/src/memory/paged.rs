@@ -5,8 +5,8 @@ use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
-use super::cacheline::CacheLine;
-use super::{Memory, MemoryProof};
+use super::cacheline::{CacheLine, ADDR_BITS};
+use super::{check_addr, check_addr_bits, Memory, MemoryProof};
 use crate::circuit::F;
 use crate::error::Result;
 
@@ -15,12 +15,21 @@ use crate::error::Result;
 /// The `Paged` memory is organized as a collection of 4K pages.
 /// Each page holds 128 `CacheLines`; a binary tree is used to
 /// represent a sparsely populated memory space.
-#[derive(Default)]
 pub struct Paged {
     tree: BTreeMap<u32, Page>,
+    addr_bits: usize,
 }
 type Page = [CacheLine; 128];
 
+impl Default for Paged {
+    fn default() -> Self {
+        Self {
+            tree: BTreeMap::new(),
+            addr_bits: ADDR_BITS,
+        }
+    }
+}
+
 /// A minimal `MemoryProof` implementation that doesn't provide
 /// in-circuit verification.
 #[derive(Default, Clone, CanonicalSerialize, CanonicalDeserialize)]
@@ -57,7 +66,18 @@ impl MemoryProof for UncheckedMemory {
 impl Memory for Paged {
     type Proof = UncheckedMemory;
 
-    fn query(&self, addr: u32) -> (&CacheLine, Self::Proof) {
+    fn with_addr_bits(addr_bits: usize) -> Result<Self> {
+        check_addr_bits(addr_bits)?;
+        Ok(Self { addr_bits, ..Self::default() })
+    }
+
+    fn query(&mut self, addr: u32) -> Result<(&CacheLine, Self::Proof)> {
+        let cl = self.peek(addr)?;
+        Ok((cl, UncheckedMemory { data: cl.scalars() }))
+    }
+
+    fn peek(&self, addr: u32) -> Result<&CacheLine> {
+        check_addr(self.addr_bits, addr)?;
         let page = addr >> 12;
         let offset = ((addr >> 5) & 0x7f) as usize;
 
@@ -66,13 +86,14 @@ impl Memory for Paged {
             None => &ZERO,
             Some(arr) => &arr[offset],
         };
-        (cl, UncheckedMemory { data: cl.scalars() })
+        Ok(cl)
     }
 
     fn update<F>(&mut self, addr: u32, f: F) -> Result<Self::Proof>
     where
         F: Fn(&mut CacheLine) -> Result<()>,
     {
+        check_addr(self.addr_bits, addr)?;
         let page = addr >> 12;
         let offset = ((addr >> 5) & 0x7f) as usize;
         let arr = self
@@ -76,10 +76,10 @@ pub fn hash_memory(params: &Params, cl: &CacheLine) -> Result<Digest, NexusVMErr
     hash_leaf(params, &cl.scalars())
 }
 
-/// Calculate a hash chain of length `CACHE_LOG`, starting from
+/// Calculate a hash chain of length `depth`, starting from
 /// a default `CacheLine`. This is used to construct paths for
 /// missing elements in the memory.
-pub fn compute_zeros(params: &Params) -> Result<Vec<Digest>, NexusVMError> {
+pub fn compute_zeros(params: &Params, depth: usize) -> Result<Vec<Digest>, NexusVMError> {
     fn f(params: &Params, v: &mut Vec<Digest>, n: usize) -> Result<Digest, NexusVMError> {
         if n == 0 {
             return hash_memory(params, &CacheLine::default());
@@ -90,7 +90,7 @@ pub fn compute_zeros(params: &Params) -> Result<Vec<Digest>, NexusVMError> {
     }
 
     let mut v = Vec::new();
-    let root = f(params, &mut v, CACHE_LOG)?;
+    let root = f(params, &mut v, depth)?;
     v.push(root);
     v.reverse();
     Ok(v)
@@ -265,7 +265,7 @@ pub mod test {
 
     // the auth path for an empty tree is the same as the zeros array
     fn empty_path(params: &Params) -> Path {
-        let zeros = compute_zeros(params).unwrap();
+        let zeros = compute_zeros(params, CACHE_LOG).unwrap();
         let auth = zeros[1..].iter().rev().map(|f| (true, *f)).collect();
         let leaf = CacheLine::default().scalars();
         Path { root: zeros[0], leaf, auth }
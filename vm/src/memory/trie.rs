@@ -3,7 +3,7 @@
 
 use super::cacheline::*;
 use super::path::*;
-use super::Memory;
+use super::{check_addr, check_addr_bits, Memory};
 use crate::circuit::F;
 use crate::error::*;
 
@@ -12,6 +12,10 @@ pub struct MerkleTrie {
     // The root node, initially `None`
     root: Option<Box<Node>>,
 
+    // Number of address bits, and the corresponding depth of the tree
+    addr_bits: usize,
+    depth: usize,
+
     // Default hashes for each level of the tree
     zeros: Vec<Digest>,
 
@@ -148,6 +152,31 @@ impl Node {
 }
 
 impl MerkleTrie {
+    /// Create an empty trie covering an address space of `addr_bits` bits.
+    /// The depth of the trie, and hence the length of each `Path`, is
+    /// `addr_bits - CACHE_BITS`.
+    ///
+    /// Fails with `InvalidAddrBits` if `addr_bits` is not supported, see
+    /// `check_addr_bits`.
+    pub fn new(addr_bits: usize) -> Result<Self> {
+        check_addr_bits(addr_bits)?;
+        let depth = addr_bits - CACHE_BITS;
+        let params = poseidon_config();
+        let zeros = compute_zeros(&params, depth)?;
+        Ok(Self {
+            root: None,
+            addr_bits,
+            depth,
+            zeros,
+            params,
+        })
+    }
+
+    /// Return the number of address bits covered by this trie.
+    pub fn addr_bits(&self) -> usize {
+        self.addr_bits
+    }
+
     // return merkle root
     #[allow(clippy::question_mark)]
     pub fn root(&self) -> Digest {
@@ -162,14 +191,21 @@ impl MerkleTrie {
         }
     }
 
+    // Reverse the cacheline index of `addr`, such that bit `l` selects
+    // the child at level `l` of the tree (starting from 1 at the root).
+    fn index(&self, addr: u32) -> Result<u32> {
+        check_addr(self.addr_bits, addr)?;
+        Ok((addr >> CACHE_BITS).reverse_bits() >> (31 - self.depth))
+    }
+
     /// Query the tree at `addr` returning the `CacheLine` (and `Path` if hashes enabled).
     /// The default CacheLine is returned if the tree is unpopulated at `addr`.
-    pub fn query(&self, addr: u32) -> (&CacheLine, Path) {
-        let addr = addr.reverse_bits();
+    pub fn query(&self, addr: u32) -> Result<(&CacheLine, Path)> {
+        let addr = self.index(addr)?;
         let mut auth = Vec::new();
         let cl = self.query_inner(&self.root, &mut auth, 0, addr);
         let path = Path::new(self.root(), cl.scalars(), auth);
-        (cl, path)
+        Ok((cl, path))
     }
 
     fn query_inner<'a>(
@@ -179,7 +215,7 @@ impl MerkleTrie {
         level: usize,
         addr: u32,
     ) -> &CacheLine {
-        if level == CACHE_LOG {
+        if level == self.depth {
             return Node::leaf(node);
         }
 
@@ -198,7 +234,7 @@ impl MerkleTrie {
     where
         F: Fn(&mut CacheLine) -> Result<()>,
     {
-        let addr = addr.reverse_bits();
+        let addr = self.index(addr)?;
         let mut auth = Vec::new();
         if self.root.is_none() {
             self.root = Some(Box::new(Node::new_node()));
@@ -224,7 +260,7 @@ impl MerkleTrie {
     where
         UF: Fn(&mut CacheLine) -> Result<()>,
     {
-        if level == CACHE_LOG {
+        if level == self.depth {
             f(node.data.leaf_mut())?;
             node.digest = hash_memory(&self.params, node.data.leaf())?;
             return Ok(node.data.leaf().scalars());
@@ -233,7 +269,7 @@ impl MerkleTrie {
         let level = level + 1;
         let addr = addr >> 1;
         let is_left = (addr & 1) == 0;
-        let b = node.descend(is_left, level == CACHE_LOG);
+        let b = node.descend(is_left, level == self.depth);
         let cl = self.update_inner(b, auth, level, addr, f)?;
 
         let sibling = Node::sibling(node, is_left);
@@ -247,17 +283,23 @@ impl MerkleTrie {
 
 impl Default for MerkleTrie {
     fn default() -> Self {
-        let params = poseidon_config();
-        let zeros = compute_zeros(&params).unwrap();
-        Self { root: None, zeros, params }
+        Self::new(ADDR_BITS).unwrap()
     }
 }
 
 impl Memory for MerkleTrie {
     type Proof = Path;
 
-    fn query(&self, addr: u32) -> (&CacheLine, Self::Proof) {
-        self.query(addr)
+    fn with_addr_bits(addr_bits: usize) -> Result<Self> {
+        Self::new(addr_bits)
+    }
+
+    fn query(&mut self, addr: u32) -> Result<(&CacheLine, Self::Proof)> {
+        MerkleTrie::query(self, addr)
+    }
+
+    fn peek(&self, addr: u32) -> Result<&CacheLine> {
+        Ok(MerkleTrie::query(self, addr)?.0)
     }
 
     fn update<F>(&mut self, addr: u32, f: F) -> Result<Self::Proof>
@@ -302,7 +344,7 @@ mod test {
         let zeros = &CacheLine::default();
         let mt = MerkleTrie::default();
         let params = &mt.params;
-        let x = mt.query(0);
+        let x = mt.query(0).unwrap();
         let path = x.1;
         assert_eq!(zeros, x.0);
         assert!(path.verify(params).unwrap());
@@ -311,7 +353,7 @@ mod test {
     #[test]
     fn trie_empty_circuit() {
         let mt = MerkleTrie::default();
-        let x = mt.query(0);
+        let x = mt.query(0).unwrap();
         let path = x.1;
 
         verify_circuit_sat(&path);
@@ -323,7 +365,7 @@ mod test {
         let _ = mt.update(0, |cl| cl.sw(0, 1)).unwrap();
 
         let cl = CacheLine::from([1u32, 0, 0, 0, 0, 0, 0, 0]);
-        let x = mt.query(0);
+        let x = mt.query(0).unwrap();
         assert_eq!(cl, *x.0);
     }
 
@@ -336,7 +378,7 @@ mod test {
         let leaf = cl.scalars();
         assert_eq!(leaf, path.leaf);
 
-        let x = mt.query(0);
+        let x = mt.query(0).unwrap();
         assert_eq!(cl, *x.0);
 
         let params = &mt.params;
@@ -346,4 +388,24 @@ mod test {
 
         verify_circuit_sat(&path);
     }
+
+    #[test]
+    fn trie_addr_bits() {
+        let mut mt = MerkleTrie::new(22).unwrap();
+        let path = mt.update(0x3fffe0, |cl| cl.sw(0x3fffe0, 1)).unwrap();
+        assert_eq!(path.auth.len(), 22 - CACHE_BITS);
+        assert!(path.verify(&mt.params).unwrap());
+        verify_circuit_sat(&path);
+
+        let (cl, path) = mt.query(0x3fffe0).unwrap();
+        assert_eq!(cl.lw(0x3fffe0).unwrap(), 1);
+        assert!(path.verify(&mt.params).unwrap());
+
+        // distinct cachelines do not alias
+        let (cl, _) = mt.query(0x1fffe0).unwrap();
+        assert_eq!(cl.lw(0x1fffe0).unwrap(), 0);
+
+        assert!(mt.query(0x400000).is_err());
+        assert!(mt.update(0x400000, |cl| cl.sw(0x400000, 1)).is_err());
+    }
 }
@@ -0,0 +1,154 @@
+//! Native implementations of precompiles.
+//!
+//! Precompiles are operations which are expensive to express in
+//! RV32I, and which guest programs request from the host with an
+//! `ecall`. The host evaluates them natively, taking the operands from
+//! registers and writing the result back to registers (see
+//! `syscalls::PRECOMPILE_REGS`), and the step circuit checks the result
+//! (see `circuit::precompiles`).
+//!
+//! The gadgets checking a precompile are part of every step, so they
+//! are only added to the step circuit when the precompile is selected
+//! (see `Precompiles`), and programs may only call selected precompiles.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+pub mod bigint;
+pub mod ed25519;
+pub mod poseidon;
+pub mod secp256k1;
+
+/// The precompiles a program may call, and which the step circuit
+/// checks. The circuit, and hence the public parameters, depend on the
+/// selection, which defaults to no precompiles.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct Precompiles {
+    /// 256-bit add, mul and modmul, ecalls 12 to 14
+    pub bigint: bool,
+    /// Poseidon hash and compress, ecalls 15 and 16
+    pub poseidon: bool,
+    /// secp256k1 point addition and doubling, ecalls 17 and 18
+    pub secp256k1: bool,
+    /// Ed25519 point addition, ecall 19
+    pub ed25519: bool,
+}
+
+impl Precompiles {
+    /// Every precompile.
+    pub const ALL: Self = Self {
+        bigint: true,
+        poseidon: true,
+        secp256k1: true,
+        ed25519: true,
+    };
+
+    /// Returns false if ecall `num` calls a precompile which is not
+    /// selected; see `Syscalls::syscall` for the ecall numbers.
+    pub fn allows(&self, num: u32) -> bool {
+        match num {
+            12..=14 => self.bigint,
+            15 | 16 => self.poseidon,
+            17 | 18 => self.secp256k1,
+            19 => self.ed25519,
+            _ => true,
+        }
+    }
+
+    fn names(&self) -> Vec<&'static str> {
+        [
+            (self.bigint, "bigint"),
+            (self.poseidon, "poseidon"),
+            (self.secp256k1, "secp256k1"),
+            (self.ed25519, "ed25519"),
+        ]
+        .into_iter()
+        .filter_map(|(selected, name)| selected.then_some(name))
+        .collect()
+    }
+}
+
+/// Comma-separated names of the selected precompiles, or `none`.
+impl Display for Precompiles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let names = self.names();
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(","))
+        }
+    }
+}
+
+impl FromStr for Precompiles {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut p = Self::default();
+        if s == "none" {
+            return Ok(p);
+        }
+        for name in s.split(',') {
+            match name {
+                "bigint" => p.bigint = true,
+                "poseidon" => p.poseidon = true,
+                "secp256k1" => p.secp256k1 = true,
+                "ed25519" => p.ed25519 = true,
+                _ => {
+                    return Err(format!(
+                        "unknown precompile {name}, expected bigint, poseidon, secp256k1, ed25519 or none"
+                    ))
+                }
+            }
+        }
+        Ok(p)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_precompiles() {
+        for p in [Precompiles::default(), Precompiles::ALL] {
+            assert_eq!(p.to_string().parse(), Ok(p));
+        }
+        assert_eq!("none".parse(), Ok(Precompiles::default()));
+        assert_eq!(
+            "bigint".parse(),
+            Ok(Precompiles { bigint: true, ..Default::default() })
+        );
+        assert_eq!(
+            "ed25519,poseidon,secp256k1,bigint".parse(),
+            Ok(Precompiles::ALL)
+        );
+        assert_eq!(
+            Precompiles::ALL.to_string(),
+            "bigint,poseidon,secp256k1,ed25519"
+        );
+        assert!("sha256".parse::<Precompiles>().is_err());
+        assert!("".parse::<Precompiles>().is_err());
+
+        assert!(!Precompiles::default().allows(12));
+        assert!(Precompiles::default().allows(11));
+        assert!(Precompiles::ALL.allows(14));
+        assert!(!Precompiles { bigint: true, ..Default::default() }.allows(16));
+        assert!(Precompiles { secp256k1: true, ..Default::default() }.allows(18));
+        assert!(!Precompiles { secp256k1: true, ..Default::default() }.allows(19));
+    }
+}
@@ -0,0 +1,241 @@
+//! 256-bit integer arithmetic.
+//!
+//! Integers are stored as 8 little-endian 32-bit words, which is how
+//! they are laid out in guest memory.
+
+/// A 256-bit integer, least significant word first.
+pub type U256 = [u32; 8];
+
+/// A 512-bit integer, least significant word first.
+pub type U512 = [u32; 16];
+
+/// Operations of the bigint precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BigintOp {
+    /// `a + b`, as 8 words of sum followed by the carry
+    Add,
+    /// `a * b`, as 16 words
+    Mul,
+    /// `a * b mod m`, as 8 words
+    ModMul,
+}
+
+/// Compute `a + b`, returning the sum modulo 2^256 and the carry.
+pub fn add(a: &U256, b: &U256) -> (U256, bool) {
+    let mut s = [0; 8];
+    let mut carry = 0u64;
+    for (i, w) in s.iter_mut().enumerate() {
+        let x = a[i] as u64 + b[i] as u64 + carry;
+        *w = x as u32;
+        carry = x >> 32;
+    }
+    (s, carry != 0)
+}
+
+/// Compute `a - b`, returning the difference modulo 2^256 and the
+/// borrow.
+pub fn sub(a: &U256, b: &U256) -> (U256, bool) {
+    let mut d = [0; 8];
+    let mut borrow = 0i64;
+    for (i, w) in d.iter_mut().enumerate() {
+        let x = a[i] as i64 - b[i] as i64 - borrow;
+        *w = x as u32;
+        borrow = (x < 0) as i64;
+    }
+    (d, borrow != 0)
+}
+
+/// Compute `a * b`.
+pub fn mul(a: &U256, b: &U256) -> U512 {
+    let mut p = [0; 16];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let t = *x as u64 * *y as u64 + p[i + j] as u64 + carry;
+            p[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        p[i + 8] = carry as u32;
+    }
+    p
+}
+
+/// Divide `x` by `m`, returning the quotient and remainder, or
+/// `None` if `m` is zero.
+pub fn divrem(x: &U512, m: &U256) -> Option<(U512, U256)> {
+    if m.iter().all(|w| *w == 0) {
+        return None;
+    }
+
+    // schoolbook binary long division; the remainder is less than m,
+    // so doubling it needs at most one extra word
+    let mut q = [0; 16];
+    let mut r = [0u32; 9];
+    for i in (0..512).rev() {
+        let bit = (x[i / 32] >> (i % 32)) & 1;
+        let mut carry = bit;
+        for w in r.iter_mut() {
+            let top = *w >> 31;
+            *w = (*w << 1) | carry;
+            carry = top;
+        }
+        if !less(&r, m) {
+            let mut borrow = 0i64;
+            for (j, w) in r.iter_mut().enumerate() {
+                let y = *w as i64 - m.get(j).copied().unwrap_or(0) as i64 - borrow;
+                *w = y as u32;
+                borrow = (y < 0) as i64;
+            }
+            q[i / 32] |= 1 << (i % 32);
+        }
+    }
+    Some((q, r[..8].try_into().unwrap()))
+}
+
+// compare a 9-word integer with a 256-bit integer
+fn less(r: &[u32; 9], m: &U256) -> bool {
+    if r[8] != 0 {
+        return false;
+    }
+    for (x, y) in r[..8].iter().zip(m).rev() {
+        if x != y {
+            return x < y;
+        }
+    }
+    false
+}
+
+/// Compute `a * b mod m`, or `None` if `m` is zero.
+pub fn modmul(a: &U256, b: &U256, m: &U256) -> Option<U256> {
+    divrem(&mul(a, b), m).map(|(_, r)| r)
+}
+
+/// Compute `a + b mod m`, for `a` and `b` less than `m`.
+pub fn modadd(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (s, carry) = add(a, b);
+    let (d, borrow) = sub(&s, m);
+    if carry || !borrow {
+        d
+    } else {
+        s
+    }
+}
+
+/// Compute `a - b mod m`, for `a` and `b` less than `m`.
+pub fn modsub(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (d, borrow) = sub(a, b);
+    if borrow {
+        add(&d, m).0
+    } else {
+        d
+    }
+}
+
+/// Compute `x^e mod m`, or `None` if `m` is zero.
+pub fn modpow(x: &U256, e: &U256, m: &U256) -> Option<U256> {
+    let mut r = modmul(&[1, 0, 0, 0, 0, 0, 0, 0], &[1, 0, 0, 0, 0, 0, 0, 0], m)?;
+    for i in (0..256).rev() {
+        r = modmul(&r, &r, m)?;
+        if (e[i / 32] >> (i % 32)) & 1 == 1 {
+            r = modmul(&r, x, m)?;
+        }
+    }
+    Some(r)
+}
+
+/// Evaluate `op`, returning the words of the result. Returns `None`
+/// for a modular multiplication by a zero modulus.
+pub fn eval(op: BigintOp, a: &U256, b: &U256, m: &U256) -> Option<Vec<u32>> {
+    let res = match op {
+        BigintOp::Add => {
+            let (s, carry) = add(a, b);
+            let mut v = s.to_vec();
+            v.push(carry as u32);
+            v
+        }
+        BigintOp::Mul => mul(a, b).to_vec(),
+        BigintOp::ModMul => modmul(a, b, m)?.to_vec(),
+    };
+    Some(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_u128(x: u128) -> U256 {
+        let mut r = [0; 8];
+        for (i, w) in r.iter_mut().take(4).enumerate() {
+            *w = (x >> (32 * i)) as u32;
+        }
+        r
+    }
+
+    #[test]
+    fn test_add() {
+        let max = [u32::MAX; 8];
+        assert_eq!(add(&max, &from_u128(1)), ([0; 8], true));
+
+        let (s, carry) = add(&from_u128(u64::MAX as u128), &from_u128(1));
+        assert_eq!(s, from_u128(1 << 64));
+        assert!(!carry);
+
+        assert_eq!(sub(&[0; 8], &from_u128(1)), (max, true));
+        assert_eq!(
+            sub(&from_u128(1 << 64), &from_u128(1)).0,
+            from_u128(u64::MAX as u128)
+        );
+    }
+
+    #[test]
+    fn test_mul() {
+        let p = mul(&from_u128(u64::MAX as u128), &from_u128(3));
+        assert_eq!(p[..8], from_u128(3 * u64::MAX as u128));
+        assert_eq!(p[8..], [0; 8]);
+
+        // (2^256 - 1)^2 = 2^512 - 2^257 + 1
+        let p = mul(&[u32::MAX; 8], &[u32::MAX; 8]);
+        let mut expected = [u32::MAX; 16];
+        expected[..8].copy_from_slice(&from_u128(1));
+        expected[8] = 0xffff_fffe;
+        assert_eq!(p, expected);
+    }
+
+    #[test]
+    fn test_modmul() {
+        let (a, b, m) = ((1u128 << 100) + 3, (1u128 << 27) + 1, (1u128 << 120) - 1);
+        let r = modmul(&from_u128(a), &from_u128(b), &from_u128(m));
+        assert_eq!(r, Some(from_u128(a * b % m)));
+
+        let max = [u32::MAX; 8];
+        assert_eq!(modmul(&max, &max, &max), Some([0; 8]));
+        assert_eq!(modmul(&max, &max, &from_u128(1)), Some([0; 8]));
+        assert_eq!(modmul(&max, &max, &[0; 8]), None);
+
+        // the quotient does not fit in 256 bits
+        let (q, r) = divrem(&mul(&max, &max), &from_u128(2)).unwrap();
+        assert_eq!(r, from_u128(1));
+        assert_eq!(q[15], u32::MAX >> 1);
+    }
+
+    #[test]
+    fn test_modular() {
+        let m = from_u128(1000);
+        assert_eq!(modadd(&from_u128(700), &from_u128(400), &m), from_u128(100));
+        assert_eq!(modadd(&from_u128(2), &from_u128(3), &m), from_u128(5));
+        assert_eq!(modsub(&from_u128(2), &from_u128(3), &m), from_u128(999));
+
+        // no overflow for moduli close to 2^256
+        let max = [u32::MAX; 8];
+        let (m, _) = sub(&max, &from_u128(4));
+        let (a, _) = sub(&m, &from_u128(1));
+        assert_eq!(modadd(&a, &a, &m), sub(&m, &from_u128(2)).0);
+
+        assert_eq!(
+            modpow(&from_u128(3), &from_u128(5), &from_u128(7)),
+            Some(from_u128(5))
+        );
+        assert_eq!(modpow(&from_u128(3), &[0; 8], &from_u128(1)), Some([0; 8]));
+        assert_eq!(modpow(&from_u128(3), &from_u128(5), &[0; 8]), None);
+    }
+}
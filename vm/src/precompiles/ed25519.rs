@@ -0,0 +1,160 @@
+//! Point addition on the twisted Edwards curve of Ed25519,
+//! -x^2 + y^2 = 1 + d x^2 y^2.
+//!
+//! Points are affine, with coordinates stored as 8 little-endian
+//! 32-bit words. To fit in the registers of a precompile, the second
+//! operand of an addition is given by its encoding, as in RFC 8032: its
+//! y coordinate, with the parity of its x coordinate in bit 255.
+//! Operands are reduced modulo the field prime, and results are fully
+//! reduced. The addition formula is complete, so the same operation
+//! doubles points.
+
+use super::bigint::{modadd, modmul, modpow, modsub, sub, U256};
+
+/// The prime of the base field, 2^255 - 19.
+pub const P: U256 = [
+    0xffffffed, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x7fffffff,
+];
+
+/// The coefficient d of the curve equation, -121665 / 121666.
+pub const D: U256 = [
+    0x135978a3, 0x75eb4dca, 0x4141d8ab, 0x00700a4d, 0x7779e898, 0x8cc74079, 0x2b6ffe73, 0x52036cee,
+];
+
+// a square root of -1, 2^((p - 1) / 4)
+const SQRT_M1: U256 = [
+    0x4a0ea0b0, 0xc4ee1b27, 0xad2fe478, 0x2f431806, 0x3dfbd7a7, 0x2b4d0099, 0x4fc1df0b, 0x2b832480,
+];
+
+// (p - 5) / 8
+const SQRT_EXP: U256 = [
+    0xfffffffd, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x0fffffff,
+];
+
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+fn mul(a: &U256, b: &U256) -> U256 {
+    modmul(a, b, &P).unwrap()
+}
+
+fn reduce(x: &U256) -> U256 {
+    mul(x, &ONE)
+}
+
+fn inv(x: &U256) -> U256 {
+    modpow(x, &sub(&P, &[2, 0, 0, 0, 0, 0, 0, 0]).0, &P).unwrap()
+}
+
+// a square root of u / v, for v not zero
+fn sqrt_ratio(u: &U256, v: &U256) -> Option<U256> {
+    let v3 = mul(&mul(v, v), v);
+    let v7 = mul(&mul(&v3, &v3), v);
+    let x = mul(&mul(u, &v3), &modpow(&mul(u, &v7), &SQRT_EXP, &P)?);
+    let vxx = mul(v, &mul(&x, &x));
+    if vxx == *u {
+        Some(x)
+    } else if vxx == modsub(&[0; 8], u, &P) {
+        Some(mul(&x, &SQRT_M1))
+    } else {
+        None
+    }
+}
+
+/// Returns the coordinates of the point with encoding `q`, or `None`
+/// if there is no such point.
+pub fn decompress(q: &U256) -> Option<(U256, U256)> {
+    let mut y = *q;
+    y[7] &= 0x7fff_ffff;
+    let y = reduce(&y);
+    let sign = q[7] >> 31;
+
+    // x^2 = (y^2 - 1) / (d y^2 + 1), where the denominator is not zero
+    // as d is not a square
+    let yy = mul(&y, &y);
+    let u = modsub(&yy, &ONE, &P);
+    let v = modadd(&mul(&D, &yy), &ONE, &P);
+    let mut x = sqrt_ratio(&u, &v)?;
+    if x[0] & 1 != sign {
+        x = modsub(&[0; 8], &x, &P);
+    }
+    // zero has no negative
+    if x[0] & 1 != sign {
+        return None;
+    }
+    Some((x, y))
+}
+
+/// Compute `(x1, y1) + q`, where `q` is the encoding of a point,
+/// returning the coordinates of the result, x first. Returns `None` if
+/// `q` is not the encoding of a point, or if `(x1, y1)` is not on the
+/// curve and the formula divides by zero.
+pub fn eval(x1: &U256, y1: &U256, q: &U256) -> Option<Vec<u32>> {
+    let (x2, y2) = decompress(q)?;
+    let (x1, y1) = (reduce(x1), reduce(y1));
+
+    let (a, b) = (mul(&x1, &y2), mul(&y1, &x2));
+    let (c, e) = (mul(&y1, &y2), mul(&x1, &x2));
+    let dt = mul(&D, &mul(&e, &c));
+    let den_x = modadd(&ONE, &dt, &P);
+    let den_y = modsub(&ONE, &dt, &P);
+    if den_x == [0; 8] || den_y == [0; 8] {
+        return None;
+    }
+    let x3 = mul(&modadd(&a, &b, &P), &inv(&den_x));
+    let y3 = mul(&modadd(&c, &e, &P), &inv(&den_y));
+    Some([x3, y3].concat())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // the base point, 2B and 3B; the x coordinates are even
+    const BX: U256 = [
+        0x8f25d51a, 0xc9562d60, 0x9525a7b2, 0x692cc760, 0xfdd6dc5c, 0xc0a4e231, 0xcd6e53fe,
+        0x216936d3,
+    ];
+    const BY: U256 = [
+        0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+        0x66666666,
+    ];
+    const X2: U256 = [
+        0x2843ce0e, 0x83c5a14e, 0x15d7a45f, 0x080d8e45, 0x1833e7ac, 0x3d043b7d, 0x9f5a046c,
+        0x36ab384c,
+    ];
+    const Y2: U256 = [
+        0x6af8a3c9, 0x0e5f46ae, 0x64385156, 0x97390f51, 0xc9a21f56, 0x1da25ee8, 0x092329c2,
+        0x2260cdf3,
+    ];
+    const X3: U256 = [
+        0xd3f8e25c, 0xac62485f, 0x81624886, 0x63439819, 0x3edac83a, 0x1ff4ae74, 0x22928f49,
+        0x67ae9c4a,
+    ];
+    const Y3: U256 = [
+        0x78f5b4d4, 0x02c36848, 0x67240304, 0x9f16ec17, 0x60269ef7, 0xa126a18e, 0x77ee69ab,
+        0x1267b1d1,
+    ];
+
+    #[test]
+    fn ed25519_ops() {
+        assert_eq!(decompress(&BY), Some((BX, BY)));
+        assert_eq!(eval(&BX, &BY, &BY).unwrap(), [X2, Y2].concat());
+        assert_eq!(eval(&X2, &Y2, &BY).unwrap(), [X3, Y3].concat());
+
+        // B - B is the identity
+        let mut minus_b = BY;
+        minus_b[7] |= 1 << 31;
+        assert_eq!(decompress(&minus_b).unwrap().0, modsub(&[0; 8], &BX, &P));
+        assert_eq!(eval(&BX, &BY, &minus_b).unwrap(), [[0; 8], ONE].concat());
+    }
+
+    #[test]
+    fn ed25519_undefined() {
+        // (2^2 - 1) / (4 d + 1) is not a square
+        assert_eq!(decompress(&[2, 0, 0, 0, 0, 0, 0, 0]), None);
+        // the x coordinate of (0, 1) is not negative
+        assert_eq!(decompress(&[1, 0, 0, 0, 0, 0, 0, 1 << 31]), None);
+        assert_eq!(decompress(&ONE), Some(([0; 8], ONE)));
+        assert_eq!(eval(&BX, &BY, &[2, 0, 0, 0, 0, 0, 0, 0]), None);
+    }
+}
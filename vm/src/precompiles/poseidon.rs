@@ -0,0 +1,93 @@
+//! Poseidon hashing of field elements.
+//!
+//! The hash functions are those of the memory trie (see
+//! `memory::path`), over the scalar field of BN254. Field elements
+//! are stored as 8 little-endian 32-bit words, and must be less than
+//! the field modulus.
+
+use ark_ff::{BigInt, PrimeField};
+
+use crate::{
+    circuit::F,
+    error::NexusVMError,
+    memory::path::{compress, hash_leaf, Params},
+};
+
+/// Operations of the Poseidon precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoseidonOp {
+    /// Hash field elements, as the trie hashes leaves
+    Hash,
+    /// Hash two field elements, as the trie hashes inner nodes
+    Compress,
+}
+
+/// Convert words to a field element, or `None` if the value is not
+/// less than the field modulus.
+pub fn from_words(w: &[u32; 8]) -> Option<F> {
+    let limb = |i: usize| w[2 * i] as u64 | (w[2 * i + 1] as u64) << 32;
+    F::from_bigint(BigInt([limb(0), limb(1), limb(2), limb(3)]))
+}
+
+/// Convert a field element to words.
+pub fn to_words(f: F) -> [u32; 8] {
+    let BigInt(limbs) = f.into_bigint();
+    let mut w = [0; 8];
+    for (i, x) in limbs.iter().enumerate() {
+        w[2 * i] = *x as u32;
+        w[2 * i + 1] = (*x >> 32) as u32;
+    }
+    w
+}
+
+/// Evaluate `op` on `elems`. `Compress` takes exactly two elements.
+pub fn eval(params: &Params, op: PoseidonOp, elems: &[F]) -> Result<F, NexusVMError> {
+    match (op, elems) {
+        (PoseidonOp::Hash, _) => hash_leaf(params, elems),
+        (PoseidonOp::Compress, [l, r]) => compress(params, l, r),
+        (PoseidonOp::Compress, _) => Err(NexusVMError::HashError(format!(
+            "compress takes 2 elements, found {}",
+            elems.len()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memory::path::poseidon_config;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn test_words() {
+        let mut rng = ark_std::test_rng();
+        for _ in 0..10 {
+            let f = F::rand(&mut rng);
+            assert_eq!(from_words(&to_words(f)), Some(f));
+        }
+        assert_eq!(from_words(&[7, 0, 0, 0, 0, 0, 0, 0]), Some(F::from(7u64)));
+
+        // the modulus itself is not a field element
+        let p = to_words(-F::from(1u64));
+        let mut q = p;
+        q[0] += 1;
+        assert!(from_words(&p).is_some());
+        assert_eq!(from_words(&q), None);
+        assert_eq!(from_words(&[u32::MAX; 8]), None);
+    }
+
+    #[test]
+    fn test_eval() {
+        let params = poseidon_config();
+        let (a, b) = (F::from(1u64), F::from(2u64));
+        assert_eq!(
+            eval(&params, PoseidonOp::Hash, &[a, b]).unwrap(),
+            hash_leaf(&params, &[a, b]).unwrap()
+        );
+        assert_eq!(
+            eval(&params, PoseidonOp::Compress, &[a, b]).unwrap(),
+            compress(&params, &a, &b).unwrap()
+        );
+        assert!(eval(&params, PoseidonOp::Compress, &[a]).is_err());
+    }
+}
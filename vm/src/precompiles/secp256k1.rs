@@ -0,0 +1,164 @@
+//! Point addition and doubling on secp256k1, the curve y^2 = x^3 + 7.
+//!
+//! Points are affine, with coordinates stored as 8 little-endian
+//! 32-bit words. To fit in the registers of a precompile, the second
+//! operand of an addition is given by its x coordinate alone: it is the
+//! point with that x coordinate and an even y coordinate. Operands are
+//! reduced modulo the field prime, and results are fully reduced.
+
+use super::bigint::{modadd, modmul, modpow, modsub, sub, U256};
+
+/// The prime of the base field, 2^256 - 2^32 - 977.
+pub const P: U256 = [
+    0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+/// The x coordinate of the generator.
+pub const GX: U256 = [
+    0x16f81798, 0x59f2815b, 0x2dce28d9, 0x029bfcdb, 0xce870b07, 0x55a06295, 0xf9dcbbac, 0x79be667e,
+];
+
+/// The y coordinate of the generator.
+pub const GY: U256 = [
+    0xfb10d4b8, 0x9c47d08f, 0xa6855419, 0xfd17b448, 0x0e1108a8, 0x5da4fbfc, 0x26a3c465, 0x483ada77,
+];
+
+/// The constant term of the curve equation.
+pub const B: U256 = [7, 0, 0, 0, 0, 0, 0, 0];
+
+// (p + 1) / 4, as p = 3 mod 4
+const SQRT_EXP: U256 = [
+    0xbfffff0c, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x3fffffff,
+];
+
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Operations of the secp256k1 precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Secp256k1Op {
+    /// `(x1, y1) + (x2, y2)`, where `y2` is even
+    Add,
+    /// `2 (x1, y1)`
+    Double,
+}
+
+fn mul(a: &U256, b: &U256) -> U256 {
+    modmul(a, b, &P).unwrap()
+}
+
+fn reduce(x: &U256) -> U256 {
+    mul(x, &ONE)
+}
+
+fn inv(x: &U256) -> U256 {
+    modpow(x, &sub(&P, &[2, 0, 0, 0, 0, 0, 0, 0]).0, &P).unwrap()
+}
+
+/// Returns the even y coordinate of the point with x coordinate `x`,
+/// or `None` if there is no such point.
+pub fn even_y(x: &U256) -> Option<U256> {
+    let x = reduce(x);
+    let c = modadd(&mul(&mul(&x, &x), &x), &B, &P);
+    let y = modpow(&c, &SQRT_EXP, &P)?;
+    if mul(&y, &y) != c {
+        return None;
+    }
+    Some(if y[0] & 1 == 1 {
+        modsub(&[0; 8], &y, &P)
+    } else {
+        y
+    })
+}
+
+/// Returns the slope of the line through the operands of `op`, or
+/// `None` if the operation is not defined: if `x2` is not the x
+/// coordinate of a point, or `x1 = x2`, for additions, and if `y1 = 0`
+/// for doublings.
+pub fn slope(op: Secp256k1Op, x1: &U256, y1: &U256, x2: &U256) -> Option<U256> {
+    let (x1, y1) = (reduce(x1), reduce(y1));
+    let (num, den) = match op {
+        Secp256k1Op::Add => {
+            let y2 = even_y(x2)?;
+            let x2 = reduce(x2);
+            (modsub(&y2, &y1, &P), modsub(&x2, &x1, &P))
+        }
+        Secp256k1Op::Double => {
+            let xx = mul(&x1, &x1);
+            (mul(&[3, 0, 0, 0, 0, 0, 0, 0], &xx), modadd(&y1, &y1, &P))
+        }
+    };
+    if den == [0; 8] {
+        return None;
+    }
+    Some(mul(&num, &inv(&den)))
+}
+
+/// Evaluate `op`, returning the coordinates of the result, x first, or
+/// `None` if the operation is not defined (see `slope`).
+pub fn eval(op: Secp256k1Op, x1: &U256, y1: &U256, x2: &U256) -> Option<Vec<u32>> {
+    let l = slope(op, x1, y1, x2)?;
+    let (x1, y1) = (reduce(x1), reduce(y1));
+    let x2 = match op {
+        Secp256k1Op::Add => reduce(x2),
+        Secp256k1Op::Double => x1,
+    };
+    let x3 = modsub(&modsub(&mul(&l, &l), &x1, &P), &x2, &P);
+    let y3 = modsub(&mul(&l, &modsub(&x1, &x3, &P)), &y1, &P);
+    Some([x3, y3].concat())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::precompiles::bigint;
+
+    // 2G and 3G
+    const X2: U256 = [
+        0x5c709ee5, 0xabac09b9, 0x8cef3ca7, 0x5c778e4b, 0x95c07cd8, 0x3045406e, 0x41ed7d6d,
+        0xc6047f94,
+    ];
+    const Y2: U256 = [
+        0x50cfe52a, 0x236431a9, 0x3266d0e1, 0xf7f63265, 0x466ceaee, 0xa3c58419, 0xa63dc339,
+        0x1ae168fe,
+    ];
+    const X3: U256 = [
+        0xbce036f9, 0x8601f113, 0x836f99b0, 0xb531c845, 0xf89d5229, 0x49344f85, 0x9258c310,
+        0xf9308a01,
+    ];
+    const Y3: U256 = [
+        0x84b8e672, 0x6cb9fd75, 0x34c2231b, 0x6500a999, 0x2a37f356, 0x0fe337e6, 0x632de814,
+        0x388f7b0f,
+    ];
+
+    #[test]
+    fn secp256k1_ops() {
+        assert_eq!(even_y(&GX), Some(GY));
+        assert_eq!(even_y(&X2), Some(Y2));
+
+        let double = eval(Secp256k1Op::Double, &GX, &GY, &[0; 8]).unwrap();
+        assert_eq!(double, [X2, Y2].concat());
+        let add = eval(Secp256k1Op::Add, &X2, &Y2, &GX).unwrap();
+        assert_eq!(add, [X3, Y3].concat());
+
+        // operands are reduced
+        let one = [1, 0, 0, 0, 0, 0, 0, 0];
+        let (x, _) = bigint::add(&one, &P);
+        assert_eq!(even_y(&x), even_y(&one));
+        assert_eq!(
+            eval(Secp256k1Op::Add, &GX, &GY, &x),
+            eval(Secp256k1Op::Add, &GX, &GY, &one)
+        );
+    }
+
+    #[test]
+    fn secp256k1_undefined() {
+        // 5^3 + 7 = 132 is not a square modulo p
+        assert_eq!(even_y(&[5, 0, 0, 0, 0, 0, 0, 0]), None);
+        assert_eq!(
+            eval(Secp256k1Op::Add, &GX, &GY, &[5, 0, 0, 0, 0, 0, 0, 0]),
+            None
+        );
+        assert_eq!(eval(Secp256k1Op::Add, &GX, &GY, &GX), None);
+        assert_eq!(eval(Secp256k1Op::Double, &GX, &[0; 8], &[0; 8]), None);
+    }
+}
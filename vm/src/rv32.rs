@@ -1,5 +1,6 @@
 //! Abstract syntax of RV32 (based on RISC-V ISA V20191213)
 
+pub mod asm;
 mod display;
 pub mod parse;
 
@@ -0,0 +1,666 @@
+//! A small assembler for RV32 programs.
+//!
+//! The assembler accepts the instructions of `RV32`, with registers
+//! named either `x0`..`x31` or by their ABI names (`zero`, `ra`, `sp`,
+//! `t0`, `a0`, `s0`, ...). Lines may be labelled with `label:`, and
+//! comments start with `#` or `//`. Immediates are decimal, or
+//! hexadecimal with a `0x` prefix. Branch and jump targets are either
+//! labels, or offsets relative to the instruction.
+//!
+//! Loads and stores may be written as `lw rd, imm(rs1)` and
+//! `sw rs2, imm(rs1)`, or with three operands in the order used by
+//! `Display`. The `.word` directive emits literal words, and the
+//! following pseudo-instructions are supported:
+//!
+//! `nop`, `li`, `la`, `mv`, `not`, `neg`, `seqz`, `snez`, `j`, `jr`,
+//! `ret`, `call`, `beqz`, `bnez`, `blez`, `bgez`, `bltz`, `bgtz`,
+//! `bgt`, `ble`, `bgtu` and `bleu`.
+
+use std::collections::HashMap;
+
+use super::{parse::*, *};
+use crate::error::{NexusVMError::AsmError, Result};
+
+fn enc_r(opc: u32, f3: u32, f7: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    (f7 << 25) | (rs2 << 20) | (rs1 << 15) | (f3 << 12) | (rd << 7) | opc
+}
+
+fn enc_i(opc: u32, f3: u32, rd: u32, rs1: u32, imm: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (f3 << 12) | (rd << 7) | opc
+}
+
+fn enc_s(f3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    let hi = (imm >> 5) & 0x7f;
+    let lo = imm & 0x1f;
+    (hi << 25) | (rs2 << 20) | (rs1 << 15) | (f3 << 12) | (lo << 7) | OPC_STORE
+}
+
+fn enc_b(f3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    let b12 = (imm >> 12) & 1;
+    let b11 = (imm >> 11) & 1;
+    let b10_5 = (imm >> 5) & 0x3f;
+    let b4_1 = (imm >> 1) & 0xf;
+    (b12 << 31)
+        | (b10_5 << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (f3 << 12)
+        | (b4_1 << 8)
+        | (b11 << 7)
+        | OPC_BR
+}
+
+fn enc_j(rd: u32, imm: u32) -> u32 {
+    let b20 = (imm >> 20) & 1;
+    let b19_12 = (imm >> 12) & 0xff;
+    let b11 = (imm >> 11) & 1;
+    let b10_1 = (imm >> 1) & 0x3ff;
+    (b20 << 31) | (b10_1 << 21) | (b11 << 20) | (b19_12 << 12) | (rd << 7) | OPC_JAL
+}
+
+// funct3 and funct7 of ALU operations
+fn alu_funct(aop: AOP) -> (u32, u32) {
+    match aop {
+        ADD => (0b000, 0),
+        SUB => (0b000, 0b0100000),
+        SLL => (0b001, 0),
+        SLT => (0b010, 0),
+        SLTU => (0b011, 0),
+        XOR => (0b100, 0),
+        SRL => (0b101, 0),
+        SRA => (0b101, 0b0100000),
+        OR => (0b110, 0),
+        AND => (0b111, 0),
+    }
+}
+
+/// Encode an instruction as a 32-bit word. This is the inverse of
+/// `parse::parse_u32`.
+pub fn encode(inst: RV32) -> u32 {
+    match inst {
+        LUI { rd, imm } => (imm & 0xfffff000) | (rd << 7) | OPC_LUI,
+        AUIPC { rd, imm } => (imm & 0xfffff000) | (rd << 7) | OPC_AUIPC,
+        JAL { rd, imm } => enc_j(rd, imm),
+        JALR { rd, rs1, imm } => enc_i(OPC_JALR, 0, rd, rs1, imm),
+        BR { bop, rs1, rs2, imm } => {
+            let f3 = match bop {
+                BEQ => 0b000,
+                BNE => 0b001,
+                BLT => 0b100,
+                BGE => 0b101,
+                BLTU => 0b110,
+                BGEU => 0b111,
+            };
+            enc_b(f3, rs1, rs2, imm)
+        }
+        LOAD { lop, rd, rs1, imm } => {
+            let f3 = match lop {
+                LB => 0b000,
+                LH => 0b001,
+                LW => 0b010,
+                LBU => 0b100,
+                LHU => 0b101,
+            };
+            enc_i(OPC_LOAD, f3, rd, rs1, imm)
+        }
+        STORE { sop, rs1, rs2, imm } => {
+            let f3 = match sop {
+                SB => 0b000,
+                SH => 0b001,
+                SW => 0b010,
+            };
+            enc_s(f3, rs1, rs2, imm)
+        }
+        ALUI { aop, rd, rs1, imm } => {
+            let (f3, f7) = alu_funct(aop);
+            let imm = match aop {
+                SLL | SRL | SRA => (f7 << 5) | (imm & 0x1f),
+                _ => imm,
+            };
+            enc_i(OPC_ALUI, f3, rd, rs1, imm)
+        }
+        ALU { aop, rd, rs1, rs2 } => {
+            let (f3, f7) = alu_funct(aop);
+            enc_r(OPC_ALU, f3, f7, rd, rs1, rs2)
+        }
+        FENCE => 0x0ff0000f,
+        ECALL { rd } => (rd << 7) | OPC_ECALL,
+        EBREAK { rd } => 0x00100000 | (rd << 7) | OPC_ECALL,
+        UNIMP => 0xc0001073,
+    }
+}
+
+// errors are reported with their line number by `assemble_at`
+type Res<T> = std::result::Result<T, String>;
+
+#[rustfmt::skip]
+const ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2",
+    "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+    "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+    "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+];
+
+fn reg(s: &str) -> Res<u32> {
+    if let Some(n) = s.strip_prefix('x').and_then(|n| n.parse::<u32>().ok()) {
+        if n < 32 {
+            return Ok(n);
+        }
+    }
+    if s == "fp" {
+        return Ok(8);
+    }
+    ABI_NAMES
+        .iter()
+        .position(|r| *r == s)
+        .map(|n| n as u32)
+        .ok_or_else(|| format!("invalid register {s}"))
+}
+
+fn num(s: &str) -> Option<i64> {
+    let (neg, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let n = match s.strip_prefix("0x") {
+        Some(h) => i64::from_str_radix(h, 16).ok()?,
+        None => s.parse::<i64>().ok()?,
+    };
+    Some(if neg { -n } else { n })
+}
+
+fn check_range(x: i64, bits: u32, what: &str) -> Res<u32> {
+    let min = -(1i64 << (bits - 1));
+    let max = (1i64 << bits) - 1;
+    if x < min || x > max {
+        return Err(format!("{what} {x} out of range"));
+    }
+    Ok(x as u32)
+}
+
+fn is_label(s: &str) -> bool {
+    let mut cs = s.chars();
+    cs.next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '.')
+        && cs.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+// a statement, encoded once the addresses of all labels are known
+struct Stmt<'a> {
+    line: usize,
+    pc: u32,
+    op: &'a str,
+    args: Vec<&'a str>,
+}
+
+impl Stmt<'_> {
+    // number of words emitted by the statement
+    fn size(&self) -> u32 {
+        match self.op {
+            "la" => 2,
+            "li" => match self.args.get(1).and_then(|s| num(s)).map(li_parts) {
+                Some((hi, lo)) if hi != 0 && lo != 0 => 2,
+                _ => 1,
+            },
+            ".word" => self.args.len() as u32,
+            _ => 1,
+        }
+    }
+}
+
+// split a value into lui and addi immediates
+fn li_parts(v: i64) -> (u32, u32) {
+    let v = v as u32;
+    let lo = ((v << 20) as i32 >> 20) as u32;
+    let hi = v.wrapping_sub(lo) & 0xfffff000;
+    (hi, lo)
+}
+
+struct Asm<'a> {
+    labels: HashMap<&'a str, u32>,
+}
+
+fn args<'s, const N: usize>(s: &'s Stmt) -> Res<[&'s str; N]> {
+    <[&str; N]>::try_from(s.args.as_slice()).map_err(|_| format!("{} expects {N} operands", s.op))
+}
+
+impl Asm<'_> {
+    fn value(&self, s: &str) -> Res<i64> {
+        if let Some(n) = num(s) {
+            return Ok(n);
+        }
+        match self.labels.get(s) {
+            Some(a) => Ok(*a as i64),
+            None => Err(format!("undefined label or invalid number {s}")),
+        }
+    }
+
+    fn imm12(&self, s: &str) -> Res<u32> {
+        let x = self.value(s)?;
+        if !(-2048..2048).contains(&x) {
+            return Err(format!("immediate {x} out of range"));
+        }
+        Ok(x as u32)
+    }
+
+    fn shamt(&self, s: &str) -> Res<u32> {
+        match self.value(s)? {
+            x @ 0..=31 => Ok(x as u32),
+            x => Err(format!("shift amount {x} out of range")),
+        }
+    }
+
+    // offset of a branch or jump target, relative to pc
+    fn target(&self, pc: u32, s: &str, bits: u32) -> Res<u32> {
+        let off = match self.labels.get(s) {
+            Some(a) => *a as i64 - pc as i64,
+            None => num(s).ok_or_else(|| format!("undefined label {s}"))?,
+        };
+        if off & 1 != 0 {
+            return Err(format!("misaligned target {s}"));
+        }
+        let max = 1i64 << (bits - 1);
+        if !(-max..max).contains(&off) {
+            return Err(format!("offset {off} out of range"));
+        }
+        Ok(off as u32)
+    }
+
+    // memory operand, written `imm(rs1)`
+    fn mem(&self, s: &str) -> Res<(u32, u32)> {
+        let (imm, rest) = s
+            .split_once('(')
+            .ok_or_else(|| format!("invalid memory operand {s}"))?;
+        let rs1 = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("invalid memory operand {s}"))?;
+        let imm = if imm.trim().is_empty() {
+            0
+        } else {
+            self.imm12(imm.trim())?
+        };
+        Ok((reg(rs1.trim())?, imm))
+    }
+
+    fn stmt(&self, s: &Stmt) -> Res<Vec<u32>> {
+        let pc = s.pc;
+        let br = |bop: BOP, rs1: u32, rs2: u32, t: &str| -> Res<RV32> {
+            let imm = self.target(pc, t, 13)?;
+            Ok(BR { bop, rs1, rs2, imm })
+        };
+
+        let inst = match s.op {
+            ".word" => {
+                return s
+                    .args
+                    .iter()
+                    .map(|a| self.value(a).map(|v| v as u32))
+                    .collect()
+            }
+            "li" => {
+                let [rd, v] = args(s)?;
+                let v = num(v).ok_or_else(|| format!("li expects a constant, found {v}"))?;
+                check_range(v, 32, "constant")?;
+                let (hi, lo) = li_parts(v);
+                let insts = if hi == 0 {
+                    vec![ALUI { aop: ADD, rd: reg(rd)?, rs1: 0, imm: lo }]
+                } else if lo == 0 {
+                    vec![LUI { rd: reg(rd)?, imm: hi }]
+                } else {
+                    let rd = reg(rd)?;
+                    vec![LUI { rd, imm: hi }, ALUI { aop: ADD, rd, rs1: rd, imm: lo }]
+                };
+                return Ok(insts.into_iter().map(encode).collect());
+            }
+            "la" => {
+                let [rd, l] = args(s)?;
+                let rd = reg(rd)?;
+                let addr = self
+                    .labels
+                    .get(l)
+                    .ok_or_else(|| format!("undefined label {l}"))?;
+                let (hi, lo) = li_parts(addr.wrapping_sub(pc) as i64);
+                let insts = [
+                    AUIPC { rd, imm: hi },
+                    ALUI { aop: ADD, rd, rs1: rd, imm: lo },
+                ];
+                return Ok(insts.into_iter().map(encode).collect());
+            }
+
+            "lui" | "auipc" => {
+                let [rd, v] = args(s)?;
+                let imm = check_range(self.value(v)?, 20, "immediate")? << 12;
+                let rd = reg(rd)?;
+                if s.op == "lui" {
+                    LUI { rd, imm }
+                } else {
+                    AUIPC { rd, imm }
+                }
+            }
+            "jal" => match s.args.as_slice() {
+                [t] => JAL { rd: 1, imm: self.target(pc, t, 21)? },
+                [rd, t] => JAL {
+                    rd: reg(rd)?,
+                    imm: self.target(pc, t, 21)?,
+                },
+                _ => return Err("jal expects 1 or 2 operands".to_string()),
+            },
+            "jalr" => match s.args.as_slice() {
+                [rs1] => JALR { rd: 1, rs1: reg(rs1)?, imm: 0 },
+                [rd, m] if m.contains('(') => {
+                    let (rs1, imm) = self.mem(m)?;
+                    JALR { rd: reg(rd)?, rs1, imm }
+                }
+                [rd, rs1] => JALR { rd: reg(rd)?, rs1: reg(rs1)?, imm: 0 },
+                [rd, rs1, imm] => JALR {
+                    rd: reg(rd)?,
+                    rs1: reg(rs1)?,
+                    imm: self.imm12(imm)?,
+                },
+                _ => return Err("jalr expects 1 to 3 operands".to_string()),
+            },
+
+            "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" => {
+                let [rs1, rs2, t] = args(s)?;
+                let bop = match s.op {
+                    "beq" => BEQ,
+                    "bne" => BNE,
+                    "blt" => BLT,
+                    "bge" => BGE,
+                    "bltu" => BLTU,
+                    _ => BGEU,
+                };
+                br(bop, reg(rs1)?, reg(rs2)?, t)?
+            }
+            "bgt" | "ble" | "bgtu" | "bleu" => {
+                let [rs1, rs2, t] = args(s)?;
+                let bop = match s.op {
+                    "bgt" => BLT,
+                    "ble" => BGE,
+                    "bgtu" => BLTU,
+                    _ => BGEU,
+                };
+                br(bop, reg(rs2)?, reg(rs1)?, t)?
+            }
+            "beqz" | "bnez" | "bltz" | "bgez" | "blez" | "bgtz" => {
+                let [rs, t] = args(s)?;
+                let rs = reg(rs)?;
+                match s.op {
+                    "beqz" => br(BEQ, rs, 0, t)?,
+                    "bnez" => br(BNE, rs, 0, t)?,
+                    "bltz" => br(BLT, rs, 0, t)?,
+                    "bgez" => br(BGE, rs, 0, t)?,
+                    "blez" => br(BGE, 0, rs, t)?,
+                    _ => br(BLT, 0, rs, t)?,
+                }
+            }
+
+            "lb" | "lh" | "lw" | "lbu" | "lhu" => {
+                let lop = match s.op {
+                    "lb" => LB,
+                    "lh" => LH,
+                    "lw" => LW,
+                    "lbu" => LBU,
+                    _ => LHU,
+                };
+                let (rd, rs1, imm) = match s.args.as_slice() {
+                    [rd, m] => {
+                        let (rs1, imm) = self.mem(m)?;
+                        (reg(rd)?, rs1, imm)
+                    }
+                    [rd, rs1, imm] => (reg(rd)?, reg(rs1)?, self.imm12(imm)?),
+                    _ => return Err(format!("{} expects 2 or 3 operands", s.op)),
+                };
+                LOAD { lop, rd, rs1, imm }
+            }
+            "sb" | "sh" | "sw" => {
+                let sop = match s.op {
+                    "sb" => SB,
+                    "sh" => SH,
+                    _ => SW,
+                };
+                let (rs1, rs2, imm) = match s.args.as_slice() {
+                    [rs2, m] => {
+                        let (rs1, imm) = self.mem(m)?;
+                        (rs1, reg(rs2)?, imm)
+                    }
+                    [rs1, rs2, imm] => (reg(rs1)?, reg(rs2)?, self.imm12(imm)?),
+                    _ => return Err(format!("{} expects 2 or 3 operands", s.op)),
+                };
+                STORE { sop, rs1, rs2, imm }
+            }
+
+            "addi" | "slti" | "sltiu" | "sltui" | "xori" | "ori" | "andi" => {
+                let [rd, rs1, imm] = args(s)?;
+                let aop = match s.op {
+                    "addi" => ADD,
+                    "slti" => SLT,
+                    "sltiu" | "sltui" => SLTU,
+                    "xori" => XOR,
+                    "ori" => OR,
+                    _ => AND,
+                };
+                ALUI {
+                    aop,
+                    rd: reg(rd)?,
+                    rs1: reg(rs1)?,
+                    imm: self.imm12(imm)?,
+                }
+            }
+            "slli" | "srli" | "srai" => {
+                let [rd, rs1, imm] = args(s)?;
+                let aop = match s.op {
+                    "slli" => SLL,
+                    "srli" => SRL,
+                    _ => SRA,
+                };
+                ALUI {
+                    aop,
+                    rd: reg(rd)?,
+                    rs1: reg(rs1)?,
+                    imm: self.shamt(imm)?,
+                }
+            }
+            "add" | "sub" | "sll" | "slt" | "sltu" | "xor" | "srl" | "sra" | "or" | "and" => {
+                let [rd, rs1, rs2] = args(s)?;
+                let aop = match s.op {
+                    "add" => ADD,
+                    "sub" => SUB,
+                    "sll" => SLL,
+                    "slt" => SLT,
+                    "sltu" => SLTU,
+                    "xor" => XOR,
+                    "srl" => SRL,
+                    "sra" => SRA,
+                    "or" => OR,
+                    _ => AND,
+                };
+                ALU {
+                    aop,
+                    rd: reg(rd)?,
+                    rs1: reg(rs1)?,
+                    rs2: reg(rs2)?,
+                }
+            }
+
+            "fence" => FENCE,
+            "ecall" | "ebreak" => {
+                let rd = match s.args.as_slice() {
+                    [] => 0,
+                    [rd] => reg(rd)?,
+                    _ => return Err(format!("{} expects at most 1 operand", s.op)),
+                };
+                if s.op == "ecall" {
+                    ECALL { rd }
+                } else {
+                    EBREAK { rd }
+                }
+            }
+            "unimp" => UNIMP,
+
+            "nop" => ALUI { aop: ADD, rd: 0, rs1: 0, imm: 0 },
+            "mv" | "not" | "seqz" => {
+                let [rd, rs] = args(s)?;
+                let (aop, imm) = match s.op {
+                    "mv" => (ADD, 0),
+                    "not" => (XOR, -1i32 as u32),
+                    _ => (SLTU, 1),
+                };
+                ALUI { aop, rd: reg(rd)?, rs1: reg(rs)?, imm }
+            }
+            "neg" | "snez" => {
+                let [rd, rs] = args(s)?;
+                let aop = if s.op == "neg" { SUB } else { SLTU };
+                ALU { aop, rd: reg(rd)?, rs1: 0, rs2: reg(rs)? }
+            }
+            "j" => {
+                let [t] = args(s)?;
+                JAL { rd: 0, imm: self.target(pc, t, 21)? }
+            }
+            "call" => {
+                let [t] = args(s)?;
+                JAL { rd: 1, imm: self.target(pc, t, 21)? }
+            }
+            "jr" => {
+                let [rs] = args(s)?;
+                JALR { rd: 0, rs1: reg(rs)?, imm: 0 }
+            }
+            "ret" => JALR { rd: 0, rs1: 1, imm: 0 },
+
+            op => return Err(format!("unknown instruction {op}")),
+        };
+        Ok(vec![encode(inst)])
+    }
+}
+
+/// Assemble a program to be loaded at address 0.
+pub fn assemble(src: &str) -> Result<Vec<u32>> {
+    assemble_at(0, src)
+}
+
+/// Assemble a program to be loaded at address `base`.
+pub fn assemble_at(base: u32, src: &str) -> Result<Vec<u32>> {
+    let mut labels = HashMap::new();
+    let mut stmts = Vec::new();
+    let mut pc = base;
+
+    for (i, line) in src.lines().enumerate() {
+        let line = line.split('#').next().unwrap();
+        let mut line = line.split("//").next().unwrap().trim();
+
+        while let Some((l, rest)) = line.split_once(':') {
+            let l = l.trim();
+            if !is_label(l) {
+                break;
+            }
+            if labels.insert(l, pc).is_some() {
+                return Err(AsmError(i + 1, format!("duplicate label {l}")));
+            }
+            line = rest.trim();
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let (op, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .collect();
+        let stmt = Stmt { line: i + 1, pc, op, args };
+        pc += 4 * stmt.size();
+        stmts.push(stmt);
+    }
+
+    let asm = Asm { labels };
+    let mut words = Vec::new();
+    for s in &stmts {
+        let w = asm.stmt(s).map_err(|e| AsmError(s.line, e))?;
+        debug_assert_eq!(w.len() as u32, s.size());
+        words.extend(w);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let insts = [
+            LUI { rd: 1, imm: 0xaaaab000 },
+            AUIPC { rd: 31, imm: 0x1000 },
+            JAL { rd: 1, imm: 0xffffffe0 },
+            JALR { rd: 3, rs1: 1, imm: 12 },
+            BR {
+                bop: BLTU,
+                rs1: 2,
+                rs2: 3,
+                imm: 0xfffff800,
+            },
+            LOAD { lop: LHU, rd: 4, rs1: 5, imm: 0xfffffffc },
+            STORE { sop: SB, rs1: 6, rs2: 7, imm: 0x7ff },
+            ALUI { aop: SRA, rd: 8, rs1: 9, imm: 31 },
+            ALUI {
+                aop: SLTU,
+                rd: 8,
+                rs1: 9,
+                imm: 0xfffff800,
+            },
+            ALU { aop: SUB, rd: 10, rs1: 11, rs2: 12 },
+            FENCE,
+            ECALL { rd: 10 },
+            EBREAK { rd: 3 },
+            UNIMP,
+        ];
+        for inst in insts {
+            assert_eq!(parse_u32(encode(inst)), Some(inst));
+        }
+    }
+
+    #[test]
+    fn test_assemble() {
+        let src = "
+            start:  li   t0, 0x12345678   # two instructions
+                    li   t1, -1
+                    addi x1, x0, 1
+            loop:   bne  x1, t1, loop
+                    sw   t0, 4(sp)
+                    lw   a0, -4(sp)
+                    j    start
+                    .word 0xc0001073, start
+        ";
+        let words = assemble(src).unwrap();
+        assert_eq!(words.len(), 10);
+        assert_eq!(words[2], 0xfff00313); // addi t1, x0, -1
+        assert_eq!(words[3], 0x00100093); // addi x1, x0, 1
+        assert_eq!(words[4], 0x00609063); // bne x1, t1, 0
+        assert_eq!(words[5], 0x00512223); // sw t0, 4(sp)
+        assert_eq!(words[6], 0xffc12503); // lw a0, -4(sp)
+        assert_eq!(words[7], 0xfe5ff06f); // jal x0, -28
+        assert_eq!(words[8..], [0xc0001073, 0]);
+
+        let (hi, lo) = (words[0], words[1]);
+        assert_eq!(parse_u32(hi), Some(LUI { rd: 5, imm: 0x12345000 }));
+        assert_eq!(
+            parse_u32(lo),
+            Some(ALUI { aop: ADD, rd: 5, rs1: 5, imm: 0x678 })
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        for src in [
+            "addi x1, x2",
+            "addi x1, x2, 4096",
+            "add x1, x2, x32",
+            "beq x1, x2, nowhere",
+            "foo x1",
+            "a: nop\na: nop",
+        ] {
+            assert!(matches!(assemble(src), Err(AsmError(..))), "{src}");
+        }
+    }
+}
@@ -1,25 +1,112 @@
 //! Implementation of system calls
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::io::Write;
 
 use crate::{
-    error::{NexusVMError::UnknownECall, Result},
-    memory::Memory,
-    rv32::LOP,
+    error::{
+        NexusVMError::{
+            ArgumentTooLong, HintExhausted, InputExhausted, InvalidAddress, InvalidCurvePoint,
+            InvalidFieldElement, PrecompileNotSelected, UnknownECall, UnknownHint,
+            UnmatchedProfileEnd, ZeroModulus,
+        },
+        Result,
+    },
+    memory::{
+        path::{poseidon_config, Params},
+        Memory,
+    },
+    precompiles::{
+        bigint::{self, BigintOp, U256},
+        ed25519,
+        poseidon::{self, PoseidonOp},
+        secp256k1::{self, Secp256k1Op},
+        Precompiles,
+    },
 };
 
+/// Heap usage statistics reported by a program; see `nexus_rt::HeapStats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeapStats {
+    /// Number of bytes currently allocated
+    pub allocated: u32,
+    /// Largest number of bytes allocated at any time
+    pub peak: u32,
+    /// Number of bytes taken from the heap, including freed blocks
+    pub heap_size: u32,
+    /// Number of allocations
+    pub allocs: u32,
+    /// Number of deallocations
+    pub frees: u32,
+}
+
+/// Execution cost of a profiled region of a program.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileRegion {
+    /// Name of the region
+    pub name: String,
+    /// Number of times the region was executed
+    pub count: u32,
+    /// Total number of instructions executed in the region
+    pub cycles: u64,
+}
+
+/// Largest number of values an ecall reads from memory, which bounds
+/// the memory allocated by the host for ecall arguments.
+pub const MAX_ARG_LEN: u32 = 1 << 16;
+
+/// Registers read and written by precompiles which take their operands
+/// from registers: a0-a7, which hold the first operand and receive the
+/// result, followed by s4-s11, which hold the second operand and
+/// receive the high half of 512-bit results. Operands and results are
+/// 256-bit values, least significant word first.
+pub const PRECOMPILE_REGS: [usize; 16] = [
+    10, 11, 12, 13, 14, 15, 16, 17, 20, 21, 22, 23, 24, 25, 26, 27,
+];
+
+/// Registers holding the modulus of modular multiplications, and the
+/// second point of elliptic curve additions: t0-t2, s3 and t3-t6.
+pub const MODULUS_REGS: [usize; 8] = [5, 6, 7, 19, 28, 29, 30, 31];
+
+/// Identifier of the input stream holding the private input.
+pub const PRIVATE_INPUT: u32 = 0;
+
+/// Largest number of bytes read from an input stream by one ecall.
+/// The bytes are returned in a0-a7, and stored to memory by the program.
+pub const MAX_STREAM_READ: u32 = 32;
+
+/// A host function computing a hint from its arguments.
+pub type Hint = Box<dyn FnMut(&[u32]) -> Vec<u32> + Send>;
+
 /// Holds information related to syscall implementation.
 pub struct Syscalls {
     output_enable: bool,
-    input: VecDeque<u8>,
+    streams: HashMap<u32, VecDeque<u8>>,
+    heap_stats: Option<HeapStats>,
+    panic: Option<Vec<u8>>,
+    profile: Vec<ProfileRegion>,
+    open_regions: Vec<(String, u64)>,
+    hints: HashMap<u32, Hint>,
+    hint_output: VecDeque<u32>,
+    reg_writes: Vec<(u32, u32)>,
+    poseidon_params: Option<Params>,
+    precompiles: Precompiles,
 }
 
 impl Default for Syscalls {
     fn default() -> Self {
         Self {
             output_enable: true,
-            input: VecDeque::new(),
+            streams: HashMap::new(),
+            heap_stats: None,
+            panic: None,
+            profile: Vec::new(),
+            open_regions: Vec::new(),
+            hints: HashMap::new(),
+            hint_output: VecDeque::new(),
+            reg_writes: Vec::new(),
+            poseidon_params: None,
+            precompiles: Precompiles::default(),
         }
     }
 }
@@ -33,31 +120,240 @@ impl Syscalls {
         self.output_enable = false;
     }
 
+    /// Set the private input of the program.
     pub fn set_input(&mut self, slice: &[u8]) {
-        self.input = slice.to_owned().into();
+        self.set_stream(PRIVATE_INPUT, slice);
+    }
+
+    /// Set the contents of input stream `id`.
+    pub fn set_stream(&mut self, id: u32, slice: &[u8]) {
+        self.streams.insert(id, slice.to_owned().into());
+    }
+
+    /// Register a hint with identifier `id`. When the program requests
+    /// the hint, `f` is called with the arguments supplied by the
+    /// program, and its result is passed back to the program. Hints
+    /// are not checked by the proof: the program must check that the
+    /// result is correct.
+    pub fn register_hint(&mut self, id: u32, f: impl FnMut(&[u32]) -> Vec<u32> + Send + 'static) {
+        self.hints.insert(id, Box::new(f));
+    }
+
+    /// Select the precompiles the program may call, which must be the
+    /// ones the step circuit is set up for.
+    pub fn set_precompiles(&mut self, precompiles: Precompiles) {
+        self.precompiles = precompiles;
+    }
+
+    /// Returns the precompiles the program may call.
+    pub fn precompiles(&self) -> Precompiles {
+        self.precompiles
+    }
+
+    /// Returns the heap usage statistics last reported by the program.
+    pub fn heap_stats(&self) -> Option<HeapStats> {
+        self.heap_stats
+    }
+
+    /// If the program panicked, returns the log output written since
+    /// the panic, which holds the panic message.
+    pub fn panic_message(&self) -> Option<String> {
+        self.panic
+            .as_ref()
+            .map(|msg| String::from_utf8_lossy(msg).trim_end().to_string())
+    }
+
+    /// Returns the profiled regions of the program, in the order in
+    /// which they were first completed.
+    pub fn profile(&self) -> &[ProfileRegion] {
+        &self.profile
+    }
+
+    /// Returns the registers written by the last ecall besides its
+    /// destination register, and their values. These are written after
+    /// the destination register.
+    pub fn take_reg_writes(&mut self) -> Vec<(u32, u32)> {
+        std::mem::take(&mut self.reg_writes)
     }
 
-    pub fn syscall(&mut self, pc: u32, regs: [u32; 32], memory: &impl Memory) -> Result<u32> {
+    // write the result of a precompile to a0-a7, and then s4-s11
+    fn write_result(&mut self, words: &[u32]) {
+        self.reg_writes = PRECOMPILE_REGS
+            .iter()
+            .zip(words)
+            .map(|(r, w)| (*r as u32, *w))
+            .collect();
+    }
+
+    fn end_region(&mut self, name: String, cycles: u64) -> Result<()> {
+        let Some(i) = self.open_regions.iter().rposition(|(n, _)| *n == name) else {
+            return Err(UnmatchedProfileEnd(name));
+        };
+        let (_, start) = self.open_regions.remove(i);
+        let region = match self.profile.iter_mut().find(|r| r.name == name) {
+            Some(r) => r,
+            None => {
+                self.profile
+                    .push(ProfileRegion { name, ..ProfileRegion::default() });
+                self.profile.last_mut().unwrap()
+            }
+        };
+        region.count += 1;
+        region.cycles += cycles - start;
+        Ok(())
+    }
+
+    /// Execute the system call requested by the program. `cycles` is
+    /// the number of instructions executed before the call.
+    pub fn syscall(
+        &mut self,
+        pc: u32,
+        cycles: u64,
+        regs: [u32; 32],
+        memory: &impl Memory,
+    ) -> Result<u32> {
         let num = regs[18]; // s2 = x18  syscall number
         let inp1 = regs[11]; // a1 = x11
         let inp2 = regs[12]; // a2 = x12
+        let inp3 = regs[13]; // a3 = x13
 
         let mut out = 0x0;
+        self.reg_writes.clear();
+
+        if !self.precompiles.allows(num) {
+            return Err(PrecompileNotSelected(pc, num));
+        }
 
         if num == 1 {
             // write_log
             let mut stdout = std::io::stdout();
-            for addr in inp1..inp1 + inp2 {
-                let b = memory.load(LOP::LBU, addr)?.0;
+            for addr in inp1..offset(inp1, inp2 as u64)? {
+                let b = memory.peek(addr)?.lbu(addr)?;
                 stdout.write_all(&[b as u8])?;
+                if let Some(msg) = &mut self.panic {
+                    msg.push(b as u8);
+                }
             }
             let _ = stdout.flush();
         } else if num == 2 {
             // read_from_private_input
-            match self.input.pop_front() {
+            let input = self.streams.entry(PRIVATE_INPUT).or_default();
+            match input.pop_front() {
                 Some(b) => out = b as u32,
                 None => out = u32::MAX, // out of range of possible u8 inputs
             }
+        } else if num == 3 {
+            // report_heap_stats
+            let mut w = [0u32; 5];
+            for (i, x) in w.iter_mut().enumerate().take(inp2 as usize / 4) {
+                let addr = offset(inp1, 4 * i as u64)?;
+                *x = memory.peek(addr)?.lw(addr)?;
+            }
+            let [allocated, peak, heap_size, allocs, frees] = w;
+            self.heap_stats = Some(HeapStats {
+                allocated,
+                peak,
+                heap_size,
+                allocs,
+                frees,
+            });
+        } else if num == 4 {
+            // panic: log output written from now on holds the message
+            self.panic = Some(Vec::new());
+        } else if num == 5 {
+            // cycle_count
+            out = cycles as u32;
+        } else if num == 6 || num == 7 {
+            // profile_start, profile_end
+            let name = read_string(memory, pc, inp1, inp2)?;
+            if num == 6 {
+                self.open_regions.push((name, cycles));
+            } else {
+                self.end_region(name, cycles)?;
+            }
+        } else if num == 8 {
+            // hint: a1 = id, a2 = address of arguments, a3 = number of arguments
+            let f = self.hints.get_mut(&inp1).ok_or(UnknownHint(pc, inp1))?;
+            check_len(pc, inp3)?;
+            let args = (0..inp3)
+                .map(|i| {
+                    let addr = offset(inp2, 4 * i as u64)?;
+                    memory.peek(addr)?.lw(addr)
+                })
+                .collect::<Result<Vec<u32>>>()?;
+            self.hint_output = f(&args).into();
+            out = self.hint_output.len() as u32;
+        } else if num == 9 {
+            // read_hint
+            out = self.hint_output.pop_front().ok_or(HintExhausted(pc))?;
+        } else if num == 10 {
+            // stream_len: a1 = id, the length is written to a0
+            let len = self.streams.get(&inp1).map_or(0, |s| s.len() as u32);
+            self.write_result(&[len]);
+        } else if num == 11 {
+            // read_stream: a1 = id, a2 = number of bytes, at most
+            // `MAX_STREAM_READ`, written to a0-a7
+            let stream = self.streams.entry(inp1).or_default();
+            let n = inp2.min(MAX_STREAM_READ) as usize;
+            if stream.len() < n {
+                return Err(InputExhausted(pc, inp1));
+            }
+            let mut words = [0u32; 8];
+            for (i, b) in stream.drain(..n).enumerate() {
+                words[i / 4] |= (b as u32) << (8 * (i % 4));
+            }
+            self.write_result(&words);
+        } else if (12..=14).contains(&num) {
+            // bigint add, mul, modmul: a0-a7, s4-s11, t0-t2 s3 t3-t6 = a, b, m
+            let op = [BigintOp::Add, BigintOp::Mul, BigintOp::ModMul][num as usize - 12];
+            let a = operand(&regs, &PRECOMPILE_REGS[..8]);
+            let b = operand(&regs, &PRECOMPILE_REGS[8..]);
+            let m = operand(&regs, &MODULUS_REGS);
+            let mut res = bigint::eval(op, &a, &b, &m).ok_or(ZeroModulus(pc))?;
+            if op == BigintOp::Add {
+                // the carry is written to s4, and s5-s11 are cleared
+                res.resize(16, 0);
+            }
+            self.write_result(&res);
+        } else if num == 15 || num == 16 {
+            // poseidon hash, compress: a0-a7, s4-s11 = field elements
+            let op = if num == 15 {
+                PoseidonOp::Hash
+            } else {
+                PoseidonOp::Compress
+            };
+            let [l, r] = [&PRECOMPILE_REGS[..8], &PRECOMPILE_REGS[8..]]
+                .map(|rs| poseidon::from_words(&operand(&regs, rs)).ok_or(InvalidFieldElement(pc)));
+            let params = self.poseidon_params.get_or_insert_with(poseidon_config);
+            let digest = poseidon::eval(params, op, &[l?, r?])?;
+            self.write_result(&poseidon::to_words(digest));
+        } else if num == 17 || num == 18 {
+            // secp256k1 add, double: a0-a7, s4-s11 = x1, y1, and for
+            // additions, t0-t2 s3 t3-t6 = x2 of the point with even y2
+            let op = if num == 17 {
+                Secp256k1Op::Add
+            } else {
+                Secp256k1Op::Double
+            };
+            let [x1, y1, x2] = [
+                &PRECOMPILE_REGS[..8],
+                &PRECOMPILE_REGS[8..],
+                &MODULUS_REGS[..],
+            ]
+            .map(|rs| operand(&regs, rs));
+            let res = secp256k1::eval(op, &x1, &y1, &x2).ok_or(InvalidCurvePoint(pc))?;
+            self.write_result(&res);
+        } else if num == 19 {
+            // ed25519 add: a0-a7, s4-s11 = x1, y1, and t0-t2 s3 t3-t6 =
+            // the encoding of the second point
+            let [x1, y1, q] = [
+                &PRECOMPILE_REGS[..8],
+                &PRECOMPILE_REGS[8..],
+                &MODULUS_REGS[..],
+            ]
+            .map(|rs| operand(&regs, rs));
+            let res = ed25519::eval(&x1, &y1, &q).ok_or(InvalidCurvePoint(pc))?;
+            self.write_result(&res);
         } else {
             return Err(UnknownECall(pc, num));
         }
@@ -65,3 +361,343 @@ impl Syscalls {
         Ok(out)
     }
 }
+
+fn check_len(pc: u32, len: u32) -> Result<()> {
+    if len > MAX_ARG_LEN {
+        return Err(ArgumentTooLong(pc, len));
+    }
+    Ok(())
+}
+
+// Returns `addr + off`, failing if it is beyond the address space.
+fn offset(addr: u32, off: u64) -> Result<u32> {
+    u32::try_from(addr as u64 + off).map_err(|_| InvalidAddress(addr))
+}
+
+fn read_bytes(memory: &impl Memory, pc: u32, addr: u32, len: u32) -> Result<Vec<u8>> {
+    check_len(pc, len)?;
+    (addr..offset(addr, len as u64)?)
+        .map(|a| Ok(memory.peek(a)?.lbu(a)? as u8))
+        .collect()
+}
+
+fn read_string(memory: &impl Memory, pc: u32, addr: u32, len: u32) -> Result<String> {
+    let bytes = read_bytes(memory, pc, addr, len)?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+// read a 256-bit operand from registers `rs`
+fn operand(regs: &[u32; 32], rs: &[usize]) -> U256 {
+    std::array::from_fn(|i| regs[rs[i]])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        circuit::F,
+        memory::{
+            paged::Paged,
+            path::{compress, hash_leaf},
+        },
+    };
+
+    #[test]
+    fn test_heap_stats() {
+        let mut mem = Paged::default();
+        for (i, w) in [10u32, 20, 30, 4, 3].iter().enumerate() {
+            let addr = 0x100 + 4 * i as u32;
+            mem.store(crate::rv32::SOP::SW, addr, *w).unwrap();
+        }
+
+        let mut sys = Syscalls::default();
+        assert_eq!(sys.heap_stats(), None);
+
+        let mut regs = [0; 32];
+        regs[18] = 3;
+        regs[11] = 0x100;
+        regs[12] = 20;
+        assert_eq!(sys.syscall(0, 0, regs, &mem).unwrap(), 0);
+
+        let stats = HeapStats {
+            allocated: 10,
+            peak: 20,
+            heap_size: 30,
+            allocs: 4,
+            frees: 3,
+        };
+        assert_eq!(sys.heap_stats(), Some(stats));
+    }
+
+    #[test]
+    fn test_heap_stats_bounds() {
+        let mem = Paged::default();
+        let mut sys = Syscalls::default();
+        let mut regs = [0; 32];
+        regs[18] = 3;
+        regs[11] = u32::MAX - 7;
+        regs[12] = 20;
+        assert!(matches!(
+            sys.syscall(0, 0, regs, &mem),
+            Err(InvalidAddress(a)) if a == u32::MAX - 7
+        ));
+    }
+
+    #[test]
+    fn test_panic() {
+        let mut mem = Paged::default();
+        for (i, b) in b"oops\n".iter().enumerate() {
+            mem.store(crate::rv32::SOP::SB, 0x100 + i as u32, *b as u32)
+                .unwrap();
+        }
+
+        let mut sys = Syscalls::default();
+        let mut regs = [0; 32];
+        regs[11] = 0x100;
+        regs[12] = 5;
+
+        // output before the panic is not part of the message
+        regs[18] = 1;
+        sys.syscall(0, 0, regs, &mem).unwrap();
+        assert_eq!(sys.panic_message(), None);
+
+        regs[18] = 4;
+        sys.syscall(0, 0, regs, &mem).unwrap();
+        assert_eq!(sys.panic_message().as_deref(), Some(""));
+
+        regs[18] = 1;
+        sys.syscall(0, 0, regs, &mem).unwrap();
+        assert_eq!(sys.panic_message().as_deref(), Some("oops"));
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut mem = Paged::default();
+        for (i, b) in b"ab".iter().enumerate() {
+            mem.store(crate::rv32::SOP::SB, 0x100 + i as u32, *b as u32)
+                .unwrap();
+        }
+
+        let mut sys = Syscalls::default();
+        let mut call = |num, name: u32, cycles| {
+            let mut regs = [0; 32];
+            regs[18] = num;
+            regs[11] = name;
+            regs[12] = if name == 0x100 { 2 } else { 1 };
+            sys.syscall(0, cycles, regs, &mem)
+        };
+
+        assert_eq!(call(5, 0, 1234).unwrap(), 1234);
+
+        // "ab" contains "b", and is executed twice
+        call(6, 0x100, 10).unwrap();
+        call(6, 0x101, 12).unwrap();
+        call(7, 0x101, 20).unwrap();
+        call(7, 0x100, 30).unwrap();
+        call(6, 0x100, 40).unwrap();
+        call(7, 0x100, 45).unwrap();
+        assert!(matches!(
+            call(7, 0x101, 50),
+            Err(UnmatchedProfileEnd(n)) if n == "b"
+        ));
+
+        // names must lie within the address space, and be of bounded length
+        let mut regs = [0; 32];
+        regs[18] = 6;
+        regs[11] = u32::MAX - 1;
+        regs[12] = 4;
+        assert!(matches!(
+            sys.syscall(0, 0, regs, &mem),
+            Err(InvalidAddress(_))
+        ));
+        regs[11] = 0x100;
+        regs[12] = u32::MAX;
+        assert!(matches!(
+            sys.syscall(0, 0, regs, &mem),
+            Err(ArgumentTooLong(0, u32::MAX))
+        ));
+
+        let region =
+            |name: &str, count, cycles| ProfileRegion { name: name.to_string(), count, cycles };
+        assert_eq!(sys.profile(), [region("b", 1, 8), region("ab", 2, 25)]);
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut mem = Paged::default();
+        mem.store(crate::rv32::SOP::SW, 0x100, 17).unwrap();
+        mem.store(crate::rv32::SOP::SW, 0x104, 5).unwrap();
+
+        let mut sys = Syscalls::default();
+        sys.register_hint(1, |args| vec![args[0] / args[1], args[0] % args[1]]);
+
+        let mut call = |num, id| {
+            let mut regs = [0; 32];
+            regs[18] = num;
+            regs[11] = id;
+            regs[12] = 0x100;
+            regs[13] = 2;
+            sys.syscall(0, 0, regs, &mem)
+        };
+
+        assert_eq!(call(8, 1).unwrap(), 2);
+        assert_eq!(call(9, 0).unwrap(), 3);
+        assert_eq!(call(9, 0).unwrap(), 2);
+        assert!(matches!(call(9, 0), Err(HintExhausted(0))));
+        assert!(matches!(call(8, 2), Err(UnknownHint(0, 2))));
+
+        let mut regs = [0; 32];
+        regs[18] = 8;
+        regs[11] = 1;
+        regs[12] = u32::MAX - 3;
+        regs[13] = 2;
+        assert!(matches!(
+            sys.syscall(0, 0, regs, &mem),
+            Err(InvalidAddress(_))
+        ));
+        regs[12] = 0x100;
+        regs[13] = MAX_ARG_LEN + 1;
+        assert!(matches!(
+            sys.syscall(0, 0, regs, &mem),
+            Err(ArgumentTooLong(0, n)) if n == MAX_ARG_LEN + 1
+        ));
+    }
+
+    #[test]
+    fn test_streams() {
+        let mem = Paged::default();
+        let mut sys = Syscalls::default();
+        sys.set_input(&[7]);
+        let bytes: Vec<u8> = (1..=40).collect();
+        sys.set_stream(1, &bytes);
+
+        let mut call = |num, id, n| {
+            let mut regs = [0; 32];
+            regs[18] = num;
+            regs[11] = id;
+            regs[12] = n;
+            sys.syscall(0, 0, regs, &mem).map(|out| {
+                let writes = sys.take_reg_writes();
+                let regs: Vec<usize> = writes.iter().map(|(r, _)| *r as usize).collect();
+                assert_eq!(regs, PRECOMPILE_REGS[..writes.len()]);
+                (
+                    out,
+                    writes.into_iter().map(|(_, w)| w).collect::<Vec<u32>>(),
+                )
+            })
+        };
+        let words = |bytes: &[u8]| -> Vec<u32> {
+            let mut w = vec![0; 8];
+            for (i, b) in bytes.iter().enumerate() {
+                w[i / 4] |= (*b as u32) << (8 * (i % 4));
+            }
+            w
+        };
+
+        assert_eq!(call(10, 1, 0).unwrap().1, [40]);
+        assert_eq!(call(10, 2, 0).unwrap().1, [0]);
+
+        // reads are truncated to `MAX_STREAM_READ` bytes
+        assert_eq!(call(11, 1, 100).unwrap().1, words(&bytes[..32]));
+        assert_eq!(call(10, 1, 0).unwrap().1, [8]);
+        assert!(matches!(call(11, 1, 9), Err(InputExhausted(0, 1))));
+        assert_eq!(call(11, 1, 6).unwrap().1, words(&bytes[32..38]));
+        assert_eq!(call(11, 1, 2).unwrap().1, words(&bytes[38..]));
+
+        // the private input is stream 0
+        assert_eq!(call(10, PRIVATE_INPUT, 0).unwrap().1, [1]);
+        assert_eq!(call(2, 0, 0).unwrap(), (7, vec![]));
+        assert_eq!(call(2, 0, 0).unwrap(), (u32::MAX, vec![]));
+    }
+
+    #[test]
+    fn test_bigint() {
+        let mem = Paged::default();
+        let mut sys = Syscalls::default();
+        let mut regs = [0; 32];
+        regs[18] = 12;
+        assert!(matches!(
+            sys.syscall(4, 0, regs, &mem),
+            Err(PrecompileNotSelected(4, 12))
+        ));
+
+        sys.set_precompiles(Precompiles::ALL);
+        let mut call = |num, a: U256, b: U256, m: U256| {
+            let mut regs = [0; 32];
+            regs[18] = num;
+            for i in 0..8 {
+                regs[PRECOMPILE_REGS[i]] = a[i];
+                regs[PRECOMPILE_REGS[8 + i]] = b[i];
+                regs[MODULUS_REGS[i]] = m[i];
+            }
+            sys.syscall(0, 0, regs, &mem).map(|_| {
+                let writes = sys.take_reg_writes();
+                let regs: Vec<usize> = writes.iter().map(|(r, _)| *r as usize).collect();
+                assert_eq!(regs, PRECOMPILE_REGS[..writes.len()]);
+                writes.into_iter().map(|(_, w)| w).collect::<Vec<u32>>()
+            })
+        };
+
+        let max = [u32::MAX; 8];
+        let three = [3, 0, 0, 0, 0, 0, 0, 0];
+        let ten = [10, 0, 0, 0, 0, 0, 0, 0];
+
+        // (2^256 - 1) + 3 = 2 + 2^256
+        let mut sum = vec![0; 16];
+        sum[0] = 2;
+        sum[8] = 1;
+        assert_eq!(call(12, max, three, [0; 8]).unwrap(), sum);
+
+        let prod = call(13, max, three, [0; 8]).unwrap();
+        assert_eq!(prod, bigint::mul(&max, &three));
+
+        // (2^256 - 1) * 3 mod 10 = 5
+        assert_eq!(call(14, max, three, ten).unwrap(), [5, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(call(14, max, three, [0; 8]), Err(ZeroModulus(0))));
+    }
+
+    #[test]
+    fn test_poseidon() {
+        let mem = Paged::default();
+        let mut sys = Syscalls::default();
+        sys.set_precompiles(Precompiles { bigint: true, ..Default::default() });
+        let mut regs = [0; 32];
+        regs[18] = 15;
+        assert!(matches!(
+            sys.syscall(4, 0, regs, &mem),
+            Err(PrecompileNotSelected(4, 15))
+        ));
+
+        sys.set_precompiles(Precompiles::ALL);
+        let mut call = |num, l: [u32; 8], r: [u32; 8]| {
+            let mut regs = [0; 32];
+            regs[18] = num;
+            for i in 0..8 {
+                regs[PRECOMPILE_REGS[i]] = l[i];
+                regs[PRECOMPILE_REGS[8 + i]] = r[i];
+            }
+            sys.syscall(0, 0, regs, &mem).map(|_| sys.take_reg_writes())
+        };
+
+        let params = poseidon_config();
+        let (a, b) = (F::from(1u64), F::from(2u64));
+        let (wa, wb) = (poseidon::to_words(a), poseidon::to_words(b));
+        for (num, expected) in [
+            (15, hash_leaf(&params, &[a, b]).unwrap()),
+            (16, compress(&params, &a, &b).unwrap()),
+        ] {
+            let writes = call(num, wa, wb).unwrap();
+            let regs: Vec<u32> = writes.iter().map(|(r, _)| *r).collect();
+            let words: Vec<u32> = writes.iter().map(|(_, w)| *w).collect();
+            assert_eq!(regs, [10, 11, 12, 13, 14, 15, 16, 17]);
+            assert_eq!(words, poseidon::to_words(expected));
+        }
+
+        // other ecalls write no other registers
+        assert_eq!(call(5, wa, wb).unwrap(), []);
+        assert!(matches!(
+            call(16, wa, [u32::MAX; 8]),
+            Err(InvalidFieldElement(0))
+        ));
+    }
+}
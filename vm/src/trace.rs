@@ -17,7 +17,9 @@ use crate::circuit::F;
 use crate::error::Result;
 use crate::eval::{eval_inst, NexusVM, Regs};
 use crate::memory::{Memory, MemoryProof};
+use crate::precompiles::Precompiles;
 use crate::rv32::{parse::*, RV32::UNIMP};
+use crate::syscalls::PRECOMPILE_REGS;
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
@@ -29,6 +31,8 @@ pub struct Trace<P: MemoryProof> {
     pub k: usize,
     /// First block in this (sub)trace.
     pub start: usize,
+    /// Precompiles the program may call.
+    pub precompiles: Precompiles,
     /// The blocks contained in this trace.
     pub blocks: Vec<Block<P>>,
 }
@@ -51,6 +55,8 @@ pub struct Step<P: MemoryProof> {
     pub Z: u32,
     /// Next program counter, for jump and branch instructions.
     pub PC: Option<u32>,
+    /// Values of `PRECOMPILE_REGS` after precompile ecalls.
+    pub precompile_regs: Option<[u32; 16]>,
     /// Merkle proof for instruction at pc.
     #[serde(with = "crate::ark_serde")]
     pub pc_proof: P,
@@ -70,7 +76,12 @@ impl<P: MemoryProof> Trace<P> {
         self.blocks.chunks(n).map(move |bs| {
             let start = index;
             index += n;
-            Trace { k: self.k, start, blocks: bs.to_vec() }
+            Trace {
+                k: self.k,
+                start,
+                precompiles: self.precompiles,
+                blocks: bs.to_vec(),
+            }
         })
     }
 
@@ -87,6 +98,7 @@ impl<P: MemoryProof> Trace<P> {
         Some(Trace {
             k: self.k,
             start: n,
+            precompiles: self.precompiles,
             blocks: vec![self.block(n)?.clone()],
         })
     }
@@ -100,7 +112,7 @@ impl<P: MemoryProof> Trace<P> {
         for x in b.regs.x {
             v.push(F::from(x));
         }
-        v.push(b.steps[0].pc_proof.commit());
+        v.push(b.steps[0].pc_proof.commit_prior());
         Some(v)
     }
 
@@ -126,6 +138,7 @@ fn step<M: Memory>(vm: &mut NexusVM<M>) -> Result<Step<M::Proof>> {
         } else {
             Some(vm.regs.pc)
         },
+        precompile_regs: vm.precompile_regs,
         pc_proof: vm.pc_proof.clone(),
         read_proof: vm.read_proof.clone(),
         write_proof: vm.write_proof.clone(),
@@ -134,7 +147,7 @@ fn step<M: Memory>(vm: &mut NexusVM<M>) -> Result<Step<M::Proof>> {
 }
 
 // Generate a `Block` by evaluating `k` steps of `vm`.
-fn k_step<M: Memory>(vm: &mut NexusVM<M>, k: usize) -> Result<Block<M::Proof>> {
+pub(crate) fn k_step<M: Memory>(vm: &mut NexusVM<M>, k: usize) -> Result<Block<M::Proof>> {
     let mut block = Block { regs: vm.regs.clone(), steps: Vec::new() };
 
     for _ in 0..k {
@@ -149,7 +162,12 @@ fn k_step<M: Memory>(vm: &mut NexusVM<M>, k: usize) -> Result<Block<M::Proof>> {
 /// rounded up to the nearest power of two by inserting UNIMP
 /// instructions.
 pub fn trace<M: Memory>(vm: &mut NexusVM<M>, k: usize, pow: bool) -> Result<Trace<M::Proof>> {
-    let mut trace = Trace { k, start: 0, blocks: Vec::new() };
+    let mut trace = Trace {
+        k,
+        start: 0,
+        precompiles: vm.syscalls.precompiles(),
+        blocks: Vec::new(),
+    };
 
     loop {
         let block = k_step(vm, k)?;
@@ -169,6 +187,56 @@ pub fn trace<M: Memory>(vm: &mut NexusVM<M>, k: usize, pow: bool) -> Result<Trac
     Ok(trace)
 }
 
+/// Generates a program trace incrementally, so that only the part
+/// currently being processed has to be held in memory.
+pub struct Tracer<M: Memory> {
+    vm: NexusVM<M>,
+    k: usize,
+    start: usize,
+    halted: bool,
+}
+
+impl<M: Memory> Tracer<M> {
+    /// Create a tracer evaluating `vm`, using `k` steps per block.
+    pub fn new(vm: NexusVM<M>, k: usize) -> Self {
+        Self { vm, k, start: 0, halted: false }
+    }
+
+    /// Index of the next block to be evaluated.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns true if the program has halted.
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Evaluate up to `n` blocks, stopping after the block in which the
+    /// program halts. Once the program has halted, every call returns
+    /// `n` blocks of UNIMP instructions, which can be used as padding.
+    pub fn next_blocks(&mut self, n: usize) -> Result<Trace<M::Proof>> {
+        let mut trace = Trace {
+            k: self.k,
+            start: self.start,
+            precompiles: self.vm.syscalls.precompiles(),
+            blocks: Vec::new(),
+        };
+        let halted = self.halted;
+        while trace.blocks.len() < n {
+            trace.blocks.push(k_step(&mut self.vm, self.k)?);
+            if self.vm.inst.inst == UNIMP {
+                self.halted = true;
+                if !halted {
+                    break;
+                }
+            }
+        }
+        self.start += trace.blocks.len();
+        Ok(trace)
+    }
+}
+
 /// Witness for a single VM step.
 #[derive(Default, Debug)]
 pub struct Witness<P: MemoryProof> {
@@ -191,6 +259,8 @@ pub struct Witness<P: MemoryProof> {
     pub Z: u32,
     /// Program counter.
     pub PC: u32,
+    /// Values of `PRECOMPILE_REGS` after precompile ecalls.
+    pub precompile_regs: Option<[u32; 16]>,
     /// Proof for reading instruction at pc.
     pub pc_proof: P,
     /// Proof for load instructions.
@@ -248,14 +318,20 @@ impl<P: MemoryProof> Iterator for BlockIter<'_, P> {
         } else {
             self.regs.pc + 4
         };
+        w.precompile_regs = s.precompile_regs;
         w.pc_proof = s.pc_proof.clone();
-        w.read_proof = s.read_proof.as_ref().unwrap_or(&w.pc_proof).clone();
-        w.write_proof = s.write_proof.as_ref().unwrap_or(&w.read_proof).clone();
+        w.read_proof = s.read_proof.clone().unwrap_or_else(|| w.pc_proof.skip());
+        w.write_proof = s.write_proof.clone().unwrap_or_else(|| w.read_proof.skip());
 
         self.regs.pc = w.PC;
         if w.rd > 0 {
             self.regs.x[w.rd as usize] = w.Z;
         }
+        if let Some(x) = s.precompile_regs {
+            for (r, x) in PRECOMPILE_REGS.iter().zip(x) {
+                self.regs.x[*r] = x;
+            }
+        }
         self.index += 1;
         Some(w)
     }
@@ -320,7 +396,11 @@ fn parse_alt<P: MemoryProof>(regs: &Regs, word: u32) -> Witness<P> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{machines::loop_vm, memory::paged::Paged, memory::trie::MerkleTrie};
+    use crate::{
+        machines::{loop_vm, nop_vm},
+        memory::paged::Paged,
+        memory::trie::MerkleTrie,
+    };
 
     // basic check that tracing and iteration succeeds
     fn trace_test_machine(mut nvm: NexusVM<impl Memory>) {
@@ -339,4 +419,27 @@ mod test {
         trace_test_machine(loop_vm::<Paged>(5));
         trace_test_machine(loop_vm::<MerkleTrie>(5));
     }
+
+    #[test]
+    fn tracer_matches_trace() {
+        let tr = trace(&mut nop_vm::<MerkleTrie>(6), 1, false).unwrap();
+        let mut tracer = Tracer::new(nop_vm::<MerkleTrie>(6), 1);
+
+        let mut blocks = Vec::new();
+        while !tracer.halted() {
+            let part = tracer.next_blocks(3).unwrap();
+            assert_eq!(part.start, blocks.len());
+            blocks.extend(part.blocks);
+        }
+        assert_eq!(blocks.len(), tr.blocks.len());
+        assert_eq!(tracer.start(), tr.blocks.len());
+        for (a, b) in blocks.iter().zip(&tr.blocks) {
+            assert_eq!(a.regs, b.regs);
+        }
+
+        // padding repeats the halting instruction
+        let pad = tracer.next_blocks(2).unwrap();
+        assert_eq!(pad.blocks.len(), 2);
+        assert_eq!(pad.blocks[0].regs, pad.blocks[1].regs);
+    }
 }
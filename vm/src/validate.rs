@@ -0,0 +1,418 @@
+//! Pre-flight validation of ELF files.
+//!
+//! `init_vm` loads every loadable segment of an ELF file without
+//! checking that the program can be executed by the VM; problems only
+//! show up during execution, as an invalid instruction at some pc.
+//! `validate_elf` checks an ELF file before it is executed or proved:
+//!
+//!  - the file is a 32-bit, little-endian, RISC-V executable,
+//!  - it is statically linked, without dynamic relocations,
+//!  - the loadable segments lie within the file, do not overlap, and
+//!    fit below the top of memory used by the runtime,
+//!  - the entry point is in an executable segment, and
+//!  - all reachable instructions decode under the ISA supported by
+//!    the VM.
+//!
+//! Reachable instructions are found by following control flow from
+//! the entry point and from each function symbol. Indirect jumps are
+//! not followed, hence the function symbols, which cover the targets
+//! of indirect calls in programs built with the runtime.
+
+use std::collections::{BTreeSet, HashSet};
+
+use elf::{
+    abi::{
+        EI_CLASS, EI_DATA, ELFCLASS32, ELFDATA2LSB, EM_RISCV, ET_EXEC, PT_DYNAMIC, PT_INTERP,
+        SHF_ALLOC, SHT_REL, SHT_RELA, STT_FUNC,
+    },
+    endian::LittleEndian,
+    ElfBytes,
+};
+use thiserror::Error;
+
+use crate::{
+    disasm::{decode, segments, Segment, Unsupported},
+    error::Result,
+    parse_elf_bytes,
+    rv32::RV32::*,
+};
+
+/// Top of memory used by the runtime, when the ELF file does not
+/// define the symbol `__memory_top`.
+pub const MEMORY_TOP: u32 = 0x400000;
+
+/// Reasons an ELF file cannot be executed by the VM.
+#[derive(Debug, Error, PartialEq)]
+pub enum ValidationError {
+    /// ELF class is not 32-bit
+    #[error("ELF class {0} not supported, expected 32-bit")]
+    Class(u8),
+
+    /// ELF file is not little-endian
+    #[error("ELF data encoding {0} not supported, expected little-endian")]
+    Endianness(u8),
+
+    /// ELF machine is not RISC-V
+    #[error("ELF machine {0} not supported, expected RISC-V")]
+    Machine(u16),
+
+    /// ELF file is not an executable
+    #[error("ELF type {0} not supported, expected executable")]
+    FileType(u16),
+
+    /// ELF file requires dynamic linking or relocation
+    #[error("dynamic linking and relocations are not supported")]
+    Dynamic,
+
+    /// ELF file has no loadable segments
+    #[error("no loadable segments")]
+    NoSegments,
+
+    /// Segment extends beyond the end of the file
+    #[error("segment at {0:x} extends beyond end of file")]
+    SegmentBounds(u32),
+
+    /// Segments overlap in memory
+    #[error("segments at {0:x} and {1:x} overlap")]
+    Overlap(u32, u32),
+
+    /// Segment does not fit below the top of memory
+    #[error("segment at {0:x} ends at {1:x}, above memory top {2:x}")]
+    MemoryTop(u32, u64, u64),
+
+    /// Entry point is not in an executable segment
+    #[error("entry point {0:x} is not in an executable segment")]
+    Entry(u32),
+
+    /// Reachable instruction not supported by the VM
+    #[error("{2} {1:08x} at pc:{0:x}")]
+    Unsupported(u32, u32, Unsupported),
+
+    /// Jump target is not in an executable segment
+    #[error("jump at pc:{0:x} to {1:x} outside of executable segments")]
+    JumpTarget(u32, u32),
+}
+
+use ValidationError::*;
+
+/// Check that the ELF file in `bytes` can be executed by the VM. If
+/// given, `addr_bits` further limits the top of memory.
+pub fn validate_elf(bytes: &[u8], addr_bits: Option<usize>) -> Result<()> {
+    // the ELF parser rejects these without saying why
+    if bytes.len() > EI_DATA && bytes.starts_with(b"\x7fELF") {
+        if bytes[EI_CLASS] != ELFCLASS32 {
+            return Err(Class(bytes[EI_CLASS]).into());
+        }
+        if bytes[EI_DATA] != ELFDATA2LSB {
+            return Err(Endianness(bytes[EI_DATA]).into());
+        }
+    }
+
+    let elf = parse_elf_bytes(bytes)?;
+    if elf.ehdr.e_machine != EM_RISCV {
+        return Err(Machine(elf.ehdr.e_machine).into());
+    }
+    if elf.ehdr.e_type != ET_EXEC {
+        return Err(FileType(elf.ehdr.e_type).into());
+    }
+    check_dynamic(&elf)?;
+
+    let segs = segments(&elf);
+    check_segments(&segs, bytes.len(), memory_top(&elf, addr_bits)?)?;
+
+    let entry = elf.ehdr.e_entry as u32;
+    let mut roots = vec![entry];
+    if let Some((syms, _)) = elf.symbol_table()? {
+        roots.extend(
+            syms.iter()
+                .filter(|s| s.st_symtype() == STT_FUNC)
+                .map(|s| s.st_value as u32),
+        );
+    }
+    check_text(&segs, bytes, entry, &roots)
+}
+
+fn check_dynamic(elf: &ElfBytes<LittleEndian>) -> Result<()> {
+    if let Some(phdrs) = elf.segments() {
+        if phdrs
+            .iter()
+            .any(|p| p.p_type == PT_DYNAMIC || p.p_type == PT_INTERP)
+        {
+            return Err(Dynamic.into());
+        }
+    }
+    if let Some(shdrs) = elf.section_headers() {
+        if shdrs.iter().any(|s| {
+            (s.sh_type == SHT_REL || s.sh_type == SHT_RELA) && s.sh_flags & SHF_ALLOC as u64 != 0
+        }) {
+            return Err(Dynamic.into());
+        }
+    }
+    Ok(())
+}
+
+// the runtime linker script defines `__memory_top`
+fn memory_top(elf: &ElfBytes<LittleEndian>, addr_bits: Option<usize>) -> Result<u64> {
+    let mut top = MEMORY_TOP as u64;
+    if let Some((syms, strs)) = elf.symbol_table()? {
+        for sym in syms.iter() {
+            if strs.get(sym.st_name as usize)? == "__memory_top" {
+                top = sym.st_value;
+            }
+        }
+    }
+    // address widths of 64 bits or more do not limit the top of memory
+    if let Some(limit) = addr_bits.and_then(|bits| 1u64.checked_shl(bits.try_into().ok()?)) {
+        top = top.min(limit);
+    }
+    Ok(top)
+}
+
+fn check_segments(segs: &[Segment], file_len: usize, top: u64) -> Result<()> {
+    if segs.is_empty() {
+        return Err(NoSegments.into());
+    }
+
+    for s in segs {
+        if s.offset as u64 + s.filesz as u64 > file_len as u64 {
+            return Err(SegmentBounds(s.vaddr).into());
+        }
+        let end = s.vaddr as u64 + s.memsz as u64;
+        if end > top {
+            return Err(MemoryTop(s.vaddr, end, top).into());
+        }
+    }
+
+    let mut sorted: Vec<&Segment> = segs.iter().filter(|s| s.memsz > 0).collect();
+    sorted.sort_by_key(|s| s.vaddr);
+    for w in sorted.windows(2) {
+        if w[0].vaddr as u64 + w[0].memsz as u64 > w[1].vaddr as u64 {
+            return Err(Overlap(w[0].vaddr, w[1].vaddr).into());
+        }
+    }
+    Ok(())
+}
+
+fn check_text(segs: &[Segment], bytes: &[u8], entry: u32, roots: &[u32]) -> Result<()> {
+    let text: Vec<&Segment> = segs.iter().filter(|s| s.is_exec()).collect();
+    let find = |pc: u32| {
+        text.iter()
+            .find(|s| s.vaddr <= pc && pc - s.vaddr < s.filesz)
+            .map(|s| &bytes[(s.offset + pc - s.vaddr) as usize..(s.offset + s.filesz) as usize])
+    };
+
+    if find(entry).is_none() {
+        return Err(Entry(entry).into());
+    }
+
+    // function symbols outside of text, e.g. absolute symbols, are ignored
+    let mut todo: BTreeSet<u32> = roots
+        .iter()
+        .copied()
+        .filter(|&pc| find(pc).is_some())
+        .collect();
+    let mut seen = HashSet::new();
+
+    while let Some(pc) = todo.pop_first() {
+        if !seen.insert(pc) {
+            continue;
+        }
+        let Some(line) = find(pc).and_then(|b| decode(pc, b)) else {
+            return Err(ValidationError::Unsupported(pc, 0, Unsupported::Invalid).into());
+        };
+        let inst = line
+            .inst
+            .map_err(|u| ValidationError::Unsupported(pc, line.word, u))?;
+
+        let next = pc.wrapping_add(line.len);
+        let targets = match inst {
+            JAL { rd: 0, imm } => vec![pc.wrapping_add(imm)],
+            JAL { imm, .. } => vec![pc.wrapping_add(imm), next],
+            BR { imm, .. } => vec![pc.wrapping_add(imm), next],
+            JALR { rd: 0, .. } => vec![],
+            UNIMP => vec![],
+            _ => vec![next],
+        };
+        for t in targets {
+            if find(t).is_none() {
+                return Err(JumpTarget(pc, t).into());
+            }
+            if !seen.contains(&t) {
+                todo.insert(t);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{error::NexusVMError, rv32::asm::assemble};
+    use elf::abi::{ET_DYN, PF_R, PF_W, PF_X, PT_LOAD};
+
+    // build a minimal ELF file with the given segments
+    fn elf(entry: u32, segs: &[(u32, u32, u32, &[u8])]) -> Vec<u8> {
+        let phoff: u32 = 52;
+        let mut data_off = phoff + 32 * segs.len() as u32;
+
+        let mut out = Vec::new();
+        out.extend(b"\x7fELF");
+        out.extend([ELFCLASS32, ELFDATA2LSB, 1, 0]);
+        out.extend([0; 8]);
+        out.extend(ET_EXEC.to_le_bytes());
+        out.extend(EM_RISCV.to_le_bytes());
+        out.extend(1u32.to_le_bytes());
+        out.extend(entry.to_le_bytes());
+        out.extend(phoff.to_le_bytes());
+        out.extend(0u32.to_le_bytes()); // e_shoff
+        out.extend(0u32.to_le_bytes()); // e_flags
+        out.extend(52u16.to_le_bytes());
+        out.extend(32u16.to_le_bytes());
+        out.extend((segs.len() as u16).to_le_bytes());
+        out.extend(40u16.to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+
+        for (vaddr, memsz, flags, data) in segs {
+            for x in [
+                PT_LOAD,
+                data_off,
+                *vaddr,
+                *vaddr,
+                data.len() as u32,
+                *memsz,
+                *flags,
+                4,
+            ] {
+                out.extend(x.to_le_bytes());
+            }
+            data_off += data.len() as u32;
+        }
+        for (_, _, _, data) in segs {
+            out.extend(*data);
+        }
+        out
+    }
+
+    fn text(src: &str) -> Vec<u8> {
+        assemble(src)
+            .unwrap()
+            .into_iter()
+            .flat_map(u32::to_le_bytes)
+            .collect()
+    }
+
+    fn check(bytes: &[u8]) -> std::result::Result<(), ValidationError> {
+        match validate_elf(bytes, None) {
+            Ok(()) => Ok(()),
+            Err(NexusVMError::InvalidELF(e)) => Err(e),
+            Err(e) => panic!("unexpected error {e}"),
+        }
+    }
+
+    const RX: u32 = PF_R | PF_X;
+    const RW: u32 = PF_R | PF_W;
+
+    #[test]
+    fn test_valid() {
+        let code = text("li a0, 1\nbeqz a0, end\ncall f\nend: unimp\nf: ret");
+        let data = [1, 2, 3, 4];
+        let bytes = elf(
+            0,
+            &[
+                (0, code.len() as u32, RX, &code[..]),
+                (0x1000, 8, RW, &data[..]),
+            ],
+        );
+        assert_eq!(check(&bytes), Ok(()));
+
+        // unreachable words are not checked
+        let mut code = code;
+        code.extend(0x023100b3u32.to_le_bytes()); // mul
+        let bytes = elf(0, &[(0, code.len() as u32, RX, &code[..])]);
+        assert_eq!(check(&bytes), Ok(()));
+    }
+
+    #[test]
+    fn test_header() {
+        let code = text("unimp");
+        let bytes = elf(0, &[(0, 4, RX, &code[..])]);
+
+        let mut b = bytes.clone();
+        b[EI_CLASS] = 2;
+        assert_eq!(check(&b), Err(Class(2)));
+
+        let mut b = bytes.clone();
+        b[EI_DATA] = 2;
+        assert_eq!(check(&b), Err(Endianness(2)));
+
+        let mut b = bytes.clone();
+        b[18] = 0x3e; // x86-64
+        assert_eq!(check(&b), Err(Machine(0x3e)));
+
+        let mut b = bytes.clone();
+        b[16] = ET_DYN as u8;
+        assert_eq!(check(&b), Err(FileType(ET_DYN)));
+    }
+
+    #[test]
+    fn test_segments() {
+        let code = text("unimp");
+        let data = [0; 8];
+
+        let bytes = elf(0, &[(0, 4, RX, &code[..]), (0, 8, RW, &data[..])]);
+        assert_eq!(check(&bytes), Err(Overlap(0, 0)));
+
+        let bytes = elf(0, &[(0, 4, RX, &code[..]), (0x3ffffc, 8, RW, &data[..])]);
+        assert_eq!(
+            check(&bytes),
+            Err(MemoryTop(0x3ffffc, 0x400004, MEMORY_TOP as u64))
+        );
+        assert!(matches!(
+            validate_elf(
+                &elf(0, &[(0, 4, RX, &code[..]), (0x1000, 8, RW, &data[..])]),
+                Some(12)
+            ),
+            Err(NexusVMError::InvalidELF(MemoryTop(0x1000, 0x1008, 0x1000)))
+        ));
+        for bits in [64, usize::MAX] {
+            assert!(validate_elf(&elf(0, &[(0, 4, RX, &code[..])]), Some(bits)).is_ok());
+        }
+
+        let mut bytes = elf(0, &[(0, 4, RX, &code[..])]);
+        bytes.truncate(bytes.len() - 2);
+        assert_eq!(check(&bytes), Err(SegmentBounds(0)));
+
+        assert_eq!(check(&elf(0, &[])), Err(NoSegments));
+        assert_eq!(
+            check(&elf(0x10, &[(0, 4, RX, &code[..])])),
+            Err(Entry(0x10))
+        );
+        assert_eq!(check(&elf(0, &[(0, 4, RW, &code[..])])), Err(Entry(0)));
+    }
+
+    #[test]
+    fn test_text() {
+        let mut code = text("nop");
+        code.extend(0x023100b3u32.to_le_bytes()); // mul x1, x2, x3
+        let bytes = elf(0, &[(0, 8, RX, &code[..])]);
+        assert_eq!(
+            check(&bytes),
+            Err(ValidationError::Unsupported(
+                4,
+                0x023100b3,
+                Unsupported::MulDiv("mul")
+            ))
+        );
+
+        let code = text("nop\nj 0x100");
+        let bytes = elf(0, &[(0, 8, RX, &code[..])]);
+        assert_eq!(check(&bytes), Err(JumpTarget(4, 0x104)));
+
+        // falling off the end of the text segment
+        let code = text("nop");
+        let bytes = elf(0, &[(0, 4, RX, &code[..])]);
+        assert_eq!(check(&bytes), Err(JumpTarget(0, 4)));
+    }
+}